@@ -1,18 +1,362 @@
 fn main() {
+    // the strict feature replaces all of the shims with plain rust operations, so there's nothing
+    // to build (and no need for clang). Targets which can't use the shims fall back to the same
+    // rust operations
+    println!("cargo:rustc-check-cfg=cfg(scalar_fallback)");
+    println!("cargo:rustc-check-cfg=cfg(inline_arith)");
+    println!("cargo:rustc-check-cfg=cfg(poison_free)");
+    write_sin_tables();
+    if std::env::var_os("CARGO_FEATURE_STRICT").is_some() || scalar_target() {
+        println!("cargo:rustc-cfg=scalar_fallback");
+        // ...except for FF128, which has no rust float type to fall back on. Its shims are
+        // compiled without any fast-math flags instead, with whichever compiler is configured
+        if ff128() {
+            let mut builder = cc::Build::new();
+            builder
+                .opt_level(3)
+                .file("src/math/ff128_safe.c")
+                .file("src/math/ff128_unsafe.c")
+                .compile("ff128");
+        }
+        return;
+    }
+
     let mut builder = cc::Build::new();
 
-    if !builder.get_compiler().is_like_clang() {
-        // if the default/configured cc is not clang, try to call clang manually
-        builder.compiler("clang");
+    let cross = std::env::var("TARGET").unwrap() != std::env::var("HOST").unwrap();
+
+    // FAST_FP_CLANG selects a specific clang binary (or zig), over the default/configured cc
+    println!("cargo:rerun-if-env-changed=FAST_FP_CLANG");
+    let clang = std::env::var_os("FAST_FP_CLANG");
+
+    if clang.is_some() || !builder.get_compiler().is_like_clang() {
+        // if the default/configured cc is not clang, try to call clang manually. When cross
+        // compiling, the configured cc was likely a target-specific gcc, while this is a generic
+        // clang which has to be told the target explicitly
+        let clang = clang.unwrap_or_else(default_clang);
+        if is_zig(&clang) {
+            builder.compiler(zig_cc(&clang));
+        } else {
+            builder.compiler(clang);
+        }
+        if cross {
+            builder.flag(format!("--target={}", clang_target()));
+        }
+    }
+
+    if cross {
+        if let Some(sysroot) = sysroot() {
+            builder.flag(format!("--sysroot={}", sysroot.display()));
+        }
+    }
+
+    if cross_lang_lto() {
+        check_cross_lang_lto(&builder);
+    }
+
+    if inline_arith() {
+        println!("cargo:rustc-cfg=inline_arith");
+    }
+
+    // without finite-math-only, no operation can produce poison
+    if !finite_math() {
+        println!("cargo:rustc-cfg=poison_free");
     }
 
     builder.warnings_into_errors(true);
-    builder.flag("-flto=thin");
+    if let Some(lto) = lto_flag() {
+        builder.flag(lto);
+    }
+
+    // FAST_FP_CFLAGS adds whitespace-separated flags to every compilation
+    println!("cargo:rerun-if-env-changed=FAST_FP_CFLAGS");
+    if let Ok(cflags) = std::env::var("FAST_FP_CFLAGS") {
+        for flag in cflags.split_whitespace() {
+            builder.flag(flag);
+        }
+    }
 
     build_ll(builder.clone());
     build_c(builder);
 }
 
+// clang before 13 doesn't support -fapprox-func
+const MIN_CLANG_VERSION: u32 = 13;
+
+// The system clang, unless it's missing or too old and zig is available instead
+fn default_clang() -> std::ffi::OsString {
+    let clang_version = std::process::Command::new("clang")
+        .arg("--version")
+        .output()
+        .ok()
+        .and_then(|output| {
+            let output = String::from_utf8_lossy(&output.stdout).into_owned();
+            let version = output.split("clang version ").nth(1)?;
+            major_version(version).parse::<u32>().ok()
+        });
+
+    let zig_version = std::process::Command::new("zig").arg("version").output();
+    let zig_available = matches!(zig_version, Ok(output) if output.status.success());
+
+    match clang_version {
+        Some(version) if version >= MIN_CLANG_VERSION => "clang".into(),
+        _ if zig_available => "zig".into(),
+        _ => "clang".into(),
+    }
+}
+
+fn is_zig(compiler: &std::ffi::OsStr) -> bool {
+    std::path::Path::new(compiler).file_stem() == Some("zig".as_ref())
+}
+
+// `zig cc` bundles a clang which can target most platforms without any other toolchain. cc only
+// runs single binaries, so it's given a wrapper script which calls `zig cc`.
+//
+// zig spells target triples without the vendor, so the wrapper also removes the vendors rust uses
+// from `--target` (e.g. x86_64-unknown-linux-gnu becomes x86_64-linux-gnu)
+fn zig_cc(zig: &std::ffi::OsStr) -> std::path::PathBuf {
+    let out_dir = std::path::PathBuf::from(std::env::var_os("OUT_DIR").unwrap());
+    let zig = zig.to_str().expect("the path to zig must be valid unicode");
+
+    let (wrapper, script) = if cfg!(windows) {
+        (
+            out_dir.join("zig-clang.cmd"),
+            format!("@\"{}\" cc %*\r\n", zig),
+        )
+    } else {
+        let script = format!(
+            "#!/bin/sh\n\
+             for arg; do\n\
+             shift\n\
+             case \"$arg\" in\n\
+             --target=*) arg=$(echo \"$arg\" | sed -e 's/-unknown-/-/' -e 's/-pc-/-/') ;;\n\
+             esac\n\
+             set -- \"$@\" \"$arg\"\n\
+             done\n\
+             exec \"{}\" cc \"$@\"\n",
+            zig
+        );
+        (out_dir.join("zig-clang"), script)
+    };
+
+    std::fs::write(&wrapper, script).expect("failed to write the zig cc wrapper");
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        let permissions = std::fs::Permissions::from_mode(0o755);
+        std::fs::set_permissions(&wrapper, permissions)
+            .expect("failed to make the zig cc wrapper executable");
+    }
+
+    wrapper
+}
+
+// The default tables of the lookup-table sine and cosine, from the `lut` module. Float arithmetic
+// isn't allowed in a const fn on the minimum supported rust version, so the tables are generated
+// here and included in the crate as arrays of literals, rather than built at runtime
+fn write_sin_tables() {
+    // must match `DEFAULT_SIZE` in src/lut.rs. The extra entry repeats the first, so interpolation
+    // never needs to wrap around
+    const SIZE: usize = 1024;
+    let out_dir = std::path::PathBuf::from(std::env::var_os("OUT_DIR").unwrap());
+    for (fast_ty, base_ty) in [("FF32", "f32"), ("FF64", "f64")] {
+        let mut table = String::from("[\n");
+        for i in 0..=SIZE {
+            let x = (std::f64::consts::TAU * (i % SIZE) as f64 / SIZE as f64).sin();
+            let x = if base_ty == "f32" {
+                format!("{:?}", x as f32)
+            } else {
+                format!("{:?}", x)
+            };
+            table.push_str(&format!("    {}::new({}),\n", fast_ty, x));
+        }
+        table.push(']');
+        std::fs::write(out_dir.join(format!("sin_table_{}.rs", base_ty)), table)
+            .expect("failed to write the sine table");
+    }
+}
+
+// Hosted targets without a hardware FPU, where every float operation is a library call that gains
+// nothing from the fast-math flags. These include the soft-float arm linux targets (like
+// arm-unknown-linux-gnueabi) and the riscv targets without the F extension. Bare-metal targets
+// aren't included, as the crate requires std
+fn scalar_target() -> bool {
+    let arch = std::env::var("CARGO_CFG_TARGET_ARCH").unwrap();
+    let os = std::env::var("CARGO_CFG_TARGET_OS").unwrap();
+    let target = std::env::var("TARGET").unwrap();
+    if os == "none" {
+        return false;
+    }
+
+    match arch.as_str() {
+        "riscv32" | "riscv64" => !target_feature("f"),
+        "arm" => target.ends_with("eabi") || target_feature("soft-float"),
+        _ => target_feature("soft-float"),
+    }
+}
+
+// rust's target triples mostly match clang's, except for a few architecture spellings
+fn clang_target() -> String {
+    let target = std::env::var("TARGET").unwrap();
+    let (arch, rest) = target.split_once('-').unwrap();
+
+    let arch = match arch {
+        // clang doesn't understand the extension suffixes, they're selected with -march instead
+        arch if arch.starts_with("riscv64") => "riscv64",
+        arch if arch.starts_with("riscv32") => "riscv32",
+        // the android triple names the armv7-a profile explicitly
+        "armv7" if rest.contains("android") => "armv7a",
+        arch => arch,
+    };
+
+    format!("{}-{}", arch, rest)
+}
+
+// The sysroot holding the target's C headers. FAST_FP_SYSROOT takes priority; otherwise android
+// targets use the NDK's sysroot, and other targets rely on the compiler's default
+fn sysroot() -> Option<std::path::PathBuf> {
+    println!("cargo:rerun-if-env-changed=FAST_FP_SYSROOT");
+    if let Some(sysroot) = std::env::var_os("FAST_FP_SYSROOT") {
+        return Some(sysroot.into());
+    }
+
+    if std::env::var("CARGO_CFG_TARGET_OS").unwrap() != "android" {
+        return None;
+    }
+
+    // the NDK ships a single prebuilt toolchain, in a directory named after the host platform
+    let ndk = ["ANDROID_NDK_HOME", "ANDROID_NDK_ROOT", "ANDROID_NDK"]
+        .iter()
+        .find_map(|var| {
+            println!("cargo:rerun-if-env-changed={}", var);
+            std::env::var_os(var)
+        })?;
+    let prebuilt = std::path::Path::new(&ndk).join("toolchains/llvm/prebuilt");
+    let host = std::fs::read_dir(prebuilt).ok()?.next()?.ok()?;
+    Some(host.path().join("sysroot"))
+}
+
+fn approx_math() -> bool {
+    std::env::var_os("CARGO_FEATURE_APPROX_MATH").is_some()
+}
+
+fn ff128() -> bool {
+    std::env::var_os("CARGO_FEATURE_FF128").is_some()
+}
+
+fn num_complex() -> bool {
+    std::env::var_os("CARGO_FEATURE_NUM_COMPLEX_V04").is_some()
+}
+
+fn cross_lang_lto() -> bool {
+    std::env::var_os("CARGO_FEATURE_CROSS_LANG_LTO").is_some()
+}
+
+// Cross-language LTO only works when clang's bitcode can be read by rustc's LLVM, and only when
+// rustc is asked to link with it. Either mistake otherwise surfaces as an obscure linker error, or
+// as shims which are silently never inlined, so the cross-lang-lto feature checks both up front
+fn check_cross_lang_lto(builder: &cc::Build) {
+    let rustc = std::env::var_os("RUSTC").unwrap_or_else(|| "rustc".into());
+    let rustc_output = command_output(std::process::Command::new(rustc).arg("-vV"));
+    let rustc_llvm = rustc_output
+        .lines()
+        .find_map(|line| line.strip_prefix("LLVM version: ").map(major_version))
+        .expect("couldn't find rustc's LLVM version in `rustc -vV`");
+
+    let compiler = builder.get_compiler();
+    let clang_output = command_output(compiler.to_command().arg("--version"));
+    let clang_llvm = clang_output
+        .split("clang version ")
+        .nth(1)
+        .map(major_version)
+        .unwrap_or_else(|| panic!("couldn't parse the clang version from:\n{}", clang_output));
+
+    if clang_llvm != rustc_llvm {
+        panic!(
+            "the cross-lang-lto feature requires clang {0}, to match rustc's LLVM version, but {1:?} \
+             is clang {2}. Select clang {0} with CC or FAST_FP_CLANG, e.g. FAST_FP_CLANG=clang-{0}",
+            rustc_llvm,
+            compiler.path(),
+            clang_llvm,
+        );
+    }
+
+    if lto_flag().is_none() {
+        panic!("the cross-lang-lto feature can't be used with FAST_FP_LTO=off");
+    }
+
+    if !linker_plugin_lto() {
+        panic!(
+            "the cross-lang-lto feature requires linking with clang {0} and its LTO plugin, e.g. \
+             RUSTFLAGS=\"-Clinker-plugin-lto -Clinker=clang-{0} -Clink-arg=-fuse-ld=lld-{0}\"",
+            rustc_llvm,
+        );
+    }
+}
+
+// FAST_FP_LTO selects how the C code takes part in LTO: "thin" (the default) and "fat" emit
+// bitcode for the matching kind of LTO, while "off" emits plain object files. Those can be linked
+// without an LLVM linker plugin (for example by a classic `ld`), but never inline into rust code
+fn lto_flag() -> Option<&'static str> {
+    println!("cargo:rerun-if-env-changed=FAST_FP_LTO");
+    match std::env::var("FAST_FP_LTO").as_deref() {
+        Err(_) | Ok("thin") => Some("-flto=thin"),
+        Ok("fat") => Some("-flto=full"),
+        Ok("off") => None,
+        Ok(other) => panic!(
+            "unknown FAST_FP_LTO value {:?}, expected thin, fat or off",
+            other
+        ),
+    }
+}
+
+fn linker_plugin_lto() -> bool {
+    // the flag is either bare, a path to the plugin, or an explicit yes/no
+    codegen_flags().iter().any(|flag| {
+        let value = flag.strip_prefix("linker-plugin-lto");
+        matches!(value, Some(value) if !matches!(value, "=n" | "=no" | "=off" | "=false"))
+    })
+}
+
+// Without cross-language LTO every shim is an out-of-line call, which is a poor trade for the
+// basic arithmetic. The inline-fallback feature replaces those operations with rust's algebraic
+// float operations in that case, which have most of the same fast-math flags but can be inlined.
+// Those flags don't include finite-math-only, so the C code is compiled without it too: then
+// nothing produces poison, and freezing is a no-op which also doesn't need a call (see
+// `finite_math`)
+fn inline_arith() -> bool {
+    std::env::var_os("CARGO_FEATURE_INLINE_FALLBACK").is_some()
+        && (lto_flag().is_none() || !linker_plugin_lto())
+}
+
+// The non-finite feature drops finite-math-only for users who need infinities and NaN to survive
+// the fast operations, keeping the other fast-math flags. The inline fallback drops it too, as
+// its algebraic operations don't have the flag
+fn finite_math() -> bool {
+    std::env::var_os("CARGO_FEATURE_NON_FINITE").is_none() && !inline_arith()
+}
+
+fn command_output(command: &mut std::process::Command) -> String {
+    let output = command
+        .output()
+        .unwrap_or_else(|err| panic!("failed to run {:?}: {}", command, err));
+    String::from_utf8_lossy(&output.stdout).into_owned()
+}
+
+// "13.0.1-++20220120" -> "13"
+fn major_version(version: &str) -> &str {
+    version.split(|c: char| !c.is_ascii_digit()).next().unwrap()
+}
+
+fn reproducible() -> bool {
+    std::env::var_os("CARGO_FEATURE_REPRODUCIBLE").is_some()
+}
+
+fn runtime_dispatch() -> bool {
+    std::env::var_os("CARGO_FEATURE_RUNTIME_DISPATCH").is_some()
+}
+
 fn build_ll(mut builder: cc::Build) {
     // the ll files are written bare, let the compiler override module annotations and don't warn
     // about it
@@ -24,6 +368,16 @@ fn build_ll(mut builder: cc::Build) {
 fn build_c(mut builder: cc::Build) {
     builder.opt_level(3);
 
+    cpu_flags(&mut builder);
+
+    // the contract-only types get fused multiply-add codegen and nothing else, so they're built
+    // before any of the other fast-math flags are added
+    contract(builder.clone());
+
+    // the single-flag libraries back the per-operation methods like `add_reassoc`
+    reassoc(builder.clone());
+    reciprocal(builder.clone());
+
     // TODO control flags with generics
     builder.flag("-fassociative-math");
     builder.flag("-freciprocal-math");
@@ -40,11 +394,14 @@ fn build_c(mut builder: cc::Build) {
     // flag which doesn't seem to mix well with the is_supported api checks. Instead, do the dumb
     // thing and don't enable this flag when compiling on docs.rs. That way, normal users should at
     // least get a slightly informative error if they have an incompatible clang
+    //
+    // Approximate functions may be replaced by implementations which differ between machines, so
+    // reproducible builds leave the flag off
     match std::env::var("DOCS_RS") {
-        Err(std::env::VarError::NotPresent) => {
+        Err(std::env::VarError::NotPresent) if !reproducible() => {
             builder.flag("-Xclang").flag("-fapprox-func");
         }
-        Ok(_) => {}
+        Ok(_) | Err(std::env::VarError::NotPresent) => {}
         Err(err) => panic!("{}", err),
     }
 
@@ -54,19 +411,281 @@ fn build_c(mut builder: cc::Build) {
     // see its docs for details
     poison_unsafe(builder.clone());
 
-    builder.flag("-ffinite-math-only");
+    if finite_math() {
+        builder.flag("-ffinite-math-only");
+    }
+
+    check_fast_math(&builder);
 
     poison_safe(builder);
 }
 
+// A compiler which ignores some of the fast-math flags still builds the shims successfully, just
+// without the optimizations they're for. To catch that, a probe with the poison_safe flags is
+// compiled to IR, and the flags on its float operations are checked: a warning is all that's
+// needed, as the build is still correct
+fn check_fast_math(builder: &cc::Build) {
+    let out_dir = std::path::PathBuf::from(std::env::var_os("OUT_DIR").unwrap());
+    let probe = out_dir.join("fast_math_probe.c");
+    let probe_ll = out_dir.join("fast_math_probe.ll");
+    std::fs::write(
+        &probe,
+        "float probe(float a, float b, float c) { return (a + b) + c; }\n",
+    )
+    .expect("failed to write the fast-math probe");
+
+    let status = builder
+        .get_compiler()
+        .to_command()
+        .args(["-S", "-emit-llvm", "-fno-lto", "-o"])
+        .arg(&probe_ll)
+        .arg(&probe)
+        .status();
+    let ir = match status {
+        Ok(status) if status.success() => std::fs::read_to_string(&probe_ll).unwrap_or_default(),
+        _ => {
+            println!(
+                "cargo:warning=couldn't compile the fast-math probe, the C compiler may not be \
+                 clang"
+            );
+            return;
+        }
+    };
+
+    // clang prints `fast` when every flag is set, and the individual flags otherwise
+    let fadd_flags = ir
+        .lines()
+        .find(|line| line.contains(" fadd "))
+        .unwrap_or_default();
+    let mut expected = vec!["reassoc", "nsz", "arcp", "contract"];
+    if finite_math() {
+        expected.extend(&["nnan", "ninf"]);
+    }
+    let missing: Vec<_> = expected
+        .into_iter()
+        .filter(|flag| {
+            !fadd_flags.contains(" fast ") && !fadd_flags.contains(&format!(" {} ", flag))
+        })
+        .collect();
+
+    if !missing.is_empty() {
+        println!(
+            "cargo:warning=the C compiler didn't apply the fast-math flags {:?} to the shims, so \
+             fast_fp will be slower than expected. Check that it's a recent clang",
+            missing
+        );
+    }
+}
+
+// The C code targets the same CPU and features as the rust code, so building with
+// `-Ctarget-cpu=native` (or any other CPU or `-Ctarget-feature`) doesn't leave the shims on the
+// baseline ISA. On x86, -march also sets -mtune to the same CPU.
+//
+// Without an explicit CPU, clang compiles aarch64 code for a generic core with a minimal cost
+// model. Apple's aarch64 targets always run on Apple silicon, so they can be compiled for the
+// M1; other aarch64 targets (such as Graviton) stay on the baseline ISA, but with NEON enabled
+// explicitly for the intrinsics in slice_neon.h
+//
+// wasm engines without SIMD support reject a whole module if any of it uses SIMD, so simd128 is
+// only enabled in the C code when the rust code enables it too (with `-Ctarget-feature=+simd128`)
+//
+// Reproducible builds ignore the rust CPU and features: the vector width and the availability of
+// fused multiply-add both change the results of the fast-math code, so it's always compiled for
+// the baseline
+fn cpu_flags(builder: &mut cc::Build) {
+    let target_arch = std::env::var("CARGO_CFG_TARGET_ARCH").unwrap();
+    let target_vendor = std::env::var("CARGO_CFG_TARGET_VENDOR").unwrap();
+    let target_cpu = target_cpu().filter(|_| !reproducible());
+    let target_feature = |feature: &str| !reproducible() && target_feature(feature);
+
+    match target_arch.as_str() {
+        "x86" | "x86_64" => {
+            if let Some(cpu) = target_cpu {
+                builder.flag(format!("-march={}", cpu));
+            }
+
+            // rust and clang share the names of the x86 features, except for bmi1
+            let features = [
+                "sse3", "ssse3", "sse4.1", "sse4.2", "popcnt", "avx", "avx2", "fma", "f16c",
+                "bmi1", "bmi2", "lzcnt", "avx512f", "avx512cd", "avx512bw", "avx512dq", "avx512vl",
+            ];
+            for feature in features.iter().filter(|f| target_feature(f)) {
+                let feature = if *feature == "bmi1" { "bmi" } else { feature };
+                builder.flag(format!("-m{}", feature));
+            }
+        }
+        "aarch64" => {
+            // aarch64 extensions are appended to the -mcpu or -march value
+            let extensions: String = ["sve", "sve2", "dotprod", "fp16", "i8mm"]
+                .iter()
+                .filter(|f| target_feature(f))
+                .map(|f| format!("+{}", f))
+                .collect();
+
+            if let Some(cpu) = target_cpu {
+                builder.flag(format!("-mcpu={}{}", cpu, extensions));
+            } else if target_vendor == "apple" {
+                builder.flag(format!("-mcpu=apple-m1{}", extensions));
+            } else {
+                builder.flag(format!("-march=armv8-a+simd{}", extensions));
+            }
+        }
+        "wasm32" => {
+            if target_feature("simd128") {
+                builder.flag("-msimd128");
+            }
+        }
+        _ => {
+            if let Some(cpu) = target_cpu {
+                builder.flag(format!("-mcpu={}", cpu));
+            }
+        }
+    }
+}
+
+// The CPU given to rustc with `-Ctarget-cpu`, if any. Unlike the target features, cargo doesn't
+// expose the CPU as a cfg, so it's read from the flags themselves
+fn target_cpu() -> Option<String> {
+    // the last occurrence takes priority, like it does for rustc
+    let cpu = codegen_flags()
+        .into_iter()
+        .rev()
+        .find_map(|flag| flag.strip_prefix("target-cpu=").map(str::to_string));
+
+    // "generic" is rustc's name for the target's default CPU, which clang doesn't accept on every
+    // architecture
+    cpu.filter(|cpu| cpu != "generic")
+}
+
+// The values of the `-C`/`--codegen` options in the rustflags, like `target-cpu=native`
+fn codegen_flags() -> Vec<String> {
+    let rustflags = std::env::var("CARGO_ENCODED_RUSTFLAGS").unwrap_or_default();
+    let mut flags = rustflags.split('\x1f');
+    let mut codegen_flags = Vec::new();
+
+    while let Some(flag) = flags.next() {
+        let codegen = match flag {
+            "-C" | "--codegen" => flags.next(),
+            flag => flag
+                .strip_prefix("-C")
+                .or_else(|| flag.strip_prefix("--codegen=")),
+        };
+        codegen_flags.extend(codegen.map(str::to_string));
+    }
+
+    codegen_flags
+}
+
+fn target_feature(feature: &str) -> bool {
+    std::env::var("CARGO_CFG_TARGET_FEATURE")
+        .map(|features| features.split(',').any(|f| f == feature))
+        .unwrap_or(false)
+}
+
+fn contract(mut builder: cc::Build) {
+    builder
+        .flag("-ffp-contract=fast")
+        .file("src/math/contract.c")
+        .compile("contract")
+}
+
+fn reassoc(mut builder: cc::Build) {
+    // clang only enables reassociation if signed zeros and trapping are also relaxed
+    builder
+        .flag("-fassociative-math")
+        .flag("-fno-signed-zeros")
+        .flag("-fno-trapping-math")
+        .file("src/math/reassoc.c")
+        .compile("reassoc")
+}
+
+fn reciprocal(mut builder: cc::Build) {
+    builder
+        .flag("-freciprocal-math")
+        .file("src/math/reciprocal.c")
+        .compile("reciprocal")
+}
+
 fn poison_unsafe(mut builder: cc::Build) {
     builder
         .file("src/math/poison_unsafe.c")
-        .compile("poison_unsafe")
+        .file("src/math/activation_unsafe.c")
+        .file("src/math/decibel_unsafe.c")
+        .file("src/math/loss_unsafe.c")
+        .file("src/math/matrix_unsafe.c")
+        .file("src/math/quantize_unsafe.c");
+
+    if approx_math() {
+        builder.define("FAST_FP_APPROX_MATH", None);
+    }
+
+    if ff128() {
+        builder.file("src/math/ff128_unsafe.c");
+    }
+
+    if num_complex() {
+        builder.file("src/math/fft_unsafe.c");
+    }
+
+    builder.compile("poison_unsafe")
 }
 
 fn poison_safe(mut builder: cc::Build) {
+    if runtime_dispatch() {
+        kernel_variants(builder.clone());
+    }
+
     builder
         .file("src/math/poison_safe.c")
-        .compile("poison_safe")
+        .file("src/math/activation.c")
+        .file("src/math/approx_safe.c")
+        .file("src/math/blas1.c")
+        .file("src/math/blas2.c")
+        .file("src/math/blas3.c")
+        .file("src/math/distance.c")
+        .file("src/math/dsp.c")
+        .file("src/math/matrix.c")
+        .file("src/math/mixed.c")
+        .file("src/math/quantize.c")
+        .file("src/math/rsqrt.c")
+        .file("src/math/simd.c")
+        .file("src/math/slice.c")
+        .file("src/math/stats.c");
+
+    if ff128() {
+        builder.file("src/math/ff128_safe.c");
+    }
+
+    if num_complex() {
+        builder.file("src/math/complex.c").file("src/math/fft.c");
+    }
+
+    builder.compile("poison_safe")
+}
+
+// Compile the slice and matrix kernels once for each instruction set that the rust side can
+// select at runtime. Each variant gets its own library, with the variant name appended to its
+// symbols (see kernel.h)
+fn kernel_variants(builder: cc::Build) {
+    let target_arch = std::env::var("CARGO_CFG_TARGET_ARCH").unwrap();
+    let variants: &[(&str, &[&str])] = match target_arch.as_str() {
+        "x86" | "x86_64" => &[
+            ("avx2", &["-mavx2", "-mfma"]),
+            ("avx512", &["-mavx512f", "-mavx2", "-mfma"]),
+        ],
+        // NEON is part of the aarch64 baseline, so the baseline kernels already use it
+        _ => &[],
+    };
+
+    for (variant, flags) in variants {
+        let mut builder = builder.clone();
+        for flag in flags.iter() {
+            builder.flag(flag);
+        }
+        builder
+            .define("KERNEL_VARIANT", *variant)
+            .file("src/math/matrix.c")
+            .file("src/math/slice.c")
+            .compile(&format!("poison_safe_{}", variant));
+    }
 }
@@ -0,0 +1,189 @@
+use crate::{FF32, FF64};
+use paste::paste;
+
+/// The accuracy of a transcendental function, selected per call with the `*_with` methods like
+/// [`FF32::exp_with`].
+///
+/// The build-wide choice made by the `approx-math` feature only affects [`Fast`](Self::Fast); the
+/// other tiers are the same with or without it. Strict builds compute every tier with the standard
+/// library's functions.
+///
+/// ```
+/// use fast_fp::{ff32, Accuracy};
+///
+/// let x = ff32(0.75);
+/// let precise = x.exp_with(Accuracy::Precise);
+/// assert!((x.exp_with(Accuracy::Fast) - precise).abs() < ff32(1e-6));
+/// assert!((x.exp_with(Accuracy::Fastest) - precise).abs() < ff32(1e-5));
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Accuracy {
+    /// The standard library's implementation, as accurate as the plain `f32` and `f64` functions
+    Precise,
+    /// The implementation used by the regular methods like [`FF32::exp`]: the platform libm
+    /// compiled with approximate functions allowed, or the polynomial approximations with the
+    /// `approx-math` feature
+    Fast,
+    /// Polynomial approximations, with a relative error of about `1e-6`. Functions without an
+    /// approximation use the [`Fast`](Self::Fast) implementation instead
+    Fastest,
+}
+
+// Generate the `*_with` method of each function. The fastest tier calls the given path, with the
+// same arguments as the function
+macro_rules! accuracy_fns {
+    ($fast_ty:ident, $base_ty:ident:
+     $(fn $fn:ident(self $(, $arg:ident : Self)*) => $fastest:path;)*) => {
+        paste! {
+            impl $fast_ty {
+                $(
+                    #[doc = concat!("[`", stringify!($fn), "`](Self::", stringify!($fn), ") ")]
+                    #[doc = "with the given [`Accuracy`]"]
+                    #[inline]
+                    pub fn [<$fn _with>](self $(, $arg: Self)*, accuracy: Accuracy) -> Self {
+                        match accuracy {
+                            Accuracy::Precise => <$fast_ty>::new(
+                                <$base_ty>::$fn(self.freeze_raw() $(, $arg.freeze_raw())*)
+                            ),
+                            Accuracy::Fast => self.$fn($($arg),*),
+                            Accuracy::Fastest => $fastest(self $(, $arg)*),
+                        }
+                    }
+                )*
+            }
+        }
+    };
+}
+
+macro_rules! impl_accuracy {
+    ($fast_ty:ident, $base_ty:ident) => {
+        paste! {
+            #[cfg(not(scalar_fallback))]
+            #[link(name = "poison_unsafe")]
+            extern "C" {
+                // the approximations which aren't poison safe are compiled alongside the libm
+                // wrappers, so their arguments must be frozen
+                fn [<cos_approx_ $base_ty>](a: $base_ty) -> $fast_ty;
+                fn [<exp2_approx_ $base_ty>](a: $base_ty) -> $fast_ty;
+                fn [<log2_approx_ $base_ty>](a: $base_ty) -> $fast_ty;
+                fn [<log10_approx_ $base_ty>](a: $base_ty) -> $fast_ty;
+                fn [<powf_approx_ $base_ty>](a: $base_ty, b: $base_ty) -> $fast_ty;
+                fn [<sin_approx_ $base_ty>](a: $base_ty) -> $fast_ty;
+                fn [<tan_approx_ $base_ty>](a: $base_ty) -> $fast_ty;
+            }
+
+            // with the strict feature, the approximations are replaced by the exact functions
+            #[cfg(scalar_fallback)]
+            mod [<strict_accuracy_ $base_ty>] {
+                use super::*;
+
+                #[inline(always)]
+                pub(super) unsafe fn [<cos_approx_ $base_ty>](a: $base_ty) -> $fast_ty {
+                    <$fast_ty>::new(a.cos())
+                }
+
+                #[inline(always)]
+                pub(super) unsafe fn [<exp2_approx_ $base_ty>](a: $base_ty) -> $fast_ty {
+                    <$fast_ty>::new(a.exp2())
+                }
+
+                #[inline(always)]
+                pub(super) unsafe fn [<log2_approx_ $base_ty>](a: $base_ty) -> $fast_ty {
+                    <$fast_ty>::new(a.log2())
+                }
+
+                #[inline(always)]
+                pub(super) unsafe fn [<log10_approx_ $base_ty>](a: $base_ty) -> $fast_ty {
+                    <$fast_ty>::new(a.log10())
+                }
+
+                #[inline(always)]
+                pub(super) unsafe fn [<powf_approx_ $base_ty>](a: $base_ty, b: $base_ty) -> $fast_ty {
+                    <$fast_ty>::new(a.powf(b))
+                }
+
+                #[inline(always)]
+                pub(super) unsafe fn [<sin_approx_ $base_ty>](a: $base_ty) -> $fast_ty {
+                    <$fast_ty>::new(a.sin())
+                }
+
+                #[inline(always)]
+                pub(super) unsafe fn [<tan_approx_ $base_ty>](a: $base_ty) -> $fast_ty {
+                    <$fast_ty>::new(a.tan())
+                }
+            }
+
+            #[cfg(scalar_fallback)]
+            use [<strict_accuracy_ $base_ty>]::*;
+
+            // safe wrappers which freeze the arguments, to be called like the methods
+            mod [<fastest_ $base_ty>] {
+                use super::*;
+
+                #[inline(always)]
+                pub(super) fn cos(a: $fast_ty) -> $fast_ty {
+                    unsafe { [<cos_approx_ $base_ty>](a.freeze_raw()) }
+                }
+
+                #[inline(always)]
+                pub(super) fn exp2(a: $fast_ty) -> $fast_ty {
+                    unsafe { [<exp2_approx_ $base_ty>](a.freeze_raw()) }
+                }
+
+                #[inline(always)]
+                pub(super) fn log2(a: $fast_ty) -> $fast_ty {
+                    unsafe { [<log2_approx_ $base_ty>](a.freeze_raw()) }
+                }
+
+                #[inline(always)]
+                pub(super) fn log10(a: $fast_ty) -> $fast_ty {
+                    unsafe { [<log10_approx_ $base_ty>](a.freeze_raw()) }
+                }
+
+                #[inline(always)]
+                pub(super) fn powf(a: $fast_ty, b: $fast_ty) -> $fast_ty {
+                    unsafe { [<powf_approx_ $base_ty>](a.freeze_raw(), b.freeze_raw()) }
+                }
+
+                #[inline(always)]
+                pub(super) fn sin(a: $fast_ty) -> $fast_ty {
+                    unsafe { [<sin_approx_ $base_ty>](a.freeze_raw()) }
+                }
+
+                #[inline(always)]
+                pub(super) fn tan(a: $fast_ty) -> $fast_ty {
+                    unsafe { [<tan_approx_ $base_ty>](a.freeze_raw()) }
+                }
+            }
+
+            accuracy_fns! {
+                $fast_ty, $base_ty:
+                fn acos(self) => Self::acos;
+                fn acosh(self) => Self::acosh;
+                fn asin(self) => Self::asin;
+                fn asinh(self) => Self::asinh;
+                fn atan(self) => Self::atan;
+                fn atan2(self, other: Self) => Self::atan2;
+                fn atanh(self) => Self::atanh;
+                fn cbrt(self) => Self::cbrt;
+                fn cos(self) => [<fastest_ $base_ty>]::cos;
+                fn cosh(self) => Self::cosh;
+                fn exp(self) => Self::exp_approx;
+                fn exp2(self) => [<fastest_ $base_ty>]::exp2;
+                fn exp_m1(self) => Self::exp_m1;
+                fn ln(self) => Self::ln_approx;
+                fn ln_1p(self) => Self::ln_1p;
+                fn log2(self) => [<fastest_ $base_ty>]::log2;
+                fn log10(self) => [<fastest_ $base_ty>]::log10;
+                fn powf(self, n: Self) => [<fastest_ $base_ty>]::powf;
+                fn sin(self) => [<fastest_ $base_ty>]::sin;
+                fn sinh(self) => Self::sinh;
+                fn tan(self) => [<fastest_ $base_ty>]::tan;
+                fn tanh(self) => Self::tanh_approx;
+            }
+        }
+    };
+}
+
+impl_accuracy! { FF32, f32 }
+impl_accuracy! { FF64, f64 }
@@ -0,0 +1,309 @@
+//! Activation functions for neural network inference, over scalars and whole slices.
+//!
+//! Each function is available as a method on [`FF32`] and [`FF64`], and as an in-place kernel
+//! over a slice here. [`relu`](FF32::relu) and [`leaky_relu`](FF32::leaky_relu) only need a max,
+//! so they accept poison values like the arithmetic operators. The others need `exp` or `tanh`,
+//! and like the other transcendental functions their inputs are frozen first; the slice kernels
+//! freeze a block of values at a time and then run the whole loop in C, where the math functions
+//! are compiled with the fast-math flags (and replaced by the inlinable approximations with the
+//! `approx-math` feature).
+//!
+//! ```
+//! use fast_fp::{activation, ff32};
+//!
+//! assert_eq!(ff32(-2.0).relu(), 0.0);
+//! assert_eq!(ff32(-2.0).leaky_relu(ff32(0.5)), -1.0);
+//! assert_eq!(ff32(0.0).sigmoid(), 0.5);
+//!
+//! let mut a = [-1.0, 0.0, 3.0].map(ff32);
+//! activation::relu_in_place(&mut a);
+//! assert_eq!(a, [0.0, 0.0, 3.0]);
+//!
+//! let mut b = [-200.0, 0.0, 200.0].map(ff32);
+//! activation::sigmoid_in_place(&mut b);
+//! assert_eq!(b, [0.0, 0.5, 1.0]);
+//! ```
+
+use crate::{FF32, FF64};
+use paste::paste;
+
+// the number of values frozen at a time before calling the poison unsafe slice kernels
+const ACTIVATION_BLOCK: usize = 256;
+
+/// The fast scalar types which have activation kernels, used to select the implementation of the
+/// functions in this module.
+pub trait ActivationKernels: Sized {
+    /// See [`relu_in_place`]
+    fn relu_in_place(a: &mut [Self]);
+
+    /// See [`leaky_relu_in_place`]
+    fn leaky_relu_in_place(a: &mut [Self], slope: Self);
+
+    /// See [`sigmoid_in_place`]
+    fn sigmoid_in_place(a: &mut [Self]);
+
+    /// See [`gelu_in_place`]
+    fn gelu_in_place(a: &mut [Self]);
+
+    /// See [`silu_in_place`]
+    fn silu_in_place(a: &mut [Self]);
+
+    /// See [`softplus_in_place`]
+    fn softplus_in_place(a: &mut [Self]);
+}
+
+/// Replace every value in a slice with its [`relu`](FF32::relu).
+#[inline]
+pub fn relu_in_place<T: ActivationKernels>(a: &mut [T]) {
+    T::relu_in_place(a)
+}
+
+/// Replace every value in a slice with its [`leaky_relu`](FF32::leaky_relu).
+///
+/// # Panics
+/// Panics if `slope` is not between zero and one
+#[inline]
+pub fn leaky_relu_in_place<T: ActivationKernels>(a: &mut [T], slope: T) {
+    T::leaky_relu_in_place(a, slope)
+}
+
+/// Replace every value in a slice with its [`sigmoid`](FF32::sigmoid).
+#[inline]
+pub fn sigmoid_in_place<T: ActivationKernels>(a: &mut [T]) {
+    T::sigmoid_in_place(a)
+}
+
+/// Replace every value in a slice with its [`gelu`](FF32::gelu).
+#[inline]
+pub fn gelu_in_place<T: ActivationKernels>(a: &mut [T]) {
+    T::gelu_in_place(a)
+}
+
+/// Replace every value in a slice with its [`silu`](FF32::silu).
+#[inline]
+pub fn silu_in_place<T: ActivationKernels>(a: &mut [T]) {
+    T::silu_in_place(a)
+}
+
+/// Replace every value in a slice with its [`softplus`](FF32::softplus).
+#[inline]
+pub fn softplus_in_place<T: ActivationKernels>(a: &mut [T]) {
+    T::softplus_in_place(a)
+}
+
+macro_rules! impl_activation {
+    ($fast_ty:ident, $base_ty:ident, $softplus_limit:literal) => {
+        paste! {
+            #[cfg(not(scalar_fallback))]
+            #[link(name = "poison_safe")]
+            extern "C" {
+                // like the scalar poison_safe functions, these can accept poison values
+                fn [<relu_slice_ $base_ty>](a: *mut $fast_ty, len: usize);
+                fn [<leaky_relu_slice_ $base_ty>](a: *mut $fast_ty, len: usize, slope: $fast_ty);
+            }
+
+            #[cfg(not(scalar_fallback))]
+            #[link(name = "poison_unsafe")]
+            extern "C" {
+                // functions in the poison_unsafe lib must have their arguments frozen, so the
+                // inputs are passed as the base type
+                fn [<sigmoid_ $base_ty>](x: $base_ty) -> $fast_ty;
+                fn [<gelu_ $base_ty>](x: $base_ty) -> $fast_ty;
+                fn [<silu_ $base_ty>](x: $base_ty) -> $fast_ty;
+                fn [<softplus_ $base_ty>](x: $base_ty) -> $fast_ty;
+
+                fn [<sigmoid_slice_ $base_ty>](x: *const $base_ty, out: *mut $fast_ty, len: usize);
+                fn [<gelu_slice_ $base_ty>](x: *const $base_ty, out: *mut $fast_ty, len: usize);
+                fn [<silu_slice_ $base_ty>](x: *const $base_ty, out: *mut $fast_ty, len: usize);
+                fn [<softplus_slice_ $base_ty>](x: *const $base_ty, out: *mut $fast_ty, len: usize);
+            }
+
+            // with the strict feature, the shims are replaced by the same formulas over the base
+            // type
+            #[cfg(scalar_fallback)]
+            mod [<strict_activation_ $base_ty>] {
+                use super::*;
+
+                // the constants of the tanh approximation of GELU, as in activation_unsafe.c
+                const GELU_SCALE: $base_ty =
+                    core::$base_ty::consts::FRAC_2_SQRT_PI * core::$base_ty::consts::FRAC_1_SQRT_2;
+                const GELU_CUBIC: $base_ty = 0.044715;
+
+                #[inline(always)]
+                unsafe fn map(x: *const $base_ty, out: *mut $fast_ty, len: usize, op: fn($base_ty) -> $fast_ty) {
+                    let x = core::slice::from_raw_parts(x, len);
+                    for (out, x) in core::slice::from_raw_parts_mut(out, len).iter_mut().zip(x) {
+                        *out = op(*x);
+                    }
+                }
+
+                #[inline(always)]
+                pub(super) unsafe fn [<relu_slice_ $base_ty>](a: *mut $fast_ty, len: usize) {
+                    for a in core::slice::from_raw_parts_mut(a, len) {
+                        *a = <$fast_ty>::new(a.freeze_raw().max(0.0));
+                    }
+                }
+
+                #[inline(always)]
+                pub(super) unsafe fn [<leaky_relu_slice_ $base_ty>](a: *mut $fast_ty, len: usize, slope: $fast_ty) {
+                    let slope = slope.freeze_raw();
+                    for a in core::slice::from_raw_parts_mut(a, len) {
+                        let a_raw = a.freeze_raw();
+                        *a = <$fast_ty>::new(a_raw.max(slope * a_raw));
+                    }
+                }
+
+                #[inline(always)]
+                pub(super) unsafe fn [<sigmoid_ $base_ty>](x: $base_ty) -> $fast_ty {
+                    <$fast_ty>::new(1.0 / (1.0 + (-x).exp()))
+                }
+
+                #[inline(always)]
+                pub(super) unsafe fn [<silu_ $base_ty>](x: $base_ty) -> $fast_ty {
+                    <$fast_ty>::new(x / (1.0 + (-x).exp()))
+                }
+
+                #[inline(always)]
+                pub(super) unsafe fn [<gelu_ $base_ty>](x: $base_ty) -> $fast_ty {
+                    let inner = GELU_SCALE * (x + GELU_CUBIC * x * x * x);
+                    <$fast_ty>::new(0.5 * x * (1.0 + inner.tanh()))
+                }
+
+                #[inline(always)]
+                pub(super) unsafe fn [<softplus_ $base_ty>](x: $base_ty) -> $fast_ty {
+                    <$fast_ty>::new(if x > $softplus_limit { x } else { x.exp().ln_1p() })
+                }
+
+                #[inline(always)]
+                pub(super) unsafe fn [<sigmoid_slice_ $base_ty>](x: *const $base_ty, out: *mut $fast_ty, len: usize) {
+                    map(x, out, len, |x| unsafe { [<sigmoid_ $base_ty>](x) })
+                }
+
+                #[inline(always)]
+                pub(super) unsafe fn [<silu_slice_ $base_ty>](x: *const $base_ty, out: *mut $fast_ty, len: usize) {
+                    map(x, out, len, |x| unsafe { [<silu_ $base_ty>](x) })
+                }
+
+                #[inline(always)]
+                pub(super) unsafe fn [<gelu_slice_ $base_ty>](x: *const $base_ty, out: *mut $fast_ty, len: usize) {
+                    map(x, out, len, |x| unsafe { [<gelu_ $base_ty>](x) })
+                }
+
+                #[inline(always)]
+                pub(super) unsafe fn [<softplus_slice_ $base_ty>](x: *const $base_ty, out: *mut $fast_ty, len: usize) {
+                    map(x, out, len, |x| unsafe { [<softplus_ $base_ty>](x) })
+                }
+            }
+
+            #[cfg(scalar_fallback)]
+            use [<strict_activation_ $base_ty>]::*;
+
+            // freeze a block of values at a time into a buffer, which the poison unsafe kernel
+            // reads while writing its results back over the original values
+            #[inline(always)]
+            fn [<map_frozen_ $base_ty>](a: &mut [$fast_ty], kernel: impl Fn(&[$base_ty], &mut [$fast_ty])) {
+                let mut frozen = [0.0; ACTIVATION_BLOCK];
+                for block in a.chunks_mut(ACTIVATION_BLOCK) {
+                    let frozen = &mut frozen[..block.len()];
+                    for (frozen, a) in frozen.iter_mut().zip(block.iter()) {
+                        *frozen = a.freeze_raw();
+                    }
+                    kernel(frozen, block);
+                }
+            }
+
+            impl $fast_ty {
+                /// The rectified linear unit, `max(self, 0)`
+                #[inline]
+                pub fn relu(self) -> Self {
+                    self.max(Self::ZERO)
+                }
+
+                /// The leaky rectified linear unit, which is `self` for positive values and
+                /// `slope * self` otherwise.
+                ///
+                /// # Panics
+                /// Panics if `slope` is not between zero and one
+                #[inline]
+                pub fn leaky_relu(self, slope: Self) -> Self {
+                    assert!(Self::ZERO <= slope && slope <= Self::ONE, "slope must be between zero and one");
+                    // with a slope between zero and one, the larger of the two is the right one
+                    self.max(self * slope)
+                }
+
+                /// The logistic sigmoid, `1 / (1 + exp(-self))`
+                #[inline]
+                pub fn sigmoid(self) -> Self {
+                    unsafe { [<sigmoid_ $base_ty>](self.freeze_raw()) }
+                }
+
+                /// The Gaussian error linear unit, using the common tanh approximation
+                /// `0.5 * self * (1 + tanh(sqrt(2 / pi) * (self + 0.044715 * self^3)))`
+                #[inline]
+                pub fn gelu(self) -> Self {
+                    unsafe { [<gelu_ $base_ty>](self.freeze_raw()) }
+                }
+
+                /// The sigmoid linear unit (also called swish), `self * sigmoid(self)`
+                #[inline]
+                pub fn silu(self) -> Self {
+                    unsafe { [<silu_ $base_ty>](self.freeze_raw()) }
+                }
+
+                /// The softplus function, `ln(1 + exp(self))`, a smooth approximation of
+                /// [`relu`](Self::relu).
+                ///
+                /// Large values are returned as-is, since the result rounds to the value itself
+                /// well before the exponential would overflow.
+                #[inline]
+                pub fn softplus(self) -> Self {
+                    unsafe { [<softplus_ $base_ty>](self.freeze_raw()) }
+                }
+            }
+
+            impl ActivationKernels for $fast_ty {
+                #[inline]
+                fn relu_in_place(a: &mut [Self]) {
+                    unsafe { [<relu_slice_ $base_ty>](a.as_mut_ptr(), a.len()) }
+                }
+
+                #[inline]
+                fn leaky_relu_in_place(a: &mut [Self], slope: Self) {
+                    assert!(Self::ZERO <= slope && slope <= Self::ONE, "slope must be between zero and one");
+                    unsafe { [<leaky_relu_slice_ $base_ty>](a.as_mut_ptr(), a.len(), slope) }
+                }
+
+                #[inline]
+                fn sigmoid_in_place(a: &mut [Self]) {
+                    [<map_frozen_ $base_ty>](a, |x, out| unsafe {
+                        [<sigmoid_slice_ $base_ty>](x.as_ptr(), out.as_mut_ptr(), out.len())
+                    })
+                }
+
+                #[inline]
+                fn gelu_in_place(a: &mut [Self]) {
+                    [<map_frozen_ $base_ty>](a, |x, out| unsafe {
+                        [<gelu_slice_ $base_ty>](x.as_ptr(), out.as_mut_ptr(), out.len())
+                    })
+                }
+
+                #[inline]
+                fn silu_in_place(a: &mut [Self]) {
+                    [<map_frozen_ $base_ty>](a, |x, out| unsafe {
+                        [<silu_slice_ $base_ty>](x.as_ptr(), out.as_mut_ptr(), out.len())
+                    })
+                }
+
+                #[inline]
+                fn softplus_in_place(a: &mut [Self]) {
+                    [<map_frozen_ $base_ty>](a, |x, out| unsafe {
+                        [<softplus_slice_ $base_ty>](x.as_ptr(), out.as_mut_ptr(), out.len())
+                    })
+                }
+            }
+        }
+    };
+}
+
+impl_activation! { FF32, f32, 20.0 }
+impl_activation! { FF64, f64, 40.0 }
@@ -0,0 +1,176 @@
+//! Conversions between [`FF32`] and [bfloat16] values.
+//!
+//! bfloat16 values are represented by their raw `u16` bit patterns, which is how they're
+//! typically stored in model weight files. A bfloat16 value is the upper half of an `f32`, so
+//! decoding is exact, while encoding rounds to the nearest representable value (ties to even).
+//!
+//! Encoding can also round stochastically, up or down with probabilities proportional to the
+//! distance from each neighbor, which keeps the rounding errors unbiased on average. Training at
+//! low precision relies on this, as small updates to a weight would otherwise always round away.
+//!
+//! ```
+//! use fast_fp::{bf16, ff32};
+//!
+//! // 1 + 2^-9 is a quarter of the way between two bfloat16 values
+//! let src = [ff32(1.0 + 1.0 / 512.0); 1000];
+//! let mut dst = [0; 1000];
+//! bf16::encode_stochastic(&src, &mut dst, 42);
+//!
+//! let mut decoded = [ff32(0.0); 1000];
+//! bf16::decode(&dst, &mut decoded);
+//! let mean = fast_fp::slice::sum(&decoded) / 1000.0;
+//! assert!((mean - src[0]).abs() < ff32(0.001));
+//! ```
+//!
+//! [`dot`] computes a dot product directly from bfloat16 inputs, widening each value as it's
+//! loaded and accumulating in `f32` precision.
+//!
+//! [bfloat16]: https://en.wikipedia.org/wiki/Bfloat16_floating-point_format
+
+use crate::FF32;
+
+impl FF32 {
+    /// Convert this value to the bit pattern of the nearest bfloat16 value, rounding ties to even.
+    ///
+    /// Values larger in magnitude than the largest finite bfloat16 round to infinity, and NaN
+    /// (with the `non-finite` feature) converts to a quiet NaN.
+    #[inline]
+    pub fn to_bf16(self) -> u16 {
+        // bit manipulation requires a fixed value, so this must freeze
+        let bits = self.freeze_raw().to_bits();
+        if let Some(nan) = bf16_nan(bits) {
+            return nan;
+        }
+        let round = 0x7fff + ((bits >> 16) & 1);
+        (bits.wrapping_add(round) >> 16) as u16
+    }
+
+    /// Convert this value to the bit pattern of a neighboring bfloat16 value, rounding down or up
+    /// stochastically.
+    ///
+    /// `random` must be uniformly distributed random bits, which decide the rounding: the value
+    /// is rounded up with a probability proportional to its distance from the value below. NaN
+    /// (with the `non-finite` feature) converts to a quiet NaN.
+    #[inline]
+    pub fn to_bf16_stochastic(self, random: u16) -> u16 {
+        let bits = self.freeze_raw().to_bits();
+        if let Some(nan) = bf16_nan(bits) {
+            return nan;
+        }
+        (bits.wrapping_add(u32::from(random)) >> 16) as u16
+    }
+
+    /// Create a new `FF32` instance from the bit pattern of a bfloat16 value.
+    ///
+    /// The conversion is exact. As with [`FF32::new`], the given value **MUST NOT** be infinite or
+    /// NaN.
+    #[inline]
+    pub fn from_bf16(bits: u16) -> Self {
+        FF32::new(f32::from_bits(u32::from(bits) << 16))
+    }
+}
+
+// The bfloat16 quiet NaN with the sign and upper payload of `bits`, if they're an f32 NaN.
+// Rounding would otherwise carry a payload held only in the lower half into the exponent, giving
+// infinity
+#[inline(always)]
+fn bf16_nan(bits: u32) -> Option<u16> {
+    if bits & 0x7fff_ffff > 0x7f80_0000 {
+        Some((bits >> 16) as u16 | 0x0040)
+    } else {
+        None
+    }
+}
+
+#[cfg(not(scalar_fallback))]
+#[link(name = "poison_safe")]
+extern "C" {
+    // the inputs are integer bit patterns, which are widened in C
+    fn dot_bf16(a: *const u16, b: *const u16, len: usize) -> FF32;
+}
+
+// with the strict feature, the shim is replaced by a loop over the decoded values
+#[cfg(scalar_fallback)]
+unsafe fn dot_bf16(a: *const u16, b: *const u16, len: usize) -> FF32 {
+    let a = core::slice::from_raw_parts(a, len);
+    let b = core::slice::from_raw_parts(b, len);
+    a.iter().zip(b).fold(FF32::ZERO, |acc, (a, b)| {
+        acc + FF32::from_bf16(*a) * FF32::from_bf16(*b)
+    })
+}
+
+/// Decode a slice of bfloat16 bit patterns into `dst`.
+///
+/// # Panics
+/// Panics if the two slices have different lengths
+pub fn decode(src: &[u16], dst: &mut [FF32]) {
+    assert_eq!(
+        src.len(),
+        dst.len(),
+        "source and destination lengths differ"
+    );
+    for (dst, src) in dst.iter_mut().zip(src) {
+        *dst = FF32::from_bf16(*src);
+    }
+}
+
+/// Encode a slice of values into bfloat16 bit patterns in `dst`, rounding ties to even.
+///
+/// # Panics
+/// Panics if the two slices have different lengths
+pub fn encode(src: &[FF32], dst: &mut [u16]) {
+    assert_eq!(
+        src.len(),
+        dst.len(),
+        "source and destination lengths differ"
+    );
+    for (dst, src) in dst.iter_mut().zip(src) {
+        *dst = src.to_bf16();
+    }
+}
+
+/// The dot product of two slices of bfloat16 bit patterns, accumulated as [`FF32`] values.
+///
+/// Each value is widened to `f32` as it's loaded, without decoding the slices into a buffer
+/// first. The products are summed in an unspecified order, like [`slice::dot`](crate::slice::dot).
+/// As with [`FF32::from_bf16`], the values **MUST NOT** be infinite or NaN.
+///
+/// # Panics
+/// Panics if the two slices have different lengths
+pub fn dot(a: &[u16], b: &[u16]) -> FF32 {
+    assert_eq!(a.len(), b.len(), "slice lengths differ");
+    unsafe { dot_bf16(a.as_ptr(), b.as_ptr(), a.len()) }
+}
+
+/// Encode a slice of values into bfloat16 bit patterns in `dst`, rounding stochastically as
+/// [`FF32::to_bf16_stochastic`].
+///
+/// The random bits for each value are derived from `seed` and the value's index, so the result
+/// is reproducible for a given seed.
+///
+/// # Panics
+/// Panics if the two slices have different lengths
+pub fn encode_stochastic(src: &[FF32], dst: &mut [u16], seed: u64) {
+    assert_eq!(
+        src.len(),
+        dst.len(),
+        "source and destination lengths differ"
+    );
+    for (i, (dst, src)) in dst.iter_mut().zip(src).enumerate() {
+        *dst = src.to_bf16_stochastic(random_bits(seed, i) as u16);
+    }
+}
+
+// 32 random bits for the value at `index`, from the splitmix64 generator. Hashing the index
+// rather than advancing a state keeps every value independent of the others
+#[inline(always)]
+pub(crate) fn random_bits(seed: u64, index: usize) -> u32 {
+    let mut z = seed.wrapping_add(
+        (index as u64)
+            .wrapping_add(1)
+            .wrapping_mul(0x9e37_79b9_7f4a_7c15),
+    );
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    ((z ^ (z >> 31)) >> 32) as u32
+}
@@ -0,0 +1,244 @@
+//! A subset of the BLAS level-1 routines for the fast types.
+//!
+//! These follow the semantics of the classic routines (`saxpy`/`daxpy`, `sscal`/`dscal`, and so
+//! on) over contiguous slices. They're compiled with fast-math flags, and reductions use several
+//! independent accumulators, so they're well suited to small vectors where calling out to a full
+//! BLAS isn't worth the overhead.
+//!
+//! The vector norms are provided under their conventional names as well, along with
+//! [`normalize_in_place`] to scale a vector to unit length.
+//!
+//! ```
+//! use fast_fp::{blas1, ff64};
+//!
+//! let x = [3.0, -4.0, 1.0].map(ff64);
+//! let mut y = [1.0, 1.0, 1.0].map(ff64);
+//!
+//! blas1::axpy(ff64(2.0), &x, &mut y);
+//! assert_eq!(y, [7.0, -7.0, 3.0]);
+//!
+//! assert_eq!(blas1::asum(&x), 8.0);
+//! assert_eq!(blas1::iamax(&y), Some(0));
+//!
+//! assert_eq!(blas1::norm_inf(&x), 4.0);
+//! let mut z = [0.0, -2.0, 0.0].map(ff64);
+//! assert_eq!(blas1::normalize_in_place(&mut z), 2.0);
+//! assert_eq!(z, [0.0, -1.0, 0.0]);
+//! ```
+
+use crate::{FF32, FF64};
+use paste::paste;
+
+/// The fast scalar types which have BLAS level-1 kernels, used to select the implementation of
+/// the functions in this module.
+pub trait Blas1Kernels: Sized {
+    /// See [`axpy`]
+    fn axpy(alpha: Self, x: &[Self], y: &mut [Self]);
+
+    /// See [`scal`]
+    fn scal(alpha: Self, x: &mut [Self]);
+
+    /// See [`nrm2`]
+    fn nrm2(x: &[Self]) -> Self;
+
+    /// See [`asum`]
+    fn asum(x: &[Self]) -> Self;
+
+    /// See [`iamax`]
+    fn iamax(x: &[Self]) -> Option<usize>;
+
+    /// See [`norm_inf`]
+    fn norm_inf(x: &[Self]) -> Self;
+
+    /// See [`normalize_in_place`]
+    fn normalize_in_place(x: &mut [Self]) -> Self;
+}
+
+/// Compute `y[i] += alpha * x[i]` for every index.
+///
+/// # Panics
+/// Panics if the two slices have different lengths
+#[inline]
+pub fn axpy<T: Blas1Kernels>(alpha: T, x: &[T], y: &mut [T]) {
+    T::axpy(alpha, x, y)
+}
+
+/// Compute `x[i] *= alpha` for every index.
+#[inline]
+pub fn scal<T: Blas1Kernels>(alpha: T, x: &mut [T]) {
+    T::scal(alpha, x)
+}
+
+/// Compute the euclidean norm of a vector.
+///
+/// Unlike reference BLAS implementations, this doesn't scale the values to guard against overflow
+/// in the intermediate squares, so the output is unspecified if they aren't finite.
+#[inline]
+pub fn nrm2<T: Blas1Kernels>(x: &[T]) -> T {
+    T::nrm2(x)
+}
+
+/// Sum the absolute values of a vector, added in an unspecified order.
+#[inline]
+pub fn asum<T: Blas1Kernels>(x: &[T]) -> T {
+    T::asum(x)
+}
+
+/// Find the index of the value with the largest absolute value, or `None` if the vector is empty.
+///
+/// If several values share the largest absolute value, the first index is returned. Finding an
+/// index requires comparing values, so like the fast types' comparisons, each value is frozen
+/// first.
+#[inline]
+pub fn iamax<T: Blas1Kernels>(x: &[T]) -> Option<usize> {
+    T::iamax(x)
+}
+
+/// Compute the L1 norm of a vector, the sum of its absolute values.
+///
+/// This is the same as [`asum`].
+#[inline]
+pub fn norm_l1<T: Blas1Kernels>(x: &[T]) -> T {
+    T::asum(x)
+}
+
+/// Compute the L2 (euclidean) norm of a vector.
+///
+/// This is the same as [`nrm2`], and likewise doesn't guard against overflow in the squares.
+#[inline]
+pub fn norm_l2<T: Blas1Kernels>(x: &[T]) -> T {
+    T::nrm2(x)
+}
+
+/// Compute the L-infinity norm of a vector, its largest absolute value.
+///
+/// The norm of an empty vector is zero.
+#[inline]
+pub fn norm_inf<T: Blas1Kernels>(x: &[T]) -> T {
+    T::norm_inf(x)
+}
+
+/// Scale a vector to unit L2 norm, in place, returning its norm before scaling.
+///
+/// The vector is multiplied by the reciprocal of its norm, so the results may differ in the last
+/// bit from dividing each value by the norm. A vector with a norm of zero is left unchanged.
+#[inline]
+pub fn normalize_in_place<T: Blas1Kernels>(x: &mut [T]) -> T {
+    T::normalize_in_place(x)
+}
+
+macro_rules! impl_blas1 {
+    ($fast_ty:ident, $base_ty:ident) => {
+        paste! {
+            #[cfg(not(scalar_fallback))]
+            #[link(name = "poison_safe")]
+            extern "C" {
+                // slices are passed as a pointer and a length. Like the scalar poison_safe
+                // functions, these can accept poison values
+                fn [<axpy_ $base_ty>](alpha: $fast_ty, x: *const $fast_ty, y: *mut $fast_ty, len: usize);
+                fn [<scal_ $base_ty>](alpha: $fast_ty, x: *mut $fast_ty, len: usize);
+                fn [<asum_ $base_ty>](x: *const $fast_ty, len: usize) -> $fast_ty;
+                fn [<sum_squares_ $base_ty>](x: *const $fast_ty, len: usize) -> $fast_ty;
+                fn [<max_abs_ $base_ty>](x: *const $fast_ty, len: usize) -> $fast_ty;
+            }
+
+            // with the strict feature, the shims are replaced by loops over the base type
+            #[cfg(scalar_fallback)]
+            mod [<strict_blas1_ $base_ty>] {
+                use super::*;
+
+                #[inline(always)]
+                unsafe fn sum(x: *const $fast_ty, len: usize, op: fn($base_ty) -> $base_ty) -> $fast_ty {
+                    let x = core::slice::from_raw_parts(x, len);
+                    <$fast_ty>::new(x.iter().fold(0.0, |acc, x| acc + op(x.freeze_raw())))
+                }
+
+                #[inline(always)]
+                pub(super) unsafe fn [<axpy_ $base_ty>](alpha: $fast_ty, x: *const $fast_ty, y: *mut $fast_ty, len: usize) {
+                    let alpha = alpha.freeze_raw();
+                    let x = core::slice::from_raw_parts(x, len);
+                    for (y, x) in core::slice::from_raw_parts_mut(y, len).iter_mut().zip(x) {
+                        *y = <$fast_ty>::new(y.freeze_raw() + alpha * x.freeze_raw());
+                    }
+                }
+
+                #[inline(always)]
+                pub(super) unsafe fn [<scal_ $base_ty>](alpha: $fast_ty, x: *mut $fast_ty, len: usize) {
+                    let alpha = alpha.freeze_raw();
+                    for x in core::slice::from_raw_parts_mut(x, len) {
+                        *x = <$fast_ty>::new(x.freeze_raw() * alpha);
+                    }
+                }
+
+                #[inline(always)]
+                pub(super) unsafe fn [<asum_ $base_ty>](x: *const $fast_ty, len: usize) -> $fast_ty {
+                    sum(x, len, <$base_ty>::abs)
+                }
+
+                #[inline(always)]
+                pub(super) unsafe fn [<sum_squares_ $base_ty>](x: *const $fast_ty, len: usize) -> $fast_ty {
+                    sum(x, len, |x| x * x)
+                }
+
+                #[inline(always)]
+                pub(super) unsafe fn [<max_abs_ $base_ty>](x: *const $fast_ty, len: usize) -> $fast_ty {
+                    let x = core::slice::from_raw_parts(x, len);
+                    <$fast_ty>::new(x.iter().fold(0.0, |acc, x| acc.max(x.freeze_raw().abs())))
+                }
+            }
+
+            #[cfg(scalar_fallback)]
+            use [<strict_blas1_ $base_ty>]::*;
+
+            impl Blas1Kernels for $fast_ty {
+                #[inline]
+                fn axpy(alpha: Self, x: &[Self], y: &mut [Self]) {
+                    assert_eq!(x.len(), y.len(), "slice lengths differ");
+                    unsafe { [<axpy_ $base_ty>](alpha, x.as_ptr(), y.as_mut_ptr(), x.len()) }
+                }
+
+                #[inline]
+                fn scal(alpha: Self, x: &mut [Self]) {
+                    unsafe { [<scal_ $base_ty>](alpha, x.as_mut_ptr(), x.len()) }
+                }
+
+                #[inline]
+                fn nrm2(x: &[Self]) -> Self {
+                    // sqrt is poison unsafe, so only the sum of squares is computed in the kernel
+                    unsafe { [<sum_squares_ $base_ty>](x.as_ptr(), x.len()) }.sqrt()
+                }
+
+                #[inline]
+                fn asum(x: &[Self]) -> Self {
+                    unsafe { [<asum_ $base_ty>](x.as_ptr(), x.len()) }
+                }
+
+                #[inline]
+                fn iamax(x: &[Self]) -> Option<usize> {
+                    let mut values = x.iter().map(|x| x.freeze_raw().abs()).enumerate();
+                    let first = values.next()?;
+                    let (index, _) = values.fold(first, |max, value| if value.1 > max.1 { value } else { max });
+                    Some(index)
+                }
+
+                #[inline]
+                fn norm_inf(x: &[Self]) -> Self {
+                    unsafe { [<max_abs_ $base_ty>](x.as_ptr(), x.len()) }
+                }
+
+                #[inline]
+                fn normalize_in_place(x: &mut [Self]) -> Self {
+                    let norm = Self::nrm2(x);
+                    // comparisons freeze their operands, so this is safe for poison values
+                    if norm > Self::ZERO {
+                        Self::scal(Self::ONE / norm, x);
+                    }
+                    norm
+                }
+            }
+        }
+    };
+}
+
+impl_blas1! { FF32, f32 }
+impl_blas1! { FF64, f64 }
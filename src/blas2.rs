@@ -0,0 +1,156 @@
+//! A subset of the BLAS level-2 routines for the fast types.
+//!
+//! Matrices are given as dense row-major slices, so element `(i, j)` of an `m x n` matrix is at
+//! index `i * n + j`.
+//!
+//! ```
+//! use fast_fp::{blas2, ff32};
+//!
+//! // a 2x3 matrix
+//! let a = [1.0, 2.0, 3.0, 4.0, 5.0, 6.0].map(ff32);
+//! let x = [1.0, 0.0, -1.0].map(ff32);
+//! let mut y = [1.0, 1.0].map(ff32);
+//!
+//! blas2::gemv(2, 3, ff32(2.0), &a, &x, ff32(1.0), &mut y);
+//! assert_eq!(y, [-3.0, -3.0]);
+//!
+//! let mut b = [ff32(0.0); 6];
+//! blas2::ger(ff32(1.0), &y, &x, &mut b);
+//! assert_eq!(b, [-3.0, 0.0, 3.0, -3.0, 0.0, 3.0]);
+//! ```
+
+use crate::{FF32, FF64};
+use paste::paste;
+
+/// The fast scalar types which have BLAS level-2 kernels, used to select the implementation of
+/// the functions in this module.
+pub trait Blas2Kernels: Sized {
+    /// See [`gemv`]
+    fn gemv(m: usize, n: usize, alpha: Self, a: &[Self], x: &[Self], beta: Self, y: &mut [Self]);
+
+    /// See [`ger`]
+    fn ger(alpha: Self, x: &[Self], y: &[Self], a: &mut [Self]);
+}
+
+/// Compute the matrix-vector product `y = alpha * A * x + beta * y`, where `A` is an `m x n`
+/// row-major matrix.
+///
+/// Each row's dot product is added in an unspecified order.
+///
+/// # Panics
+/// Panics if `a.len() != m * n`, `x.len() != n`, or `y.len() != m`
+#[inline]
+pub fn gemv<T: Blas2Kernels>(m: usize, n: usize, alpha: T, a: &[T], x: &[T], beta: T, y: &mut [T]) {
+    T::gemv(m, n, alpha, a, x, beta, y)
+}
+
+/// Compute the rank-1 update `A += alpha * x * yᵀ`, where `A` is an `x.len() x y.len()` row-major
+/// matrix.
+///
+/// # Panics
+/// Panics if `a.len() != x.len() * y.len()`
+#[inline]
+pub fn ger<T: Blas2Kernels>(alpha: T, x: &[T], y: &[T], a: &mut [T]) {
+    T::ger(alpha, x, y, a)
+}
+
+macro_rules! impl_blas2 {
+    ($fast_ty:ident, $base_ty:ident) => {
+        paste! {
+            #[cfg(not(scalar_fallback))]
+            #[link(name = "poison_safe")]
+            extern "C" {
+                // matrices and vectors are passed as pointers, with their dimensions. Like the
+                // scalar poison_safe functions, these can accept poison values
+                fn [<gemv_ $base_ty>](
+                    m: usize,
+                    n: usize,
+                    alpha: $fast_ty,
+                    a: *const $fast_ty,
+                    x: *const $fast_ty,
+                    beta: $fast_ty,
+                    y: *mut $fast_ty,
+                );
+                fn [<ger_ $base_ty>](
+                    m: usize,
+                    n: usize,
+                    alpha: $fast_ty,
+                    x: *const $fast_ty,
+                    y: *const $fast_ty,
+                    a: *mut $fast_ty,
+                );
+            }
+
+            // with the strict feature, the shims are replaced by loops over the base type
+            #[cfg(scalar_fallback)]
+            mod [<strict_blas2_ $base_ty>] {
+                use super::*;
+
+                #[inline(always)]
+                pub(super) unsafe fn [<gemv_ $base_ty>](
+                    m: usize,
+                    n: usize,
+                    alpha: $fast_ty,
+                    a: *const $fast_ty,
+                    x: *const $fast_ty,
+                    beta: $fast_ty,
+                    y: *mut $fast_ty,
+                ) {
+                    let (alpha, beta) = (alpha.freeze_raw(), beta.freeze_raw());
+                    let a = core::slice::from_raw_parts(a, m * n);
+                    let x = core::slice::from_raw_parts(x, n);
+                    let y = core::slice::from_raw_parts_mut(y, m);
+                    for (i, y) in y.iter_mut().enumerate() {
+                        let row = &a[i * n..(i + 1) * n];
+                        let dot = row.iter().zip(x).fold(0.0, |acc, (a, x)| acc + a.freeze_raw() * x.freeze_raw());
+                        *y = <$fast_ty>::new(alpha * dot + beta * y.freeze_raw());
+                    }
+                }
+
+                #[inline(always)]
+                pub(super) unsafe fn [<ger_ $base_ty>](
+                    m: usize,
+                    n: usize,
+                    alpha: $fast_ty,
+                    x: *const $fast_ty,
+                    y: *const $fast_ty,
+                    a: *mut $fast_ty,
+                ) {
+                    let alpha = alpha.freeze_raw();
+                    let x = core::slice::from_raw_parts(x, m);
+                    let y = core::slice::from_raw_parts(y, n);
+                    let a = core::slice::from_raw_parts_mut(a, m * n);
+                    for (i, x) in x.iter().enumerate() {
+                        let scale = alpha * x.freeze_raw();
+                        for (a, y) in a[i * n..(i + 1) * n].iter_mut().zip(y) {
+                            *a = <$fast_ty>::new(a.freeze_raw() + scale * y.freeze_raw());
+                        }
+                    }
+                }
+            }
+
+            #[cfg(scalar_fallback)]
+            use [<strict_blas2_ $base_ty>]::*;
+
+            impl Blas2Kernels for $fast_ty {
+                #[inline]
+                fn gemv(m: usize, n: usize, alpha: Self, a: &[Self], x: &[Self], beta: Self, y: &mut [Self]) {
+                    assert_eq!(a.len(), m * n, "matrix length doesn't match its dimensions");
+                    assert_eq!(x.len(), n, "x length doesn't match the matrix columns");
+                    assert_eq!(y.len(), m, "y length doesn't match the matrix rows");
+                    unsafe { [<gemv_ $base_ty>](m, n, alpha, a.as_ptr(), x.as_ptr(), beta, y.as_mut_ptr()) }
+                }
+
+                #[inline]
+                fn ger(alpha: Self, x: &[Self], y: &[Self], a: &mut [Self]) {
+                    let (m, n) = (x.len(), y.len());
+                    assert_eq!(a.len(), m * n, "matrix length doesn't match the vector lengths");
+                    unsafe { [<ger_ $base_ty>](m, n, alpha, x.as_ptr(), y.as_ptr(), a.as_mut_ptr()) }
+                }
+            }
+        }
+    };
+}
+
+impl_blas2! { FF32, f32 }
+impl_blas2! { FF64, f64 }
@@ -0,0 +1,200 @@
+//! Matrix-matrix multiplication for the fast types.
+//!
+//! The kernel is cache-blocked with a register-tiled inner loop, targeting the small to medium
+//! sizes where calling out to a full BLAS is overkill but naive loops are slow. Matrices are
+//! row-major; [`gemm_raw`] accepts an explicit row stride for each matrix (so sub-matrices can be
+//! multiplied in place), while [`gemm`] is a safe wrapper for dense slices.
+//!
+//! ```
+//! use fast_fp::{blas3, ff32};
+//!
+//! // (2x3) * (3x2)
+//! let a = [1.0, 2.0, 3.0, 4.0, 5.0, 6.0].map(ff32);
+//! let b = [1.0, 0.0, 0.0, 1.0, 1.0, 1.0].map(ff32);
+//! let mut c = [ff32(0.0); 4];
+//!
+//! blas3::gemm(2, 2, 3, ff32(1.0), &a, &b, ff32(0.0), &mut c);
+//! assert_eq!(c, [4.0, 5.0, 10.0, 11.0]);
+//! ```
+
+use crate::{FF32, FF64};
+use paste::paste;
+
+/// The fast scalar types which have BLAS level-3 kernels, used to select the implementation of
+/// the functions in this module.
+pub trait Blas3Kernels: Sized {
+    /// See [`gemm_raw`]
+    ///
+    /// # Safety
+    /// See [`gemm_raw`]
+    #[allow(clippy::too_many_arguments)]
+    unsafe fn gemm_raw(
+        m: usize,
+        n: usize,
+        k: usize,
+        alpha: Self,
+        a: *const Self,
+        lda: usize,
+        b: *const Self,
+        ldb: usize,
+        beta: Self,
+        c: *mut Self,
+        ldc: usize,
+    );
+}
+
+/// Compute the matrix product `C = alpha * A * B + beta * C`, where `A` is `m x k`, `B` is
+/// `k x n`, and `C` is `m x n`, all dense and row-major.
+///
+/// Each element's dot product is added in an unspecified order.
+///
+/// # Panics
+/// Panics if `a.len() != m * k`, `b.len() != k * n`, or `c.len() != m * n`
+#[allow(clippy::too_many_arguments)]
+#[inline]
+pub fn gemm<T: Blas3Kernels>(
+    m: usize,
+    n: usize,
+    k: usize,
+    alpha: T,
+    a: &[T],
+    b: &[T],
+    beta: T,
+    c: &mut [T],
+) {
+    assert_eq!(a.len(), m * k, "a length doesn't match its dimensions");
+    assert_eq!(b.len(), k * n, "b length doesn't match its dimensions");
+    assert_eq!(c.len(), m * n, "c length doesn't match its dimensions");
+    unsafe {
+        T::gemm_raw(
+            m,
+            n,
+            k,
+            alpha,
+            a.as_ptr(),
+            k,
+            b.as_ptr(),
+            n,
+            beta,
+            c.as_mut_ptr(),
+            n,
+        )
+    }
+}
+
+/// Compute the matrix product `C = alpha * A * B + beta * C`, where `A` is `m x k`, `B` is
+/// `k x n`, and `C` is `m x n`, all row-major with the given row strides.
+///
+/// Element `(i, j)` of `A` is read from `a.add(i * lda + j)`, and likewise for `B` and `C`.
+/// Elements between the end of one row and the start of the next are not accessed.
+///
+/// # Panics
+/// Panics if `lda < k`, `ldb < n`, or `ldc < n`
+///
+/// # Safety
+/// Every element of each matrix must be valid to access at the addresses described above (reads
+/// for `A` and `B`, reads and writes for `C`), and `C` must not overlap `A` or `B`.
+#[allow(clippy::too_many_arguments)]
+#[inline]
+pub unsafe fn gemm_raw<T: Blas3Kernels>(
+    m: usize,
+    n: usize,
+    k: usize,
+    alpha: T,
+    a: *const T,
+    lda: usize,
+    b: *const T,
+    ldb: usize,
+    beta: T,
+    c: *mut T,
+    ldc: usize,
+) {
+    assert!(lda >= k, "lda must be at least k");
+    assert!(ldb >= n, "ldb must be at least n");
+    assert!(ldc >= n, "ldc must be at least n");
+    T::gemm_raw(m, n, k, alpha, a, lda, b, ldb, beta, c, ldc)
+}
+
+macro_rules! impl_blas3 {
+    ($fast_ty:ident, $base_ty:ident) => {
+        paste! {
+            #[cfg(not(scalar_fallback))]
+            #[link(name = "poison_safe")]
+            extern "C" {
+                // matrices are passed as pointers, with their dimensions and row strides. Like the
+                // scalar poison_safe functions, these can accept poison values
+                fn [<gemm_ $base_ty>](
+                    m: usize,
+                    n: usize,
+                    k: usize,
+                    alpha: $fast_ty,
+                    a: *const $fast_ty,
+                    lda: usize,
+                    b: *const $fast_ty,
+                    ldb: usize,
+                    beta: $fast_ty,
+                    c: *mut $fast_ty,
+                    ldc: usize,
+                );
+            }
+
+            // with the strict feature, the shims are replaced by loops over the base type
+            #[cfg(scalar_fallback)]
+            mod [<strict_blas3_ $base_ty>] {
+                use super::*;
+
+                #[allow(clippy::too_many_arguments)]
+                #[inline(always)]
+                pub(super) unsafe fn [<gemm_ $base_ty>](
+                    m: usize,
+                    n: usize,
+                    k: usize,
+                    alpha: $fast_ty,
+                    a: *const $fast_ty,
+                    lda: usize,
+                    b: *const $fast_ty,
+                    ldb: usize,
+                    beta: $fast_ty,
+                    c: *mut $fast_ty,
+                    ldc: usize,
+                ) {
+                    let (alpha, beta) = (alpha.freeze_raw(), beta.freeze_raw());
+                    for i in 0..m {
+                        for j in 0..n {
+                            let dot = (0..k).fold(0.0, |acc, p| {
+                                acc + (*a.add(i * lda + p)).freeze_raw() * (*b.add(p * ldb + j)).freeze_raw()
+                            });
+                            let c = &mut *c.add(i * ldc + j);
+                            *c = <$fast_ty>::new(alpha * dot + beta * c.freeze_raw());
+                        }
+                    }
+                }
+            }
+
+            #[cfg(scalar_fallback)]
+            use [<strict_blas3_ $base_ty>]::*;
+
+            impl Blas3Kernels for $fast_ty {
+                #[inline]
+                unsafe fn gemm_raw(
+                    m: usize,
+                    n: usize,
+                    k: usize,
+                    alpha: Self,
+                    a: *const Self,
+                    lda: usize,
+                    b: *const Self,
+                    ldb: usize,
+                    beta: Self,
+                    c: *mut Self,
+                    ldc: usize,
+                ) {
+                    [<gemm_ $base_ty>](m, n, k, alpha, a, lda, b, ldb, beta, c, ldc)
+                }
+            }
+        }
+    };
+}
+
+impl_blas3! { FF32, f32 }
+impl_blas3! { FF64, f64 }
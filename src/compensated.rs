@@ -0,0 +1,135 @@
+use crate::{slice, FF32, FF64};
+use core::iter::FromIterator;
+
+/// A running sum which tracks the rounding error of its additions, using Neumaier's variant of
+/// Kahan summation.
+///
+/// A long accumulation with fast additions drifts: every addition rounds, and the errors grow with
+/// the number of values. This sum keeps a second compensation term holding the error of each
+/// addition, which is added back at the end. The additions to the running sum itself use the fast
+/// type, while the error terms are computed from frozen values with IEEE 754 semantics, since
+/// fast-math reassociation would otherwise cancel them out to zero.
+///
+/// [`add_slice`](CompensatedSum::add_slice) sums slices in blocks with the fast kernel from
+/// [`slice::sum`], and only compensates the addition of each block to the total. The error then
+/// depends on the block size rather than the number of values.
+///
+/// ```
+/// use fast_fp::{ff32, CompensatedSum, FF32};
+///
+/// let mut sum = CompensatedSum::<FF32>::new();
+/// sum.add(ff32(1.0));
+/// for _ in 0..1000 {
+///     // each of these is smaller than the rounding error of the running total
+///     sum.add(ff32(1e-8));
+/// }
+/// assert!((sum.sum() - 1.00001).abs() < ff32(1e-7));
+///
+/// let values = vec![ff32(0.1); 10_000];
+/// let mut sum = CompensatedSum::<FF32>::new();
+/// sum.add_slice(&values);
+/// assert!((sum.sum() - 1000.0).abs() < ff32(1e-3));
+/// ```
+#[derive(Clone, Copy, Debug)]
+pub struct CompensatedSum<T> {
+    sum: T,
+    compensation: T,
+}
+
+// the number of values summed by the fast kernel in `add_slice` before compensating
+const COMPENSATED_BLOCK: usize = 64;
+
+macro_rules! impl_compensated_sum {
+    ($fast_ty:ident) => {
+        impl CompensatedSum<$fast_ty> {
+            /// Create an empty sum
+            #[inline]
+            pub fn new() -> Self {
+                CompensatedSum {
+                    sum: <$fast_ty>::ZERO,
+                    compensation: <$fast_ty>::ZERO,
+                }
+            }
+
+            /// Add a value to the sum
+            #[inline]
+            pub fn add(&mut self, value: $fast_ty) {
+                let sum = self.sum + value;
+
+                // the error is computed against the frozen result of the fast addition, so
+                // it's correct however that addition was optimized
+                let (prev, value, next) =
+                    (self.sum.freeze_raw(), value.freeze_raw(), sum.freeze_raw());
+                let error = if prev.abs() >= value.abs() {
+                    (prev - next) + value
+                } else {
+                    (value - next) + prev
+                };
+
+                self.compensation = self.compensation.strict_add(<$fast_ty>::new(error));
+                self.sum = sum;
+            }
+
+            /// Add all values in a slice to the sum.
+            ///
+            /// The values are summed in blocks with [`slice::sum`], and each block's sum is
+            /// then added with compensation.
+            #[inline]
+            pub fn add_slice(&mut self, values: &[$fast_ty]) {
+                for block in values.chunks(COMPENSATED_BLOCK) {
+                    self.add(slice::sum(block));
+                }
+            }
+
+            /// The compensated total of the values added so far
+            #[inline]
+            pub fn sum(&self) -> $fast_ty {
+                self.sum.strict_add(self.compensation)
+            }
+        }
+
+        impl Default for CompensatedSum<$fast_ty> {
+            #[inline]
+            fn default() -> Self {
+                Self::new()
+            }
+        }
+
+        impl Extend<$fast_ty> for CompensatedSum<$fast_ty> {
+            #[inline]
+            fn extend<I: IntoIterator<Item = $fast_ty>>(&mut self, iter: I) {
+                for value in iter {
+                    self.add(value);
+                }
+            }
+        }
+
+        impl<'a> Extend<&'a $fast_ty> for CompensatedSum<$fast_ty> {
+            #[inline]
+            fn extend<I: IntoIterator<Item = &'a $fast_ty>>(&mut self, iter: I) {
+                self.extend(iter.into_iter().copied())
+            }
+        }
+
+        impl FromIterator<$fast_ty> for CompensatedSum<$fast_ty> {
+            #[inline]
+            fn from_iter<I: IntoIterator<Item = $fast_ty>>(iter: I) -> Self {
+                let mut sum = Self::new();
+                sum.extend(iter);
+                sum
+            }
+        }
+
+        impl<'a> FromIterator<&'a $fast_ty> for CompensatedSum<$fast_ty> {
+            #[inline]
+            fn from_iter<I: IntoIterator<Item = &'a $fast_ty>>(iter: I) -> Self {
+                let mut sum = Self::new();
+                sum.extend(iter);
+                sum
+            }
+        }
+    };
+}
+
+impl_compensated_sum! { FF32 }
+impl_compensated_sum! { FF64 }
@@ -0,0 +1,181 @@
+//! Kernels for complex values with fast real and imaginary parts.
+//!
+//! `Complex<FF32>` and `Complex<FF64>` from [num-complex] support the usual arithmetic through the
+//! fast types' `num-traits` implementations. However the fast types can't implement `Float`, which
+//! num-complex requires for methods like `norm`, so this module provides fast replacements along
+//! with dot product kernels over slices.
+//!
+//! ```
+//! use fast_fp::{complex, ff32};
+//! use num_complex_v04::Complex;
+//!
+//! let a = [Complex::new(ff32(1.0), ff32(2.0)), Complex::new(ff32(3.0), ff32(-1.0))];
+//! let b = [Complex::new(ff32(0.5), ff32(0.0)), Complex::new(ff32(0.0), ff32(1.0))];
+//!
+//! assert_eq!(complex::dot(&a, &b), Complex::new(ff32(1.5), ff32(4.0)));
+//! assert_eq!(complex::dotc(&a, &b), Complex::new(ff32(-0.5), ff32(2.0)));
+//! assert_eq!(complex::norm(Complex::new(ff32(3.0), ff32(4.0))), 5.0);
+//! ```
+//!
+//! [num-complex]: https://docs.rs/num-complex
+#![cfg_attr(docsrs, doc(cfg(feature = "num-complex-v04")))]
+
+use crate::{FF32, FF64};
+use num_complex_v04::Complex;
+use paste::paste;
+
+/// The fast scalar types which have complex kernels, used to select the implementation of the
+/// functions in this module.
+pub trait ComplexKernels: Sized {
+    /// See [`dot`]
+    fn dot(a: &[Complex<Self>], b: &[Complex<Self>]) -> Complex<Self>;
+
+    /// See [`dotc`]
+    fn dotc(a: &[Complex<Self>], b: &[Complex<Self>]) -> Complex<Self>;
+
+    /// See [`norm`]
+    fn norm(z: Complex<Self>) -> Self;
+}
+
+/// Compute the dot product of two complex slices, `sum(a[i] * b[i])`, added in an unspecified
+/// order.
+///
+/// # Panics
+/// Panics if the two slices have different lengths
+#[inline]
+pub fn dot<T: ComplexKernels>(a: &[Complex<T>], b: &[Complex<T>]) -> Complex<T> {
+    T::dot(a, b)
+}
+
+/// Compute the conjugated dot product of two complex slices, `sum(conj(a[i]) * b[i])`, added in an
+/// unspecified order.
+///
+/// # Panics
+/// Panics if the two slices have different lengths
+#[inline]
+pub fn dotc<T: ComplexKernels>(a: &[Complex<T>], b: &[Complex<T>]) -> Complex<T> {
+    T::dotc(a, b)
+}
+
+/// Compute the magnitude of a complex value.
+///
+/// Unlike num-complex's `norm`, this doesn't guard against overflow in the intermediate squares, so
+/// the output is unspecified if they aren't finite.
+#[inline]
+pub fn norm<T: ComplexKernels>(z: Complex<T>) -> T {
+    T::norm(z)
+}
+
+/// Compute the magnitude of each complex value in `src`, storing them in `dst`.
+///
+/// # Panics
+/// Panics if the two slices have different lengths
+#[inline]
+pub fn norms<T: ComplexKernels + Copy>(src: &[Complex<T>], dst: &mut [T]) {
+    assert_eq!(
+        src.len(),
+        dst.len(),
+        "source and destination lengths differ"
+    );
+    for (dst, src) in dst.iter_mut().zip(src) {
+        *dst = T::norm(*src);
+    }
+}
+
+macro_rules! impl_complex {
+    ($fast_ty:ident, $base_ty:ident) => {
+        paste! {
+            #[cfg(not(scalar_fallback))]
+            #[link(name = "poison_safe")]
+            extern "C" {
+                // slices are passed as a pointer and a count of complex values. Like the scalar
+                // poison_safe functions, these can accept poison values
+                fn [<dot_complex_ $base_ty>](
+                    out: *mut Complex<$fast_ty>,
+                    a: *const Complex<$fast_ty>,
+                    b: *const Complex<$fast_ty>,
+                    len: usize,
+                );
+                fn [<dotc_complex_ $base_ty>](
+                    out: *mut Complex<$fast_ty>,
+                    a: *const Complex<$fast_ty>,
+                    b: *const Complex<$fast_ty>,
+                    len: usize,
+                );
+            }
+
+            // with the strict feature, the shims are replaced by loops over the base type
+            #[cfg(scalar_fallback)]
+            mod [<strict_complex_ $base_ty>] {
+                use super::*;
+
+                #[inline(always)]
+                unsafe fn fold(
+                    out: *mut Complex<$fast_ty>,
+                    a: *const Complex<$fast_ty>,
+                    b: *const Complex<$fast_ty>,
+                    len: usize,
+                    op: fn(Complex<$base_ty>, Complex<$base_ty>) -> Complex<$base_ty>,
+                ) {
+                    let a = core::slice::from_raw_parts(a, len);
+                    let b = core::slice::from_raw_parts(b, len);
+                    let acc = a.iter().zip(b).fold(Complex::new(0.0, 0.0), |acc, (a, b)| {
+                        let a = Complex::new(a.re.freeze_raw(), a.im.freeze_raw());
+                        let b = Complex::new(b.re.freeze_raw(), b.im.freeze_raw());
+                        acc + op(a, b)
+                    });
+                    *out = Complex::new(<$fast_ty>::new(acc.re), <$fast_ty>::new(acc.im));
+                }
+
+                #[inline(always)]
+                pub(super) unsafe fn [<dot_complex_ $base_ty>](
+                    out: *mut Complex<$fast_ty>,
+                    a: *const Complex<$fast_ty>,
+                    b: *const Complex<$fast_ty>,
+                    len: usize,
+                ) {
+                    fold(out, a, b, len, |a, b| a * b)
+                }
+
+                #[inline(always)]
+                pub(super) unsafe fn [<dotc_complex_ $base_ty>](
+                    out: *mut Complex<$fast_ty>,
+                    a: *const Complex<$fast_ty>,
+                    b: *const Complex<$fast_ty>,
+                    len: usize,
+                ) {
+                    fold(out, a, b, len, |a, b| a.conj() * b)
+                }
+            }
+
+            #[cfg(scalar_fallback)]
+            use [<strict_complex_ $base_ty>]::*;
+
+            impl ComplexKernels for $fast_ty {
+                #[inline]
+                fn dot(a: &[Complex<Self>], b: &[Complex<Self>]) -> Complex<Self> {
+                    assert_eq!(a.len(), b.len(), "slice lengths differ");
+                    let mut out = Complex::new(<$fast_ty>::ZERO, <$fast_ty>::ZERO);
+                    unsafe { [<dot_complex_ $base_ty>](&mut out, a.as_ptr(), b.as_ptr(), a.len()) };
+                    out
+                }
+
+                #[inline]
+                fn dotc(a: &[Complex<Self>], b: &[Complex<Self>]) -> Complex<Self> {
+                    assert_eq!(a.len(), b.len(), "slice lengths differ");
+                    let mut out = Complex::new(<$fast_ty>::ZERO, <$fast_ty>::ZERO);
+                    unsafe { [<dotc_complex_ $base_ty>](&mut out, a.as_ptr(), b.as_ptr(), a.len()) };
+                    out
+                }
+
+                #[inline]
+                fn norm(z: Complex<Self>) -> Self {
+                    (z.re * z.re + z.im * z.im).sqrt()
+                }
+            }
+        }
+    };
+}
+
+impl_complex! { FF32, f32 }
+impl_complex! { FF64, f64 }
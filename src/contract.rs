@@ -0,0 +1,130 @@
+use core::{
+    fmt,
+    iter::{Product, Sum},
+    ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Rem, RemAssign, Sub, SubAssign},
+};
+
+// The contract-only types enable a single fast-math flag: `contract`, which allows a separate
+// multiplication and addition to be fused into a single FMA operation. Unlike the other fast-math
+// flags, contraction never produces poison values, so these types can hold their values directly
+// and skip all of the freezing precautions taken by the fast types. Aside from the extra precision
+// that fusing may provide, operations follow IEEE 754 semantics, including infinities and NaN.
+
+/// A wrapper over `f32` which enables fused multiply-add contraction, and no other fast-math
+/// optimizations.
+///
+/// Unlike [`FF32`](crate::FF32), this type may hold infinite or NaN values, and operations on it
+/// otherwise follow IEEE 754 semantics.
+#[derive(Clone, Copy, PartialEq, PartialOrd)]
+#[repr(transparent)]
+pub struct CF32(f32);
+
+/// Create a new `CF32` instance from the given float value.
+///
+/// This is syntax sugar for constructing the `CF32` type, and equivalent to `CF32::new(f)`
+#[inline(always)]
+pub fn cf32(f: f32) -> CF32 {
+    CF32::new(f)
+}
+
+/// A wrapper over `f64` which enables fused multiply-add contraction, and no other fast-math
+/// optimizations.
+///
+/// Unlike [`FF64`](crate::FF64), this type may hold infinite or NaN values, and operations on it
+/// otherwise follow IEEE 754 semantics.
+#[derive(Clone, Copy, PartialEq, PartialOrd)]
+#[repr(transparent)]
+pub struct CF64(f64);
+
+/// Create a new `CF64` instance from the given float value.
+///
+/// This is syntax sugar for constructing the `CF64` type, and equivalent to `CF64::new(f)`
+#[inline(always)]
+pub fn cf64(f: f64) -> CF64 {
+    CF64::new(f)
+}
+
+macro_rules! impls {
+    ($contract_ty:ident, $base_ty: ident) => {
+        impl $contract_ty {
+            const ONE: $contract_ty = <$contract_ty>::new(1.0);
+            const ZERO: $contract_ty = <$contract_ty>::new(0.0);
+
+            /// The smallest finite value
+            pub const MIN: $contract_ty = <$contract_ty>::new($base_ty::MIN);
+
+            /// The smallest positive value
+            pub const MIN_POSITIVE: $contract_ty = <$contract_ty>::new($base_ty::MIN_POSITIVE);
+
+            /// The largest finite value
+            pub const MAX: $contract_ty = <$contract_ty>::new($base_ty::MAX);
+
+            #[doc = "Create a new `"]
+            #[doc = stringify!($contract_ty)]
+            #[doc = "` instance from the given float value."]
+            #[inline(always)]
+            pub const fn new(f: $base_ty) -> Self {
+                $contract_ty(f)
+            }
+
+            /// Compute `(self * mul) + add`, which may be fused into a single operation.
+            ///
+            /// Unlike the base type's `mul_add`, fusing is permitted but not guaranteed.
+            #[inline]
+            pub fn mul_add(self, mul: Self, add: Self) -> Self {
+                self * mul + add
+            }
+        }
+
+        impl_fmt! {
+            $contract_ty, $base_ty,
+            fmt::Debug, fmt::Display, fmt::LowerExp, fmt::UpperExp,
+        }
+
+        impl_assign_ops! {
+            $contract_ty, $base_ty:
+            AddAssign, add_assign, add,
+            SubAssign, sub_assign, sub,
+            MulAssign, mul_assign, mul,
+            DivAssign, div_assign, div,
+            RemAssign, rem_assign, rem,
+        }
+
+        impl_reduce_ops! {
+            $contract_ty, $base_ty, crate::reduce_in_order:
+            Sum, sum, add, Self::ZERO,
+            Product, product, mul, Self::ONE,
+        }
+
+        impl PartialEq<$base_ty> for $contract_ty {
+            #[inline]
+            fn eq(&self, other: &$base_ty) -> bool {
+                self.0 == *other
+            }
+        }
+
+        impl PartialEq<$contract_ty> for $base_ty {
+            #[inline]
+            fn eq(&self, other: &$contract_ty) -> bool {
+                *self == other.0
+            }
+        }
+
+        impl From<$contract_ty> for $base_ty {
+            #[inline(always)]
+            fn from(from: $contract_ty) -> Self {
+                from.0
+            }
+        }
+
+        impl From<$base_ty> for $contract_ty {
+            #[inline(always)]
+            fn from(from: $base_ty) -> Self {
+                <$contract_ty>::new(from)
+            }
+        }
+    };
+}
+
+impls! { CF32, f32 }
+impls! { CF64, f64 }
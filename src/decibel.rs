@@ -0,0 +1,192 @@
+//! Conversions between linear amplitudes or powers and decibels, over scalars and whole slices.
+//!
+//! Each conversion is available as a method on [`FF32`] and [`FF64`], and as an in-place kernel
+//! over a slice here. They need `log10` or `exp`, and like the other transcendental functions
+//! their inputs are frozen first; the slice kernels freeze a block of values at a time and then
+//! run the whole loop in C, where the math functions are compiled with the fast-math flags (and
+//! replaced by the inlinable approximations with the `approx-math` feature).
+//!
+//! ```
+//! use fast_fp::{decibel, ff32};
+//!
+//! assert!((ff32(10.0).amp_to_db() - 20.0).abs() < ff32(1e-5));
+//! assert!((ff32(10.0).power_to_db() - 10.0).abs() < ff32(1e-5));
+//! assert!((ff32(-6.0).db_to_amp() - 0.501187).abs() < ff32(1e-5));
+//!
+//! let mut a = [1.0, 0.1, 0.001].map(ff32);
+//! decibel::amp_to_db_in_place(&mut a);
+//! assert!((a[2] + 60.0).abs() < ff32(1e-4));
+//! ```
+
+use crate::{FF32, FF64};
+use paste::paste;
+
+// the number of values frozen at a time before calling the poison unsafe slice kernels
+const DECIBEL_BLOCK: usize = 256;
+
+/// The fast scalar types which have decibel kernels, used to select the implementation of the
+/// functions in this module.
+pub trait DecibelKernels: Sized {
+    /// See [`amp_to_db_in_place`]
+    fn amp_to_db_in_place(a: &mut [Self]);
+
+    /// See [`db_to_amp_in_place`]
+    fn db_to_amp_in_place(a: &mut [Self]);
+
+    /// See [`power_to_db_in_place`]
+    fn power_to_db_in_place(a: &mut [Self]);
+}
+
+/// Replace every amplitude in a slice with its level in decibels, [`amp_to_db`](FF32::amp_to_db).
+#[inline]
+pub fn amp_to_db_in_place<T: DecibelKernels>(a: &mut [T]) {
+    T::amp_to_db_in_place(a)
+}
+
+/// Replace every level in decibels in a slice with its amplitude,
+/// [`db_to_amp`](FF32::db_to_amp).
+#[inline]
+pub fn db_to_amp_in_place<T: DecibelKernels>(a: &mut [T]) {
+    T::db_to_amp_in_place(a)
+}
+
+/// Replace every power in a slice with its level in decibels,
+/// [`power_to_db`](FF32::power_to_db).
+#[inline]
+pub fn power_to_db_in_place<T: DecibelKernels>(a: &mut [T]) {
+    T::power_to_db_in_place(a)
+}
+
+macro_rules! impl_decibel {
+    ($fast_ty:ident, $base_ty:ident) => {
+        paste! {
+            #[cfg(not(scalar_fallback))]
+            #[link(name = "poison_unsafe")]
+            extern "C" {
+                // functions in the poison_unsafe lib must have their arguments frozen, so the
+                // inputs are passed as the base type
+                fn [<amp_to_db_ $base_ty>](x: $base_ty) -> $fast_ty;
+                fn [<db_to_amp_ $base_ty>](x: $base_ty) -> $fast_ty;
+                fn [<power_to_db_ $base_ty>](x: $base_ty) -> $fast_ty;
+
+                fn [<amp_to_db_slice_ $base_ty>](x: *const $base_ty, out: *mut $fast_ty, len: usize);
+                fn [<db_to_amp_slice_ $base_ty>](x: *const $base_ty, out: *mut $fast_ty, len: usize);
+                fn [<power_to_db_slice_ $base_ty>](x: *const $base_ty, out: *mut $fast_ty, len: usize);
+            }
+
+            // with the strict feature, the shims are replaced by the same formulas over the base
+            // type
+            #[cfg(scalar_fallback)]
+            mod [<strict_decibel_ $base_ty>] {
+                use super::*;
+
+                #[inline(always)]
+                unsafe fn map(x: *const $base_ty, out: *mut $fast_ty, len: usize, op: fn($base_ty) -> $fast_ty) {
+                    let x = core::slice::from_raw_parts(x, len);
+                    for (out, x) in core::slice::from_raw_parts_mut(out, len).iter_mut().zip(x) {
+                        *out = op(*x);
+                    }
+                }
+
+                #[inline(always)]
+                pub(super) unsafe fn [<amp_to_db_ $base_ty>](x: $base_ty) -> $fast_ty {
+                    <$fast_ty>::new(20.0 * x.log10())
+                }
+
+                #[inline(always)]
+                pub(super) unsafe fn [<db_to_amp_ $base_ty>](x: $base_ty) -> $fast_ty {
+                    <$fast_ty>::new((x * (core::$base_ty::consts::LN_10 / 20.0)).exp())
+                }
+
+                #[inline(always)]
+                pub(super) unsafe fn [<power_to_db_ $base_ty>](x: $base_ty) -> $fast_ty {
+                    <$fast_ty>::new(10.0 * x.log10())
+                }
+
+                #[inline(always)]
+                pub(super) unsafe fn [<amp_to_db_slice_ $base_ty>](x: *const $base_ty, out: *mut $fast_ty, len: usize) {
+                    map(x, out, len, |x| unsafe { [<amp_to_db_ $base_ty>](x) })
+                }
+
+                #[inline(always)]
+                pub(super) unsafe fn [<db_to_amp_slice_ $base_ty>](x: *const $base_ty, out: *mut $fast_ty, len: usize) {
+                    map(x, out, len, |x| unsafe { [<db_to_amp_ $base_ty>](x) })
+                }
+
+                #[inline(always)]
+                pub(super) unsafe fn [<power_to_db_slice_ $base_ty>](x: *const $base_ty, out: *mut $fast_ty, len: usize) {
+                    map(x, out, len, |x| unsafe { [<power_to_db_ $base_ty>](x) })
+                }
+            }
+
+            #[cfg(scalar_fallback)]
+            use [<strict_decibel_ $base_ty>]::*;
+
+            // freeze a block of values at a time into a buffer, which the poison unsafe kernel
+            // reads while writing its results back over the original values
+            #[inline(always)]
+            fn [<map_frozen_ $base_ty>](a: &mut [$fast_ty], kernel: impl Fn(&[$base_ty], &mut [$fast_ty])) {
+                let mut frozen = [0.0; DECIBEL_BLOCK];
+                for block in a.chunks_mut(DECIBEL_BLOCK) {
+                    let frozen = &mut frozen[..block.len()];
+                    for (frozen, a) in frozen.iter_mut().zip(block.iter()) {
+                        *frozen = a.freeze_raw();
+                    }
+                    kernel(frozen, block);
+                }
+            }
+
+            impl $fast_ty {
+                /// The level of an amplitude in decibels, `20 * log10(self)`.
+                ///
+                /// The amplitude must be positive: zero would be negative infinity decibels, which
+                /// is not a valid fast value.
+                #[inline]
+                pub fn amp_to_db(self) -> Self {
+                    unsafe { [<amp_to_db_ $base_ty>](self.freeze_raw()) }
+                }
+
+                /// The amplitude of a level in decibels, `10^(self / 20)`
+                #[inline]
+                pub fn db_to_amp(self) -> Self {
+                    unsafe { [<db_to_amp_ $base_ty>](self.freeze_raw()) }
+                }
+
+                /// The level of a power in decibels, `10 * log10(self)`.
+                ///
+                /// The power must be positive: zero would be negative infinity decibels, which is
+                /// not a valid fast value.
+                #[inline]
+                pub fn power_to_db(self) -> Self {
+                    unsafe { [<power_to_db_ $base_ty>](self.freeze_raw()) }
+                }
+            }
+
+            impl DecibelKernels for $fast_ty {
+                #[inline]
+                fn amp_to_db_in_place(a: &mut [Self]) {
+                    [<map_frozen_ $base_ty>](a, |x, out| unsafe {
+                        [<amp_to_db_slice_ $base_ty>](x.as_ptr(), out.as_mut_ptr(), out.len())
+                    })
+                }
+
+                #[inline]
+                fn db_to_amp_in_place(a: &mut [Self]) {
+                    [<map_frozen_ $base_ty>](a, |x, out| unsafe {
+                        [<db_to_amp_slice_ $base_ty>](x.as_ptr(), out.as_mut_ptr(), out.len())
+                    })
+                }
+
+                #[inline]
+                fn power_to_db_in_place(a: &mut [Self]) {
+                    [<map_frozen_ $base_ty>](a, |x, out| unsafe {
+                        [<power_to_db_slice_ $base_ty>](x.as_ptr(), out.as_mut_ptr(), out.len())
+                    })
+                }
+            }
+        }
+    };
+}
+
+impl_decibel! { FF32, f32 }
+impl_decibel! { FF64, f64 }
@@ -0,0 +1,63 @@
+//! Numerical differentiation with central differences, for checking analytic derivatives.
+//!
+//! A central difference `(f(x + h) - f(x - h)) / 2h` has a truncation error which shrinks with
+//! `h^2`, but a rounding error which grows with `1 / h`, as the two evaluations of `f` cancel. The
+//! two balance at a step of about the cube root of the machine epsilon, relative to the size of
+//! `x`, which [`step`] computes and [`gradient`] uses for each coordinate. At that step the
+//! derivative of a well-scaled function is accurate to about two thirds of the digits of the type.
+//!
+//! ```
+//! use fast_fp::{differentiate, ff64};
+//!
+//! let x = ff64(1.0);
+//! let d = differentiate::derivative(|x| x.sin(), x, differentiate::step(x));
+//! assert!((d - x.cos()).abs() < ff64(1e-10));
+//!
+//! // the gradient of x^2 + 3xy is (2x + 3y, 3x)
+//! let g = differentiate::gradient(|p| p[0] * p[0] + 3.0 * p[0] * p[1], &[ff64(1.0), ff64(2.0)]);
+//! assert!((g[0] - 8.0).abs() < ff64(1e-8));
+//! assert!((g[1] - 3.0).abs() < ff64(1e-8));
+//! ```
+
+use crate::FastFloat;
+
+/// The step for a central difference at `x`, `cbrt(epsilon) * max(|x|, 1)`.
+///
+/// This balances the truncation and rounding errors for a function whose value and derivatives
+/// are about as large as `x` (or as 1, for small `x`). A function which varies over a much
+/// shorter or longer scale needs a step scaled to match.
+#[inline]
+pub fn step<T: FastFloat>(x: T) -> T {
+    x.abs().max(T::ONE) * T::EPSILON.cbrt()
+}
+
+/// The derivative of `f` at `x`, estimated by the central difference with step `h`.
+///
+/// [`step`] is a good default for `h`. Because of reassociation, the step isn't rounded to a value
+/// exactly representable around `x`, so the error is somewhat larger than under strict
+/// arithmetic.
+#[inline]
+pub fn derivative<T: FastFloat, F: FnMut(T) -> T>(mut f: F, x: T, h: T) -> T {
+    (f(x + h) - f(x - h)) / (T::from_f64(2.0) * h)
+}
+
+/// The gradient of `f` at the point `x`, with a central difference along each coordinate.
+///
+/// Each coordinate uses the [`step`] for its own value. `f` is evaluated twice for each
+/// coordinate, at a copy of `x` with only that coordinate moved.
+#[inline]
+pub fn gradient<T: FastFloat, F: FnMut(&[T]) -> T>(mut f: F, x: &[T]) -> Vec<T> {
+    let mut point = x.to_vec();
+    x.iter()
+        .enumerate()
+        .map(|(i, &xi)| {
+            let h = step(xi);
+            point[i] = xi + h;
+            let forward = f(&point);
+            point[i] = xi - h;
+            let backward = f(&point);
+            point[i] = xi;
+            (forward - backward) / (T::from_f64(2.0) * h)
+        })
+        .collect()
+}
@@ -0,0 +1,123 @@
+//! Runtime selection between the kernel variants compiled for each instruction set.
+//!
+//! With the `runtime-dispatch` feature, build.rs compiles the slice and matrix kernels once for
+//! the target's baseline and once more for each wider instruction set (AVX2 and AVX-512 on x86).
+//! Kernels declared with [`dispatch_kernels`] call the widest variant supported by the running
+//! CPU, so binaries built for a baseline target still get wide vectors on capable machines. The CPU features are only detected once, and cached for later calls.
+//!
+//! With the `reproducible` feature, the automatic selection always uses the baseline variant, so
+//! results don't depend on the running CPU.
+
+/// Declare poison_safe kernels from C.
+///
+/// Without the `runtime-dispatch` feature this is a plain extern block. With it, each kernel is
+/// instead a function of the same name which selects the variant to call using [`isa`], along
+/// with a `_on` suffixed function taking the variant explicitly (used by
+/// [`kernels`](crate::kernels)). Nothing is declared in strict builds, which replace the kernels
+/// with rust.
+macro_rules! dispatch_kernels {
+    ($(
+        fn $name:ident($($arg:ident: $arg_ty:ty),* $(,)?) $(-> $ret:ty)?;
+    )*) => {
+        #[cfg(all(not(scalar_fallback), not(feature = "runtime-dispatch")))]
+        #[link(name = "poison_safe")]
+        extern "C" {
+            $(fn $name($($arg: $arg_ty),*) $(-> $ret)?;)*
+        }
+
+        #[cfg(all(not(scalar_fallback), feature = "runtime-dispatch"))]
+        paste::paste! {
+            #[link(name = "poison_safe")]
+            extern "C" {
+                $(
+                    #[link_name = stringify!($name)]
+                    fn [<$name _baseline>]($($arg: $arg_ty),*) $(-> $ret)?;
+                )*
+            }
+
+            #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+            #[link(name = "poison_safe_avx2")]
+            extern "C" {
+                $(fn [<$name _avx2>]($($arg: $arg_ty),*) $(-> $ret)?;)*
+            }
+
+            #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+            #[link(name = "poison_safe_avx512")]
+            extern "C" {
+                $(fn [<$name _avx512>]($($arg: $arg_ty),*) $(-> $ret)?;)*
+            }
+
+            $(
+                #[inline(always)]
+                pub(crate) unsafe fn [<$name _on>](
+                    isa: crate::kernels::Isa,
+                    $($arg: $arg_ty),*
+                ) $(-> $ret)? {
+                    use crate::kernels::Isa;
+                    match isa {
+                        Isa::Baseline => [<$name _baseline>]($($arg),*),
+                        #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+                        Isa::Avx2 => [<$name _avx2>]($($arg),*),
+                        #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+                        Isa::Avx512 => [<$name _avx512>]($($arg),*),
+                    }
+                }
+
+                #[inline(always)]
+                unsafe fn $name($($arg: $arg_ty),*) $(-> $ret)? {
+                    [<$name _on>](crate::dispatch::isa(), $($arg),*)
+                }
+            )*
+        }
+    };
+}
+
+#[cfg(all(not(scalar_fallback), feature = "runtime-dispatch"))]
+pub(crate) use detect::*;
+
+#[cfg(all(not(scalar_fallback), feature = "runtime-dispatch"))]
+mod detect {
+    use crate::kernels::Isa;
+    use core::sync::atomic::{AtomicU8, Ordering};
+
+    const UNDETECTED: u8 = u8::MAX;
+
+    static DETECTED: AtomicU8 = AtomicU8::new(UNDETECTED);
+
+    /// The widest instruction set supported by the running CPU
+    #[inline(always)]
+    pub(crate) fn isa() -> Isa {
+        if cfg!(feature = "reproducible") {
+            return Isa::Baseline;
+        }
+
+        match DETECTED.load(Ordering::Relaxed) {
+            UNDETECTED => detect(),
+            #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+            isa if isa == Isa::Avx2 as u8 => Isa::Avx2,
+            #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+            isa if isa == Isa::Avx512 as u8 => Isa::Avx512,
+            _ => Isa::Baseline,
+        }
+    }
+
+    // racing threads detect the same value, so the cache needs no synchronization beyond the
+    // atomic itself
+    #[cold]
+    fn detect() -> Isa {
+        #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+        let isa = if !(is_x86_feature_detected!("avx2") && is_x86_feature_detected!("fma")) {
+            Isa::Baseline
+        } else if is_x86_feature_detected!("avx512f") {
+            Isa::Avx512
+        } else {
+            Isa::Avx2
+        };
+
+        #[cfg(not(any(target_arch = "x86", target_arch = "x86_64")))]
+        let isa = Isa::Baseline;
+
+        DETECTED.store(isa as u8, Ordering::Relaxed);
+        isa
+    }
+}
@@ -0,0 +1,208 @@
+//! Distance and similarity matrices between two sets of vectors.
+//!
+//! [`pairwise_distances`] compares every query vector against every corpus vector, as in the
+//! scoring step of a brute-force vector search. Both sets are dense row-major slices with `dim`
+//! values per vector. The kernel is blocked like [`blas3::gemm`](crate::blas3::gemm): a block of
+//! corpus vectors stays in cache while all queries are compared against it, and small tiles of
+//! the output are accumulated in registers, with each element's sum added in an unspecified order.
+//!
+//! ```
+//! use fast_fp::{distance::{self, Metric}, ff32};
+//!
+//! let queries = [1.0, 0.0, 0.0, 2.0].map(ff32);
+//! let corpus = [1.0, 0.0, 3.0, 4.0, 0.0, -1.0].map(ff32);
+//! let mut out = [ff32(0.0); 6];
+//!
+//! distance::pairwise_distances(&queries, &corpus, 2, Metric::SquaredEuclidean, &mut out);
+//! assert_eq!(out, [0.0, 20.0, 2.0, 5.0, 13.0, 9.0]);
+//!
+//! distance::pairwise_distances(&queries, &corpus, 2, Metric::Dot, &mut out);
+//! assert_eq!(out, [1.0, 3.0, 0.0, 0.0, 8.0, -2.0]);
+//! ```
+
+use crate::{blas1, FF32, FF64};
+use paste::paste;
+
+/// The measure computed between each pair of vectors by [`pairwise_distances`]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Metric {
+    /// The squared euclidean distance, `sum((q[i] - c[i])^2)`
+    SquaredEuclidean,
+    /// The euclidean distance, the square root of [`SquaredEuclidean`](Metric::SquaredEuclidean)
+    Euclidean,
+    /// The dot product, `sum(q[i] * c[i])`, which is larger for more similar vectors
+    Dot,
+    /// The cosine similarity, the dot product divided by both vectors' euclidean norms. The
+    /// similarity is zero if either vector is all zeros
+    Cosine,
+}
+
+/// The fast scalar types which have distance kernels, used to select the implementation of the
+/// functions in this module.
+pub trait DistanceKernels: Sized {
+    /// See [`pairwise_distances`]
+    fn pairwise_distances(
+        queries: &[Self],
+        corpus: &[Self],
+        dim: usize,
+        metric: Metric,
+        out: &mut [Self],
+    );
+}
+
+/// Compute `metric` between every query and every corpus vector, storing the results in `out`.
+///
+/// `queries` holds `m` vectors and `corpus` holds `n` vectors, each of `dim` values. `out` is an
+/// `m x n` row-major matrix, so `out[i * n + j]` compares query `i` with corpus vector `j`.
+///
+/// # Panics
+/// Panics if `dim` is zero, the length of `queries` or `corpus` isn't a multiple of `dim`, or
+/// `out.len() != m * n`
+#[inline]
+pub fn pairwise_distances<T: DistanceKernels>(
+    queries: &[T],
+    corpus: &[T],
+    dim: usize,
+    metric: Metric,
+    out: &mut [T],
+) {
+    T::pairwise_distances(queries, corpus, dim, metric, out)
+}
+
+macro_rules! impl_distance {
+    ($fast_ty:ident, $base_ty:ident) => {
+        paste! {
+            #[cfg(not(scalar_fallback))]
+            #[link(name = "poison_safe")]
+            extern "C" {
+                // the vector sets are passed as pointers with their number of vectors, and the
+                // output as a dense matrix. Like the scalar poison_safe functions, these can
+                // accept poison values
+                fn [<pairwise_dot_ $base_ty>](
+                    queries: *const $fast_ty,
+                    m: usize,
+                    corpus: *const $fast_ty,
+                    n: usize,
+                    dim: usize,
+                    out: *mut $fast_ty,
+                );
+                fn [<pairwise_squared_l2_ $base_ty>](
+                    queries: *const $fast_ty,
+                    m: usize,
+                    corpus: *const $fast_ty,
+                    n: usize,
+                    dim: usize,
+                    out: *mut $fast_ty,
+                );
+            }
+
+            // with the strict feature, the shims are replaced by loops over the base type
+            #[cfg(scalar_fallback)]
+            mod [<strict_distance_ $base_ty>] {
+                use super::*;
+
+                #[inline(always)]
+                unsafe fn pairwise(
+                    queries: *const $fast_ty,
+                    m: usize,
+                    corpus: *const $fast_ty,
+                    n: usize,
+                    dim: usize,
+                    out: *mut $fast_ty,
+                    term: fn($base_ty, $base_ty) -> $base_ty,
+                ) {
+                    let queries = core::slice::from_raw_parts(queries, m * dim);
+                    let corpus = core::slice::from_raw_parts(corpus, n * dim);
+                    let out = core::slice::from_raw_parts_mut(out, m * n);
+                    for (q, out) in queries.chunks_exact(dim).zip(out.chunks_exact_mut(n)) {
+                        for (c, out) in corpus.chunks_exact(dim).zip(out) {
+                            let sum = q.iter().zip(c).fold(0.0, |acc, (q, c)| acc + term(q.freeze_raw(), c.freeze_raw()));
+                            *out = <$fast_ty>::new(sum);
+                        }
+                    }
+                }
+
+                #[inline(always)]
+                pub(super) unsafe fn [<pairwise_dot_ $base_ty>](
+                    queries: *const $fast_ty,
+                    m: usize,
+                    corpus: *const $fast_ty,
+                    n: usize,
+                    dim: usize,
+                    out: *mut $fast_ty,
+                ) {
+                    pairwise(queries, m, corpus, n, dim, out, |q, c| q * c)
+                }
+
+                #[inline(always)]
+                pub(super) unsafe fn [<pairwise_squared_l2_ $base_ty>](
+                    queries: *const $fast_ty,
+                    m: usize,
+                    corpus: *const $fast_ty,
+                    n: usize,
+                    dim: usize,
+                    out: *mut $fast_ty,
+                ) {
+                    pairwise(queries, m, corpus, n, dim, out, |q, c| (q - c) * (q - c))
+                }
+            }
+
+            #[cfg(scalar_fallback)]
+            use [<strict_distance_ $base_ty>]::*;
+
+            impl DistanceKernels for $fast_ty {
+                #[inline]
+                fn pairwise_distances(
+                    queries: &[Self],
+                    corpus: &[Self],
+                    dim: usize,
+                    metric: Metric,
+                    out: &mut [Self],
+                ) {
+                    assert!(dim > 0, "dim must be non-zero");
+                    assert_eq!(queries.len() % dim, 0, "queries length isn't a multiple of dim");
+                    assert_eq!(corpus.len() % dim, 0, "corpus length isn't a multiple of dim");
+                    let (m, n) = (queries.len() / dim, corpus.len() / dim);
+                    assert_eq!(out.len(), m * n, "out length doesn't match the number of pairs");
+                    if out.is_empty() {
+                        return;
+                    }
+
+                    let kernel = match metric {
+                        Metric::SquaredEuclidean | Metric::Euclidean => [<pairwise_squared_l2_ $base_ty>],
+                        Metric::Dot | Metric::Cosine => [<pairwise_dot_ $base_ty>],
+                    };
+                    unsafe { kernel(queries.as_ptr(), m, corpus.as_ptr(), n, dim, out.as_mut_ptr()) };
+
+                    match metric {
+                        Metric::SquaredEuclidean | Metric::Dot => {}
+                        // sqrt is poison unsafe, so it's applied to the kernel's output in rust
+                        Metric::Euclidean => out.iter_mut().for_each(|out| *out = out.sqrt()),
+                        Metric::Cosine => {
+                            // the reciprocal norms are computed once per vector, and zero vectors
+                            // are given a reciprocal of zero so their similarities are zero
+                            let reciprocal = |v: &[Self]| {
+                                let norm = blas1::nrm2(v);
+                                if norm > Self::ZERO {
+                                    Self::ONE / norm
+                                } else {
+                                    Self::ZERO
+                                }
+                            };
+                            let corpus: Vec<_> = corpus.chunks_exact(dim).map(reciprocal).collect();
+                            for (q, out) in queries.chunks_exact(dim).zip(out.chunks_exact_mut(n)) {
+                                let q = reciprocal(q);
+                                for (out, c) in out.iter_mut().zip(&corpus) {
+                                    *out *= q * *c;
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    };
+}
+
+impl_distance! { FF32, f32 }
+impl_distance! { FF64, f64 }
@@ -0,0 +1,714 @@
+//! Signal processing kernels: convolution, and finite and infinite impulse response filters.
+//!
+//! [`convolve`] computes the full convolution of a signal with a kernel, and [`FirFilter`]
+//! applies a filter to a stream which arrives one block at a time, carrying the end of each block
+//! over to the next. [`convolve2d_separable`] filters an image with a separable kernel, as a pass
+//! over the rows and a pass over the columns. These filters run the same loop in C, which
+//! computes several outputs together so each tap is loaded once for all of them, and adds the
+//! products of each output in an unspecified order.
+//!
+//! [`Biquad`] is a recursive second-order filter, with constructors for the common audio
+//! equalizer shapes, and [`Goertzel`] measures the power of a single frequency in a stream, for
+//! detecting tones. [`Resampler`] converts a stream between sample rates.
+//!
+//! ```
+//! use fast_fp::{dsp::{self, FirFilter}, ff32, FF32};
+//!
+//! let signal = [1.0, 2.0, 3.0].map(ff32);
+//! let kernel = [1.0, 1.0].map(ff32);
+//! assert_eq!(dsp::convolve(&signal, &kernel), [1.0, 3.0, 5.0, 3.0]);
+//!
+//! // a moving sum of two values, over a stream split into blocks
+//! let mut filter = FirFilter::<FF32>::new(kernel.to_vec());
+//! let mut out = [ff32(0.0); 2];
+//! filter.process_block(&[1.0, 2.0].map(ff32), &mut out);
+//! assert_eq!(out, [1.0, 3.0]);
+//! filter.process_block(&[3.0, 4.0].map(ff32), &mut out);
+//! assert_eq!(out, [5.0, 7.0]);
+//! ```
+
+use crate::{FF32, FF64};
+use paste::paste;
+
+/// The fast scalar types which have signal processing kernels, used to select the implementation
+/// of the functions in this module.
+pub trait DspKernels: Sized {
+    /// See [`convolve`]
+    fn convolve(signal: &[Self], kernel: &[Self]) -> Vec<Self>;
+
+    /// See [`convolve2d_separable`]
+    fn convolve2d_separable(rows: usize, cols: usize, data: &mut [Self], kx: &[Self], ky: &[Self]);
+}
+
+/// The full convolution of `signal` with `kernel`, `out[i] = sum(signal[j] * kernel[i - j])`.
+///
+/// The result has `signal.len() + kernel.len() - 1` values, covering every overlap of the two. It
+/// is empty if either slice is empty.
+#[inline]
+pub fn convolve<T: DspKernels>(signal: &[T], kernel: &[T]) -> Vec<T> {
+    T::convolve(signal, kernel)
+}
+
+/// Convolve a `rows x cols` row-major image in place with the separable kernel `ky * kx`, where
+/// `kx` filters along each row and `ky` along each column.
+///
+/// The output has the same size as the image, with each kernel centered on the value at index
+/// `(len - 1) / 2`. Values beyond the edges of the image are treated as zeros. The rows are
+/// filtered into a temporary buffer one at a time, and the columns are then filtered together,
+/// in strips of columns which stay in cache for the whole height of the kernel.
+///
+/// ```
+/// use fast_fp::{dsp, ff32};
+///
+/// let mut image = [0.0, 0.0, 0.0, 0.0, 4.0, 0.0, 0.0, 0.0, 0.0].map(ff32);
+/// let blur = [0.25, 0.5, 0.25].map(ff32);
+/// dsp::convolve2d_separable(3, 3, &mut image, &blur, &blur);
+/// assert_eq!(image, [0.25, 0.5, 0.25, 0.5, 1.0, 0.5, 0.25, 0.5, 0.25]);
+/// ```
+///
+/// # Panics
+/// Panics if `data.len() != rows * cols`, or if either kernel is empty
+#[inline]
+pub fn convolve2d_separable<T: DspKernels>(
+    rows: usize,
+    cols: usize,
+    data: &mut [T],
+    kx: &[T],
+    ky: &[T],
+) {
+    T::convolve2d_separable(rows, cols, data, kx, ky)
+}
+
+/// A finite impulse response filter, which keeps the end of the input between blocks so that a
+/// stream can be filtered one block at a time.
+///
+/// Each output is the sum of the taps multiplied by the most recent inputs,
+/// `out[i] = sum(taps[j] * x[i - j])`. Before the first input, the filter's history is zero.
+#[derive(Clone, Debug)]
+pub struct FirFilter<T> {
+    // the taps in reverse order, so the kernel reads both them and the inputs forwards
+    reversed: Vec<T>,
+    // the previous `taps - 1` inputs, followed by the current block while it's processed
+    history: Vec<T>,
+}
+
+/// A second-order recursive (IIR) filter, the building block of audio equalizers.
+///
+/// The filter is computed in transposed direct form II, keeping two values of state between
+/// samples, so a stream can be filtered one block at a time. The constructors for the standard
+/// shapes follow the formulas of Robert Bristow-Johnson's Audio EQ Cookbook.
+///
+/// ```
+/// use fast_fp::{dsp::Biquad, ff32, FF32};
+///
+/// // a low-pass filter passes a constant signal through unchanged, once it settles
+/// let mut filter = Biquad::<FF32>::lowpass(ff32(48000.0), ff32(1000.0), ff32(0.707));
+/// let mut block = [ff32(1.0); 2000];
+/// filter.process_block(&mut block);
+/// assert!((block[1999] - 1.0).abs() < ff32(1e-4));
+///
+/// // and a high-pass filter removes it
+/// let mut filter = Biquad::<FF32>::highpass(ff32(48000.0), ff32(1000.0), ff32(0.707));
+/// let mut block = [ff32(1.0); 2000];
+/// filter.process_block(&mut block);
+/// assert!(block[1999].abs() < ff32(1e-4));
+/// ```
+#[derive(Clone, Debug)]
+pub struct Biquad<T> {
+    // b0, b1, b2, a1, a2, normalized so that a0 is one
+    coeffs: [T; 5],
+    state: [T; 2],
+}
+
+/// A detector for the power of a single frequency in a stream, with the Goertzel algorithm.
+///
+/// Each sample updates a second-order recurrence in a single multiply and two additions, which is
+/// much cheaper than a whole FFT when only a few frequencies are of interest (as in DTMF tone
+/// detection). After `n` samples, [`power`](Self::power) is the squared magnitude of the DFT of
+/// those samples at the detector's frequency. The samples can arrive over any number of blocks;
+/// [`reset`](Self::reset) starts the next measurement.
+///
+/// ```
+/// use fast_fp::{dsp::Goertzel, ff32, FF32};
+///
+/// // 10 cycles of a 1 kHz tone, sampled at 8 kHz
+/// let step = std::f32::consts::PI / 4.0;
+/// let tone: Vec<_> = (0..80).map(|i| ff32((i as f32 * step).sin())).collect();
+///
+/// let mut present = Goertzel::<FF32>::new(ff32(8000.0), ff32(1000.0));
+/// let mut absent = Goertzel::<FF32>::new(ff32(8000.0), ff32(2000.0));
+/// for block in tone.chunks(16) {
+///     present.process_block(block);
+///     absent.process_block(block);
+/// }
+/// // a sine of amplitude one over n samples has a DFT magnitude of n / 2
+/// assert!((present.power() - 1600.0).abs() < ff32(0.1));
+/// assert!(absent.power() < ff32(0.1));
+/// ```
+#[derive(Clone, Debug)]
+pub struct Goertzel<T> {
+    // 2 cos(w), for the normalized angular frequency w
+    coeff: T,
+    // the last two values of the recurrence
+    state: [T; 2],
+}
+
+/// A resampler, which converts a stream from one sample rate to another by linear interpolation
+/// or with a windowed-sinc polyphase filter.
+///
+/// The ratio of the rates is reduced to a fraction `up / down`, and each output is computed from
+/// a window of inputs, weighted by one of `up` precomputed phases of the filter. Both kinds of
+/// resampling share this loop, with linear interpolation as a filter of two taps. The filter
+/// bank has `up * taps` coefficients, which is small for common pairs of rates (44.1 kHz to
+/// 48 kHz has 160 phases), but grows with rates that have no large common factor.
+///
+/// The stream can be processed in blocks of any size. The outputs are aligned with the inputs,
+/// the first output being at the time of the first input, so the outputs whose windows extend
+/// past the inputs so far are produced by later blocks.
+///
+/// ```
+/// use fast_fp::{dsp::Resampler, ff32, FF32};
+///
+/// let mut resampler = Resampler::<FF32>::linear(1000, 2000);
+/// let mut out = Vec::new();
+/// resampler.process(&[0.0, 2.0, 4.0].map(ff32), &mut out);
+/// assert_eq!(out, [0.0, 1.0, 2.0, 3.0]);
+/// resampler.process(&[6.0].map(ff32), &mut out);
+/// assert_eq!(out, [0.0, 1.0, 2.0, 3.0, 4.0, 5.0]);
+/// ```
+#[derive(Clone, Debug)]
+pub struct Resampler<T> {
+    // the phases of the filter, each holding `taps` coefficients to apply to consecutive inputs
+    bank: Vec<T>,
+    taps: usize,
+    // the ratio of the output rate to the input rate, as a reduced fraction
+    up: usize,
+    down: usize,
+    // the inputs which later outputs still need, primed with zeros before the first input
+    history: Vec<T>,
+    // the position of the next output in the history, in units of `1 / up` inputs
+    pos: usize,
+}
+
+// the coefficients of a polyphase filter bank with `up` phases of `taps` taps, sampling a kernel
+// at each tap's distance (in inputs) from the output. Each phase is normalized to a gain of one
+fn filter_bank(up: usize, taps: usize, kernel: impl Fn(f64) -> f64) -> Vec<f64> {
+    // the output falls between the taps either side of the middle
+    let center = (taps / 2 - 1) as f64;
+    let mut bank = Vec::with_capacity(up * taps);
+    for p in 0..up {
+        let start = bank.len();
+        bank.extend((0..taps).map(|j| kernel(j as f64 - center - p as f64 / up as f64)));
+        let gain: f64 = bank[start..].iter().sum();
+        bank[start..].iter_mut().for_each(|c| *c /= gain);
+    }
+    bank
+}
+
+fn gcd(mut a: u32, mut b: u32) -> u32 {
+    while b != 0 {
+        let r = a % b;
+        a = b;
+        b = r;
+    }
+    a
+}
+
+macro_rules! impl_dsp {
+    ($fast_ty:ident, $base_ty:ident) => {
+        paste! {
+            #[cfg(not(scalar_fallback))]
+            #[link(name = "poison_safe")]
+            extern "C" {
+                // slices are passed as a pointer and a length. `x` holds `len + taps_len - 1`
+                // values. Like the scalar poison_safe functions, this can accept poison values
+                fn [<fir_ $base_ty>](
+                    x: *const $fast_ty,
+                    len: usize,
+                    reversed: *const $fast_ty,
+                    taps_len: usize,
+                    out: *mut $fast_ty,
+                );
+                fn [<fir_columns_ $base_ty>](
+                    x: *const $fast_ty,
+                    rows: usize,
+                    cols: usize,
+                    reversed: *const $fast_ty,
+                    taps_len: usize,
+                    out: *mut $fast_ty,
+                );
+                fn [<biquad_ $base_ty>](x: *mut $fast_ty, len: usize, coeffs: *const $fast_ty, state: *mut $fast_ty);
+                fn [<goertzel_ $base_ty>](x: *const $fast_ty, len: usize, coeff: $fast_ty, state: *mut $fast_ty);
+                fn [<resample_ $base_ty>](
+                    x: *const $fast_ty,
+                    bank: *const $fast_ty,
+                    taps: usize,
+                    up: usize,
+                    down: usize,
+                    pos: usize,
+                    out: *mut $fast_ty,
+                    len: usize,
+                );
+            }
+
+            // with the strict feature, the shim is replaced by a loop over the base type
+            #[cfg(scalar_fallback)]
+            mod [<strict_dsp_ $base_ty>] {
+                use super::*;
+
+                #[inline(always)]
+                pub(super) unsafe fn [<fir_ $base_ty>](
+                    x: *const $fast_ty,
+                    len: usize,
+                    reversed: *const $fast_ty,
+                    taps_len: usize,
+                    out: *mut $fast_ty,
+                ) {
+                    let x = core::slice::from_raw_parts(x, len + taps_len - 1);
+                    let reversed = core::slice::from_raw_parts(reversed, taps_len);
+                    let out = core::slice::from_raw_parts_mut(out, len);
+                    for (out, window) in out.iter_mut().zip(x.windows(taps_len)) {
+                        let sum = reversed
+                            .iter()
+                            .zip(window)
+                            .fold(0.0, |acc, (tap, x)| acc + tap.freeze_raw() * x.freeze_raw());
+                        *out = <$fast_ty>::new(sum);
+                    }
+                }
+
+                #[inline(always)]
+                pub(super) unsafe fn [<fir_columns_ $base_ty>](
+                    x: *const $fast_ty,
+                    rows: usize,
+                    cols: usize,
+                    reversed: *const $fast_ty,
+                    taps_len: usize,
+                    out: *mut $fast_ty,
+                ) {
+                    let x = core::slice::from_raw_parts(x, (rows + taps_len - 1) * cols);
+                    let reversed = core::slice::from_raw_parts(reversed, taps_len);
+                    let out = core::slice::from_raw_parts_mut(out, rows * cols);
+                    for (r, out) in out.chunks_exact_mut(cols).enumerate() {
+                        for (c, out) in out.iter_mut().enumerate() {
+                            let sum = reversed.iter().enumerate().fold(0.0, |acc, (j, tap)| {
+                                acc + tap.freeze_raw() * x[(r + j) * cols + c].freeze_raw()
+                            });
+                            *out = <$fast_ty>::new(sum);
+                        }
+                    }
+                }
+
+                #[inline(always)]
+                pub(super) unsafe fn [<biquad_ $base_ty>](x: *mut $fast_ty, len: usize, coeffs: *const $fast_ty, state: *mut $fast_ty) {
+                    let [b0, b1, b2, a1, a2] = core::ptr::read(coeffs.cast::<[$fast_ty; 5]>()).map(|c| c.freeze_raw());
+                    let (mut z1, mut z2) = ((*state).freeze_raw(), (*state.add(1)).freeze_raw());
+                    for x in core::slice::from_raw_parts_mut(x, len) {
+                        let input = x.freeze_raw();
+                        let output = b0 * input + z1;
+                        z1 = b1 * input - a1 * output + z2;
+                        z2 = b2 * input - a2 * output;
+                        *x = <$fast_ty>::new(output);
+                    }
+                    *state = <$fast_ty>::new(z1);
+                    *state.add(1) = <$fast_ty>::new(z2);
+                }
+
+                #[inline(always)]
+                pub(super) unsafe fn [<goertzel_ $base_ty>](x: *const $fast_ty, len: usize, coeff: $fast_ty, state: *mut $fast_ty) {
+                    let coeff = coeff.freeze_raw();
+                    let (mut s1, mut s2) = ((*state).freeze_raw(), (*state.add(1)).freeze_raw());
+                    for x in core::slice::from_raw_parts(x, len) {
+                        let s0 = x.freeze_raw() + coeff * s1 - s2;
+                        s2 = s1;
+                        s1 = s0;
+                    }
+                    *state = <$fast_ty>::new(s1);
+                    *state.add(1) = <$fast_ty>::new(s2);
+                }
+
+                #[inline(always)]
+                #[allow(clippy::too_many_arguments)]
+                pub(super) unsafe fn [<resample_ $base_ty>](
+                    x: *const $fast_ty,
+                    bank: *const $fast_ty,
+                    taps: usize,
+                    up: usize,
+                    down: usize,
+                    pos: usize,
+                    out: *mut $fast_ty,
+                    len: usize,
+                ) {
+                    for (n, out) in core::slice::from_raw_parts_mut(out, len).iter_mut().enumerate() {
+                        let pos = pos + n * down;
+                        let window = core::slice::from_raw_parts(x.add(pos / up), taps);
+                        let coeffs = core::slice::from_raw_parts(bank.add((pos % up) * taps), taps);
+                        let sum = coeffs
+                            .iter()
+                            .zip(window)
+                            .fold(0.0, |acc, (c, x)| acc + c.freeze_raw() * x.freeze_raw());
+                        *out = <$fast_ty>::new(sum);
+                    }
+                }
+            }
+
+            #[cfg(scalar_fallback)]
+            use [<strict_dsp_ $base_ty>]::*;
+
+            impl DspKernels for $fast_ty {
+                #[inline]
+                fn convolve(signal: &[Self], kernel: &[Self]) -> Vec<Self> {
+                    if signal.is_empty() || kernel.is_empty() {
+                        return Vec::new();
+                    }
+
+                    // the signal is padded with zeros on both sides, so the filter covers the
+                    // partial overlaps at each end
+                    let pad = kernel.len() - 1;
+                    let mut padded = vec![Self::ZERO; signal.len() + 2 * pad];
+                    padded[pad..pad + signal.len()].copy_from_slice(signal);
+                    let reversed: Vec<_> = kernel.iter().rev().copied().collect();
+
+                    let mut out = vec![Self::ZERO; signal.len() + pad];
+                    unsafe {
+                        [<fir_ $base_ty>](
+                            padded.as_ptr(),
+                            out.len(),
+                            reversed.as_ptr(),
+                            reversed.len(),
+                            out.as_mut_ptr(),
+                        )
+                    };
+                    out
+                }
+
+                #[inline]
+                fn convolve2d_separable(rows: usize, cols: usize, data: &mut [Self], kx: &[Self], ky: &[Self]) {
+                    assert_eq!(data.len(), rows * cols, "data length doesn't match the image size");
+                    assert!(!kx.is_empty() && !ky.is_empty(), "kernels must not be empty");
+                    if data.is_empty() {
+                        return;
+                    }
+
+                    // each row is copied into a buffer with `kx.len() - 1` zeros around it, split
+                    // so that the output is centered, and filtered into the matching row of a
+                    // temporary image which has zero rows around it in the same way
+                    let reversed_x: Vec<_> = kx.iter().rev().copied().collect();
+                    let (before_x, before_y) = (kx.len() / 2, ky.len() / 2);
+                    let mut row = vec![Self::ZERO; cols + kx.len() - 1];
+                    let mut temp = vec![Self::ZERO; (rows + ky.len() - 1) * cols];
+                    let filtered = &mut temp[before_y * cols..(before_y + rows) * cols];
+                    for (data, filtered) in data.chunks_exact(cols).zip(filtered.chunks_exact_mut(cols)) {
+                        row[before_x..before_x + cols].copy_from_slice(data);
+                        unsafe {
+                            [<fir_ $base_ty>](
+                                row.as_ptr(),
+                                cols,
+                                reversed_x.as_ptr(),
+                                reversed_x.len(),
+                                filtered.as_mut_ptr(),
+                            )
+                        };
+                    }
+
+                    let reversed_y: Vec<_> = ky.iter().rev().copied().collect();
+                    unsafe {
+                        [<fir_columns_ $base_ty>](
+                            temp.as_ptr(),
+                            rows,
+                            cols,
+                            reversed_y.as_ptr(),
+                            reversed_y.len(),
+                            data.as_mut_ptr(),
+                        )
+                    };
+                }
+            }
+
+            impl FirFilter<$fast_ty> {
+                /// Create a filter with the given taps, and a history of zeros.
+                ///
+                /// # Panics
+                /// Panics if `taps` is empty
+                #[inline]
+                pub fn new(mut taps: Vec<$fast_ty>) -> Self {
+                    assert!(!taps.is_empty(), "filter must have at least one tap");
+                    taps.reverse();
+                    let history = vec![<$fast_ty>::ZERO; taps.len() - 1];
+                    FirFilter { reversed: taps, history }
+                }
+
+                /// Filter a block of inputs, storing one output for each in `output`.
+                ///
+                /// # Panics
+                /// Panics if `input` and `output` have different lengths
+                #[inline]
+                pub fn process_block(&mut self, input: &[$fast_ty], output: &mut [$fast_ty]) {
+                    assert_eq!(input.len(), output.len(), "input and output lengths differ");
+
+                    // the history's capacity is reused across blocks, so it only allocates when
+                    // a block is larger than any before it
+                    self.history.extend_from_slice(input);
+                    unsafe {
+                        [<fir_ $base_ty>](
+                            self.history.as_ptr(),
+                            input.len(),
+                            self.reversed.as_ptr(),
+                            self.reversed.len(),
+                            output.as_mut_ptr(),
+                        )
+                    };
+                    self.history.drain(..input.len());
+                }
+
+                /// Filter a single input
+                #[inline]
+                pub fn process(&mut self, input: $fast_ty) -> $fast_ty {
+                    let mut output = [<$fast_ty>::ZERO];
+                    self.process_block(&[input], &mut output);
+                    output[0]
+                }
+
+                /// Reset the filter's history to zeros, as if no inputs had been processed
+                #[inline]
+                pub fn reset(&mut self) {
+                    self.history.iter_mut().for_each(|x| *x = <$fast_ty>::ZERO);
+                }
+            }
+
+            impl Biquad<$fast_ty> {
+                /// Create a filter from the coefficients of its transfer function,
+                /// `(b0 + b1 z^-1 + b2 z^-2) / (a0 + a1 z^-1 + a2 z^-2)`, with a state of zeros.
+                ///
+                /// # Panics
+                /// Panics if `a0` is zero
+                #[inline]
+                pub fn new(
+                    b0: $fast_ty,
+                    b1: $fast_ty,
+                    b2: $fast_ty,
+                    a0: $fast_ty,
+                    a1: $fast_ty,
+                    a2: $fast_ty,
+                ) -> Self {
+                    assert!(a0 != <$fast_ty>::ZERO, "a0 must be non-zero");
+                    let coeffs = [b0, b1, b2, a1, a2].map(|c| c / a0);
+                    Biquad { coeffs, state: [<$fast_ty>::ZERO; 2] }
+                }
+
+                /// A low-pass filter, which attenuates frequencies above `cutoff` (in the same
+                /// units as `sample_rate`) by 12 dB per octave, with a resonance of `q` at the
+                /// cutoff. A `q` of `1 / sqrt(2)` gives a Butterworth response, without any peak.
+                ///
+                /// # Panics
+                /// Panics if `cutoff` is not between zero and half the sample rate, or `q` is
+                /// not positive
+                #[inline]
+                pub fn lowpass(sample_rate: $fast_ty, cutoff: $fast_ty, q: $fast_ty) -> Self {
+                    let (cos, alpha) = [<cookbook_ $base_ty>](sample_rate, cutoff, q);
+                    let b = (<$fast_ty>::ONE - cos) / 2.0;
+                    Self::new(b, b * 2.0, b, alpha + 1.0, cos * -2.0, <$fast_ty>::ONE - alpha)
+                }
+
+                /// A high-pass filter, which attenuates frequencies below `cutoff` by 12 dB per
+                /// octave. The parameters are the same as for [`lowpass`](Self::lowpass).
+                ///
+                /// # Panics
+                /// Panics if `cutoff` is not between zero and half the sample rate, or `q` is
+                /// not positive
+                #[inline]
+                pub fn highpass(sample_rate: $fast_ty, cutoff: $fast_ty, q: $fast_ty) -> Self {
+                    let (cos, alpha) = [<cookbook_ $base_ty>](sample_rate, cutoff, q);
+                    let b = (cos + 1.0) / 2.0;
+                    Self::new(b, b * -2.0, b, alpha + 1.0, cos * -2.0, <$fast_ty>::ONE - alpha)
+                }
+
+                /// A peaking equalizer, which boosts (or for a negative gain, cuts) the
+                /// frequencies around `center` by `gain_db` decibels, over a bandwidth which
+                /// narrows as `q` increases.
+                ///
+                /// # Panics
+                /// Panics if `center` is not between zero and half the sample rate, or `q` is
+                /// not positive
+                #[inline]
+                pub fn peaking(sample_rate: $fast_ty, center: $fast_ty, q: $fast_ty, gain_db: $fast_ty) -> Self {
+                    let (cos, alpha) = [<cookbook_ $base_ty>](sample_rate, center, q);
+                    let amplitude = <$fast_ty>::new(10.0).powf(gain_db / 40.0);
+                    Self::new(
+                        alpha * amplitude + 1.0,
+                        cos * -2.0,
+                        <$fast_ty>::ONE - alpha * amplitude,
+                        alpha / amplitude + 1.0,
+                        cos * -2.0,
+                        <$fast_ty>::ONE - alpha / amplitude,
+                    )
+                }
+
+                /// Filter a block of samples in place
+                #[inline]
+                pub fn process_block(&mut self, block: &mut [$fast_ty]) {
+                    unsafe {
+                        [<biquad_ $base_ty>](
+                            block.as_mut_ptr(),
+                            block.len(),
+                            self.coeffs.as_ptr(),
+                            self.state.as_mut_ptr(),
+                        )
+                    };
+                }
+
+                /// Filter a single sample
+                #[inline]
+                pub fn process(&mut self, input: $fast_ty) -> $fast_ty {
+                    let mut block = [input];
+                    self.process_block(&mut block);
+                    block[0]
+                }
+
+                /// Reset the filter's state to zeros, as if no samples had been processed
+                #[inline]
+                pub fn reset(&mut self) {
+                    self.state = [<$fast_ty>::ZERO; 2];
+                }
+            }
+
+            impl Goertzel<$fast_ty> {
+                /// Create a detector for `frequency`, in the same units as `sample_rate`.
+                ///
+                /// The frequency doesn't have to be the center of a DFT bin for the number of
+                /// samples measured, although the response to nearby frequencies is narrowest
+                /// when it is.
+                ///
+                /// # Panics
+                /// Panics if `frequency` is not between zero and half the sample rate (inclusive)
+                #[inline]
+                pub fn new(sample_rate: $fast_ty, frequency: $fast_ty) -> Self {
+                    assert!(
+                        <$fast_ty>::ZERO <= frequency && frequency * 2.0 <= sample_rate,
+                        "frequency must be between zero and half the sample rate"
+                    );
+                    let omega = frequency / sample_rate * (2.0 * core::$base_ty::consts::PI);
+                    Goertzel { coeff: omega.cos() * 2.0, state: [<$fast_ty>::ZERO; 2] }
+                }
+
+                /// Add a block of samples to the measurement
+                #[inline]
+                pub fn process_block(&mut self, block: &[$fast_ty]) {
+                    unsafe { [<goertzel_ $base_ty>](block.as_ptr(), block.len(), self.coeff, self.state.as_mut_ptr()) };
+                }
+
+                /// The squared magnitude of the DFT of the samples so far, at the detector's
+                /// frequency
+                #[inline]
+                pub fn power(&self) -> $fast_ty {
+                    let [s1, s2] = self.state;
+                    s1 * s1 + s2 * s2 - self.coeff * s1 * s2
+                }
+
+                /// Reset the detector's state to zeros, as if no samples had been processed
+                #[inline]
+                pub fn reset(&mut self) {
+                    self.state = [<$fast_ty>::ZERO; 2];
+                }
+            }
+
+            impl Resampler<$fast_ty> {
+                /// Create a resampler which linearly interpolates between each pair of inputs.
+                ///
+                /// Linear interpolation is cheap, but doesn't filter out the frequencies above
+                /// the output's Nyquist frequency when downsampling, which then alias.
+                ///
+                /// # Panics
+                /// Panics if either rate is zero
+                pub fn linear(input_rate: u32, output_rate: u32) -> Self {
+                    Self::with_kernel(input_rate, output_rate, 2, |t| 1.0 - t.abs())
+                }
+
+                /// Create a resampler with a windowed-sinc filter of `taps` taps per phase.
+                ///
+                /// The filter is a low-pass at the lower of the two Nyquist frequencies, under a
+                /// Blackman window. More taps give a sharper cutoff and less aliasing, at a
+                /// proportional cost per output.
+                ///
+                /// # Panics
+                /// Panics if either rate is zero, or `taps` is odd or zero
+                pub fn polyphase(input_rate: u32, output_rate: u32, taps: usize) -> Self {
+                    assert!(taps >= 2 && taps % 2 == 0, "taps must be even and non-zero");
+                    let cutoff = 0.5 * (f64::from(output_rate) / f64::from(input_rate)).min(1.0);
+                    let half_width = taps as f64 / 2.0;
+                    Self::with_kernel(input_rate, output_rate, taps, |t| {
+                        use core::f64::consts::PI;
+                        let x = 2.0 * cutoff * t;
+                        let sinc = if x == 0.0 { 1.0 } else { (PI * x).sin() / (PI * x) };
+                        let window = 0.42 + 0.5 * (PI * t / half_width).cos() + 0.08 * (2.0 * PI * t / half_width).cos();
+                        sinc * window
+                    })
+                }
+
+                fn with_kernel(input_rate: u32, output_rate: u32, taps: usize, kernel: impl Fn(f64) -> f64) -> Self {
+                    assert!(input_rate > 0 && output_rate > 0, "sample rates must be non-zero");
+                    let divisor = gcd(input_rate, output_rate);
+                    let (up, down) = ((output_rate / divisor) as usize, (input_rate / divisor) as usize);
+                    let bank = filter_bank(up, taps, kernel).into_iter().map(|c| <$fast_ty>::new(c as $base_ty)).collect();
+                    let mut resampler = Resampler { bank, taps, up, down, history: Vec::new(), pos: 0 };
+                    resampler.reset();
+                    resampler
+                }
+
+                /// Resample a block of inputs, appending the outputs which are complete to
+                /// `output`
+                pub fn process(&mut self, input: &[$fast_ty], output: &mut Vec<$fast_ty>) {
+                    self.history.extend_from_slice(input);
+
+                    // the outputs before this position have their whole window in the history
+                    let end = (self.history.len() + 1).saturating_sub(self.taps) * self.up;
+                    let count = if end > self.pos { (end - self.pos - 1) / self.down + 1 } else { 0 };
+
+                    let start = output.len();
+                    output.resize(start + count, <$fast_ty>::ZERO);
+                    unsafe {
+                        [<resample_ $base_ty>](
+                            self.history.as_ptr(),
+                            self.bank.as_ptr(),
+                            self.taps,
+                            self.up,
+                            self.down,
+                            self.pos,
+                            output[start..].as_mut_ptr(),
+                            count,
+                        )
+                    };
+
+                    // drop the inputs before the next window, which may skip some of the
+                    // following inputs too when downsampling by a large factor
+                    self.pos += count * self.down;
+                    let consumed = (self.pos / self.up).min(self.history.len());
+                    self.history.drain(..consumed);
+                    self.pos -= consumed * self.up;
+                }
+
+                /// Reset the resampler's state, as if no inputs had been processed
+                pub fn reset(&mut self) {
+                    self.history.clear();
+                    self.history.resize(self.taps / 2 - 1, <$fast_ty>::ZERO);
+                    self.pos = 0;
+                }
+            }
+
+            // the cosine of the normalized angular frequency, and the bandwidth term `alpha`,
+            // which are shared by the cookbook filters
+            #[inline(always)]
+            fn [<cookbook_ $base_ty>](sample_rate: $fast_ty, frequency: $fast_ty, q: $fast_ty) -> ($fast_ty, $fast_ty) {
+                assert!(
+                    <$fast_ty>::ZERO < frequency && frequency * 2.0 < sample_rate,
+                    "frequency must be between zero and half the sample rate"
+                );
+                assert!(q > <$fast_ty>::ZERO, "q must be positive");
+                let omega = frequency / sample_rate * (2.0 * core::$base_ty::consts::PI);
+                (omega.cos(), omega.sin() / (q * 2.0))
+            }
+        }
+    };
+}
+
+impl_dsp! { FF32, f32 }
+impl_dsp! { FF64, f64 }
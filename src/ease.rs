@@ -0,0 +1,96 @@
+//! Easing curves, for animation and procedural generation.
+//!
+//! Each curve maps a fraction `t` in `[0, 1]` to an eased fraction, from 0 at `t = 0` to 1 at
+//! `t = 1`, except [`smoothstep`] and [`smootherstep`] which first map a value between two edges
+//! to such a fraction. The curves are short polynomials in the fast types, whose multiplications
+//! and additions fuse under contraction. The in-out curves join their two halves with
+//! [`min`](crate::FF32::min) and [`max`](crate::FF32::max) rather than a comparison, so nothing is frozen and
+//! loops over them can be vectorized.
+//!
+//! ```
+//! use fast_fp::{ease, ff32};
+//!
+//! assert_eq!(ease::smoothstep(ff32(0.0), ff32(2.0), ff32(1.0)), 0.5);
+//! assert_eq!(ease::smoothstep(ff32(0.0), ff32(2.0), ff32(3.0)), 1.0);
+//! assert_eq!(ease::ease_in_cubic(ff32(0.5)), 0.125);
+//! assert_eq!(ease::ease_in_out_cubic(ff32(0.25)), 0.0625);
+//! assert_eq!(ease::ease_in_out_cubic(ff32(0.75)), 0.9375);
+//! ```
+
+use crate::FastFloat;
+
+/// Hermite interpolation between two edges, `t^2 * (3 - 2t)` with
+/// `t = clamp01((x - edge0) / (edge1 - edge0))`.
+///
+/// The result is 0 at or below `edge0`, 1 at or above `edge1`, and has zero slope at both. The
+/// edges must differ.
+#[inline]
+pub fn smoothstep<T: FastFloat>(edge0: T, edge1: T, x: T) -> T {
+    let t = edge0.inverse_lerp(edge1, x).clamp01();
+    t * t * (T::from_f64(3.0) - T::from_f64(2.0) * t)
+}
+
+/// Like [`smoothstep`], but with zero curvature at the edges as well,
+/// `t^3 * (t * (6t - 15) + 10)`.
+#[inline]
+pub fn smootherstep<T: FastFloat>(edge0: T, edge1: T, x: T) -> T {
+    let t = edge0.inverse_lerp(edge1, x).clamp01();
+    t * t * t * (t * (T::from_f64(6.0) * t - T::from_f64(15.0)) + T::from_f64(10.0))
+}
+
+/// `t^2`, accelerating from zero velocity
+#[inline]
+pub fn ease_in_quad<T: FastFloat>(t: T) -> T {
+    t * t
+}
+
+/// `1 - (1 - t)^2`, decelerating to zero velocity
+#[inline]
+pub fn ease_out_quad<T: FastFloat>(t: T) -> T {
+    let u = T::ONE - t;
+    T::ONE - u * u
+}
+
+/// [`ease_in_quad`] for the first half and [`ease_out_quad`] for the second, each scaled to
+/// half the time and distance
+#[inline]
+pub fn ease_in_out_quad<T: FastFloat>(t: T) -> T {
+    // The halves are evaluated at t clamped to each side of 1/2, where both are 1/2. For t below
+    // 1/2 the second half is then 1/2, and the sum minus 1/2 is the first half, and likewise above
+    let (half, two) = (T::from_f64(0.5), T::from_f64(2.0));
+    let (low, high) = (t.min(half), t.max(half));
+    let u = two - two * high;
+    two * low * low + (T::ONE - half * u * u) - half
+}
+
+/// `t^3`, accelerating from zero velocity
+#[inline]
+pub fn ease_in_cubic<T: FastFloat>(t: T) -> T {
+    t * t * t
+}
+
+/// `1 - (1 - t)^3`, decelerating to zero velocity
+#[inline]
+pub fn ease_out_cubic<T: FastFloat>(t: T) -> T {
+    let u = T::ONE - t;
+    T::ONE - u * u * u
+}
+
+/// [`ease_in_cubic`] for the first half and [`ease_out_cubic`] for the second, each scaled to
+/// half the time and distance
+#[inline]
+pub fn ease_in_out_cubic<T: FastFloat>(t: T) -> T {
+    // joined like ease_in_out_quad
+    let (half, two) = (T::from_f64(0.5), T::from_f64(2.0));
+    let (low, high) = (t.min(half), t.max(half));
+    let u = two - two * high;
+    T::from_f64(4.0) * low * low * low + (T::ONE - half * u * u * u) - half
+}
+
+/// `(1 - cos(pi * t)) / 2`, half a period of a cosine.
+///
+/// Unlike the polynomial curves, this needs a [`cos`](crate::FF32::cos), so its argument is frozen.
+#[inline]
+pub fn ease_in_out_sine<T: FastFloat>(t: T) -> T {
+    (T::ONE - (t * T::from_f64(core::f64::consts::PI)).cos()) * T::from_f64(0.5)
+}
@@ -0,0 +1,197 @@
+//! Conversions between [`FF32`] and IEEE 754 [half precision] values.
+//!
+//! Like [bfloat16](crate::bf16) values, half precision values are represented by their raw `u16`
+//! bit patterns. Every half precision value is exactly representable as an `f32`, so decoding is
+//! exact, while encoding rounds to the nearest representable value (ties to even), or
+//! stochastically.
+//!
+//! ```
+//! use fast_fp::{f16, ff32};
+//!
+//! assert_eq!(ff32(1.5).to_f16(), 0x3e00);
+//! assert_eq!(ff32(-2.0).to_f16(), 0xc000);
+//! assert_eq!(fast_fp::FF32::from_f16(0x3555), 0.333251953125);
+//!
+//! let src = [ff32(0.1); 4];
+//! let mut dst = [0; 4];
+//! f16::encode_stochastic(&src, &mut dst, 7);
+//! assert!(dst.iter().all(|&h| h == 0x2e66 || h == 0x2e67));
+//!
+//! let a = [1.5, -2.0, 0.25].map(|x| ff32(x).to_f16());
+//! let b = [2.0, 0.5, 4.0].map(|x| ff32(x).to_f16());
+//! assert_eq!(f16::dot(&a, &b), 3.0);
+//! ```
+//!
+//! [`dot`] computes a dot product directly from half precision inputs, widening each value as
+//! it's loaded and accumulating in `f32` precision.
+//!
+//! [half precision]: https://en.wikipedia.org/wiki/Half-precision_floating-point_format
+
+use crate::{bf16::random_bits, FF32};
+
+// the bit pattern of half precision infinity, which also bounds the finite values
+const F16_INFINITY: u32 = 0x7c00;
+
+impl FF32 {
+    /// Convert this value to the bit pattern of the nearest half precision value, rounding ties
+    /// to even.
+    ///
+    /// Values larger in magnitude than the largest finite half precision value round to infinity,
+    /// and values too small for the subnormals round to zero. NaN (with the `non-finite` feature)
+    /// converts to a quiet NaN.
+    #[inline]
+    pub fn to_f16(self) -> u16 {
+        // bit manipulation requires a fixed value, so this must freeze
+        let bits = self.freeze_raw().to_bits();
+        f16_from_bits(bits, |dropped, shift| {
+            ((1 << (shift - 1)) - 1) + ((dropped >> shift) & 1)
+        })
+    }
+
+    /// Convert this value to the bit pattern of a neighboring half precision value, rounding down
+    /// or up stochastically.
+    ///
+    /// `random` must be uniformly distributed random bits, which decide the rounding: the value
+    /// is rounded up with a probability proportional to its distance from the value below. NaN
+    /// (with the `non-finite` feature) converts to a quiet NaN.
+    #[inline]
+    pub fn to_f16_stochastic(self, random: u32) -> u16 {
+        let bits = self.freeze_raw().to_bits();
+        f16_from_bits(bits, |_, shift| random & ((1 << shift) - 1))
+    }
+
+    /// Create a new `FF32` instance from the bit pattern of a half precision value.
+    ///
+    /// The conversion is exact, and infinity and NaN convert to the same `f32` values. As with
+    /// [`FF32::new`], without the `non-finite` feature the given value **MUST NOT** be infinite
+    /// or NaN.
+    #[inline]
+    pub fn from_f16(bits: u16) -> Self {
+        let sign = u32::from(bits & 0x8000) << 16;
+        let exponent = (bits >> 10) & 0x1f;
+        let mantissa = u32::from(bits & 0x3ff);
+
+        let magnitude = if exponent == 0 {
+            // subnormals (and zero) are a multiple of 2^-24, which is exact in an f32
+            (mantissa as f32 * (1.0 / 16_777_216.0)).to_bits()
+        } else if exponent == 0x1f {
+            // infinity and NaN, with the NaN payload kept in the top of the mantissa
+            0x7f80_0000 | (mantissa << 13)
+        } else {
+            // rebias the exponent from 15 to 127
+            ((u32::from(exponent) + 112) << 23) | (mantissa << 13)
+        };
+        FF32::new(f32::from_bits(sign | magnitude))
+    }
+}
+
+// Convert the bits of an f32 to a half precision value. `round` is given the f32 mantissa (with
+// its implicit bit) and the number of low bits to be dropped from it, and returns the amount to
+// add before they're truncated. NaN becomes the quiet NaN of the same sign, since its payload
+// would otherwise round like a large finite value, to infinity
+#[inline(always)]
+fn f16_from_bits(bits: u32, round: impl Fn(u32, u32) -> u32) -> u16 {
+    let sign = ((bits >> 16) & 0x8000) as u16;
+    let magnitude = bits & 0x7fff_ffff;
+    let exponent = magnitude >> 23;
+
+    let half = if magnitude > 0x7f80_0000 {
+        0x7e00
+    } else if exponent >= 113 {
+        // normal half values keep the top 10 of the 23 mantissa bits, and the exponent is rebiased
+        // from 127 to 15. A carry out of the mantissa increments the exponent, up to infinity
+        let rebiased = magnitude - (112 << 23);
+        ((rebiased + round(rebiased, 13)) >> 13).min(F16_INFINITY)
+    } else if exponent >= 126 - 31 {
+        // subnormal half values are multiples of 2^-24, so more bits are dropped the smaller the
+        // exponent. A carry out of the subnormals reaches the smallest normal value
+        let mantissa = (magnitude & 0x7f_ffff) | 0x80_0000;
+        let shift = 126 - exponent;
+        (mantissa + round(mantissa, shift)) >> shift
+    } else {
+        // below 2^-32, the value rounds to zero. Stochastic rounding would round up with a
+        // probability of less than 2^-8, which is neglected
+        0
+    };
+    sign | half as u16
+}
+
+#[cfg(not(scalar_fallback))]
+#[link(name = "poison_safe")]
+extern "C" {
+    // the inputs are integer bit patterns, which are widened in C
+    fn dot_f16(a: *const u16, b: *const u16, len: usize) -> FF32;
+}
+
+// with the strict feature, the shim is replaced by a loop over the decoded values
+#[cfg(scalar_fallback)]
+unsafe fn dot_f16(a: *const u16, b: *const u16, len: usize) -> FF32 {
+    let a = core::slice::from_raw_parts(a, len);
+    let b = core::slice::from_raw_parts(b, len);
+    a.iter().zip(b).fold(FF32::ZERO, |acc, (a, b)| {
+        acc + FF32::from_f16(*a) * FF32::from_f16(*b)
+    })
+}
+
+/// Decode a slice of half precision bit patterns into `dst`.
+///
+/// # Panics
+/// Panics if the two slices have different lengths
+pub fn decode(src: &[u16], dst: &mut [FF32]) {
+    assert_eq!(
+        src.len(),
+        dst.len(),
+        "source and destination lengths differ"
+    );
+    for (dst, src) in dst.iter_mut().zip(src) {
+        *dst = FF32::from_f16(*src);
+    }
+}
+
+/// Encode a slice of values into half precision bit patterns in `dst`, rounding ties to even.
+///
+/// # Panics
+/// Panics if the two slices have different lengths
+pub fn encode(src: &[FF32], dst: &mut [u16]) {
+    assert_eq!(
+        src.len(),
+        dst.len(),
+        "source and destination lengths differ"
+    );
+    for (dst, src) in dst.iter_mut().zip(src) {
+        *dst = src.to_f16();
+    }
+}
+
+/// The dot product of two slices of half precision bit patterns, accumulated as [`FF32`] values.
+///
+/// Each value is widened to `f32` as it's loaded, without decoding the slices into a buffer
+/// first. The products are summed in an unspecified order, like [`slice::dot`](crate::slice::dot).
+/// As with [`FF32::from_f16`], without the `non-finite` feature the values **MUST NOT** be
+/// infinite or NaN.
+///
+/// # Panics
+/// Panics if the two slices have different lengths
+pub fn dot(a: &[u16], b: &[u16]) -> FF32 {
+    assert_eq!(a.len(), b.len(), "slice lengths differ");
+    unsafe { dot_f16(a.as_ptr(), b.as_ptr(), a.len()) }
+}
+
+/// Encode a slice of values into half precision bit patterns in `dst`, rounding stochastically
+/// as [`FF32::to_f16_stochastic`].
+///
+/// The random bits for each value are derived from `seed` and the value's index, so the result
+/// is reproducible for a given seed.
+///
+/// # Panics
+/// Panics if the two slices have different lengths
+pub fn encode_stochastic(src: &[FF32], dst: &mut [u16], seed: u64) {
+    assert_eq!(
+        src.len(),
+        dst.len(),
+        "source and destination lengths differ"
+    );
+    for (i, (dst, src)) in dst.iter_mut().zip(src).enumerate() {
+        *dst = src.to_f16_stochastic(random_bits(seed, i));
+    }
+}
@@ -0,0 +1,159 @@
+use crate::{FF32, FF64};
+use core::fmt::Debug;
+use core::iter::Sum;
+use core::ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Neg, Sub, SubAssign};
+
+mod private {
+    pub trait Sealed {}
+}
+
+/// The fast scalar types, [`FF32`] and [`FF64`], for writing numerical code which is generic over
+/// their precision.
+///
+/// The algorithms written purely in rust, such as the [`roots`](crate::roots) solvers and the
+/// [`integrate`](crate::integrate) rules, are generic over this trait, and so is any user code
+/// which calls them generically. Its methods forward to the inherent methods of the same names,
+/// with the same semantics.
+///
+/// This trait is sealed, so it can't be implemented outside of this crate, and new methods may be
+/// added to it without a breaking change.
+///
+/// ```
+/// use fast_fp::{ff32, ff64, FastFloat};
+///
+/// fn hypot<T: FastFloat>(a: T, b: T) -> T {
+///     (a * a + b * b).sqrt()
+/// }
+///
+/// assert_eq!(hypot(ff32(3.0), ff32(4.0)), 5.0);
+/// assert_eq!(hypot(ff64(5.0), ff64(12.0)), 13.0);
+/// ```
+pub trait FastFloat:
+    private::Sealed
+    + Copy
+    + Debug
+    + PartialEq
+    + PartialOrd
+    + Add<Output = Self>
+    + Sub<Output = Self>
+    + Mul<Output = Self>
+    + Div<Output = Self>
+    + Neg<Output = Self>
+    + AddAssign
+    + SubAssign
+    + MulAssign
+    + DivAssign
+    + Sum
+{
+    /// Zero
+    const ZERO: Self;
+
+    /// One
+    const ONE: Self;
+
+    /// The difference between one and the next larger representable value
+    const EPSILON: Self;
+
+    /// Convert a literal or other `f64` value, rounding it to the nearest value of this type
+    fn from_f64(value: f64) -> Self;
+
+    /// Convert this value to an `f64`, freezing it
+    fn to_f64(self) -> f64;
+
+    /// See [`FF32::abs`]
+    fn abs(self) -> Self;
+
+    /// See [`FF32::copysign`]
+    fn copysign(self, sign: Self) -> Self;
+
+    /// See [`FF32::min`]
+    fn min(self, other: Self) -> Self;
+
+    /// See [`FF32::max`]
+    fn max(self, other: Self) -> Self;
+
+    /// See [`FF32::clamp01`]
+    fn clamp01(self) -> Self;
+
+    /// See [`FF32::ceil`]
+    fn ceil(self) -> Self;
+
+    /// See [`FF32::lerp`]
+    fn lerp(self, end: Self, t: Self) -> Self;
+
+    /// See [`FF32::inverse_lerp`]
+    fn inverse_lerp(self, end: Self, x: Self) -> Self;
+
+    /// See [`FF32::sqrt`]
+    fn sqrt(self) -> Self;
+
+    /// See [`FF32::cbrt`]
+    fn cbrt(self) -> Self;
+
+    /// See [`FF32::exp`]
+    fn exp(self) -> Self;
+
+    /// See [`FF32::ln`]
+    fn ln(self) -> Self;
+
+    /// See [`FF32::cos`]
+    fn cos(self) -> Self;
+
+    /// See [`FF32::acos`]
+    fn acos(self) -> Self;
+}
+
+// forward each trait method to the inherent method of the same name
+macro_rules! forward_fast_float {
+    ($fast_ty:ident $(fn $fn_name:ident(self $(, $arg:ident: $arg_ty:ty)*) -> Self;)*) => {
+        $(
+            #[inline(always)]
+            fn $fn_name(self $(, $arg: $arg_ty)*) -> Self {
+                <$fast_ty>::$fn_name(self $(, $arg)*)
+            }
+        )*
+    };
+}
+
+macro_rules! impl_fast_float {
+    ($fast_ty:ident, $base_ty:ident) => {
+        impl private::Sealed for $fast_ty {}
+
+        impl FastFloat for $fast_ty {
+            const ZERO: Self = <$fast_ty>::ZERO;
+            const ONE: Self = <$fast_ty>::ONE;
+            const EPSILON: Self = <$fast_ty>::new(<$base_ty>::EPSILON);
+
+            #[inline(always)]
+            fn from_f64(value: f64) -> Self {
+                <$fast_ty>::new(value as $base_ty)
+            }
+
+            #[inline(always)]
+            fn to_f64(self) -> f64 {
+                self.freeze_raw().into()
+            }
+
+            forward_fast_float! {
+                $fast_ty
+                fn abs(self) -> Self;
+                fn copysign(self, sign: Self) -> Self;
+                fn min(self, other: Self) -> Self;
+                fn max(self, other: Self) -> Self;
+                fn clamp01(self) -> Self;
+                fn ceil(self) -> Self;
+                fn lerp(self, end: Self, t: Self) -> Self;
+                fn inverse_lerp(self, end: Self, x: Self) -> Self;
+                fn sqrt(self) -> Self;
+                fn cbrt(self) -> Self;
+                fn exp(self) -> Self;
+                fn ln(self) -> Self;
+                fn cos(self) -> Self;
+                fn acos(self) -> Self;
+            }
+        }
+    };
+}
+
+impl_fast_float! { FF32, f32 }
+impl_fast_float! { FF64, f64 }
@@ -0,0 +1,287 @@
+#![cfg(feature = "ff128")]
+#![cfg_attr(docsrs, doc(cfg(feature = "ff128")))]
+
+use crate::{poison::MaybePoison, FF32, FF64};
+use core::{
+    cmp, fmt,
+    iter::{Product, Sum},
+    ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Neg, Sub, SubAssign},
+};
+
+// Rust doesn't have a stable 128-bit float type yet, so `FF128` stores the raw 16 bytes of an IEEE
+// quad precision value and every operation goes through a C shim, passing values by pointer. The
+// usual poison discipline applies: the storage is a `MaybePoison`, poison safe shims are called
+// directly, and the two halves of the storage are frozen before any value leaves the API.
+//
+// The storage has the same size, alignment, and bit layout as `f128`, so once that type is stable
+// the shims can be swapped out without changing the public API.
+
+/// A quad precision float which enables some fast-math optimizations.
+///
+/// This type is intended for extended precision intermediate values, like accumulating a long sum
+/// of [`FF64`] values, with the same relaxed reassociation as the other fast types. Values can
+/// be converted to and from the smaller fast types; there is no standard 128-bit float type to
+/// convert with yet.
+///
+/// The same restrictions apply as apply to [`FF64`]: values **MUST NOT** be infinite or NaN, and
+/// the output of any operation involving such values is unspecified.
+///
+/// This type requires a target with IEEE quad precision support in C, either as `__float128` (x86)
+/// or as `long double` (such as aarch64 linux). Enabling the `ff128` feature on other targets
+/// fails the build.
+#[derive(Clone, Copy)]
+#[repr(C, align(16))]
+pub struct FF128(MaybePoison<[u64; 2]>);
+
+#[cfg_attr(not(scalar_fallback), link(name = "poison_safe"))]
+#[cfg_attr(scalar_fallback, link(name = "ff128"))]
+extern "C" {
+    fn add_f128(out: *mut FF128, a: *const FF128, b: *const FF128);
+    fn sub_f128(out: *mut FF128, a: *const FF128, b: *const FF128);
+    fn mul_f128(out: *mut FF128, a: *const FF128, b: *const FF128);
+    fn div_f128(out: *mut FF128, a: *const FF128, b: *const FF128);
+    fn neg_f128(out: *mut FF128, a: *const FF128);
+
+    fn from_f64_f128(out: *mut FF128, a: FF64);
+    fn to_f64_f128(a: *const FF128) -> FF64;
+}
+
+#[cfg_attr(not(scalar_fallback), link(name = "poison_unsafe"))]
+#[cfg_attr(scalar_fallback, link(name = "ff128"))]
+extern "C" {
+    fn partial_cmp_f128(a: *const FF128, b: *const FF128) -> i32;
+}
+
+impl FF128 {
+    const ZERO: FF128 = FF128(MaybePoison::new([0; 2]));
+
+    /// Create a new `FF128` instance from the raw bits of a quad precision value.
+    ///
+    /// The bit layout is the same as `f128::from_bits`. As with [`FF64::new`], the value **MUST
+    /// NOT** be infinite or NaN.
+    #[inline]
+    pub fn from_bits(bits: u128) -> Self {
+        let (lo, hi) = (bits as u64, (bits >> 64) as u64);
+
+        #[cfg(target_endian = "little")]
+        let halves = [lo, hi];
+        #[cfg(target_endian = "big")]
+        let halves = [hi, lo];
+
+        FF128(MaybePoison::new(halves))
+    }
+
+    /// Get the raw bits of this value, with the same bit layout as `f128::to_bits`.
+    #[inline]
+    pub fn to_bits(self) -> u128 {
+        // bit manipulation requires a fixed value, so this must freeze
+        let [first, second] = self.freeze_halves();
+
+        #[cfg(target_endian = "little")]
+        let (lo, hi) = (first, second);
+        #[cfg(target_endian = "big")]
+        let (lo, hi) = (second, first);
+
+        (u128::from(hi) << 64) | u128::from(lo)
+    }
+
+    #[inline(always)]
+    fn freeze_halves(self) -> [u64; 2] {
+        let [first, second] = self.0.split();
+        [first.freeze(), second.freeze()]
+    }
+
+    #[inline(always)]
+    fn freeze(self) -> Self {
+        FF128(MaybePoison::new(self.freeze_halves()))
+    }
+}
+
+macro_rules! impl_ops {
+    ($($op_trait:ident, $op_fn:ident, $assign_trait:ident, $assign_fn:ident, $shim:ident,)*) => {
+        $(
+            impl $op_trait<FF128> for FF128 {
+                type Output = FF128;
+
+                #[inline(always)]
+                fn $op_fn(self, other: FF128) -> Self::Output {
+                    let mut out = FF128::ZERO;
+                    unsafe { $shim(&mut out, &self, &other) };
+                    out
+                }
+            }
+
+            impl $op_trait<FF64> for FF128 {
+                type Output = FF128;
+
+                #[inline(always)]
+                fn $op_fn(self, other: FF64) -> Self::Output {
+                    self.$op_fn(FF128::from(other))
+                }
+            }
+
+            impl $op_trait<FF128> for FF64 {
+                type Output = FF128;
+
+                #[inline(always)]
+                fn $op_fn(self, other: FF128) -> Self::Output {
+                    FF128::from(self).$op_fn(other)
+                }
+            }
+
+            impl $assign_trait<FF128> for FF128 {
+                #[inline(always)]
+                fn $assign_fn(&mut self, rhs: FF128) {
+                    *self = FF128::$op_fn(*self, rhs)
+                }
+            }
+
+            impl $assign_trait<FF64> for FF128 {
+                #[inline(always)]
+                fn $assign_fn(&mut self, rhs: FF64) {
+                    *self = FF128::$op_fn(*self, rhs)
+                }
+            }
+        )*
+    };
+}
+
+impl_ops! {
+    Add, add, AddAssign, add_assign, add_f128,
+    Sub, sub, SubAssign, sub_assign, sub_f128,
+    Mul, mul, MulAssign, mul_assign, mul_f128,
+    Div, div, DivAssign, div_assign, div_f128,
+}
+
+impl Neg for FF128 {
+    type Output = Self;
+
+    #[inline(always)]
+    fn neg(self) -> Self::Output {
+        let mut out = FF128::ZERO;
+        unsafe { neg_f128(&mut out, &self) };
+        out
+    }
+}
+
+impl Sum<FF128> for FF128 {
+    #[inline]
+    fn sum<I: Iterator<Item = FF128>>(iter: I) -> Self {
+        iter.fold(FF128::ZERO, |acc, val| acc + val)
+    }
+}
+
+impl<'a> Sum<&'a FF128> for FF128 {
+    #[inline]
+    fn sum<I: Iterator<Item = &'a FF128>>(iter: I) -> Self {
+        iter.fold(FF128::ZERO, |acc, val| acc + *val)
+    }
+}
+
+impl Sum<FF64> for FF128 {
+    #[inline]
+    fn sum<I: Iterator<Item = FF64>>(iter: I) -> Self {
+        iter.fold(FF128::ZERO, |acc, val| acc + val)
+    }
+}
+
+impl<'a> Sum<&'a FF64> for FF128 {
+    #[inline]
+    fn sum<I: Iterator<Item = &'a FF64>>(iter: I) -> Self {
+        iter.fold(FF128::ZERO, |acc, val| acc + *val)
+    }
+}
+
+impl Product<FF128> for FF128 {
+    #[inline]
+    fn product<I: Iterator<Item = FF128>>(iter: I) -> Self {
+        iter.fold(FF128::from(1.0_f64), |acc, val| acc * val)
+    }
+}
+
+impl<'a> Product<&'a FF128> for FF128 {
+    #[inline]
+    fn product<I: Iterator<Item = &'a FF128>>(iter: I) -> Self {
+        iter.fold(FF128::from(1.0_f64), |acc, val| acc * *val)
+    }
+}
+
+// comparisons are poison unsafe, and must freeze like the other fast types
+impl PartialEq for FF128 {
+    #[inline]
+    fn eq(&self, other: &FF128) -> bool {
+        self.partial_cmp(other) == Some(cmp::Ordering::Equal)
+    }
+}
+
+impl PartialOrd for FF128 {
+    #[inline]
+    fn partial_cmp(&self, other: &FF128) -> Option<cmp::Ordering> {
+        let (a, b) = (self.freeze(), other.freeze());
+        match unsafe { partial_cmp_f128(&a, &b) } {
+            -1 => Some(cmp::Ordering::Less),
+            0 => Some(cmp::Ordering::Equal),
+            1 => Some(cmp::Ordering::Greater),
+            _ => None,
+        }
+    }
+}
+
+// there's no rust type to format with, so values are formatted at f64 precision
+impl fmt::Debug for FF128 {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Debug::fmt(&f64::from(*self), f)
+    }
+}
+
+impl fmt::Display for FF128 {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Display::fmt(&f64::from(*self), f)
+    }
+}
+
+impl From<FF64> for FF128 {
+    #[inline(always)]
+    fn from(from: FF64) -> Self {
+        let mut out = FF128::ZERO;
+        unsafe { from_f64_f128(&mut out, from) };
+        out
+    }
+}
+
+impl From<FF32> for FF128 {
+    #[inline(always)]
+    fn from(from: FF32) -> Self {
+        FF128::from(f32::from(from))
+    }
+}
+
+impl From<f64> for FF128 {
+    #[inline(always)]
+    fn from(from: f64) -> Self {
+        FF128::from(FF64::new(from))
+    }
+}
+
+impl From<f32> for FF128 {
+    #[inline(always)]
+    fn from(from: f32) -> Self {
+        FF128::from(FF64::new(f64::from(from)))
+    }
+}
+
+/// Round to the nearest `FF64` value
+impl From<FF128> for FF64 {
+    #[inline(always)]
+    fn from(from: FF128) -> Self {
+        unsafe { to_f64_f128(&from) }
+    }
+}
+
+/// Round to the nearest `f64` value
+impl From<FF128> for f64 {
+    #[inline(always)]
+    fn from(from: FF128) -> Self {
+        FF64::from(from).into()
+    }
+}
@@ -0,0 +1,639 @@
+//! Fast Fourier transforms of complex and real buffers with power-of-two lengths.
+//!
+//! A plan is created once for each length, precomputing the bit-reversal permutation and the
+//! twiddle factors (in `f64` precision, for both types), and can then transform any number of
+//! buffers of that length. [`FftPlan`] transforms [num-complex] values in place, with radix-4
+//! butterfly passes (and one radix-2 pass for odd powers of two) running in C. [`RealFftPlan`]
+//! transforms real values through a complex transform of half the length, separating the result
+//! with the fast complex arithmetic. [`DctPlan`] and [`MdctPlan`] compute the cosine transforms
+//! used by audio codecs and mel-spectrogram pipelines on top of these.
+//!
+//! The spectrum of the output can be summarized with the [`magnitude`], [`power`], [`phase`] and
+//! [`decibels`] kernels, which compute one real value for each bin. Like the other transcendental
+//! functions, the inputs of the kernels needing `sqrt`, `atan2` or `log10` are frozen first, a
+//! block of values at a time, and the whole loop over each block runs in C.
+//!
+//! Like most FFT libraries, the forward transform computes
+//! `X[k] = sum(x[j] * exp(-2 pi i j k / n))` and the inverse transform is unnormalized, so a round
+//! trip scales the values by the length.
+//!
+//! ```
+//! use fast_fp::{ff64, fft::{self, FftPlan, RealFftPlan}, FF64};
+//! use num_complex_v04::Complex;
+//!
+//! // an impulse delayed by one sample turns into a full turn of phase over the spectrum
+//! let plan = FftPlan::<FF64>::new(8);
+//! let mut data = [Complex::new(ff64(0.0), ff64(0.0)); 8];
+//! data[1].re = ff64(1.0);
+//! plan.forward(&mut data);
+//! assert!((data[2].re - 0.0).abs() < ff64(1e-12) && (data[2].im + 1.0).abs() < ff64(1e-12));
+//!
+//! plan.inverse(&mut data);
+//! assert!((data[1].re - 8.0).abs() < ff64(1e-12) && data[0].re.abs() < ff64(1e-12));
+//!
+//! // a real cosine of 3 cycles concentrates in bin 3
+//! let plan = RealFftPlan::<FF64>::new(16);
+//! let turn = std::f64::consts::TAU / 16.0;
+//! let signal: Vec<_> = (0..16).map(|j| ff64((3.0 * turn * j as f64).cos())).collect();
+//! let mut spectrum = [Complex::new(ff64(0.0), ff64(0.0)); 9];
+//! plan.forward(&signal, &mut spectrum);
+//! assert!((spectrum[3].re - 8.0).abs() < ff64(1e-12) && spectrum[2].re.abs() < ff64(1e-12));
+//!
+//! let mut levels = [ff64(0.0); 9];
+//! fft::decibels(&spectrum, &mut levels, ff64(-120.0));
+//! assert!((levels[3] - 20.0 * 8f64.log10()).abs() < ff64(1e-9));
+//! assert_eq!(levels[2], -120.0);
+//! ```
+//!
+//! [num-complex]: https://docs.rs/num-complex
+#![cfg_attr(docsrs, doc(cfg(feature = "num-complex-v04")))]
+
+use crate::{FF32, FF64};
+use num_complex_v04::Complex;
+use paste::paste;
+
+// the number of complex values frozen at a time before calling the poison unsafe spectrum kernels
+const SPECTRUM_BLOCK: usize = 256;
+
+/// A plan for complex FFTs of one power-of-two length.
+#[derive(Clone, Debug)]
+pub struct FftPlan<T> {
+    // the index of each value after the bit-reversal permutation
+    bitrev: Vec<usize>,
+    // the tables of the radix-4 passes, see fft.c
+    twiddles: Vec<Complex<T>>,
+}
+
+/// A plan for FFTs of real values, of one power-of-two length.
+///
+/// The spectrum of a real signal is conjugate symmetric, so only its first `len / 2 + 1` bins
+/// are computed. The transform packs the even and odd values into one complex buffer of half the
+/// length, so it costs about half as much as a complex transform of the full length.
+#[derive(Clone, Debug)]
+pub struct RealFftPlan<T> {
+    half: FftPlan<T>,
+    // exp(-2 pi i k / len) for k up to len / 4, which separate the halves' spectra
+    twiddles: Vec<Complex<T>>,
+}
+
+/// A plan for type II discrete cosine transforms (the DCT) of one power-of-two length.
+///
+/// The transform computes `X[k] = sum(x[j] * cos(pi / n * (j + 1/2) * k))`, without
+/// normalization: the orthonormal DCT scales `X[0]` by `sqrt(1 / n)` and the other values by
+/// `sqrt(2 / n)`. The input is reordered into a real FFT of the same length, whose bins are
+/// rotated into the cosine coefficients. The plan holds the buffers for the reordered input and
+/// its spectrum, so transforms borrow it mutably.
+///
+/// ```
+/// use fast_fp::{ff32, fft::DctPlan, FF32};
+///
+/// let mut plan = DctPlan::<FF32>::new(4);
+/// let mut out = [ff32(0.0); 4];
+/// plan.forward(&[1.0, 1.0, 1.0, 1.0].map(ff32), &mut out);
+/// assert!((out[0] - 4.0).abs() < ff32(1e-6));
+/// assert!(out[1..].iter().all(|x| x.abs() < ff32(1e-6)));
+/// ```
+#[derive(Clone, Debug)]
+pub struct DctPlan<T> {
+    real: RealFftPlan<T>,
+    // exp(-pi i k / 2n) for each output
+    twiddles: Vec<Complex<T>>,
+    reordered: Vec<T>,
+    spectrum: Vec<Complex<T>>,
+}
+
+/// A plan for modified discrete cosine transforms (the MDCT), which transform `2 * len` values
+/// into `len` coefficients, for a power-of-two `len`.
+///
+/// With `c(j, k) = cos(pi / len * (j + 1/2 + len/2) * (k + 1/2))`, the forward transform
+/// computes `X[k] = sum(x[j] * c(j, k))` and the inverse computes `y[j] = sum(X[k] * c(j, k))`,
+/// both unnormalized. The MDCT is meant for frames which overlap by half: when both the input and
+/// output of each frame are multiplied by a window `w` with `w[j]^2 + w[j + len]^2 = 1` (like the
+/// sine window), the aliasing of neighboring frames cancels out, and adding the overlapping
+/// halves of the output reconstructs the signal scaled by `len / 2`.
+///
+/// Both directions fold the frame into a type IV DCT of length `len`, computed with a complex FFT
+/// of half that length, whose buffer the plan holds.
+#[derive(Clone, Debug)]
+pub struct MdctPlan<T> {
+    half: FftPlan<T>,
+    // exp(-pi i (j + 1/4) / len) before the FFT, and exp(-pi i k / len) after
+    pre: Vec<Complex<T>>,
+    post: Vec<Complex<T>>,
+    buf: Vec<Complex<T>>,
+}
+
+// the twiddle factor exp(-2 pi i k / n), computed in f64 for both types
+#[inline]
+fn twiddle(k: usize, n: usize) -> (f64, f64) {
+    let (sin, cos) = (-2.0 * core::f64::consts::PI * k as f64 / n as f64).sin_cos();
+    (cos, sin)
+}
+
+/// The fast scalar types which have spectrum kernels, used to select the implementation of the
+/// functions in this module.
+pub trait SpectrumKernels: Sized {
+    /// See [`magnitude`]
+    fn magnitude(spectrum: &[Complex<Self>], out: &mut [Self]);
+
+    /// See [`power`]
+    fn power(spectrum: &[Complex<Self>], out: &mut [Self]);
+
+    /// See [`phase`]
+    fn phase(spectrum: &[Complex<Self>], out: &mut [Self]);
+
+    /// See [`decibels`]
+    fn decibels(spectrum: &[Complex<Self>], out: &mut [Self], floor_db: Self);
+}
+
+/// Compute the magnitude of each bin of a spectrum, `sqrt(re^2 + im^2)`, storing them in `out`.
+///
+/// Like [`complex::norm`](crate::complex::norm), this doesn't guard against overflow in the
+/// intermediate squares.
+///
+/// # Panics
+/// Panics if the two slices have different lengths
+#[inline]
+pub fn magnitude<T: SpectrumKernels>(spectrum: &[Complex<T>], out: &mut [T]) {
+    T::magnitude(spectrum, out)
+}
+
+/// Compute the power of each bin of a spectrum, `re^2 + im^2`, storing them in `out`.
+///
+/// # Panics
+/// Panics if the two slices have different lengths
+#[inline]
+pub fn power<T: SpectrumKernels>(spectrum: &[Complex<T>], out: &mut [T]) {
+    T::power(spectrum, out)
+}
+
+/// Compute the phase of each bin of a spectrum in radians, `atan2(im, re)`, storing them in
+/// `out`. The phases are between `-pi` and `pi`.
+///
+/// # Panics
+/// Panics if the two slices have different lengths
+#[inline]
+pub fn phase<T: SpectrumKernels>(spectrum: &[Complex<T>], out: &mut [T]) {
+    T::phase(spectrum, out)
+}
+
+/// Compute the level of each bin of a spectrum in decibels, `10 * log10(re^2 + im^2)`, storing
+/// them in `out`.
+///
+/// This is both the power in decibels and the magnitude in decibels, `20 * log10(magnitude)`.
+/// Levels below `floor_db` are raised to it, which also keeps empty bins (whose level would be
+/// negative infinity) finite.
+///
+/// # Panics
+/// Panics if the two slices have different lengths
+#[inline]
+pub fn decibels<T: SpectrumKernels>(spectrum: &[Complex<T>], out: &mut [T], floor_db: T) {
+    T::decibels(spectrum, out, floor_db)
+}
+
+macro_rules! impl_fft {
+    ($fast_ty:ident, $base_ty:ident) => {
+        paste! {
+            #[cfg(not(scalar_fallback))]
+            #[link(name = "poison_safe")]
+            extern "C" {
+                // `data` holds `len` complex values in bit-reversed order. Like the scalar
+                // poison_safe functions, this can accept poison values
+                fn [<fft_passes_ $base_ty>](
+                    data: *mut Complex<$fast_ty>,
+                    len: usize,
+                    twiddles: *const Complex<$fast_ty>,
+                );
+                fn [<power_spectrum_ $base_ty>](z: *const Complex<$fast_ty>, out: *mut $fast_ty, len: usize);
+            }
+
+            #[cfg(not(scalar_fallback))]
+            #[link(name = "poison_unsafe")]
+            extern "C" {
+                // functions in the poison_unsafe lib must have their arguments frozen, so the
+                // inputs are passed as the base type, with the complex values interleaved
+                fn [<magnitude_spectrum_ $base_ty>](z: *const $base_ty, out: *mut $fast_ty, len: usize);
+                fn [<phase_spectrum_ $base_ty>](z: *const $base_ty, out: *mut $fast_ty, len: usize);
+                fn [<decibel_spectrum_ $base_ty>](
+                    z: *const $base_ty,
+                    out: *mut $fast_ty,
+                    len: usize,
+                    floor_db: $base_ty,
+                );
+            }
+
+            // with the strict feature, the shims are replaced by the same passes and formulas over
+            // the base type
+            #[cfg(scalar_fallback)]
+            mod [<strict_fft_ $base_ty>] {
+                use super::*;
+
+                #[inline(always)]
+                unsafe fn map(
+                    z: *const $base_ty,
+                    out: *mut $fast_ty,
+                    len: usize,
+                    op: impl Fn($base_ty, $base_ty) -> $base_ty,
+                ) {
+                    let z = core::slice::from_raw_parts(z, 2 * len);
+                    for (out, z) in core::slice::from_raw_parts_mut(out, len).iter_mut().zip(z.chunks_exact(2)) {
+                        *out = <$fast_ty>::new(op(z[0], z[1]));
+                    }
+                }
+
+                #[inline(always)]
+                pub(super) unsafe fn [<power_spectrum_ $base_ty>](
+                    z: *const Complex<$fast_ty>,
+                    out: *mut $fast_ty,
+                    len: usize,
+                ) {
+                    for (out, z) in core::slice::from_raw_parts_mut(out, len).iter_mut().zip(core::slice::from_raw_parts(z, len)) {
+                        let (re, im) = (z.re.freeze_raw(), z.im.freeze_raw());
+                        *out = <$fast_ty>::new(re * re + im * im);
+                    }
+                }
+
+                #[inline(always)]
+                pub(super) unsafe fn [<magnitude_spectrum_ $base_ty>](z: *const $base_ty, out: *mut $fast_ty, len: usize) {
+                    map(z, out, len, |re, im| (re * re + im * im).sqrt())
+                }
+
+                #[inline(always)]
+                pub(super) unsafe fn [<phase_spectrum_ $base_ty>](z: *const $base_ty, out: *mut $fast_ty, len: usize) {
+                    map(z, out, len, |re, im| im.atan2(re))
+                }
+
+                #[inline(always)]
+                pub(super) unsafe fn [<decibel_spectrum_ $base_ty>](
+                    z: *const $base_ty,
+                    out: *mut $fast_ty,
+                    len: usize,
+                    floor_db: $base_ty,
+                ) {
+                    map(z, out, len, |re, im| (10.0 * (re * re + im * im).log10()).max(floor_db))
+                }
+
+                #[inline(always)]
+                pub(super) unsafe fn [<fft_passes_ $base_ty>](
+                    data: *mut Complex<$fast_ty>,
+                    len: usize,
+                    mut twiddles: *const Complex<$fast_ty>,
+                ) {
+                    let freeze = |z: Complex<$fast_ty>| Complex::new(z.re.freeze_raw(), z.im.freeze_raw());
+                    let thaw = |z: Complex<$base_ty>| Complex::new(<$fast_ty>::new(z.re), <$fast_ty>::new(z.im));
+                    let data = core::slice::from_raw_parts_mut(data, len);
+
+                    let mut m = 1;
+                    if len.trailing_zeros() % 2 == 1 {
+                        for pair in data.chunks_exact_mut(2) {
+                            let (a, b) = (freeze(pair[0]), freeze(pair[1]));
+                            pair[0] = thaw(a + b);
+                            pair[1] = thaw(a - b);
+                        }
+                        m = 2;
+                    }
+
+                    while 4 * m <= len {
+                        let w = core::slice::from_raw_parts(twiddles, 3 * m);
+                        for block in data.chunks_exact_mut(4 * m) {
+                            for k in 0..m {
+                                let a = freeze(block[k]);
+                                let c = freeze(block[k + m]) * freeze(w[3 * k + 1]);
+                                let b = freeze(block[k + 2 * m]) * freeze(w[3 * k]);
+                                let e = freeze(block[k + 3 * m]) * freeze(w[3 * k + 2]);
+                                let (s0, s1, s2) = (a + c, a - c, b + e);
+                                let s3 = (b - e) * Complex::i();
+                                block[k] = thaw(s0 + s2);
+                                block[k + m] = thaw(s1 - s3);
+                                block[k + 2 * m] = thaw(s0 - s2);
+                                block[k + 3 * m] = thaw(s1 + s3);
+                            }
+                        }
+                        twiddles = twiddles.add(3 * m);
+                        m *= 4;
+                    }
+                }
+            }
+
+            #[cfg(scalar_fallback)]
+            use [<strict_fft_ $base_ty>]::*;
+
+            // freeze a block of complex values at a time into a buffer, which is passed to the
+            // poison unsafe kernel along with the matching block of the output
+            #[inline(always)]
+            fn [<for_frozen_ $base_ty>](
+                spectrum: &[Complex<$fast_ty>],
+                out: &mut [$fast_ty],
+                mut kernel: impl FnMut(&[$base_ty], &mut [$fast_ty]),
+            ) {
+                assert_eq!(spectrum.len(), out.len(), "spectrum and output lengths differ");
+                let mut frozen = [0.0; 2 * SPECTRUM_BLOCK];
+                for (z, out) in spectrum.chunks(SPECTRUM_BLOCK).zip(out.chunks_mut(SPECTRUM_BLOCK)) {
+                    let frozen = &mut frozen[..2 * z.len()];
+                    for (frozen, z) in frozen.chunks_exact_mut(2).zip(z) {
+                        frozen[0] = z.re.freeze_raw();
+                        frozen[1] = z.im.freeze_raw();
+                    }
+                    kernel(frozen, out);
+                }
+            }
+
+            impl SpectrumKernels for $fast_ty {
+                #[inline]
+                fn magnitude(spectrum: &[Complex<Self>], out: &mut [Self]) {
+                    [<for_frozen_ $base_ty>](spectrum, out, |z, out| unsafe {
+                        [<magnitude_spectrum_ $base_ty>](z.as_ptr(), out.as_mut_ptr(), out.len())
+                    });
+                }
+
+                #[inline]
+                fn power(spectrum: &[Complex<Self>], out: &mut [Self]) {
+                    assert_eq!(spectrum.len(), out.len(), "spectrum and output lengths differ");
+                    unsafe { [<power_spectrum_ $base_ty>](spectrum.as_ptr(), out.as_mut_ptr(), out.len()) };
+                }
+
+                #[inline]
+                fn phase(spectrum: &[Complex<Self>], out: &mut [Self]) {
+                    [<for_frozen_ $base_ty>](spectrum, out, |z, out| unsafe {
+                        [<phase_spectrum_ $base_ty>](z.as_ptr(), out.as_mut_ptr(), out.len())
+                    });
+                }
+
+                #[inline]
+                fn decibels(spectrum: &[Complex<Self>], out: &mut [Self], floor_db: Self) {
+                    let floor_db = floor_db.freeze_raw();
+                    [<for_frozen_ $base_ty>](spectrum, out, |z, out| unsafe {
+                        [<decibel_spectrum_ $base_ty>](z.as_ptr(), out.as_mut_ptr(), out.len(), floor_db)
+                    });
+                }
+            }
+
+            // the twiddle factor exp(-2 pi i k / n), rounded from f64
+            #[inline]
+            fn [<twiddle_ $base_ty>](k: usize, n: usize) -> Complex<$fast_ty> {
+                let (re, im) = twiddle(k, n);
+                Complex::new(<$fast_ty>::new(re as $base_ty), <$fast_ty>::new(im as $base_ty))
+            }
+
+            impl FftPlan<$fast_ty> {
+                /// Create a plan for transforms of `len` values.
+                ///
+                /// # Panics
+                /// Panics if `len` is not a power of two
+                pub fn new(len: usize) -> Self {
+                    assert!(len.is_power_of_two(), "length must be a power of two");
+                    let bits = len.trailing_zeros();
+                    let bitrev =
+                        (0..len).map(|i| i.reverse_bits().checked_shr(usize::BITS - bits).unwrap_or(0)).collect();
+
+                    let mut twiddles = Vec::new();
+                    let mut m = if bits % 2 == 1 { 2 } else { 1 };
+                    while 4 * m <= len {
+                        for k in 0..m {
+                            for r in 1..=3 {
+                                twiddles.push([<twiddle_ $base_ty>](r * k, 4 * m));
+                            }
+                        }
+                        m *= 4;
+                    }
+
+                    FftPlan { bitrev, twiddles }
+                }
+
+                /// Compute the forward transform of `data` in place.
+                ///
+                /// # Panics
+                /// Panics if the length of `data` differs from the plan's
+                #[inline]
+                pub fn forward(&self, data: &mut [Complex<$fast_ty>]) {
+                    assert_eq!(data.len(), self.bitrev.len(), "data length differs from the plan's");
+                    for (i, &j) in self.bitrev.iter().enumerate() {
+                        if i < j {
+                            data.swap(i, j);
+                        }
+                    }
+                    unsafe { [<fft_passes_ $base_ty>](data.as_mut_ptr(), data.len(), self.twiddles.as_ptr()) };
+                }
+
+                /// Compute the unnormalized inverse transform of `data` in place, so that
+                /// transforming forward and back scales the values by the length.
+                ///
+                /// # Panics
+                /// Panics if the length of `data` differs from the plan's
+                #[inline]
+                pub fn inverse(&self, data: &mut [Complex<$fast_ty>]) {
+                    // swapping the real and imaginary parts conjugates the values and multiplies
+                    // them by i, so swapping around the forward transform computes the inverse
+                    let swap = |data: &mut [Complex<$fast_ty>]| {
+                        data.iter_mut().for_each(|z| core::mem::swap(&mut z.re, &mut z.im))
+                    };
+                    swap(data);
+                    self.forward(data);
+                    swap(data);
+                }
+            }
+
+            impl RealFftPlan<$fast_ty> {
+                /// Create a plan for transforms of `len` real values.
+                ///
+                /// # Panics
+                /// Panics if `len` is not a power of two, or is less than two
+                pub fn new(len: usize) -> Self {
+                    assert!(len >= 2 && len.is_power_of_two(), "length must be a power of two of at least 2");
+                    let twiddles = (0..=len / 4).map(|k| [<twiddle_ $base_ty>](k, len)).collect();
+                    RealFftPlan { half: FftPlan::<$fast_ty>::new(len / 2), twiddles }
+                }
+
+                /// Compute the first `len / 2 + 1` bins of the forward transform of `input`,
+                /// storing them in `output`. The remaining bins are the conjugates of these, in
+                /// reverse order.
+                ///
+                /// # Panics
+                /// Panics if the length of `input` differs from the plan's, or `output` doesn't
+                /// have `len / 2 + 1` values
+                pub fn forward(&self, input: &[$fast_ty], output: &mut [Complex<$fast_ty>]) {
+                    let m = self.half.bitrev.len();
+                    assert_eq!(input.len(), 2 * m, "input length differs from the plan's");
+                    assert_eq!(output.len(), m + 1, "output length must be half the plan's plus one");
+
+                    // transform the even values as the real parts and the odd values as the
+                    // imaginary parts, then separate their spectra
+                    for (out, pair) in output.iter_mut().zip(input.chunks_exact(2)) {
+                        *out = Complex::new(pair[0], pair[1]);
+                    }
+                    self.half.forward(&mut output[..m]);
+                    output[m] = output[0];
+
+                    // each pair of bins k and m - k is computed from the same two values
+                    let minus_half_i = Complex::new(<$fast_ty>::ZERO, <$fast_ty>::new(-0.5));
+                    for k in 0..=m / 2 {
+                        let (z, z_mirror) = (output[k], output[m - k].conj());
+                        let even = (z + z_mirror) * <$fast_ty>::new(0.5);
+                        let odd = (z - z_mirror) * minus_half_i * self.twiddles[k];
+                        output[k] = even + odd;
+                        output[m - k] = (even - odd).conj();
+                    }
+                }
+
+                /// Compute the unnormalized inverse transform of the first `len / 2 + 1` bins of
+                /// a conjugate symmetric spectrum, storing the real values in `output`. As with
+                /// complex transforms, transforming forward and back scales the values by the
+                /// length.
+                ///
+                /// The imaginary parts of the first and last bins are ignored.
+                ///
+                /// # Panics
+                /// Panics if `input` doesn't have `len / 2 + 1` values, or the length of `output`
+                /// differs from the plan's
+                pub fn inverse(&self, input: &[Complex<$fast_ty>], output: &mut [$fast_ty]) {
+                    let m = self.half.bitrev.len();
+                    assert_eq!(input.len(), m + 1, "input length must be half the plan's plus one");
+                    assert_eq!(output.len(), 2 * m, "output length differs from the plan's");
+
+                    // the output holds the even values in the real parts of a complex buffer,
+                    // and the odd values in the imaginary parts, which is combined from the two
+                    // halves' spectra and transformed in place
+                    let packed = unsafe {
+                        core::slice::from_raw_parts_mut(output.as_mut_ptr().cast::<Complex<$fast_ty>>(), m)
+                    };
+                    for k in 0..=m / 2 {
+                        let (x, x_mirror) = (input[k], input[m - k].conj());
+                        let even = x + x_mirror;
+                        let odd = (x - x_mirror) * self.twiddles[k].conj();
+                        packed[k] = even + odd * Complex::i();
+                        if k > 0 {
+                            packed[m - k] = even.conj() + odd.conj() * Complex::i();
+                        }
+                    }
+                    self.half.inverse(packed);
+                }
+            }
+
+            impl DctPlan<$fast_ty> {
+                /// Create a plan for transforms of `len` values.
+                ///
+                /// # Panics
+                /// Panics if `len` is not a power of two, or is less than two
+                pub fn new(len: usize) -> Self {
+                    let real = RealFftPlan::<$fast_ty>::new(len);
+                    DctPlan {
+                        real,
+                        twiddles: (0..len).map(|k| [<twiddle_ $base_ty>](k, 4 * len)).collect(),
+                        reordered: vec![<$fast_ty>::ZERO; len],
+                        spectrum: vec![Complex::new(<$fast_ty>::ZERO, <$fast_ty>::ZERO); len / 2 + 1],
+                    }
+                }
+
+                /// Compute the transform of `input`, storing it in `output`.
+                ///
+                /// # Panics
+                /// Panics if the length of either slice differs from the plan's
+                pub fn forward(&mut self, input: &[$fast_ty], output: &mut [$fast_ty]) {
+                    let len = self.twiddles.len();
+                    assert_eq!(input.len(), len, "input length differs from the plan's");
+                    assert_eq!(output.len(), len, "output length differs from the plan's");
+
+                    // the even values in order followed by the odd values in reverse, whose DFT
+                    // is the DCT rotated by a quarter of each frequency's phase step
+                    for (j, pair) in input.chunks_exact(2).enumerate() {
+                        self.reordered[j] = pair[0];
+                        self.reordered[len - 1 - j] = pair[1];
+                    }
+                    self.real.forward(&self.reordered, &mut self.spectrum);
+
+                    // the bins past the middle are the conjugates of those before it
+                    for (k, (out, twiddle)) in output.iter_mut().zip(&self.twiddles).enumerate() {
+                        let bin = if k <= len / 2 { self.spectrum[k] } else { self.spectrum[len - k].conj() };
+                        *out = (bin * twiddle).re;
+                    }
+                }
+            }
+
+            impl MdctPlan<$fast_ty> {
+                /// Create a plan for transforms of `2 * len` values into `len` coefficients.
+                ///
+                /// # Panics
+                /// Panics if `len` is not a power of two, or is less than two
+                pub fn new(len: usize) -> Self {
+                    assert!(len >= 2 && len.is_power_of_two(), "length must be a power of two of at least 2");
+                    MdctPlan {
+                        half: FftPlan::<$fast_ty>::new(len / 2),
+                        pre: (0..len / 2).map(|j| [<twiddle_ $base_ty>](4 * j + 1, 8 * len)).collect(),
+                        post: (0..len / 2).map(|k| [<twiddle_ $base_ty>](k, 2 * len)).collect(),
+                        buf: vec![Complex::new(<$fast_ty>::ZERO, <$fast_ty>::ZERO); len / 2],
+                    }
+                }
+
+                /// Compute the coefficients of a frame of `2 * len` values, storing them in
+                /// `output`.
+                ///
+                /// # Panics
+                /// Panics if `input` doesn't have `2 * len` values, or `output` doesn't have
+                /// `len` values
+                pub fn forward(&mut self, input: &[$fast_ty], output: &mut [$fast_ty]) {
+                    let len = 2 * self.pre.len();
+                    assert_eq!(input.len(), 2 * len, "input length must be twice the plan's");
+                    assert_eq!(output.len(), len, "output length differs from the plan's");
+
+                    // with the frame split into quarters (a, b, c, d), the MDCT is the DCT-IV of
+                    // (-c_reversed - d, a - b_reversed)
+                    let half = len / 2;
+                    let folded = |j: usize| {
+                        if j < half {
+                            -input[3 * half - 1 - j] - input[3 * half + j]
+                        } else {
+                            input[j - half] - input[3 * half - 1 - j]
+                        }
+                    };
+                    self.dct4(folded, |k, x| output[k] = x);
+                }
+
+                /// Compute the frame of `2 * len` values from a frame's coefficients, storing it
+                /// in `output`.
+                ///
+                /// # Panics
+                /// Panics if `input` doesn't have `len` values, or `output` doesn't have
+                /// `2 * len` values
+                pub fn inverse(&mut self, input: &[$fast_ty], output: &mut [$fast_ty]) {
+                    let len = 2 * self.pre.len();
+                    assert_eq!(input.len(), len, "input length differs from the plan's");
+                    assert_eq!(output.len(), 2 * len, "output length must be twice the plan's");
+
+                    // unfold the DCT-IV of the coefficients, (w1, w2), into
+                    // (w2, -w2_reversed, -w1_reversed, -w1)
+                    let half = len / 2;
+                    self.dct4(|k| input[k], |j, w| {
+                        if j < half {
+                            output[3 * half - 1 - j] = -w;
+                            output[3 * half + j] = -w;
+                        } else {
+                            output[j - half] = w;
+                            output[3 * half - 1 - j] = -w;
+                        }
+                    });
+                }
+
+                // the type IV DCT of the `len` values from `input`, passing each output with its
+                // index to `output`. Pairs of values from both ends are packed into complex values,
+                // transformed with a complex FFT of half the length between two rotations, and
+                // the real and imaginary parts of the result are the outputs from both ends
+                #[inline(always)]
+                fn dct4(&mut self, input: impl Fn(usize) -> $fast_ty, mut output: impl FnMut(usize, $fast_ty)) {
+                    let len = 2 * self.pre.len();
+                    for (j, (buf, pre)) in self.buf.iter_mut().zip(&self.pre).enumerate() {
+                        *buf = Complex::new(input(2 * j), input(len - 1 - 2 * j)) * pre;
+                    }
+                    self.half.forward(&mut self.buf);
+                    for (k, (buf, post)) in self.buf.iter().zip(&self.post).enumerate() {
+                        let x = buf * post;
+                        output(2 * k, x.re);
+                        output(len - 1 - 2 * k, -x.im);
+                    }
+                }
+            }
+        }
+    };
+}
+
+impl_fft! { FF32, f32 }
+impl_fft! { FF64, f64 }
@@ -0,0 +1,212 @@
+use crate::{FF32, FF64};
+
+/// A histogram of fast values, over uniform or explicit bins, which also tracks the sum of the
+/// values in each bin.
+///
+/// Each bin covers a half-open range `[lo, hi)`, except the last which also includes its upper
+/// edge. Values outside the edges aren't counted in any bin, and are tallied separately as
+/// [`underflow`](Histogram::underflow) and [`overflow`](Histogram::overflow).
+///
+/// [`add_slice`](Histogram::add_slice) ingests values in batches: the bin index of every value in
+/// a batch is computed first, in a loop that compiles to vector instructions for uniform bins, and
+/// the counts are updated afterwards. Uniform bins locate a value with a multiplication rather
+/// than by comparing it to the edges, so a value which is within rounding error of an edge may
+/// land in the neighboring bin.
+///
+/// ```
+/// use fast_fp::{ff32, Histogram, FF32};
+///
+/// let mut hist = Histogram::<FF32>::uniform(ff32(0.0), ff32(4.0), 4);
+/// hist.add_slice(&[0.5, 1.5, 1.75, 3.0, 4.0, 9.0].map(ff32));
+///
+/// assert_eq!(hist.counts(), [1, 2, 0, 2]);
+/// assert_eq!(hist.overflow(), 1);
+/// assert_eq!(hist.mean(1), Some(ff32(1.625)));
+/// assert_eq!(hist.mean(2), None);
+///
+/// let edges = [0.0, 1.0, 10.0, 100.0].map(ff32).to_vec();
+/// let mut hist = Histogram::<FF32>::with_edges(edges);
+/// hist.add_slice(&[-1.0, 0.0, 5.0, 50.0, 100.0].map(ff32));
+///
+/// assert_eq!(hist.counts(), [1, 1, 2]);
+/// assert_eq!(hist.underflow(), 1);
+/// assert_eq!(hist.total(), 5);
+/// ```
+#[derive(Clone, Debug)]
+pub struct Histogram<T> {
+    edges: Vec<T>,
+    // the slots hold the underflow first, then each bin, then the overflow
+    counts: Vec<u64>,
+    sums: Vec<T>,
+    uniform: bool,
+}
+
+// the number of values whose bin indices are computed together in `add_slice`
+const HISTOGRAM_BATCH: usize = 64;
+
+macro_rules! impl_histogram {
+    ($fast_ty:ident, $base_ty:ident) => {
+        impl Histogram<$fast_ty> {
+            /// Create an empty histogram with `bins` bins of equal width, spanning `min` to `max`.
+            ///
+            /// # Panics
+            /// Panics if `bins` is zero or `min` is not less than `max`
+            #[inline]
+            pub fn uniform(min: $fast_ty, max: $fast_ty, bins: usize) -> Self {
+                assert!(bins > 0, "histogram must have at least one bin");
+                assert!(min < max, "histogram minimum must be less than its maximum");
+
+                let width = (max - min) / bins as $base_ty;
+                let mut edges: Vec<_> = (0..bins).map(|i| min + width * i as $base_ty).collect();
+                edges.push(max);
+                Self::from_edges(edges, true)
+            }
+
+            /// Create an empty histogram with the given bin edges, so that bin `i` spans
+            /// `edges[i]` to `edges[i + 1]`.
+            ///
+            /// # Panics
+            /// Panics if there are fewer than two edges, or they are not strictly increasing
+            #[inline]
+            pub fn with_edges(edges: Vec<$fast_ty>) -> Self {
+                assert!(edges.len() >= 2, "histogram must have at least two edges");
+                assert!(
+                    edges.windows(2).all(|pair| pair[0] < pair[1]),
+                    "histogram edges must be strictly increasing"
+                );
+                Self::from_edges(edges, false)
+            }
+
+            #[inline]
+            fn from_edges(edges: Vec<$fast_ty>, uniform: bool) -> Self {
+                let slots = edges.len() + 1;
+                Histogram {
+                    edges,
+                    counts: vec![0; slots],
+                    sums: vec![<$fast_ty>::ZERO; slots],
+                    uniform,
+                }
+            }
+
+            /// Add a value to the histogram
+            #[inline]
+            pub fn add(&mut self, value: $fast_ty) {
+                let slot = self.slot(value);
+                self.record(slot, value);
+            }
+
+            /// Add all values in a slice to the histogram
+            #[inline]
+            pub fn add_slice(&mut self, values: &[$fast_ty]) {
+                let mut slots = [0; HISTOGRAM_BATCH];
+                for batch in values.chunks(HISTOGRAM_BATCH) {
+                    for (slot, value) in slots.iter_mut().zip(batch) {
+                        *slot = self.slot(*value);
+                    }
+                    for (slot, value) in slots.iter().zip(batch) {
+                        self.record(*slot, *value);
+                    }
+                }
+            }
+
+            // the slot of a value's bin, including the underflow and overflow slots
+            #[inline(always)]
+            fn slot(&self, value: $fast_ty) -> usize {
+                let bins = self.bins();
+                // the value is frozen first, so the comparisons and index below are well defined
+                // even for poison values
+                let value = value.freeze_raw();
+                let min = self.edges[0].freeze_raw();
+                let max = self.edges[bins].freeze_raw();
+
+                if value < min {
+                    0
+                } else if value > max {
+                    bins + 1
+                } else if self.uniform {
+                    let position = (value - min) / (max - min) * bins as $base_ty;
+                    // `as` saturates, and the last bin includes the maximum
+                    (position as usize).min(bins - 1) + 1
+                } else {
+                    // the number of edges at or below the value, which is the slot of its bin
+                    // except for the maximum itself
+                    let slot = self
+                        .edges
+                        .partition_point(|edge| edge.freeze_raw() <= value);
+                    slot.min(bins)
+                }
+            }
+
+            #[inline(always)]
+            fn record(&mut self, slot: usize, value: $fast_ty) {
+                self.counts[slot] += 1;
+                self.sums[slot] += value;
+            }
+
+            /// The number of bins
+            #[inline]
+            pub fn bins(&self) -> usize {
+                self.edges.len() - 1
+            }
+
+            /// The bin edges, one more than the number of bins
+            #[inline]
+            pub fn edges(&self) -> &[$fast_ty] {
+                &self.edges
+            }
+
+            /// The number of values in each bin
+            #[inline]
+            pub fn counts(&self) -> &[u64] {
+                &self.counts[1..self.edges.len()]
+            }
+
+            /// The sum of the values in each bin
+            #[inline]
+            pub fn sums(&self) -> &[$fast_ty] {
+                &self.sums[1..self.edges.len()]
+            }
+
+            /// The mean of the values in a bin, or `None` if the bin is empty.
+            ///
+            /// # Panics
+            /// Panics if `bin` is out of bounds
+            #[inline]
+            pub fn mean(&self, bin: usize) -> Option<$fast_ty> {
+                let (count, sum) = (self.counts()[bin], self.sums()[bin]);
+                if count == 0 {
+                    return None;
+                }
+                Some(sum / count as $base_ty)
+            }
+
+            /// The number of values below the first edge
+            #[inline]
+            pub fn underflow(&self) -> u64 {
+                self.counts[0]
+            }
+
+            /// The number of values above the last edge
+            #[inline]
+            pub fn overflow(&self) -> u64 {
+                self.counts[self.edges.len()]
+            }
+
+            /// The number of values added, including those outside the edges
+            #[inline]
+            pub fn total(&self) -> u64 {
+                self.counts.iter().sum()
+            }
+
+            /// Reset all counts and sums to zero, keeping the bins
+            #[inline]
+            pub fn clear(&mut self) {
+                self.counts.iter_mut().for_each(|count| *count = 0);
+                self.sums.iter_mut().for_each(|sum| *sum = <$fast_ty>::ZERO);
+            }
+        }
+    };
+}
+
+impl_histogram! { FF32, f32 }
+impl_histogram! { FF64, f64 }
@@ -0,0 +1,165 @@
+//! One-dimensional interpolation: piecewise linear, cubic Hermite, Catmull-Rom and cubic splines.
+//!
+//! [`linear`] interpolates a table of points directly, finding the segment around `x` with a
+//! binary search. [`hermite`] and [`catmull_rom`] evaluate a single cubic segment from its end
+//! points and their slopes, or from its neighbouring points, as in animation curves.
+//! [`CubicSpline`] fits a smooth curve through a whole table once, storing the polynomial of each
+//! segment, so that each evaluation is only a binary search and a short polynomial.
+//!
+//! ```
+//! use fast_fp::{ff32, interp};
+//!
+//! let xs = [0.0, 1.0, 3.0].map(ff32);
+//! let ys = [0.0, 2.0, 3.0].map(ff32);
+//! assert_eq!(interp::linear(&xs, &ys, ff32(0.5)), 1.0);
+//! assert_eq!(interp::linear(&xs, &ys, ff32(2.0)), 2.5);
+//!
+//! // values beyond the table are clamped to its ends
+//! assert_eq!(interp::linear(&xs, &ys, ff32(5.0)), 3.0);
+//!
+//! assert_eq!(interp::catmull_rom(ff32(0.0), ff32(1.0), ff32(2.0), ff32(3.0), ff32(0.5)), 1.5);
+//! ```
+
+use crate::FastFloat;
+
+/// Interpolate linearly between the points `(xs[i], ys[i])` at `x`.
+///
+/// The `xs` must be sorted in ascending order, without repeats. Outside of their range, the result
+/// is the `ys` value at the nearest end.
+///
+/// # Panics
+/// Panics if the slices have different lengths, or are empty
+#[inline]
+pub fn linear<T: FastFloat>(xs: &[T], ys: &[T], x: T) -> T {
+    assert_eq!(xs.len(), ys.len(), "slice lengths differ");
+    assert!(!xs.is_empty(), "no points to interpolate");
+    if xs.len() == 1 {
+        return ys[0];
+    }
+
+    let i = segment(xs, x);
+    let t = xs[i].inverse_lerp(xs[i + 1], x).clamp01();
+    ys[i].lerp(ys[i + 1], t)
+}
+
+/// The cubic Hermite curve from `p0` to `p1` at `t` in `[0, 1]`, with the slopes `m0` and `m1` at
+/// its ends.
+///
+/// The slopes are with respect to `t`, so for a segment of width `h` in `x` they're the
+/// derivatives in `x` multiplied by `h`.
+#[inline]
+pub fn hermite<T: FastFloat>(p0: T, m0: T, p1: T, m1: T, t: T) -> T {
+    // the curve in powers of t, rather than as a sum of the four basis functions
+    let (two, three) = (T::from_f64(2.0), T::from_f64(3.0));
+    let d = p1 - p0;
+    let c2 = three * d - two * m0 - m1;
+    let c3 = m0 + m1 - two * d;
+    p0 + t * (m0 + t * (c2 + t * c3))
+}
+
+/// The uniform Catmull-Rom curve from `p1` to `p2` at `t` in `[0, 1]`, shaped by their neighbours
+/// `p0` and `p3`.
+///
+/// This is the [`hermite`] curve with the slopes `(p2 - p0) / 2` and `(p3 - p1) / 2`, so that a
+/// sequence of segments through evenly spaced points joins with a continuous slope.
+#[inline]
+pub fn catmull_rom<T: FastFloat>(p0: T, p1: T, p2: T, p3: T, t: T) -> T {
+    let half = T::from_f64(0.5);
+    hermite(p1, half * (p2 - p0), p2, half * (p3 - p1), t)
+}
+
+/// A natural cubic spline through a table of points, for fast repeated evaluation.
+///
+/// The spline is the smoothest curve through the points: each segment between two points is a
+/// cubic, the curve and its first two derivatives are continuous, and its second derivative is
+/// zero at both ends. Its construction solves a tridiagonal system for the second derivatives,
+/// and stores the coefficients of each segment's cubic. Outside of the table, the first or last
+/// segment's cubic is extended.
+///
+/// ```
+/// use fast_fp::{ff64, interp::CubicSpline, FF64};
+///
+/// let xs = [0.0, 1.0, 2.0, 3.0, 4.0].map(ff64);
+/// let ys = xs.map(|x| x.sin());
+/// let spline = CubicSpline::<FF64>::new(&xs, &ys);
+///
+/// // the spline passes through the points, and is close to sin between them
+/// assert!((spline.eval(ff64(2.0)) - ff64(2.0).sin()).abs() < ff64(1e-12));
+/// assert!((spline.eval(ff64(1.5)) - ff64(1.5).sin()).abs() < ff64(0.02));
+/// ```
+#[derive(Clone, Debug)]
+pub struct CubicSpline<T> {
+    xs: Vec<T>,
+    // the coefficients of each segment's cubic in the distance from its start, constant term first
+    coeffs: Vec<[T; 4]>,
+}
+
+impl<T: FastFloat> CubicSpline<T> {
+    /// Fit the spline through the points `(xs[i], ys[i])`.
+    ///
+    /// The `xs` must be sorted in ascending order, without repeats. With two points the spline is
+    /// the line through them.
+    ///
+    /// # Panics
+    /// Panics if the slices have different lengths, or have fewer than two points
+    #[inline]
+    pub fn new(xs: &[T], ys: &[T]) -> Self {
+        assert_eq!(xs.len(), ys.len(), "slice lengths differ");
+        assert!(xs.len() >= 2, "a spline needs at least two points");
+        let (two, six) = (T::from_f64(2.0), T::from_f64(6.0));
+        let n = xs.len();
+        let h: Vec<T> = xs.windows(2).map(|w| w[1] - w[0]).collect();
+        let slopes: Vec<T> = ys
+            .windows(2)
+            .zip(&h)
+            .map(|(w, &h)| (w[1] - w[0]) / h)
+            .collect();
+
+        // The second derivatives m, with m[0] = m[n - 1] = 0, solve the tridiagonal system
+        // h[i - 1] m[i - 1] + 2 (h[i - 1] + h[i]) m[i] + h[i] m[i + 1]
+        //     = 6 (slopes[i] - slopes[i - 1])
+        // which is solved by forward elimination and back substitution
+        let mut m = vec![T::ZERO; n];
+        let mut upper = vec![T::ZERO; n];
+        for i in 1..n - 1 {
+            let diagonal = two * (h[i - 1] + h[i]) - h[i - 1] * upper[i - 1];
+            upper[i] = h[i] / diagonal;
+            m[i] = (six * (slopes[i] - slopes[i - 1]) - h[i - 1] * m[i - 1]) / diagonal;
+        }
+        for i in (1..n - 1).rev() {
+            let next = m[i + 1];
+            m[i] -= upper[i] * next;
+        }
+
+        let coeffs = (0..n - 1)
+            .map(|i| {
+                let b = slopes[i] - h[i] * (two * m[i] + m[i + 1]) * T::from_f64(1.0 / 6.0);
+                let d = (m[i + 1] - m[i]) / (six * h[i]);
+                [ys[i], b, T::from_f64(0.5) * m[i], d]
+            })
+            .collect();
+        CubicSpline {
+            xs: xs.to_vec(),
+            coeffs,
+        }
+    }
+
+    /// Evaluate the spline at `x`
+    #[inline]
+    pub fn eval(&self, x: T) -> T {
+        let i = segment(&self.xs, x);
+        let [a, b, c, d] = self.coeffs[i];
+        let t = x - self.xs[i];
+        a + t * (b + t * (c + t * d))
+    }
+}
+
+// The index of the segment of the sorted xs which contains x, or the nearest segment if x is
+// outside of them. There must be at least two xs. Comparisons freeze their operands, so the
+// search is safe for poison values
+#[inline(always)]
+fn segment<T: FastFloat>(xs: &[T], x: T) -> usize {
+    xs.partition_point(|&v| v <= x)
+        .saturating_sub(1)
+        .min(xs.len() - 2)
+}
@@ -0,0 +1,125 @@
+use crate::{FF32, FF64};
+
+/// Fast-math reductions for iterators of plain `f32` and `f64` values, for data which is stored
+/// as the standard float types but should still be aggregated quickly.
+///
+/// The values are converted to the fast scalar type, so the same restrictions apply: values
+/// **MUST NOT** be infinite or NaN, and the output of any reduction involving such values is
+/// unspecified.
+///
+/// ```
+/// use fast_fp::FastIterExt;
+///
+/// let values = vec![1.0f32, 2.0, 3.0, 4.0];
+///
+/// assert_eq!(values.iter().fast_sum(), 10.0);
+/// assert_eq!(values.iter().fast_product(), 24.0);
+/// assert_eq!(values.iter().copied().fast_min(), Some(1.0.into()));
+/// assert_eq!(values.iter().fast_max(), Some(4.0.into()));
+/// assert_eq!(Vec::<f64>::new().into_iter().fast_max(), None);
+/// ```
+pub trait FastIterExt: Iterator + Sized {
+    /// Sum all values, added in an unspecified order.
+    ///
+    /// The sum of an empty iterator is zero.
+    #[inline]
+    fn fast_sum(self) -> <Self::Item as FastItem>::Fast
+    where
+        Self::Item: FastItem,
+    {
+        FastItem::sum(self)
+    }
+
+    /// Multiply all values, in an unspecified order.
+    ///
+    /// The product of an empty iterator is one.
+    #[inline]
+    fn fast_product(self) -> <Self::Item as FastItem>::Fast
+    where
+        Self::Item: FastItem,
+    {
+        FastItem::product(self)
+    }
+
+    /// The minimum value, or `None` if the iterator is empty.
+    #[inline]
+    fn fast_min(self) -> Option<<Self::Item as FastItem>::Fast>
+    where
+        Self::Item: FastItem,
+    {
+        FastItem::min(self)
+    }
+
+    /// The maximum value, or `None` if the iterator is empty.
+    #[inline]
+    fn fast_max(self) -> Option<<Self::Item as FastItem>::Fast>
+    where
+        Self::Item: FastItem,
+    {
+        FastItem::max(self)
+    }
+}
+
+impl<I: Iterator> FastIterExt for I {}
+
+/// The plain float types which convert to a fast scalar type, used to select the implementation of
+/// the reductions in [`FastIterExt`].
+pub trait FastItem: Sized {
+    /// The fast scalar type produced by reductions
+    type Fast;
+
+    /// See [`FastIterExt::fast_sum`]
+    fn sum<I: Iterator<Item = Self>>(iter: I) -> Self::Fast;
+
+    /// See [`FastIterExt::fast_product`]
+    fn product<I: Iterator<Item = Self>>(iter: I) -> Self::Fast;
+
+    /// See [`FastIterExt::fast_min`]
+    fn min<I: Iterator<Item = Self>>(iter: I) -> Option<Self::Fast>;
+
+    /// See [`FastIterExt::fast_max`]
+    fn max<I: Iterator<Item = Self>>(iter: I) -> Option<Self::Fast>;
+}
+
+macro_rules! impl_fast_item {
+    ($($item_ty:ty, $fast_ty:ident, $convert:expr;)*) => {
+        $(
+            impl FastItem for $item_ty {
+                type Fast = $fast_ty;
+
+                #[inline]
+                fn sum<I: Iterator<Item = Self>>(iter: I) -> $fast_ty {
+                    iter.map($convert).sum()
+                }
+
+                #[inline]
+                fn product<I: Iterator<Item = Self>>(iter: I) -> $fast_ty {
+                    iter.map($convert).product()
+                }
+
+                // min and max are idempotent, so the first value can pad the accumulators like
+                // the identity does for sums
+                #[inline]
+                fn min<I: Iterator<Item = Self>>(iter: I) -> Option<$fast_ty> {
+                    let mut iter = iter.map($convert);
+                    let first = iter.next()?;
+                    Some(crate::reduce(iter, first, <$fast_ty>::min))
+                }
+
+                #[inline]
+                fn max<I: Iterator<Item = Self>>(iter: I) -> Option<$fast_ty> {
+                    let mut iter = iter.map($convert);
+                    let first = iter.next()?;
+                    Some(crate::reduce(iter, first, <$fast_ty>::max))
+                }
+            }
+        )*
+    };
+}
+
+impl_fast_item! {
+    f32, FF32, FF32::from;
+    f64, FF64, FF64::from;
+    &f32, FF32, |val: &f32| FF32::from(*val);
+    &f64, FF64, |val: &f64| FF64::from(*val);
+}
@@ -0,0 +1,891 @@
+//! Explicitly targeted variants of the slice and matrix kernels.
+//!
+//! With the `runtime-dispatch` feature, the functions in [`slice`](crate::slice) and
+//! [`matrix`](crate::matrix) call the kernel variant for the widest instruction set supported by
+//! the running CPU. Callers which already do their own dispatch (for example, once per batch of
+//! work) can instead call a specific variant from the submodules here, which skips the per-call
+//! check of the detected instruction set.
+//!
+//! Calling a variant on a CPU which doesn't support its instruction set is undefined behavior, so
+//! these functions are unsafe. [`detected`] returns the instruction set that the automatic
+//! dispatch would use.
+//!
+//! ```
+//! use fast_fp::{ff32, kernels::{self, Isa}};
+//!
+//! let a = [1.0, 2.0, 3.0, 4.0].map(ff32);
+//! let sum = match kernels::detected() {
+//!     // safety: the running CPU supports the detected instruction set
+//!     #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+//!     Isa::Avx512 | Isa::Avx2 => unsafe { kernels::avx2::sum(&a) },
+//!     _ => unsafe { kernels::baseline::sum(&a) },
+//! };
+//! assert_eq!(sum, 10.0);
+//! ```
+#![cfg_attr(docsrs, doc(cfg(feature = "runtime-dispatch")))]
+
+#[cfg(not(scalar_fallback))]
+use crate::{matrix, slice};
+#[cfg(scalar_fallback)]
+use crate::{matrix::MatrixKernels, slice::SliceKernels};
+use crate::{slice::Strided, FF32, FF64};
+use paste::paste;
+
+/// The instruction sets with kernel variants on the current target
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[repr(u8)]
+pub enum Isa {
+    /// The target's baseline, which the kernels are compiled for without runtime dispatch
+    Baseline,
+
+    /// AVX2 with FMA
+    #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+    Avx2,
+
+    /// AVX-512 (foundation), with AVX2 and FMA
+    #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+    Avx512,
+}
+
+/// The widest instruction set supported by the running CPU, which the functions in
+/// [`slice`](crate::slice) and [`matrix`](crate::matrix) dispatch to.
+///
+/// Strict builds don't compile any kernel variants, so this is always [`Isa::Baseline`]. It's
+/// also always [`Isa::Baseline`] with the `reproducible` feature, which pins the automatic
+/// dispatch to the baseline variant.
+#[inline]
+pub fn detected() -> Isa {
+    #[cfg(not(scalar_fallback))]
+    return crate::dispatch::isa();
+
+    #[cfg(scalar_fallback)]
+    return Isa::Baseline;
+}
+
+/// The fast scalar types which have targeted kernels, used to select the implementation of the
+/// functions in this module's submodules.
+pub trait TargetKernels: Sized {
+    /// See [`slice::sum`](crate::slice::sum)
+    ///
+    /// # Safety
+    /// The running CPU must support `isa`
+    unsafe fn sum(isa: Isa, a: &[Self]) -> Self;
+
+    /// See [`slice::sum_pairwise`](crate::slice::sum_pairwise)
+    ///
+    /// # Safety
+    /// The running CPU must support `isa`
+    unsafe fn sum_pairwise(isa: Isa, a: &[Self]) -> Self;
+
+    /// See [`slice::dot`](crate::slice::dot)
+    ///
+    /// # Safety
+    /// The running CPU must support `isa`
+    unsafe fn dot(isa: Isa, a: &[Self], b: &[Self]) -> Self;
+
+    /// See [`slice::min`](crate::slice::min)
+    ///
+    /// # Safety
+    /// The running CPU must support `isa`
+    unsafe fn min(isa: Isa, a: &[Self]) -> Option<Self>;
+
+    /// See [`slice::max`](crate::slice::max)
+    ///
+    /// # Safety
+    /// The running CPU must support `isa`
+    unsafe fn max(isa: Isa, a: &[Self]) -> Option<Self>;
+
+    /// See [`slice::argmin`](crate::slice::argmin)
+    ///
+    /// # Safety
+    /// The running CPU must support `isa`
+    unsafe fn argmin(isa: Isa, a: &[Self]) -> Option<usize>;
+
+    /// See [`slice::argmax`](crate::slice::argmax)
+    ///
+    /// # Safety
+    /// The running CPU must support `isa`
+    unsafe fn argmax(isa: Isa, a: &[Self]) -> Option<usize>;
+
+    /// See [`slice::sum_strided`](crate::slice::sum_strided)
+    ///
+    /// # Safety
+    /// The running CPU must support `isa`
+    unsafe fn sum_strided(isa: Isa, a: Strided<Self>) -> Self;
+
+    /// See [`slice::dot_strided`](crate::slice::dot_strided)
+    ///
+    /// # Safety
+    /// The running CPU must support `isa`
+    unsafe fn dot_strided(isa: Isa, a: Strided<Self>, b: Strided<Self>) -> Self;
+
+    /// See [`slice::min_strided`](crate::slice::min_strided)
+    ///
+    /// # Safety
+    /// The running CPU must support `isa`
+    unsafe fn min_strided(isa: Isa, a: Strided<Self>) -> Option<Self>;
+
+    /// See [`slice::max_strided`](crate::slice::max_strided)
+    ///
+    /// # Safety
+    /// The running CPU must support `isa`
+    unsafe fn max_strided(isa: Isa, a: Strided<Self>) -> Option<Self>;
+
+    /// See [`slice::fma_slices`](crate::slice::fma_slices)
+    ///
+    /// # Safety
+    /// The running CPU must support `isa`
+    unsafe fn fma_slices(isa: Isa, dst: &mut [Self], a: &[Self], b: &[Self], c: &[Self]);
+
+    /// See [`slice::scale`](crate::slice::scale)
+    ///
+    /// # Safety
+    /// The running CPU must support `isa`
+    unsafe fn scale(isa: Isa, a: &mut [Self], k: Self);
+
+    /// See [`slice::offset`](crate::slice::offset)
+    ///
+    /// # Safety
+    /// The running CPU must support `isa`
+    unsafe fn offset(isa: Isa, a: &mut [Self], k: Self);
+
+    /// See [`slice::abs_in_place`](crate::slice::abs_in_place)
+    ///
+    /// # Safety
+    /// The running CPU must support `isa`
+    unsafe fn abs_in_place(isa: Isa, a: &mut [Self]);
+
+    /// See [`slice::clamp_in_place`](crate::slice::clamp_in_place)
+    ///
+    /// # Safety
+    /// The running CPU must support `isa`
+    unsafe fn clamp_in_place(isa: Isa, a: &mut [Self], min: Self, max: Self);
+
+    /// See [`matrix::mul3_batch`](crate::matrix::mul3_batch)
+    ///
+    /// # Safety
+    /// The running CPU must support `isa`
+    unsafe fn mul3_batch(
+        isa: Isa,
+        a: &[[[Self; 3]; 3]],
+        b: &[[[Self; 3]; 3]],
+        out: &mut [[[Self; 3]; 3]],
+    );
+
+    /// See [`matrix::mul4_batch`](crate::matrix::mul4_batch)
+    ///
+    /// # Safety
+    /// The running CPU must support `isa`
+    unsafe fn mul4_batch(
+        isa: Isa,
+        a: &[[[Self; 4]; 4]],
+        b: &[[[Self; 4]; 4]],
+        out: &mut [[[Self; 4]; 4]],
+    );
+
+    /// See [`matrix::inv3_batch`](crate::matrix::inv3_batch)
+    ///
+    /// # Safety
+    /// The running CPU must support `isa`
+    unsafe fn inv3_batch(isa: Isa, src: &[[[Self; 3]; 3]], dst: &mut [[[Self; 3]; 3]]);
+
+    /// See [`matrix::inv4_batch`](crate::matrix::inv4_batch)
+    ///
+    /// # Safety
+    /// The running CPU must support `isa`
+    unsafe fn inv4_batch(isa: Isa, src: &[[[Self; 4]; 4]], dst: &mut [[[Self; 4]; 4]]);
+
+    /// See [`matrix::transform_points`](crate::matrix::transform_points)
+    ///
+    /// # Safety
+    /// The running CPU must support `isa`
+    unsafe fn transform_points(isa: Isa, m: &[[Self; 4]; 4], points: &mut [[Self; 3]]);
+
+    /// See [`matrix::solve_lower`](crate::matrix::solve_lower)
+    ///
+    /// # Safety
+    /// The running CPU must support `isa`
+    unsafe fn solve_lower<const N: usize>(isa: Isa, l: &[[Self; N]; N], b: &mut [Self; N]);
+
+    /// See [`matrix::solve_upper`](crate::matrix::solve_upper)
+    ///
+    /// # Safety
+    /// The running CPU must support `isa`
+    unsafe fn solve_upper<const N: usize>(isa: Isa, u: &[[Self; N]; N], b: &mut [Self; N]);
+
+    /// See [`matrix::cholesky_solve`](crate::matrix::cholesky_solve)
+    ///
+    /// # Safety
+    /// The running CPU must support `isa`
+    unsafe fn cholesky_solve<const N: usize>(isa: Isa, l: &[[Self; N]; N], b: &mut [Self; N]);
+}
+
+macro_rules! impl_target_kernels {
+    ($fast_ty:ident, $base_ty:ident) => {
+        paste! {
+            #[cfg(not(scalar_fallback))]
+            impl TargetKernels for $fast_ty {
+                #[inline(always)]
+                unsafe fn sum(isa: Isa, a: &[Self]) -> Self {
+                    slice::[<sum_slice_ $base_ty _on>](isa, a.as_ptr(), a.len())
+                }
+
+                #[inline(always)]
+                unsafe fn sum_pairwise(isa: Isa, a: &[Self]) -> Self {
+                    slice::[<sum_pairwise_slice_ $base_ty _on>](isa, a.as_ptr(), a.len())
+                }
+
+                #[inline(always)]
+                unsafe fn dot(isa: Isa, a: &[Self], b: &[Self]) -> Self {
+                    assert_eq!(a.len(), b.len(), "slice lengths differ");
+                    slice::[<dot_slice_ $base_ty _on>](isa, a.as_ptr(), b.as_ptr(), a.len())
+                }
+
+                #[inline(always)]
+                unsafe fn min(isa: Isa, a: &[Self]) -> Option<Self> {
+                    if a.is_empty() {
+                        return None;
+                    }
+                    Some(slice::[<min_slice_ $base_ty _on>](isa, a.as_ptr(), a.len()))
+                }
+
+                #[inline(always)]
+                unsafe fn max(isa: Isa, a: &[Self]) -> Option<Self> {
+                    if a.is_empty() {
+                        return None;
+                    }
+                    Some(slice::[<max_slice_ $base_ty _on>](isa, a.as_ptr(), a.len()))
+                }
+
+                #[inline(always)]
+                unsafe fn argmin(isa: Isa, a: &[Self]) -> Option<usize> {
+                    if a.is_empty() {
+                        return None;
+                    }
+                    let index = slice::[<argmin_slice_ $base_ty _on>](isa, a.as_ptr(), a.len());
+                    Some(slice::kernel_index(index, a.len()))
+                }
+
+                #[inline(always)]
+                unsafe fn argmax(isa: Isa, a: &[Self]) -> Option<usize> {
+                    if a.is_empty() {
+                        return None;
+                    }
+                    let index = slice::[<argmax_slice_ $base_ty _on>](isa, a.as_ptr(), a.len());
+                    Some(slice::kernel_index(index, a.len()))
+                }
+
+                #[inline(always)]
+                unsafe fn sum_strided(isa: Isa, a: Strided<Self>) -> Self {
+                    slice::[<sum_strided_ $base_ty _on>](isa, a.data.as_ptr(), a.stride, a.len())
+                }
+
+                #[inline(always)]
+                unsafe fn dot_strided(isa: Isa, a: Strided<Self>, b: Strided<Self>) -> Self {
+                    assert_eq!(a.len(), b.len(), "view lengths differ");
+                    slice::[<dot_strided_ $base_ty _on>](
+                        isa,
+                        a.data.as_ptr(),
+                        a.stride,
+                        b.data.as_ptr(),
+                        b.stride,
+                        a.len(),
+                    )
+                }
+
+                #[inline(always)]
+                unsafe fn min_strided(isa: Isa, a: Strided<Self>) -> Option<Self> {
+                    if a.is_empty() {
+                        return None;
+                    }
+                    Some(slice::[<min_strided_ $base_ty _on>](
+                        isa,
+                        a.data.as_ptr(),
+                        a.stride,
+                        a.len(),
+                    ))
+                }
+
+                #[inline(always)]
+                unsafe fn max_strided(isa: Isa, a: Strided<Self>) -> Option<Self> {
+                    if a.is_empty() {
+                        return None;
+                    }
+                    Some(slice::[<max_strided_ $base_ty _on>](
+                        isa,
+                        a.data.as_ptr(),
+                        a.stride,
+                        a.len(),
+                    ))
+                }
+
+                #[inline(always)]
+                unsafe fn fma_slices(
+                    isa: Isa,
+                    dst: &mut [Self],
+                    a: &[Self],
+                    b: &[Self],
+                    c: &[Self],
+                ) {
+                    assert_eq!(dst.len(), a.len(), "slice lengths differ");
+                    assert_eq!(dst.len(), b.len(), "slice lengths differ");
+                    assert_eq!(dst.len(), c.len(), "slice lengths differ");
+                    slice::[<fma_slice_ $base_ty _on>](
+                        isa,
+                        dst.as_mut_ptr(),
+                        a.as_ptr(),
+                        b.as_ptr(),
+                        c.as_ptr(),
+                        dst.len(),
+                    )
+                }
+
+                #[inline(always)]
+                unsafe fn scale(isa: Isa, a: &mut [Self], k: Self) {
+                    slice::[<scale_slice_ $base_ty _on>](isa, a.as_mut_ptr(), a.len(), k)
+                }
+
+                #[inline(always)]
+                unsafe fn offset(isa: Isa, a: &mut [Self], k: Self) {
+                    slice::[<offset_slice_ $base_ty _on>](isa, a.as_mut_ptr(), a.len(), k)
+                }
+
+                #[inline(always)]
+                unsafe fn abs_in_place(isa: Isa, a: &mut [Self]) {
+                    slice::[<abs_slice_ $base_ty _on>](isa, a.as_mut_ptr(), a.len())
+                }
+
+                #[inline(always)]
+                unsafe fn clamp_in_place(isa: Isa, a: &mut [Self], min: Self, max: Self) {
+                    assert!(min <= max);
+                    slice::[<clamp_slice_ $base_ty _on>](isa, a.as_mut_ptr(), a.len(), min, max)
+                }
+
+                #[inline(always)]
+                unsafe fn mul3_batch(
+                    isa: Isa,
+                    a: &[[[Self; 3]; 3]],
+                    b: &[[[Self; 3]; 3]],
+                    out: &mut [[[Self; 3]; 3]],
+                ) {
+                    assert_eq!(a.len(), out.len(), "slice lengths differ");
+                    assert_eq!(b.len(), out.len(), "slice lengths differ");
+                    matrix::[<mul3_batch_ $base_ty _on>](
+                        isa,
+                        out.as_mut_ptr(),
+                        a.as_ptr(),
+                        b.as_ptr(),
+                        out.len(),
+                    )
+                }
+
+                #[inline(always)]
+                unsafe fn mul4_batch(
+                    isa: Isa,
+                    a: &[[[Self; 4]; 4]],
+                    b: &[[[Self; 4]; 4]],
+                    out: &mut [[[Self; 4]; 4]],
+                ) {
+                    assert_eq!(a.len(), out.len(), "slice lengths differ");
+                    assert_eq!(b.len(), out.len(), "slice lengths differ");
+                    matrix::[<mul4_batch_ $base_ty _on>](
+                        isa,
+                        out.as_mut_ptr(),
+                        a.as_ptr(),
+                        b.as_ptr(),
+                        out.len(),
+                    )
+                }
+
+                #[inline(always)]
+                unsafe fn inv3_batch(isa: Isa, src: &[[[Self; 3]; 3]], dst: &mut [[[Self; 3]; 3]]) {
+                    assert_eq!(src.len(), dst.len(), "source and destination lengths differ");
+                    matrix::[<inv3_batch_ $base_ty _on>](
+                        isa,
+                        dst.as_mut_ptr(),
+                        src.as_ptr(),
+                        dst.len(),
+                    )
+                }
+
+                #[inline(always)]
+                unsafe fn inv4_batch(isa: Isa, src: &[[[Self; 4]; 4]], dst: &mut [[[Self; 4]; 4]]) {
+                    assert_eq!(src.len(), dst.len(), "source and destination lengths differ");
+                    matrix::[<inv4_batch_ $base_ty _on>](
+                        isa,
+                        dst.as_mut_ptr(),
+                        src.as_ptr(),
+                        dst.len(),
+                    )
+                }
+
+                #[inline(always)]
+                unsafe fn transform_points(isa: Isa, m: &[[Self; 4]; 4], points: &mut [[Self; 3]]) {
+                    matrix::[<transform_points_ $base_ty _on>](
+                        isa,
+                        m,
+                        points.as_mut_ptr(),
+                        points.len(),
+                    )
+                }
+
+                #[inline(always)]
+                unsafe fn solve_lower<const N: usize>(
+                    isa: Isa,
+                    l: &[[Self; N]; N],
+                    b: &mut [Self; N],
+                ) {
+                    matrix::[<solve_lower_ $base_ty _on>](isa, l.as_ptr().cast(), b.as_mut_ptr(), N)
+                }
+
+                #[inline(always)]
+                unsafe fn solve_upper<const N: usize>(
+                    isa: Isa,
+                    u: &[[Self; N]; N],
+                    b: &mut [Self; N],
+                ) {
+                    matrix::[<solve_upper_ $base_ty _on>](isa, u.as_ptr().cast(), b.as_mut_ptr(), N)
+                }
+
+                #[inline(always)]
+                unsafe fn cholesky_solve<const N: usize>(
+                    isa: Isa,
+                    l: &[[Self; N]; N],
+                    b: &mut [Self; N],
+                ) {
+                    matrix::[<solve_lower_ $base_ty _on>](
+                        isa,
+                        l.as_ptr().cast(),
+                        b.as_mut_ptr(),
+                        N,
+                    );
+                    matrix::[<solve_lower_transpose_ $base_ty _on>](
+                        isa,
+                        l.as_ptr().cast(),
+                        b.as_mut_ptr(),
+                        N,
+                    );
+                }
+            }
+
+            // strict builds don't compile any kernel variants, so every instruction set uses the
+            // rust fallbacks
+            #[cfg(scalar_fallback)]
+            impl TargetKernels for $fast_ty {
+                #[inline(always)]
+                unsafe fn sum(_: Isa, a: &[Self]) -> Self {
+                    <Self as SliceKernels>::sum(a)
+                }
+
+                #[inline(always)]
+                unsafe fn sum_pairwise(_: Isa, a: &[Self]) -> Self {
+                    <Self as SliceKernels>::sum_pairwise(a)
+                }
+
+                #[inline(always)]
+                unsafe fn dot(_: Isa, a: &[Self], b: &[Self]) -> Self {
+                    <Self as SliceKernels>::dot(a, b)
+                }
+
+                #[inline(always)]
+                unsafe fn min(_: Isa, a: &[Self]) -> Option<Self> {
+                    <Self as SliceKernels>::min(a)
+                }
+
+                #[inline(always)]
+                unsafe fn max(_: Isa, a: &[Self]) -> Option<Self> {
+                    <Self as SliceKernels>::max(a)
+                }
+
+                #[inline(always)]
+                unsafe fn argmin(_: Isa, a: &[Self]) -> Option<usize> {
+                    <Self as SliceKernels>::argmin(a)
+                }
+
+                #[inline(always)]
+                unsafe fn argmax(_: Isa, a: &[Self]) -> Option<usize> {
+                    <Self as SliceKernels>::argmax(a)
+                }
+
+                #[inline(always)]
+                unsafe fn sum_strided(_: Isa, a: Strided<Self>) -> Self {
+                    <Self as SliceKernels>::sum_strided(a)
+                }
+
+                #[inline(always)]
+                unsafe fn dot_strided(_: Isa, a: Strided<Self>, b: Strided<Self>) -> Self {
+                    <Self as SliceKernels>::dot_strided(a, b)
+                }
+
+                #[inline(always)]
+                unsafe fn min_strided(_: Isa, a: Strided<Self>) -> Option<Self> {
+                    <Self as SliceKernels>::min_strided(a)
+                }
+
+                #[inline(always)]
+                unsafe fn max_strided(_: Isa, a: Strided<Self>) -> Option<Self> {
+                    <Self as SliceKernels>::max_strided(a)
+                }
+
+                #[inline(always)]
+                unsafe fn fma_slices(_: Isa, dst: &mut [Self], a: &[Self], b: &[Self], c: &[Self]) {
+                    <Self as SliceKernels>::fma_slices(dst, a, b, c)
+                }
+
+                #[inline(always)]
+                unsafe fn scale(_: Isa, a: &mut [Self], k: Self) {
+                    <Self as SliceKernels>::scale(a, k)
+                }
+
+                #[inline(always)]
+                unsafe fn offset(_: Isa, a: &mut [Self], k: Self) {
+                    <Self as SliceKernels>::offset(a, k)
+                }
+
+                #[inline(always)]
+                unsafe fn abs_in_place(_: Isa, a: &mut [Self]) {
+                    <Self as SliceKernels>::abs_in_place(a)
+                }
+
+                #[inline(always)]
+                unsafe fn clamp_in_place(_: Isa, a: &mut [Self], min: Self, max: Self) {
+                    <Self as SliceKernels>::clamp_in_place(a, min, max)
+                }
+
+                #[inline(always)]
+                unsafe fn mul3_batch(
+                    _: Isa,
+                    a: &[[[Self; 3]; 3]],
+                    b: &[[[Self; 3]; 3]],
+                    out: &mut [[[Self; 3]; 3]],
+                ) {
+                    <Self as MatrixKernels>::mul3_batch(a, b, out)
+                }
+
+                #[inline(always)]
+                unsafe fn mul4_batch(
+                    _: Isa,
+                    a: &[[[Self; 4]; 4]],
+                    b: &[[[Self; 4]; 4]],
+                    out: &mut [[[Self; 4]; 4]],
+                ) {
+                    <Self as MatrixKernels>::mul4_batch(a, b, out)
+                }
+
+                #[inline(always)]
+                unsafe fn inv3_batch(_: Isa, src: &[[[Self; 3]; 3]], dst: &mut [[[Self; 3]; 3]]) {
+                    <Self as MatrixKernels>::inv3_batch(src, dst)
+                }
+
+                #[inline(always)]
+                unsafe fn inv4_batch(_: Isa, src: &[[[Self; 4]; 4]], dst: &mut [[[Self; 4]; 4]]) {
+                    <Self as MatrixKernels>::inv4_batch(src, dst)
+                }
+
+                #[inline(always)]
+                unsafe fn transform_points(_: Isa, m: &[[Self; 4]; 4], points: &mut [[Self; 3]]) {
+                    <Self as MatrixKernels>::transform_points(m, points)
+                }
+
+                #[inline(always)]
+                unsafe fn solve_lower<const N: usize>(
+                    _: Isa,
+                    l: &[[Self; N]; N],
+                    b: &mut [Self; N],
+                ) {
+                    <Self as MatrixKernels>::solve_lower(l, b)
+                }
+
+                #[inline(always)]
+                unsafe fn solve_upper<const N: usize>(
+                    _: Isa,
+                    u: &[[Self; N]; N],
+                    b: &mut [Self; N],
+                ) {
+                    <Self as MatrixKernels>::solve_upper(u, b)
+                }
+
+                #[inline(always)]
+                unsafe fn cholesky_solve<const N: usize>(
+                    _: Isa,
+                    l: &[[Self; N]; N],
+                    b: &mut [Self; N],
+                ) {
+                    <Self as MatrixKernels>::cholesky_solve(l, b)
+                }
+            }
+        }
+    };
+}
+
+impl_target_kernels! { FF32, f32 }
+impl_target_kernels! { FF64, f64 }
+
+macro_rules! impl_variant {
+    ($(#[$attr:meta])* $module:ident, $isa:ident) => {
+        $(#[$attr])*
+        pub mod $module {
+            use super::{Isa, TargetKernels};
+            use crate::slice::Strided;
+
+            /// See [`slice::sum`](crate::slice::sum)
+            ///
+            /// # Safety
+            #[doc = concat!("The running CPU must support [`Isa::", stringify!($isa), "`]")]
+            #[inline]
+            pub unsafe fn sum<T: TargetKernels>(a: &[T]) -> T {
+                T::sum(Isa::$isa, a)
+            }
+
+            /// See [`slice::sum_pairwise`](crate::slice::sum_pairwise)
+            ///
+            /// # Safety
+            #[doc = concat!("The running CPU must support [`Isa::", stringify!($isa), "`]")]
+            #[inline]
+            pub unsafe fn sum_pairwise<T: TargetKernels>(a: &[T]) -> T {
+                T::sum_pairwise(Isa::$isa, a)
+            }
+
+            /// See [`slice::dot`](crate::slice::dot)
+            ///
+            /// # Safety
+            #[doc = concat!("The running CPU must support [`Isa::", stringify!($isa), "`]")]
+            #[inline]
+            pub unsafe fn dot<T: TargetKernels>(a: &[T], b: &[T]) -> T {
+                T::dot(Isa::$isa, a, b)
+            }
+
+            /// See [`slice::min`](crate::slice::min)
+            ///
+            /// # Safety
+            #[doc = concat!("The running CPU must support [`Isa::", stringify!($isa), "`]")]
+            #[inline]
+            pub unsafe fn min<T: TargetKernels>(a: &[T]) -> Option<T> {
+                T::min(Isa::$isa, a)
+            }
+
+            /// See [`slice::max`](crate::slice::max)
+            ///
+            /// # Safety
+            #[doc = concat!("The running CPU must support [`Isa::", stringify!($isa), "`]")]
+            #[inline]
+            pub unsafe fn max<T: TargetKernels>(a: &[T]) -> Option<T> {
+                T::max(Isa::$isa, a)
+            }
+
+            /// See [`slice::argmin`](crate::slice::argmin)
+            ///
+            /// # Safety
+            #[doc = concat!("The running CPU must support [`Isa::", stringify!($isa), "`]")]
+            #[inline]
+            pub unsafe fn argmin<T: TargetKernels>(a: &[T]) -> Option<usize> {
+                T::argmin(Isa::$isa, a)
+            }
+
+            /// See [`slice::argmax`](crate::slice::argmax)
+            ///
+            /// # Safety
+            #[doc = concat!("The running CPU must support [`Isa::", stringify!($isa), "`]")]
+            #[inline]
+            pub unsafe fn argmax<T: TargetKernels>(a: &[T]) -> Option<usize> {
+                T::argmax(Isa::$isa, a)
+            }
+
+            /// See [`slice::sum_strided`](crate::slice::sum_strided)
+            ///
+            /// # Safety
+            #[doc = concat!("The running CPU must support [`Isa::", stringify!($isa), "`]")]
+            #[inline]
+            pub unsafe fn sum_strided<T: TargetKernels>(a: Strided<T>) -> T {
+                T::sum_strided(Isa::$isa, a)
+            }
+
+            /// See [`slice::dot_strided`](crate::slice::dot_strided)
+            ///
+            /// # Safety
+            #[doc = concat!("The running CPU must support [`Isa::", stringify!($isa), "`]")]
+            #[inline]
+            pub unsafe fn dot_strided<T: TargetKernels>(a: Strided<T>, b: Strided<T>) -> T {
+                T::dot_strided(Isa::$isa, a, b)
+            }
+
+            /// See [`slice::min_strided`](crate::slice::min_strided)
+            ///
+            /// # Safety
+            #[doc = concat!("The running CPU must support [`Isa::", stringify!($isa), "`]")]
+            #[inline]
+            pub unsafe fn min_strided<T: TargetKernels>(a: Strided<T>) -> Option<T> {
+                T::min_strided(Isa::$isa, a)
+            }
+
+            /// See [`slice::max_strided`](crate::slice::max_strided)
+            ///
+            /// # Safety
+            #[doc = concat!("The running CPU must support [`Isa::", stringify!($isa), "`]")]
+            #[inline]
+            pub unsafe fn max_strided<T: TargetKernels>(a: Strided<T>) -> Option<T> {
+                T::max_strided(Isa::$isa, a)
+            }
+
+            /// See [`slice::fma_slices`](crate::slice::fma_slices)
+            ///
+            /// # Safety
+            #[doc = concat!("The running CPU must support [`Isa::", stringify!($isa), "`]")]
+            #[inline]
+            pub unsafe fn fma_slices<T: TargetKernels>(dst: &mut [T], a: &[T], b: &[T], c: &[T]) {
+                T::fma_slices(Isa::$isa, dst, a, b, c)
+            }
+
+            /// See [`slice::scale`](crate::slice::scale)
+            ///
+            /// # Safety
+            #[doc = concat!("The running CPU must support [`Isa::", stringify!($isa), "`]")]
+            #[inline]
+            pub unsafe fn scale<T: TargetKernels>(a: &mut [T], k: T) {
+                T::scale(Isa::$isa, a, k)
+            }
+
+            /// See [`slice::offset`](crate::slice::offset)
+            ///
+            /// # Safety
+            #[doc = concat!("The running CPU must support [`Isa::", stringify!($isa), "`]")]
+            #[inline]
+            pub unsafe fn offset<T: TargetKernels>(a: &mut [T], k: T) {
+                T::offset(Isa::$isa, a, k)
+            }
+
+            /// See [`slice::abs_in_place`](crate::slice::abs_in_place)
+            ///
+            /// # Safety
+            #[doc = concat!("The running CPU must support [`Isa::", stringify!($isa), "`]")]
+            #[inline]
+            pub unsafe fn abs_in_place<T: TargetKernels>(a: &mut [T]) {
+                T::abs_in_place(Isa::$isa, a)
+            }
+
+            /// See [`slice::clamp_in_place`](crate::slice::clamp_in_place)
+            ///
+            /// # Safety
+            #[doc = concat!("The running CPU must support [`Isa::", stringify!($isa), "`]")]
+            #[inline]
+            pub unsafe fn clamp_in_place<T: TargetKernels>(a: &mut [T], min: T, max: T) {
+                T::clamp_in_place(Isa::$isa, a, min, max)
+            }
+
+            /// See [`matrix::mul3_batch`](crate::matrix::mul3_batch)
+            ///
+            /// # Safety
+            #[doc = concat!("The running CPU must support [`Isa::", stringify!($isa), "`]")]
+            #[inline]
+            pub unsafe fn mul3_batch<T: TargetKernels>(
+                a: &[[[T; 3]; 3]],
+                b: &[[[T; 3]; 3]],
+                out: &mut [[[T; 3]; 3]],
+            ) {
+                T::mul3_batch(Isa::$isa, a, b, out)
+            }
+
+            /// See [`matrix::mul4_batch`](crate::matrix::mul4_batch)
+            ///
+            /// # Safety
+            #[doc = concat!("The running CPU must support [`Isa::", stringify!($isa), "`]")]
+            #[inline]
+            pub unsafe fn mul4_batch<T: TargetKernels>(
+                a: &[[[T; 4]; 4]],
+                b: &[[[T; 4]; 4]],
+                out: &mut [[[T; 4]; 4]],
+            ) {
+                T::mul4_batch(Isa::$isa, a, b, out)
+            }
+
+            /// See [`matrix::inv3_batch`](crate::matrix::inv3_batch)
+            ///
+            /// # Safety
+            #[doc = concat!("The running CPU must support [`Isa::", stringify!($isa), "`]")]
+            #[inline]
+            pub unsafe fn inv3_batch<T: TargetKernels>(
+                src: &[[[T; 3]; 3]],
+                dst: &mut [[[T; 3]; 3]],
+            ) {
+                T::inv3_batch(Isa::$isa, src, dst)
+            }
+
+            /// See [`matrix::inv4_batch`](crate::matrix::inv4_batch)
+            ///
+            /// # Safety
+            #[doc = concat!("The running CPU must support [`Isa::", stringify!($isa), "`]")]
+            #[inline]
+            pub unsafe fn inv4_batch<T: TargetKernels>(
+                src: &[[[T; 4]; 4]],
+                dst: &mut [[[T; 4]; 4]],
+            ) {
+                T::inv4_batch(Isa::$isa, src, dst)
+            }
+
+            /// See [`matrix::transform_points`](crate::matrix::transform_points)
+            ///
+            /// # Safety
+            #[doc = concat!("The running CPU must support [`Isa::", stringify!($isa), "`]")]
+            #[inline]
+            pub unsafe fn transform_points<T: TargetKernels>(
+                m: &[[T; 4]; 4],
+                points: &mut [[T; 3]],
+            ) {
+                T::transform_points(Isa::$isa, m, points)
+            }
+
+            /// See [`matrix::solve_lower`](crate::matrix::solve_lower)
+            ///
+            /// # Safety
+            #[doc = concat!("The running CPU must support [`Isa::", stringify!($isa), "`]")]
+            #[inline]
+            pub unsafe fn solve_lower<T: TargetKernels, const N: usize>(
+                l: &[[T; N]; N],
+                b: &mut [T; N],
+            ) {
+                T::solve_lower(Isa::$isa, l, b)
+            }
+
+            /// See [`matrix::solve_upper`](crate::matrix::solve_upper)
+            ///
+            /// # Safety
+            #[doc = concat!("The running CPU must support [`Isa::", stringify!($isa), "`]")]
+            #[inline]
+            pub unsafe fn solve_upper<T: TargetKernels, const N: usize>(
+                u: &[[T; N]; N],
+                b: &mut [T; N],
+            ) {
+                T::solve_upper(Isa::$isa, u, b)
+            }
+
+            /// See [`matrix::cholesky_solve`](crate::matrix::cholesky_solve)
+            ///
+            /// # Safety
+            #[doc = concat!("The running CPU must support [`Isa::", stringify!($isa), "`]")]
+            #[inline]
+            pub unsafe fn cholesky_solve<T: TargetKernels, const N: usize>(
+                l: &[[T; N]; N],
+                b: &mut [T; N],
+            ) {
+                T::cholesky_solve(Isa::$isa, l, b)
+            }
+        }
+    };
+}
+
+impl_variant! {
+    /// The kernels compiled for the target's baseline instruction set
+    baseline, Baseline
+}
+
+impl_variant! {
+    /// The kernels compiled for AVX2 with FMA
+    #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+    avx2, Avx2
+}
+
+impl_variant! {
+    /// The kernels compiled for AVX-512
+    #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+    avx512, Avx512
+}
@@ -1,5 +1,7 @@
 #![doc = include_str!("../README.md")]
 #![cfg_attr(docsrs, feature(doc_cfg))]
+#![cfg_attr(feature = "portable-simd", feature(portable_simd))]
+#![cfg_attr(inline_arith, feature(float_algebraic))]
 
 use core::{
     cmp, fmt,
@@ -8,43 +10,220 @@ use core::{
     ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Rem, RemAssign, Sub, SubAssign},
 };
 
-macro_rules! forward_freeze_self {
-    ($fast_ty:ident, $base_ty:ident
-     $(
-         $(#[$attr:meta])*
-         $vis:vis fn $fn_name:ident (self $(, $arg:ident : Self)* ) -> Self ;
-     )*) => {
+macro_rules! impl_assign_ops {
+    ($fast_ty:ident, $base_ty: ident: $($op_trait:ident, $op_fn:ident, $op:ident,)*) => {
         $(
-            $(#[$attr])*
-            #[inline]
-            $vis fn $fn_name(self $(, $arg : Self)*) -> Self {
-                <$fast_ty>::new(<$base_ty>::$fn_name(self.freeze_raw() $(, $arg.freeze_raw())* ))
+            impl $op_trait <$fast_ty> for $fast_ty {
+                #[inline(always)]
+                fn $op_fn(&mut self, rhs: $fast_ty) {
+                    *self = <$fast_ty>::$op(*self, rhs)
+                }
+            }
+
+            impl $op_trait <&$fast_ty> for $fast_ty {
+                #[inline(always)]
+                fn $op_fn(&mut self, rhs: &$fast_ty) {
+                    *self = <$fast_ty>::$op(*self, rhs)
+                }
+            }
+
+            impl $op_trait <$base_ty> for $fast_ty {
+                #[inline(always)]
+                fn $op_fn(&mut self, rhs: $base_ty) {
+                    *self = <$fast_ty>::$op(*self, rhs)
+                }
+            }
+
+            impl $op_trait <&$base_ty> for $fast_ty {
+                #[inline(always)]
+                fn $op_fn(&mut self, rhs: &$base_ty) {
+                    *self = <$fast_ty>::$op(*self, rhs)
+                }
             }
         )*
-    };
+    }
+}
 
-    ($fast_ty:ident, $base_ty:ident
-     $(
-         $(#[$attr:meta])*
-         $vis:vis fn $fn_name:ident (&self $(, $arg:ident : &Self)* ) -> Self ;
-     )*) => {
+// The reductions keep several independent accumulators, so each operation doesn't wait on the
+// previous one and the accumulators can be vectorized together. Values are taken from the iterator
+// one chunk at a time, and the last chunk is padded with the identity, which leaves the
+// accumulators unchanged. The accumulators are then combined pairwise
+const REDUCE_LANES: usize = 8;
+
+#[inline(always)]
+fn reduce<T: Copy>(mut iter: impl Iterator<Item = T>, identity: T, op: impl Fn(T, T) -> T) -> T {
+    let mut lanes = [identity; REDUCE_LANES];
+    loop {
+        let mut chunk = [identity; REDUCE_LANES];
+        let mut len = 0;
+        for (slot, val) in chunk.iter_mut().zip(&mut iter) {
+            *slot = val;
+            len += 1;
+        }
+        for (lane, val) in lanes.iter_mut().zip(chunk.iter()) {
+            *lane = op(*lane, *val);
+        }
+        if len < REDUCE_LANES {
+            break;
+        }
+    }
+
+    let mut width = REDUCE_LANES;
+    while width > 1 {
+        width /= 2;
+        for i in 0..width {
+            lanes[i] = op(lanes[i], lanes[i + width]);
+        }
+    }
+    lanes[0]
+}
+
+// the contract-only types otherwise follow IEEE 754 semantics, so they reduce in order
+#[inline(always)]
+fn reduce_in_order<T>(iter: impl Iterator<Item = T>, identity: T, op: impl Fn(T, T) -> T) -> T {
+    iter.fold(identity, op)
+}
+
+macro_rules! impl_reduce_ops {
+    ($fast_ty:ident, $base_ty: ident, $reduce:path: $($op_trait:ident, $op_fn:ident, $op:ident, $identity:expr,)*) => {
         $(
-            $(#[$attr])*
-            #[inline]
-            $vis fn $fn_name(&self $(, $arg : &Self)*) -> Self {
-                <$fast_ty>::new(<$base_ty>::$fn_name(self.freeze_raw() $(, $arg.freeze_raw())* ))
+            impl $op_trait <$fast_ty> for $fast_ty {
+                #[inline]
+                fn $op_fn <I> (iter: I) -> Self
+                    where I: Iterator<Item = $fast_ty>
+                {
+                    $reduce(iter, $identity, <$fast_ty>::$op)
+                }
+            }
+
+            impl<'a> $op_trait <&'a $fast_ty> for $fast_ty {
+                #[inline]
+                fn $op_fn <I> (iter: I) -> Self
+                    where I: Iterator<Item = &'a $fast_ty>
+                {
+                    $reduce(iter.copied(), $identity, <$fast_ty>::$op)
+                }
+            }
+
+            impl $op_trait <$base_ty> for $fast_ty {
+                #[inline]
+                fn $op_fn <I> (iter: I) -> Self
+                    where I: Iterator<Item = $base_ty>
+                {
+                    $reduce(iter.map(<$fast_ty>::from), $identity, <$fast_ty>::$op)
+                }
+            }
+
+            impl<'a> $op_trait <&'a $base_ty> for $fast_ty {
+                #[inline]
+                fn $op_fn <I> (iter: I) -> Self
+                    where I: Iterator<Item = &'a $base_ty>
+                {
+                    $reduce(iter.map(|val| <$fast_ty>::from(*val)), $identity, <$fast_ty>::$op)
+                }
             }
         )*
-    };
+    }
 }
 
+macro_rules! impl_fmt {
+    ($fast_ty:ident, $base_ty:ident, $($fmt_trait:path,)*) => {
+        $(
+            impl $fmt_trait for $fast_ty {
+                fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                    <$base_ty as $fmt_trait>::fmt(&<$base_ty>::from(*self), f)
+                }
+            }
+        )*
+    }
+}
+
+mod accuracy;
+pub use accuracy::Accuracy;
+
+pub mod activation;
+pub mod bf16;
+pub mod blas1;
+pub mod blas2;
+pub mod blas3;
+
+#[cfg(feature = "num-complex-v04")]
+pub mod complex;
+
+mod compensated;
+pub use compensated::CompensatedSum;
+
+mod contract;
+pub use contract::{cf32, cf64, CF32, CF64};
+
+pub mod decibel;
+pub mod differentiate;
+
+#[macro_use]
+mod dispatch;
+
+pub mod distance;
+pub mod dsp;
+pub mod ease;
+
+pub mod f16;
+
+mod fast_float;
+pub use fast_float::FastFloat;
+
+mod ff128;
+#[cfg(feature = "ff128")]
+pub use ff128::FF128;
+
+#[cfg(feature = "num-complex-v04")]
+pub mod fft;
+
+mod histogram;
+pub use histogram::Histogram;
+
+pub mod integrate;
+pub mod interp;
+
+mod iter;
+pub use iter::{FastItem, FastIterExt};
+
+#[cfg(feature = "runtime-dispatch")]
+pub mod kernels;
+
+mod log_base;
+pub use log_base::LogBase;
+
+pub mod loss;
+pub mod lut;
+
 mod math;
+pub mod matrix;
 mod nalgebra;
 mod num_traits;
+pub mod ode;
 
 mod poison;
 use poison::MaybePoison;
 
+pub mod poly;
+
+mod portable_simd;
+
+pub mod quantize;
+
+pub mod rolling;
+pub mod roots;
+
+mod simd;
+pub use simd::{FF32x4, FF32x8, FF64x4, FastSimdExt};
+
+pub mod slice;
+pub mod space;
+
+pub mod stats;
+
+mod wide;
+
 // The big challenge with fast-math in general is avoiding UB, and to a lesser extent unspecified
 // values. LLVM's fast operations document "poison" behavior when given invalid inputs; poison
 // values have a relatively consistent behavior (stuff like transitivity), defined cases for UB,
@@ -122,8 +301,9 @@ pub struct FF32(MaybePoison<f32>);
 ///
 /// This is syntax sugar for constructing the `FF32` type, and equivalent to `FF32::new(f)`
 ///
-/// The given value **MUST NOT** be infinite or NaN, and any operations involving this value must
-/// not produce infinite or NaN results. The output of any such operation is unspecified.
+/// Without the `non-finite` feature, the given value **MUST NOT** be infinite or NaN, and any
+/// operations involving this value must not produce infinite or NaN results. The output of any such
+/// operation is unspecified.
 #[inline(always)]
 pub fn ff32(f: f32) -> FF32 {
     // TODO maybe a feature flag to make this checked -> panic?
@@ -140,102 +320,15 @@ pub struct FF64(MaybePoison<f64>);
 ///
 /// This is syntax sugar for constructing the `FF64` type, and equivalent to `FF64::new(f)`
 ///
-/// The given value **MUST NOT** be infinite or NaN, and any operations involving this value must
-/// not produce infinite or NaN results. The output of any such operation is unspecified.
+/// Without the `non-finite` feature, the given value **MUST NOT** be infinite or NaN, and any
+/// operations involving this value must not produce infinite or NaN results. The output of any such
+/// operation is unspecified.
 #[inline(always)]
 pub fn ff64(f: f64) -> FF64 {
     // TODO maybe a feature flag to make this checked -> panic?
     FF64::new(f)
 }
 
-macro_rules! impl_assign_ops {
-    ($fast_ty:ident, $base_ty: ident: $($op_trait:ident, $op_fn:ident, $op:ident,)*) => {
-        $(
-            impl $op_trait <$fast_ty> for $fast_ty {
-                #[inline(always)]
-                fn $op_fn(&mut self, rhs: $fast_ty) {
-                    *self = <$fast_ty>::$op(*self, rhs)
-                }
-            }
-
-            impl $op_trait <&$fast_ty> for $fast_ty {
-                #[inline(always)]
-                fn $op_fn(&mut self, rhs: &$fast_ty) {
-                    *self = <$fast_ty>::$op(*self, rhs)
-                }
-            }
-
-            impl $op_trait <$base_ty> for $fast_ty {
-                #[inline(always)]
-                fn $op_fn(&mut self, rhs: $base_ty) {
-                    *self = <$fast_ty>::$op(*self, rhs)
-                }
-            }
-
-            impl $op_trait <&$base_ty> for $fast_ty {
-                #[inline(always)]
-                fn $op_fn(&mut self, rhs: &$base_ty) {
-                    *self = <$fast_ty>::$op(*self, rhs)
-                }
-            }
-        )*
-    }
-}
-
-macro_rules! impl_reduce_ops {
-    ($fast_ty:ident, $base_ty: ident: $($op_trait:ident, $op_fn:ident, $op:ident, $identity:expr,)*) => {
-        $(
-            impl $op_trait <$fast_ty> for $fast_ty {
-                #[inline]
-                fn $op_fn <I> (iter: I) -> Self
-                    where I: Iterator<Item = $fast_ty>
-                {
-                    iter.fold($identity, |acc, val| acc.$op(val))
-                }
-            }
-
-            impl<'a> $op_trait <&'a $fast_ty> for $fast_ty {
-                #[inline]
-                fn $op_fn <I> (iter: I) -> Self
-                    where I: Iterator<Item = &'a $fast_ty>
-                {
-                    iter.fold($identity, |acc, val| acc.$op(val))
-                }
-            }
-
-            impl $op_trait <$base_ty> for $fast_ty {
-                #[inline]
-                fn $op_fn <I> (iter: I) -> Self
-                    where I: Iterator<Item = $base_ty>
-                {
-                    iter.fold($identity, |acc, val| acc.$op(val))
-                }
-            }
-
-            impl<'a> $op_trait <&'a $base_ty> for $fast_ty {
-                #[inline]
-                fn $op_fn <I> (iter: I) -> Self
-                    where I: Iterator<Item = &'a $base_ty>
-                {
-                    iter.fold($identity, |acc, val| acc.$op(val))
-                }
-            }
-        )*
-    }
-}
-
-macro_rules! impl_fmt {
-    ($fast_ty:ident, $base_ty:ident, $($fmt_trait:path,)*) => {
-        $(
-            impl $fmt_trait for $fast_ty {
-                fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-                    <$base_ty as $fmt_trait>::fmt(&self.freeze_raw(), f)
-                }
-            }
-        )*
-    }
-}
-
 macro_rules! impls {
     ($fast_ty:ident, $base_ty: ident) => {
         impl $fast_ty {
@@ -255,8 +348,9 @@ macro_rules! impls {
             #[doc = stringify!($fast_ty)]
             #[doc = "` instance from the given float value."]
             ///
-            /// The given value **MUST NOT** be infinite or NaN, and any operations involving this value must
-            /// not produce infinite or NaN results. The output of any such operation is unspecified.
+            /// Without the `non-finite` feature, the given value **MUST NOT** be infinite or NaN, and any
+            /// operations involving this value must not produce infinite or NaN results. The output of any
+            /// such operation is unspecified.
             #[inline(always)]
             pub const fn new(f: $base_ty) -> Self {
                 $fast_ty(MaybePoison::new(f))
@@ -285,14 +379,7 @@ macro_rules! impls {
                 self.0.freeze()
             }
 
-            forward_freeze_self! {
-                $fast_ty, $base_ty
-                pub fn div_euclid(self, rhs: Self) -> Self;
-                pub fn rem_euclid(self, rhs: Self) -> Self;
-                pub fn to_degrees(self) -> Self;
-                pub fn to_radians(self) -> Self;
-            }
-
+            #[cfg(not(feature = "non-finite"))]
             #[inline]
             pub fn classify(self) -> FpCategory {
                 // NaN and infinity should not be presented as possibilities to users, even if
@@ -304,6 +391,13 @@ macro_rules! impls {
                 }
             }
 
+            // with the non-finite feature, NaN and infinity are valid values
+            #[cfg(feature = "non-finite")]
+            #[inline]
+            pub fn classify(self) -> FpCategory {
+                self.freeze_raw().classify()
+            }
+
             #[inline]
             pub fn is_sign_negative(self) -> bool {
                 // must freeze to keep poison out of bool branching
@@ -327,38 +421,155 @@ macro_rules! impls {
             }
 
             #[inline]
-            pub fn hypot(self, other: Self) -> Self {
-                (self * self + other * other).sqrt()
+            pub fn signum(self) -> Self {
+                Self::ONE.copysign(self)
             }
 
             #[inline]
-            pub fn signum(self) -> Self {
-                Self::ONE.copysign(self)
+            pub fn fract(self) -> Self {
+                self - self.trunc()
             }
 
+            /// Linearly interpolate from this value to `end`, by the fraction `t`.
+            ///
+            /// This is computed as `self + t * (end - self)`, where the multiplication and
+            /// addition may be fused. The result is `self` when `t` is 0, but not necessarily
+            /// exactly `end` when `t` is 1.
             #[inline]
-            pub fn recip(self) -> Self {
-                Self::ONE / self
+            pub fn lerp(self, end: Self, t: Self) -> Self {
+                self + t * (end - self)
             }
 
+            /// The fraction at which [`lerp`](Self::lerp) from this value to `end` would produce
+            /// `x`, `(x - self) / (end - self)`.
+            ///
+            /// `end` must differ from this value.
             #[inline]
-            pub fn fract(self) -> Self {
-                self - self.trunc()
+            pub fn inverse_lerp(self, end: Self, x: Self) -> Self {
+                (x - self) / (end - self)
             }
 
+            /// The value halfway between this one and `other`, like the standard library's
+            /// `midpoint`.
+            ///
+            /// Unlike the standard library's, this is computed as `(self + other) * 0.5` without
+            /// guarding against overflow, so the sum must be finite.
             #[inline]
-            pub fn log(self, base: Self) -> Self {
-                self.ln() / base.ln()
+            pub fn midpoint(self, other: Self) -> Self {
+                (self + other) * 0.5
             }
 
+            /// The least value greater than this one, like the standard library's `next_up`.
+            ///
+            /// This is computed from the bits of a frozen copy of the value. The smallest
+            /// subnormal value follows zero, and the largest finite value is followed by infinity.
             #[inline]
-            pub fn mul_add(self, mul: Self, add: Self) -> Self {
-                self * mul + add
+            pub fn next_up(self) -> Self {
+                <$fast_ty>::new(Self::next_up_raw(self.freeze_raw()))
             }
 
+            /// The greatest value less than this one, like the standard library's `next_down`.
+            ///
+            /// This is computed from the bits of a frozen copy of the value, like
+            /// [`next_up`](Self::next_up).
             #[inline]
-            pub fn sin_cos(self) -> (Self, Self) {
-                (self.sin(), self.cos())
+            pub fn next_down(self) -> Self {
+                <$fast_ty>::new(-Self::next_up_raw(-self.freeze_raw()))
+            }
+
+            /// The unit in the last place of this value: the distance from its magnitude to the
+            /// next value away from zero.
+            ///
+            /// This is the spacing of the values near this one, for tolerances which scale with
+            /// the values compared. The largest finite value has no finite value above it, so its
+            /// ulp is the distance to the value below, which is the spacing of its whole binade.
+            ///
+            /// ```
+            #[doc = concat!("use fast_fp::", stringify!($fast_ty), " as Fast;")]
+            #[doc = concat!("type Base = ", stringify!($base_ty), ";")]
+            ///
+            /// assert_eq!(Fast::new(1.0).ulp(), Base::EPSILON);
+            /// assert_eq!(Fast::new(-1.0).ulp(), Base::EPSILON);
+            ///
+            /// // the spacing of the values in the top binade
+            /// let top = (2.0 as Base).powi(Base::MAX_EXP - Base::MANTISSA_DIGITS as i32);
+            /// assert_eq!(Fast::MAX.ulp(), top);
+            /// ```
+            #[inline]
+            pub fn ulp(self) -> Self {
+                let abs = self.freeze_raw().abs();
+                if abs == <$base_ty>::MAX {
+                    return <$fast_ty>::new(abs + Self::next_up_raw(-abs));
+                }
+                <$fast_ty>::new(Self::next_up_raw(abs) - abs)
+            }
+
+            // the bits of a positive value are incremented to move away from zero, and those of a
+            // negative value decremented to move towards it
+            #[inline]
+            fn next_up_raw(value: $base_ty) -> $base_ty {
+                let bits = value.to_bits();
+                if value.is_nan() || value == <$base_ty>::INFINITY {
+                    return value;
+                }
+
+                let abs = bits & (!0 >> 1);
+                let next = if abs == 0 {
+                    1
+                } else if bits == abs {
+                    bits + 1
+                } else {
+                    bits - 1
+                };
+                <$base_ty>::from_bits(next)
+            }
+
+            /// Add two values with IEEE 754 semantics, without any fast-math optimizations.
+            ///
+            /// The operands are frozen first, so this can be used for accuracy-critical steps
+            /// within an otherwise fast computation.
+            #[inline]
+            pub fn strict_add(self, other: Self) -> Self {
+                <$fast_ty>::new(self.freeze_raw() + other.freeze_raw())
+            }
+
+            /// Subtract two values with IEEE 754 semantics, without any fast-math optimizations.
+            ///
+            /// The operands are frozen first, so this can be used for accuracy-critical steps
+            /// within an otherwise fast computation.
+            #[inline]
+            pub fn strict_sub(self, other: Self) -> Self {
+                <$fast_ty>::new(self.freeze_raw() - other.freeze_raw())
+            }
+
+            /// Multiply two values with IEEE 754 semantics, without any fast-math optimizations.
+            ///
+            /// The operands are frozen first, so this can be used for accuracy-critical steps
+            /// within an otherwise fast computation.
+            #[inline]
+            pub fn strict_mul(self, other: Self) -> Self {
+                <$fast_ty>::new(self.freeze_raw() * other.freeze_raw())
+            }
+
+            /// Divide two values with IEEE 754 semantics, without any fast-math optimizations.
+            ///
+            /// The operands are frozen first, so this can be used for accuracy-critical steps
+            /// within an otherwise fast computation.
+            #[inline]
+            pub fn strict_div(self, other: Self) -> Self {
+                <$fast_ty>::new(self.freeze_raw() / other.freeze_raw())
+            }
+
+            /// Sum the values of an iterator in order with IEEE 754 semantics, without any
+            /// fast-math optimizations.
+            ///
+            /// Each value is frozen before it's accumulated. Unlike the `Sum` impl, the additions
+            /// will not be reordered or vectorized.
+            #[inline]
+            pub fn strict_sum<I>(iter: I) -> Self
+                where I: IntoIterator<Item = Self>
+            {
+                <$fast_ty>::new(iter.into_iter().fold(0.0, |acc, val| acc + val.freeze_raw()))
             }
         }
 
@@ -377,7 +588,7 @@ macro_rules! impls {
         }
 
         impl_reduce_ops! {
-            $fast_ty, $base_ty:
+            $fast_ty, $base_ty, crate::reduce:
             Sum, sum, add, Self::ZERO,
             Product, product, mul, Self::ONE,
         }
@@ -0,0 +1,51 @@
+use crate::{FF32, FF64};
+
+/// A logarithm base, for computing many logarithms in the same base.
+///
+/// [`log`](FF32::log) computes the natural logarithm of the base on every call. This computes it
+/// once, along with its reciprocal, so that each logarithm is only a natural logarithm and a
+/// multiplication.
+///
+/// ```
+/// use fast_fp::{ff32, LogBase, FF32};
+///
+/// let base = LogBase::<FF32>::new(ff32(3.0));
+/// assert!((base.log(ff32(81.0)) - 4.0).abs() < ff32(1e-5));
+/// assert!((base.log(ff32(5.0)) - ff32(5.0).log(ff32(3.0))).abs() < ff32(1e-5));
+/// ```
+#[derive(Clone, Copy, Debug)]
+pub struct LogBase<T> {
+    base: T,
+    // the reciprocal of the natural logarithm of the base
+    scale: T,
+}
+
+macro_rules! impl_log_base {
+    ($fast_ty:ident) => {
+        impl LogBase<$fast_ty> {
+            /// Create a base for logarithms, which must be positive and not 1
+            #[inline]
+            pub fn new(base: $fast_ty) -> Self {
+                LogBase {
+                    base,
+                    scale: base.ln().recip(),
+                }
+            }
+
+            /// The base of the logarithms
+            #[inline]
+            pub fn base(&self) -> $fast_ty {
+                self.base
+            }
+
+            /// The logarithm of `x` in this base
+            #[inline]
+            pub fn log(&self, x: $fast_ty) -> $fast_ty {
+                x.ln() * self.scale
+            }
+        }
+    };
+}
+
+impl_log_base! { FF32 }
+impl_log_base! { FF64 }
@@ -0,0 +1,178 @@
+//! Loss functions between probability distributions, for training loops and model evaluation.
+//!
+//! Both functions need `exp` or `ln`, and like the other transcendental functions their inputs are
+//! frozen first: a block of values at a time is frozen into a buffer, and the whole loop over the
+//! block runs in C, with the math functions fused into the sums (and replaced by the inlinable
+//! approximations with the `approx-math` feature).
+//!
+//! ```
+//! use fast_fp::{ff64, loss};
+//!
+//! // equal logits predict a uniform distribution
+//! let logits = [0.0, 0.0, 0.0, 0.0].map(ff64);
+//! let targets = [0.0, 1.0, 0.0, 0.0].map(ff64);
+//! assert!((loss::cross_entropy(&logits, &targets) - 4f64.ln()).abs() < ff64(1e-12));
+//!
+//! let p = [0.5, 0.5, 0.0].map(ff64);
+//! let q = [0.25, 0.25, 0.5].map(ff64);
+//! assert!((loss::kl_divergence(&p, &q) - 2f64.ln()).abs() < ff64(1e-12));
+//! ```
+
+use crate::{slice, FF32, FF64};
+use paste::paste;
+
+// the number of values frozen at a time before calling the poison unsafe kernels
+const LOSS_BLOCK: usize = 256;
+
+/// The fast scalar types which have loss kernels, used to select the implementation of the
+/// functions in this module.
+pub trait LossKernels: Sized {
+    /// See [`cross_entropy`]
+    fn cross_entropy(logits: &[Self], targets: &[Self]) -> Self;
+
+    /// See [`kl_divergence`]
+    fn kl_divergence(p: &[Self], q: &[Self]) -> Self;
+}
+
+/// The cross entropy of a target distribution relative to the softmax of some logits,
+/// `-sum(targets[i] * ln(softmax(logits)[i]))`.
+///
+/// The softmax is never materialized: the loss is computed from the log-sum-exp of the logits,
+/// offset by their maximum so that the exponentials can't overflow, along with the sums of the
+/// targets and of each target times its logit. For a one-hot target this is the negative
+/// log-probability of the target class.
+///
+/// # Panics
+/// Panics if the slices are empty or have different lengths
+#[inline]
+pub fn cross_entropy<T: LossKernels>(logits: &[T], targets: &[T]) -> T {
+    T::cross_entropy(logits, targets)
+}
+
+/// The Kullback-Leibler divergence of `q` from `p`, `sum(p[i] * ln(p[i] / q[i]))`.
+///
+/// Terms where `p[i]` is zero contribute nothing. `q[i]` must be positive wherever `p[i]` is,
+/// otherwise the divergence is infinite, which is not a valid fast value.
+///
+/// # Panics
+/// Panics if the slices have different lengths
+#[inline]
+pub fn kl_divergence<T: LossKernels>(p: &[T], q: &[T]) -> T {
+    T::kl_divergence(p, q)
+}
+
+macro_rules! impl_loss {
+    ($fast_ty:ident, $base_ty:ident) => {
+        paste! {
+            #[cfg(not(scalar_fallback))]
+            #[link(name = "poison_unsafe")]
+            extern "C" {
+                // functions in the poison_unsafe lib must have their arguments frozen, so the
+                // inputs are passed as the base type
+                fn [<cross_entropy_sums_ $base_ty>](
+                    z: *const $base_ty,
+                    t: *const $base_ty,
+                    len: usize,
+                    max: $base_ty,
+                    sums: *mut $base_ty,
+                );
+                fn [<kl_divergence_sum_ $base_ty>](p: *const $base_ty, q: *const $base_ty, len: usize) -> $fast_ty;
+            }
+
+            // with the strict feature, the shims are replaced by the same formulas over the base
+            // type
+            #[cfg(scalar_fallback)]
+            mod [<strict_loss_ $base_ty>] {
+                use super::*;
+
+                #[inline(always)]
+                pub(super) unsafe fn [<cross_entropy_sums_ $base_ty>](
+                    z: *const $base_ty,
+                    t: *const $base_ty,
+                    len: usize,
+                    max: $base_ty,
+                    sums: *mut $base_ty,
+                ) {
+                    let z = core::slice::from_raw_parts(z, len);
+                    let t = core::slice::from_raw_parts(t, len);
+                    let sums = core::slice::from_raw_parts_mut(sums, 3);
+                    for (z, t) in z.iter().zip(t) {
+                        sums[0] += (z - max).exp();
+                        sums[1] += t;
+                        sums[2] += t * z;
+                    }
+                }
+
+                #[inline(always)]
+                pub(super) unsafe fn [<kl_divergence_sum_ $base_ty>](
+                    p: *const $base_ty,
+                    q: *const $base_ty,
+                    len: usize,
+                ) -> $fast_ty {
+                    let p = core::slice::from_raw_parts(p, len);
+                    let q = core::slice::from_raw_parts(q, len);
+                    let sum = p
+                        .iter()
+                        .zip(q)
+                        .map(|(p, q)| if *p > 0.0 { p * (p / q).ln() } else { 0.0 })
+                        .sum();
+                    <$fast_ty>::new(sum)
+                }
+            }
+
+            #[cfg(scalar_fallback)]
+            use [<strict_loss_ $base_ty>]::*;
+
+            // freeze a block of values at a time from each slice into buffers, which are passed
+            // to the poison unsafe kernel
+            #[inline(always)]
+            fn [<for_frozen_ $base_ty>](
+                a: &[$fast_ty],
+                b: &[$fast_ty],
+                mut kernel: impl FnMut(&[$base_ty], &[$base_ty]),
+            ) {
+                assert_eq!(a.len(), b.len(), "slice lengths differ");
+                let mut frozen_a = [0.0; LOSS_BLOCK];
+                let mut frozen_b = [0.0; LOSS_BLOCK];
+                for (a, b) in a.chunks(LOSS_BLOCK).zip(b.chunks(LOSS_BLOCK)) {
+                    let frozen_a = &mut frozen_a[..a.len()];
+                    let frozen_b = &mut frozen_b[..b.len()];
+                    for ((frozen_a, frozen_b), (a, b)) in frozen_a.iter_mut().zip(frozen_b.iter_mut()).zip(a.iter().zip(b)) {
+                        *frozen_a = a.freeze_raw();
+                        *frozen_b = b.freeze_raw();
+                    }
+                    kernel(frozen_a, frozen_b);
+                }
+            }
+
+            impl LossKernels for $fast_ty {
+                #[inline]
+                fn cross_entropy(logits: &[Self], targets: &[Self]) -> Self {
+                    let max = slice::max(logits).expect("logits must not be empty").freeze_raw();
+
+                    let mut sums = [0.0; 3];
+                    [<for_frozen_ $base_ty>](logits, targets, |z, t| unsafe {
+                        [<cross_entropy_sums_ $base_ty>](z.as_ptr(), t.as_ptr(), z.len(), max, sums.as_mut_ptr())
+                    });
+
+                    // ln(sum(exp(z))) = max + ln(sum(exp(z - max))), scaled by the total target
+                    // weight, which is one for a proper distribution
+                    let [sum_exp, sum_targets, sum_products] = sums.map(Self::new);
+                    (sum_exp.ln() + max) * sum_targets - sum_products
+                }
+
+                #[inline]
+                fn kl_divergence(p: &[Self], q: &[Self]) -> Self {
+                    let mut sum = Self::ZERO;
+                    [<for_frozen_ $base_ty>](p, q, |p, q| {
+                        sum += unsafe { [<kl_divergence_sum_ $base_ty>](p.as_ptr(), q.as_ptr(), p.len()) };
+                    });
+                    sum
+                }
+            }
+        }
+    };
+}
+
+impl_loss! { FF32, f32 }
+impl_loss! { FF64, f64 }
@@ -0,0 +1,212 @@
+//! Sine and cosine from lookup tables, trading accuracy for latency.
+//!
+//! [`FF32::sin_lut`] and [`FF32::cos_lut`] (and their [`FF64`] counterparts) read a built-in
+//! table of 1024 samples per period with linear interpolation, which is accurate to about
+//! `5e-6` near zero. A [`SinCosTable`] selects its own size and [`Interpolation`] instead:
+//! larger tables are more accurate but take more cache, and nearest-sample lookups skip the
+//! interpolation entirely.
+//!
+//! Unlike [`sin`](FF32::sin) and [`cos`](FF32::cos), a lookup needs no libm call, only a
+//! multiplication to find the position in the table, a truncation, and a load (or two, and an
+//! interpolation). The position is computed in the precision of the argument, so the accuracy
+//! degrades as its magnitude grows (an `FF32` argument of 100 already doubles the error above).
+//! The lookups are intended for arguments close to zero, like the phases of oscillators which are
+//! wrapped every period or so.
+//!
+//! ```
+//! use fast_fp::{ff32, lut::{Interpolation, SinCosTable}, FF32};
+//!
+//! assert!((ff32(0.5).sin_lut() - ff32(0.5).sin()).abs() < ff32(1e-5));
+//! assert!((ff32(-2.0).cos_lut() - ff32(-2.0).cos()).abs() < ff32(1e-5));
+//! assert!((ff32(-0.1).cos_lut() - ff32(-0.1).cos()).abs() < ff32(1e-5));
+//!
+//! let table = SinCosTable::<FF32>::new(4096, Interpolation::Nearest);
+//! let (sin, cos) = table.sin_cos(ff32(1.0));
+//! assert!((sin - ff32(1.0).sin()).abs() < ff32(1e-3));
+//! assert!((cos - ff32(1.0).cos()).abs() < ff32(1e-3));
+//!
+//! // negative positions wrap around the table like positive ones
+//! let (sin, cos) = table.sin_cos(ff32(-0.1));
+//! assert!((sin - ff32(-0.1).sin()).abs() < ff32(1e-3));
+//! assert!((cos - ff32(-0.1).cos()).abs() < ff32(1e-3));
+//! ```
+
+use crate::{FF32, FF64};
+use paste::paste;
+
+// the number of samples per period in the built-in tables, which are generated by the build
+// script. Each table has one more entry, which repeats the first
+const DEFAULT_SIZE: usize = 1024;
+
+/// How a [`SinCosTable`] computes values between its samples
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Interpolation {
+    /// The nearest sample, with an error of up to about `pi / size`
+    Nearest,
+    /// A linear interpolation between the two neighboring samples, with an error of up to about
+    /// `(2 * pi / size)^2 / 8`
+    Linear,
+}
+
+/// A table of samples of one period of the sine, from which [`sin`](SinCosTable::sin) and
+/// [`cos`](SinCosTable::cos) are looked up.
+///
+/// The table is built once (with the standard library's `sin`) and can then be shared by any
+/// number of lookups. Cosines read the same samples a quarter period further on.
+#[derive(Clone, Debug)]
+pub struct SinCosTable<T> {
+    // size + 1 samples, the last repeating the first so that interpolation never wraps around
+    samples: Vec<T>,
+    // the number of samples per radian, size / (2 * pi)
+    scale: T,
+    interpolation: Interpolation,
+}
+
+macro_rules! impl_lut {
+    ($fast_ty:ident, $base_ty:ident) => {
+        paste! {
+            // some of the samples are (rounded) well-known constants, like 1 / sqrt(2)
+            #[allow(clippy::approx_constant)]
+            static [<SIN_TABLE_ $base_ty:upper>]: [$fast_ty; DEFAULT_SIZE + 1] =
+                include!(concat!(env!("OUT_DIR"), "/sin_table_", stringify!($base_ty), ".rs"));
+
+            const [<DEFAULT_SCALE_ $base_ty:upper>]: $base_ty =
+                DEFAULT_SIZE as $base_ty / core::$base_ty::consts::TAU;
+
+            // Look up the sine at `position` samples into the table, offset by `offset` samples.
+            // The size of the table must be a power of two, so the integer part of the position
+            // wraps around with a mask
+            #[inline(always)]
+            fn [<lookup_ $base_ty>](
+                samples: &[$fast_ty],
+                position: $base_ty,
+                offset: usize,
+                interpolation: Interpolation,
+            ) -> $fast_ty {
+                let mask = samples.len() - 2;
+                match interpolation {
+                    Interpolation::Nearest => {
+                        let (index, _) = [<split_ $base_ty>](position + 0.5);
+                        samples[index.wrapping_add(offset) & mask]
+                    }
+                    Interpolation::Linear => {
+                        let (index, fraction) = [<split_ $base_ty>](position);
+                        let index = index.wrapping_add(offset) & mask;
+                        let (a, b) = (samples[index], samples[index + 1]);
+                        a + (b - a) * <$fast_ty>::new(fraction)
+                    }
+                }
+            }
+
+            // Split a position into its floor (wrapped to usize, which is harmless under the mask)
+            // and the fraction above it. The floor is computed by truncation rather than with
+            // `floor`, which is a libm call on targets without a rounding instruction
+            #[inline(always)]
+            fn [<split_ $base_ty>](position: $base_ty) -> (usize, $base_ty) {
+                let mut index = position as i64;
+                if index as $base_ty > position {
+                    index -= 1;
+                }
+                (index as usize, position - index as $base_ty)
+            }
+
+            impl $fast_ty {
+                /// The sine of this value in radians, looked up in a built-in table.
+                ///
+                /// The table has 1024 samples per period with linear interpolation, so the result
+                /// is within about `5e-6` of the true sine for small arguments. See the
+                /// [`lut`](crate::lut) module for the trade-offs and a configurable table.
+                #[inline]
+                pub fn sin_lut(self) -> Self {
+                    let position = self.freeze_raw() * [<DEFAULT_SCALE_ $base_ty:upper>];
+                    [<lookup_ $base_ty>](
+                        &[<SIN_TABLE_ $base_ty:upper>],
+                        position,
+                        0,
+                        Interpolation::Linear,
+                    )
+                }
+
+                /// The cosine of this value in radians, looked up in a built-in table.
+                ///
+                /// The accuracy is the same as [`sin_lut`](Self::sin_lut).
+                #[inline]
+                pub fn cos_lut(self) -> Self {
+                    let position = self.freeze_raw() * [<DEFAULT_SCALE_ $base_ty:upper>];
+                    [<lookup_ $base_ty>](
+                        &[<SIN_TABLE_ $base_ty:upper>],
+                        position,
+                        DEFAULT_SIZE / 4,
+                        Interpolation::Linear,
+                    )
+                }
+            }
+
+            impl SinCosTable<$fast_ty> {
+                /// Build a table of `size` samples of one period of the sine, which are looked up
+                /// with the given interpolation.
+                ///
+                /// # Panics
+                /// Panics if `size` is not a power of two, or is smaller than 4
+                pub fn new(size: usize, interpolation: Interpolation) -> Self {
+                    assert!(size.is_power_of_two() && size >= 4, "invalid table size");
+                    let step = core::f64::consts::TAU / size as f64;
+                    let samples = (0..=size)
+                        .map(|i| <$fast_ty>::new((step * (i % size) as f64).sin() as $base_ty))
+                        .collect();
+                    let scale = (size as f64 / core::f64::consts::TAU) as $base_ty;
+                    SinCosTable { samples, scale: <$fast_ty>::new(scale), interpolation }
+                }
+
+                /// The number of samples per period
+                #[inline]
+                pub fn size(&self) -> usize {
+                    self.samples.len() - 1
+                }
+
+                /// The interpolation between samples
+                #[inline]
+                pub fn interpolation(&self) -> Interpolation {
+                    self.interpolation
+                }
+
+                /// The sine of `x` in radians
+                #[inline]
+                pub fn sin(&self, x: $fast_ty) -> $fast_ty {
+                    let position = (x * self.scale).freeze_raw();
+                    [<lookup_ $base_ty>](&self.samples, position, 0, self.interpolation)
+                }
+
+                /// The cosine of `x` in radians
+                #[inline]
+                pub fn cos(&self, x: $fast_ty) -> $fast_ty {
+                    let position = (x * self.scale).freeze_raw();
+                    [<lookup_ $base_ty>](
+                        &self.samples,
+                        position,
+                        self.size() / 4,
+                        self.interpolation,
+                    )
+                }
+
+                /// The sine and cosine of `x` in radians, which share the computation of the
+                /// position in the table
+                #[inline]
+                pub fn sin_cos(&self, x: $fast_ty) -> ($fast_ty, $fast_ty) {
+                    let position = (x * self.scale).freeze_raw();
+                    let sin = [<lookup_ $base_ty>](&self.samples, position, 0, self.interpolation);
+                    let cos = [<lookup_ $base_ty>](
+                        &self.samples,
+                        position,
+                        self.size() / 4,
+                        self.interpolation,
+                    );
+                    (sin, cos)
+                }
+            }
+        }
+    };
+}
+
+impl_lut! { FF32, f32 }
+impl_lut! { FF64, f64 }
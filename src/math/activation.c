@@ -0,0 +1,37 @@
+/*
+ * The poison safe activation functions, from the `activation` module. They
+ * are compiled into the poison_safe library, with the same flags and under
+ * the same constraints: every operation here can accept poison values without
+ * triggering UB.
+ *
+ * Only the piecewise linear functions are here, which need nothing beyond a
+ * max. The rest need exp or tanh, and are in activation_unsafe.c.
+ */
+
+#include <stddef.h>
+
+#include "libm.h"
+
+/*
+ * With a slope between zero and one, the leaky relu is the larger of `x` and
+ * `slope * x`, which avoids any comparison
+ */
+#define IMPL_ACTIVATION_SAFE(C_TYPE, RUST_TYPE, MAX_FN)                        \
+  __attribute__((always_inline))                                              \
+  void relu_slice_ ## RUST_TYPE(C_TYPE *restrict a, size_t len) {             \
+    for(size_t i = 0; i < len; i++) {                                         \
+      a[i] = MAX_FN(a[i], 0);                                                 \
+    }                                                                         \
+  }                                                                           \
+                                                                              \
+  __attribute__((always_inline))                                              \
+  void leaky_relu_slice_ ## RUST_TYPE(C_TYPE *restrict a,                     \
+                                      size_t len,                             \
+                                      C_TYPE slope) {                         \
+    for(size_t i = 0; i < len; i++) {                                         \
+      a[i] = MAX_FN(a[i], slope * a[i]);                                      \
+    }                                                                         \
+  }                                                                           \
+
+IMPL_ACTIVATION_SAFE(float, f32, fmaxf)
+IMPL_ACTIVATION_SAFE(double, f64, fmax)
@@ -0,0 +1,60 @@
+/*
+ * The poison unsafe activation functions, from the `activation` module. These
+ * are compiled into the poison_unsafe library, with the same flags and under
+ * the same constraints: arguments must be frozen before being passed to these
+ * functions.
+ *
+ * Each function has a scalar version and a slice version, which reads frozen
+ * values from `x` and writes the results to `out`. With the `approx-math`
+ * feature, exp is replaced by its approximation from approx.h, which unlike
+ * libm can be inlined and vectorized within the slice loops.
+ */
+
+#include <stddef.h>
+
+#include "libm.h"
+
+#ifdef FAST_FP_APPROX_MATH
+#include "approx.h"
+#define MATH(FN) approx_ ## FN
+#else
+#define MATH(FN) FN
+#endif
+
+/* the constants of the tanh approximation of GELU */
+#define GELU_SCALE 0.7978845608028654 /* sqrt(2 / pi) */
+#define GELU_CUBIC 0.044715
+
+#define IMPL_ACTIVATION_FN(C_TYPE, RUST_TYPE, FN_NAME, EXPR)                   \
+  __attribute__((always_inline))                                              \
+  C_TYPE FN_NAME ## _ ## RUST_TYPE(C_TYPE x) {                                \
+    return EXPR;                                                              \
+  }                                                                           \
+                                                                              \
+  __attribute__((always_inline))                                              \
+  void FN_NAME ## _slice_ ## RUST_TYPE(const C_TYPE *restrict in,             \
+                                       C_TYPE *restrict out,                  \
+                                       size_t len) {                          \
+    for(size_t i = 0; i < len; i++) {                                         \
+      C_TYPE x = in[i];                                                       \
+      out[i] = EXPR;                                                          \
+    }                                                                         \
+  }                                                                           \
+
+/*
+ * Above SOFTPLUS_LIMIT, softplus(x) rounds to x itself, and the exp would
+ * eventually overflow to infinity
+ */
+#define IMPL_ACTIVATION(C_TYPE, RUST_TYPE, S, SOFTPLUS_LIMIT)                 \
+  IMPL_ACTIVATION_FN(C_TYPE, RUST_TYPE, sigmoid,                              \
+                     1 / (1 + MATH(exp ## S)(-x)))                            \
+  IMPL_ACTIVATION_FN(C_TYPE, RUST_TYPE, silu,                                 \
+                     x / (1 + MATH(exp ## S)(-x)))                            \
+  IMPL_ACTIVATION_FN(C_TYPE, RUST_TYPE, gelu,                                 \
+                     (C_TYPE)0.5 * x * (1 + tanh ## S((C_TYPE)GELU_SCALE      \
+                         * (x + (C_TYPE)GELU_CUBIC * x * x * x))))            \
+  IMPL_ACTIVATION_FN(C_TYPE, RUST_TYPE, softplus,                             \
+                     x > SOFTPLUS_LIMIT ? x : log1p ## S(MATH(exp ## S)(x)))  \
+
+IMPL_ACTIVATION(float, f32, f, 20)
+IMPL_ACTIVATION(double, f64, , 40)
@@ -0,0 +1,210 @@
+/*
+ * Polynomial approximations of the most common transcendental functions,
+ * which replace the platform libm in poison_unsafe.c with the `approx-math`
+ * feature, and otherwise back the `Accuracy::Fastest` tier. They're faster
+ * and can be inlined and vectorized, but are only accurate to about single
+ * precision, for both float and double.
+ *
+ * Like the rest of poison_unsafe, they're compiled without finite-math-only
+ * and must not trigger UB for any input. Arguments are range checked before
+ * any float to integer conversion, and the special cases (NaN, infinities,
+ * zero and negative logarithms) give the same results as libm, either
+ * explicitly or by forwarding to libm itself.
+ */
+
+#ifndef FAST_FP_APPROX_H
+#define FAST_FP_APPROX_H
+
+#include <stdint.h>
+
+#include "libm.h"
+
+#define APPROX_LN_2 0.6931471805599453
+#define APPROX_LOG2_E 1.4426950408889634
+#define APPROX_LOG10_2 0.3010299956639812
+#define APPROX_SQRT_2 1.4142135623730951
+#define APPROX_2_PI 0.6366197723675814
+
+/*
+ * pi/2 split into three parts for the Cody-Waite range reduction of the
+ * trigonometric functions. The first two have few enough bits that their
+ * products with the quadrant number are exact, up to `SIN_LIMIT`
+ */
+#define APPROX_PI_2_A 1.5703125
+#define APPROX_PI_2_B 4.837512969970703125e-4
+#define APPROX_PI_2_C 7.549789948768648e-8
+
+/* clang only, other compilers have no equivalent pragma */
+#ifdef __clang__
+#define APPROX_NO_REASSOC _Pragma("clang fp reassociate(off)")
+#else
+#define APPROX_NO_REASSOC
+#endif
+
+/*
+ * `S` is the suffix of the libm functions for the type (empty for double),
+ * `UINT_TYPE` is the unsigned integer of the same size, and `MANT_BITS` and
+ * `EXP_BIAS` describe the type's binary representation. Trigonometric
+ * arguments beyond `SIN_LIMIT` are forwarded to libm, as the range reduction
+ * loses too much precision for them.
+ */
+#define IMPL_APPROX(C_TYPE, S, UINT_TYPE, MANT_BITS, EXP_BIAS, MIN_NORMAL,      \
+                    SIN_LIMIT)                                                  \
+  /* 2^x = 2^n * 2^f, with n the nearest integer and a polynomial for 2^f */    \
+  __attribute__((always_inline))                                                \
+  static inline C_TYPE approx_exp2 ## S(C_TYPE x) {                             \
+    if(__builtin_isnan(x)) {                                                    \
+      return x;                                                                 \
+    }                                                                           \
+    /* large results overflow to inf, and small ones flush to zero */           \
+    x = fmin ## S(fmax ## S(x, -(EXP_BIAS)), EXP_BIAS + 1);                     \
+    C_TYPE n = floor ## S(x + (C_TYPE)0.5);                                     \
+    C_TYPE f = x - n;                                                           \
+    C_TYPE p = 1 + f * ((C_TYPE)0.6931471805599453                              \
+                 + f * ((C_TYPE)0.2402265069591007                              \
+                 + f * ((C_TYPE)0.055504108664821576                            \
+                 + f * ((C_TYPE)0.009618129107628477                            \
+                 + f * ((C_TYPE)0.0013333558146428441                           \
+                 + f * ((C_TYPE)0.00015403530393381606                          \
+                 + f * (C_TYPE)1.5252733804059838e-05))))));                    \
+    UINT_TYPE bits = (UINT_TYPE)(int64_t)(n + EXP_BIAS) << MANT_BITS;           \
+    C_TYPE scale;                                                               \
+    __builtin_memcpy(&scale, &bits, sizeof(scale));                             \
+    return p * scale;                                                           \
+  }                                                                             \
+                                                                                \
+  __attribute__((always_inline))                                                \
+  static inline C_TYPE approx_exp ## S(C_TYPE x) {                              \
+    return approx_exp2 ## S(x * (C_TYPE)APPROX_LOG2_E);                         \
+  }                                                                             \
+                                                                                \
+  /*                                                                            \
+   * log2(x) = e + log2(m), with m in [sqrt(1/2), sqrt(2)] and log2(m) from     \
+   * the series of atanh((m - 1) / (m + 1))                                     \
+   */                                                                           \
+  __attribute__((always_inline))                                                \
+  static inline C_TYPE approx_log2 ## S(C_TYPE x) {                             \
+    if(__builtin_isnan(x) || x == (C_TYPE)__builtin_inf()) {                    \
+      return x;                                                                 \
+    }                                                                           \
+    if(x == 0) {                                                                \
+      return -(C_TYPE)__builtin_inf();                                          \
+    }                                                                           \
+    if(x < 0) {                                                                 \
+      return (C_TYPE)__builtin_nan("");                                         \
+    }                                                                           \
+    C_TYPE e = 0;                                                               \
+    if(x < (C_TYPE)MIN_NORMAL) {                                                \
+      /* subnormals are scaled up to be normalized like the others */           \
+      x *= (C_TYPE)((UINT_TYPE)1 << MANT_BITS);                                 \
+      e = -(MANT_BITS);                                                         \
+    }                                                                           \
+    UINT_TYPE bits;                                                             \
+    __builtin_memcpy(&bits, &x, sizeof(bits));                                  \
+    e += (C_TYPE)(int64_t)(bits >> MANT_BITS) - EXP_BIAS;                       \
+    bits = (bits & (((UINT_TYPE)1 << MANT_BITS) - 1))                           \
+         | ((UINT_TYPE)EXP_BIAS << MANT_BITS);                                  \
+    C_TYPE m;                                                                   \
+    __builtin_memcpy(&m, &bits, sizeof(m));                                     \
+    if(m > (C_TYPE)APPROX_SQRT_2) {                                             \
+      m *= (C_TYPE)0.5;                                                         \
+      e += 1;                                                                   \
+    }                                                                           \
+    C_TYPE s = (m - 1) / (m + 1);                                               \
+    C_TYPE s2 = s * s;                                                          \
+    C_TYPE atanh = s * (1 + s2 * ((C_TYPE)(1.0 / 3)                             \
+                          + s2 * ((C_TYPE)(1.0 / 5)                             \
+                          + s2 * ((C_TYPE)(1.0 / 7)                             \
+                          + s2 * (C_TYPE)(1.0 / 9)))));                         \
+    return e + atanh * (C_TYPE)(2 / APPROX_LN_2);                               \
+  }                                                                             \
+                                                                                \
+  __attribute__((always_inline))                                                \
+  static inline C_TYPE approx_log ## S(C_TYPE x) {                              \
+    return approx_log2 ## S(x) * (C_TYPE)APPROX_LN_2;                           \
+  }                                                                             \
+                                                                                \
+  __attribute__((always_inline))                                                \
+  static inline C_TYPE approx_log10 ## S(C_TYPE x) {                            \
+    return approx_log2 ## S(x) * (C_TYPE)APPROX_LOG10_2;                        \
+  }                                                                             \
+                                                                                \
+  /* the special cases of pow are left to libm */                               \
+  __attribute__((always_inline))                                                \
+  static inline C_TYPE approx_pow ## S(C_TYPE a, C_TYPE b) {                    \
+    if(!(a > 0) || !__builtin_isfinite(a) || !__builtin_isfinite(b)) {         \
+      return pow ## S(a, b);                                                    \
+    }                                                                           \
+    return approx_exp2 ## S(b * approx_log2 ## S(a));                           \
+  }                                                                             \
+                                                                                \
+  /*                                                                            \
+   * reduce x to r in [-pi/4, pi/4], with x = r + q * pi/2. Reassociation must  \
+   * be disabled, as it would fold the parts of pi/2 back together              \
+   */                                                                           \
+  __attribute__((always_inline))                                                \
+  static inline C_TYPE approx_reduce ## S(C_TYPE x, int64_t *q) {               \
+    APPROX_NO_REASSOC                                                           \
+    C_TYPE k = floor ## S(x * (C_TYPE)APPROX_2_PI + (C_TYPE)0.5);               \
+    *q = (int64_t)k & 3;                                                        \
+    return ((x - k * (C_TYPE)APPROX_PI_2_A) - k * (C_TYPE)APPROX_PI_2_B)        \
+           - k * (C_TYPE)APPROX_PI_2_C;                                         \
+  }                                                                             \
+                                                                                \
+  __attribute__((always_inline))                                                \
+  static inline C_TYPE approx_sin_poly ## S(C_TYPE r) {                         \
+    C_TYPE r2 = r * r;                                                          \
+    return r * (1 + r2 * ((C_TYPE)(-1.0 / 6)                                    \
+                  + r2 * ((C_TYPE)(1.0 / 120)                                   \
+                  + r2 * ((C_TYPE)(-1.0 / 5040)                                 \
+                  + r2 * (C_TYPE)(1.0 / 362880)))));                            \
+  }                                                                             \
+                                                                                \
+  __attribute__((always_inline))                                                \
+  static inline C_TYPE approx_cos_poly ## S(C_TYPE r) {                         \
+    C_TYPE r2 = r * r;                                                          \
+    return 1 + r2 * ((C_TYPE)(-1.0 / 2)                                         \
+             + r2 * ((C_TYPE)(1.0 / 24)                                         \
+             + r2 * ((C_TYPE)(-1.0 / 720)                                       \
+             + r2 * ((C_TYPE)(1.0 / 40320)                                      \
+             + r2 * (C_TYPE)(-1.0 / 3628800)))));                               \
+  }                                                                             \
+                                                                                \
+  __attribute__((always_inline))                                                \
+  static inline C_TYPE approx_sin ## S(C_TYPE x) {                              \
+    if(!(fabs ## S(x) <= SIN_LIMIT)) {                                          \
+      return sin ## S(x);                                                       \
+    }                                                                           \
+    int64_t q;                                                                  \
+    C_TYPE r = approx_reduce ## S(x, &q);                                       \
+    C_TYPE y = q & 1 ? approx_cos_poly ## S(r) : approx_sin_poly ## S(r);       \
+    return q & 2 ? -y : y;                                                      \
+  }                                                                             \
+                                                                                \
+  __attribute__((always_inline))                                                \
+  static inline C_TYPE approx_cos ## S(C_TYPE x) {                              \
+    if(!(fabs ## S(x) <= SIN_LIMIT)) {                                          \
+      return cos ## S(x);                                                       \
+    }                                                                           \
+    int64_t q;                                                                  \
+    C_TYPE r = approx_reduce ## S(x, &q);                                       \
+    C_TYPE y = q & 1 ? approx_sin_poly ## S(r) : approx_cos_poly ## S(r);       \
+    return (q + 1) & 2 ? -y : y;                                                \
+  }                                                                             \
+                                                                                \
+  __attribute__((always_inline))                                                \
+  static inline C_TYPE approx_tan ## S(C_TYPE x) {                              \
+    if(!(fabs ## S(x) <= SIN_LIMIT)) {                                          \
+      return tan ## S(x);                                                       \
+    }                                                                           \
+    int64_t q;                                                                  \
+    C_TYPE r = approx_reduce ## S(x, &q);                                       \
+    C_TYPE s = approx_sin_poly ## S(r);                                         \
+    C_TYPE c = approx_cos_poly ## S(r);                                         \
+    return q & 1 ? -c / s : s / c;                                              \
+  }                                                                             \
+
+IMPL_APPROX(double, , uint64_t, 52, 1023, 2.2250738585072014e-308, 8192)
+IMPL_APPROX(float, f, uint32_t, 23, 127, 1.17549435e-38, 8192)
+
+#endif
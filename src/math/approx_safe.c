@@ -0,0 +1,105 @@
+/*
+ * Polynomial approximations of exp, ln and tanh, behind the `exp_approx`,
+ * `ln_approx` and `tanh_approx` methods. Unlike the ones in approx.h (which
+ * stand in for libm in poison_unsafe), they're compiled into the poison_safe
+ * library, so they must accept poison without triggering UB: there are no
+ * branches on the arguments, only selects, and arguments are clamped into
+ * range before any float to integer conversion, which then at worst produces
+ * poison from poison.
+ *
+ * The coefficients are near-minimax (Chebyshev) fits, with a relative error
+ * of about 1e-7 for the polynomials themselves. The same coefficients are
+ * used for float and double, so both are accurate to about 1e-6 relative
+ * error overall.
+ */
+
+#include <stdint.h>
+
+#include "libm.h"
+
+#define APPROX_SAFE_LOG2_E 1.4426950408889634
+#define APPROX_SAFE_LN_2 0.6931471805599453
+#define APPROX_SAFE_SQRT_2 1.4142135623730951
+
+/*
+ * `S` is the suffix of the libm functions for the type (empty for double),
+ * and `INT_TYPE`, `MANT_BITS` and `EXP_BIAS` describe the type's binary
+ * representation.
+ */
+#define IMPL_APPROX_SAFE(C_TYPE, RUST_TYPE, S, INT_TYPE, MANT_BITS, EXP_BIAS) \
+  /*                                                                          \
+   * exp(x) = 2^n * 2^f, with n the nearest integer to x * log2(e) and a      \
+   * polynomial for 2^f. The exponent is clamped to the normal range, so      \
+   * large results saturate at the largest power of two and small ones at     \
+   * the smallest normal value, rather than overflowing or flushing to zero   \
+   */                                                                         \
+  __attribute__((always_inline))                                              \
+  static inline C_TYPE approx_safe_exp ## S(C_TYPE x) {                       \
+    C_TYPE t = fmin ## S(fmax ## S(x * (C_TYPE)APPROX_SAFE_LOG2_E,            \
+                                   -(EXP_BIAS - 1)),                          \
+                         EXP_BIAS);                                           \
+    C_TYPE n = floor ## S(t + (C_TYPE)0.5);                                   \
+    C_TYPE f = t - n;                                                         \
+    C_TYPE p = (C_TYPE)1.0000000754548972                                     \
+      + f * ((C_TYPE)0.69314718802622878                                      \
+      + f * ((C_TYPE)0.24022107485308338                                      \
+      + f * ((C_TYPE)0.055503571142190778                                     \
+      + f * ((C_TYPE)0.0096760319183220541                                    \
+      + f * (C_TYPE)0.0013390863364671234))));                                \
+    INT_TYPE bits = ((INT_TYPE)n + EXP_BIAS) << MANT_BITS;                    \
+    C_TYPE scale;                                                             \
+    __builtin_memcpy(&scale, &bits, sizeof(scale));                           \
+    return p * scale;                                                         \
+  }                                                                           \
+                                                                              \
+  __attribute__((always_inline))                                              \
+  C_TYPE exp_approx_ ## RUST_TYPE(C_TYPE x) {                                 \
+    return approx_safe_exp ## S(x);                                           \
+  }                                                                           \
+                                                                              \
+  /*                                                                          \
+   * ln(x) = e * ln(2) + ln(m), with m in [sqrt(1/2), sqrt(2)] and ln(m)      \
+   * from a polynomial for atanh((m - 1) / (m + 1)). The argument must be a   \
+   * positive normal value                                                    \
+   */                                                                         \
+  __attribute__((always_inline))                                              \
+  C_TYPE ln_approx_ ## RUST_TYPE(C_TYPE x) {                                  \
+    INT_TYPE bits;                                                            \
+    __builtin_memcpy(&bits, &x, sizeof(bits));                                \
+    C_TYPE e = (C_TYPE)((bits >> MANT_BITS) - EXP_BIAS);                      \
+    bits = (bits & (((INT_TYPE)1 << MANT_BITS) - 1))                          \
+         | ((INT_TYPE)EXP_BIAS << MANT_BITS);                                 \
+    C_TYPE m;                                                                 \
+    __builtin_memcpy(&m, &bits, sizeof(m));                                   \
+    C_TYPE high = m > (C_TYPE)APPROX_SAFE_SQRT_2 ? 1 : 0;                     \
+    m *= 1 - high * (C_TYPE)0.5;                                              \
+    e += high;                                                                \
+    C_TYPE s = (m - 1) / (m + 1);                                             \
+    C_TYPE z = s * s;                                                         \
+    C_TYPE p = (C_TYPE)1.9999999986313182                                     \
+      + z * ((C_TYPE)0.66666815338151188                                      \
+      + z * ((C_TYPE)0.39974850517519048                                      \
+      + z * (C_TYPE)0.29924390479144987));                                    \
+    return e * (C_TYPE)APPROX_SAFE_LN_2 + s * p;                              \
+  }                                                                           \
+                                                                              \
+  /*                                                                          \
+   * tanh(x) = x * P(x^2) for |x| < 0.55, and 1 - 2 / (exp(2|x|) + 1) with    \
+   * the sign of x otherwise, which would lose too much precision to          \
+   * cancellation near zero. Both are computed, and one is selected           \
+   */                                                                         \
+  __attribute__((always_inline))                                              \
+  C_TYPE tanh_approx_ ## RUST_TYPE(C_TYPE x) {                                \
+    C_TYPE a = fabs ## S(x);                                                  \
+    C_TYPE z = x * x;                                                         \
+    C_TYPE small = x * ((C_TYPE)0.99999996729807168                           \
+      + z * ((C_TYPE)-0.33332791480236107                                     \
+      + z * ((C_TYPE)0.1331881878050193                                       \
+      + z * ((C_TYPE)-0.052586260038930358                                    \
+      + z * (C_TYPE)0.016310403867162955))));                                 \
+    C_TYPE large = 1 - 2 / (approx_safe_exp ## S(2 * a) + 1);                 \
+    return a < (C_TYPE)0.55 ? small : copysign ## S(large, x);                \
+  }                                                                           \
+
+IMPL_APPROX_SAFE(float, f32, f, int32_t, 23, 127)
+IMPL_APPROX_SAFE(double, f64, , int64_t, 52, 1023)
@@ -0,0 +1,81 @@
+/*
+ * The functions in this file implement the BLAS level-1 routines, from the
+ * `blas1` module. They are compiled into the poison_safe library, with the
+ * same flags and under the same constraints: every operation here can accept
+ * poison values without triggering UB.
+ *
+ * The reductions are written with several independent accumulators, which
+ * breaks the dependency chain between iterations even when the compiler
+ * chooses not to vectorize (for example for small or odd lengths).
+ */
+
+#include <stddef.h>
+
+#include "libm.h"
+
+#define IMPL_BLAS1_REDUCE(C_TYPE, RUST_TYPE, FN_NAME, EXPR)                \
+  __attribute__((always_inline))                                           \
+  C_TYPE FN_NAME ## _ ## RUST_TYPE(const C_TYPE *restrict x, size_t len) { \
+    C_TYPE acc0 = 0;                                                       \
+    C_TYPE acc1 = 0;                                                       \
+    C_TYPE acc2 = 0;                                                       \
+    C_TYPE acc3 = 0;                                                       \
+    size_t i = 0;                                                          \
+    for(; i + 4 <= len; i += 4) {                                          \
+      acc0 += EXPR(x[i]);                                                  \
+      acc1 += EXPR(x[i + 1]);                                              \
+      acc2 += EXPR(x[i + 2]);                                              \
+      acc3 += EXPR(x[i + 3]);                                              \
+    }                                                                      \
+    for(; i < len; i++) {                                                  \
+      acc0 += EXPR(x[i]);                                                  \
+    }                                                                      \
+    return (acc0 + acc1) + (acc2 + acc3);                                  \
+  }                                                                        \
+
+#define IMPL_BLAS1_MAX_ABS(C_TYPE, RUST_TYPE, ABS_FN, MAX_FN)             \
+  __attribute__((always_inline))                                           \
+  C_TYPE max_abs_ ## RUST_TYPE(const C_TYPE *restrict x, size_t len) {     \
+    C_TYPE acc0 = 0;                                                       \
+    C_TYPE acc1 = 0;                                                       \
+    C_TYPE acc2 = 0;                                                       \
+    C_TYPE acc3 = 0;                                                       \
+    size_t i = 0;                                                          \
+    for(; i + 4 <= len; i += 4) {                                          \
+      acc0 = MAX_FN(acc0, ABS_FN(x[i]));                                   \
+      acc1 = MAX_FN(acc1, ABS_FN(x[i + 1]));                               \
+      acc2 = MAX_FN(acc2, ABS_FN(x[i + 2]));                               \
+      acc3 = MAX_FN(acc3, ABS_FN(x[i + 3]));                               \
+    }                                                                      \
+    for(; i < len; i++) {                                                  \
+      acc0 = MAX_FN(acc0, ABS_FN(x[i]));                                   \
+    }                                                                      \
+    return MAX_FN(MAX_FN(acc0, acc1), MAX_FN(acc2, acc3));                 \
+  }                                                                        \
+
+#define SQUARE(a) ((a) * (a))
+
+#define IMPL_BLAS1(C_TYPE, RUST_TYPE, ABS_FN, MAX_FN)                      \
+  __attribute__((always_inline))                                           \
+  void axpy_ ## RUST_TYPE(C_TYPE alpha,                                    \
+                          const C_TYPE *restrict x,                        \
+                          C_TYPE *restrict y,                              \
+                          size_t len) {                                    \
+    for(size_t i = 0; i < len; i++) {                                      \
+      y[i] += alpha * x[i];                                                \
+    }                                                                      \
+  }                                                                        \
+                                                                           \
+  __attribute__((always_inline))                                           \
+  void scal_ ## RUST_TYPE(C_TYPE alpha, C_TYPE *restrict x, size_t len) {  \
+    for(size_t i = 0; i < len; i++) {                                      \
+      x[i] *= alpha;                                                       \
+    }                                                                      \
+  }                                                                        \
+                                                                           \
+  IMPL_BLAS1_REDUCE(C_TYPE, RUST_TYPE, asum, ABS_FN)                       \
+  IMPL_BLAS1_REDUCE(C_TYPE, RUST_TYPE, sum_squares, SQUARE)                \
+  IMPL_BLAS1_MAX_ABS(C_TYPE, RUST_TYPE, ABS_FN, MAX_FN)                    \
+
+IMPL_BLAS1(float, f32, fabsf, fmaxf)
+IMPL_BLAS1(double, f64, fabs, fmax)
@@ -0,0 +1,71 @@
+/*
+ * The functions in this file implement the BLAS level-2 routines, from the
+ * `blas2` module. They are compiled into the poison_safe library, with the
+ * same flags and under the same constraints: every operation here can accept
+ * poison values without triggering UB.
+ *
+ * Matrices are dense and row-major, so element (i, j) of an m x n matrix is
+ * at index `i * n + j`.
+ */
+
+#include <stddef.h>
+
+/*
+ * Each row of a matrix-vector product is a dot product, which uses several
+ * independent accumulators like the level-1 reductions
+ */
+#define IMPL_ROW_DOT(C_TYPE, RUST_TYPE)                                    \
+  __attribute__((always_inline))                                           \
+  static inline C_TYPE row_dot_ ## RUST_TYPE(const C_TYPE *restrict row,   \
+                                             const C_TYPE *restrict x,     \
+                                             size_t n) {                   \
+    C_TYPE acc0 = 0;                                                       \
+    C_TYPE acc1 = 0;                                                       \
+    C_TYPE acc2 = 0;                                                       \
+    C_TYPE acc3 = 0;                                                       \
+    size_t j = 0;                                                          \
+    for(; j + 4 <= n; j += 4) {                                            \
+      acc0 += row[j] * x[j];                                               \
+      acc1 += row[j + 1] * x[j + 1];                                       \
+      acc2 += row[j + 2] * x[j + 2];                                       \
+      acc3 += row[j + 3] * x[j + 3];                                       \
+    }                                                                      \
+    for(; j < n; j++) {                                                    \
+      acc0 += row[j] * x[j];                                               \
+    }                                                                      \
+    return (acc0 + acc1) + (acc2 + acc3);                                  \
+  }                                                                        \
+
+#define IMPL_BLAS2(C_TYPE, RUST_TYPE)                                      \
+  IMPL_ROW_DOT(C_TYPE, RUST_TYPE)                                          \
+                                                                           \
+  __attribute__((always_inline))                                           \
+  void gemv_ ## RUST_TYPE(size_t m,                                        \
+                          size_t n,                                        \
+                          C_TYPE alpha,                                    \
+                          const C_TYPE *restrict a,                        \
+                          const C_TYPE *restrict x,                        \
+                          C_TYPE beta,                                     \
+                          C_TYPE *restrict y) {                            \
+    for(size_t i = 0; i < m; i++) {                                        \
+      y[i] = alpha * row_dot_ ## RUST_TYPE(a + i * n, x, n) + beta * y[i]; \
+    }                                                                      \
+  }                                                                        \
+                                                                           \
+  __attribute__((always_inline))                                           \
+  void ger_ ## RUST_TYPE(size_t m,                                         \
+                         size_t n,                                         \
+                         C_TYPE alpha,                                     \
+                         const C_TYPE *restrict x,                         \
+                         const C_TYPE *restrict y,                         \
+                         C_TYPE *restrict a) {                             \
+    for(size_t i = 0; i < m; i++) {                                        \
+      C_TYPE scale = alpha * x[i];                                         \
+      for(size_t j = 0; j < n; j++) {                                      \
+        a[i * n + j] += scale * y[j];                                      \
+      }                                                                    \
+    }                                                                      \
+  }                                                                        \
+
+IMPL_BLAS2(float, f32)
+IMPL_BLAS2(double, f64)
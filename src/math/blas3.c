@@ -0,0 +1,110 @@
+/*
+ * The functions in this file implement the BLAS level-3 routines, from the
+ * `blas3` module. They are compiled into the poison_safe library, with the
+ * same flags and under the same constraints: every operation here can accept
+ * poison values without triggering UB.
+ *
+ * Matrices are row-major with an explicit row stride (the "leading
+ * dimension"), so element (i, j) of a matrix with stride `ld` is at index
+ * `i * ld + j`.
+ */
+
+#include <stddef.h>
+
+/*
+ * The product is computed in blocks sized to stay resident in cache: KC
+ * columns of A (and rows of B) at a time, MC rows of A, and NC columns of B.
+ * Each block is then covered by MR x NR micro-tiles of C, whose accumulators
+ * are held in registers for the whole KC loop. The micro-tile's fixed size
+ * lets the compiler fully unroll and vectorize it.
+ */
+#define GEMM_MR 4
+#define GEMM_NR 8
+#define GEMM_KC 256
+#define GEMM_MC 64
+#define GEMM_NC 256
+
+#define GEMM_MIN(a, b) ((a) < (b) ? (a) : (b))
+
+#define IMPL_GEMM_TILE(C_TYPE, RUST_TYPE, FN_NAME, MR, NR)                    \
+  __attribute__((always_inline))                                              \
+  static inline void FN_NAME ## _ ## RUST_TYPE(size_t mr,                     \
+                                               size_t nr,                     \
+                                               size_t kc,                     \
+                                               C_TYPE alpha,                  \
+                                               const C_TYPE *restrict a,      \
+                                               size_t lda,                    \
+                                               const C_TYPE *restrict b,      \
+                                               size_t ldb,                    \
+                                               C_TYPE *restrict c,            \
+                                               size_t ldc) {                  \
+    /* the full tile ignores the runtime sizes in favor of the constants */   \
+    (void)mr;                                                                 \
+    (void)nr;                                                                 \
+    C_TYPE acc[GEMM_MR][GEMM_NR] = {{0}};                                     \
+    for(size_t p = 0; p < kc; p++) {                                          \
+      for(size_t i = 0; i < MR; i++) {                                        \
+        for(size_t j = 0; j < NR; j++) {                                      \
+          acc[i][j] += a[i * lda + p] * b[p * ldb + j];                       \
+        }                                                                     \
+      }                                                                       \
+    }                                                                         \
+    for(size_t i = 0; i < MR; i++) {                                          \
+      for(size_t j = 0; j < NR; j++) {                                        \
+        c[i * ldc + j] += alpha * acc[i][j];                                  \
+      }                                                                       \
+    }                                                                         \
+  }                                                                           \
+
+#define IMPL_GEMM(C_TYPE, RUST_TYPE)                                          \
+  /* full tiles use the constant sizes, partial tiles at the edges don't */   \
+  IMPL_GEMM_TILE(C_TYPE, RUST_TYPE, gemm_tile, GEMM_MR, GEMM_NR)              \
+  IMPL_GEMM_TILE(C_TYPE, RUST_TYPE, gemm_edge_tile, mr, nr)                   \
+                                                                              \
+  __attribute__((always_inline))                                              \
+  void gemm_ ## RUST_TYPE(size_t m,                                           \
+                          size_t n,                                           \
+                          size_t k,                                           \
+                          C_TYPE alpha,                                       \
+                          const C_TYPE *restrict a,                           \
+                          size_t lda,                                         \
+                          const C_TYPE *restrict b,                           \
+                          size_t ldb,                                         \
+                          C_TYPE beta,                                        \
+                          C_TYPE *restrict c,                                 \
+                          size_t ldc) {                                       \
+    for(size_t i = 0; i < m; i++) {                                           \
+      for(size_t j = 0; j < n; j++) {                                         \
+        c[i * ldc + j] *= beta;                                               \
+      }                                                                       \
+    }                                                                         \
+                                                                              \
+    for(size_t jc = 0; jc < n; jc += GEMM_NC) {                               \
+      size_t nc = GEMM_MIN(GEMM_NC, n - jc);                                  \
+      for(size_t pc = 0; pc < k; pc += GEMM_KC) {                             \
+        size_t kc = GEMM_MIN(GEMM_KC, k - pc);                                \
+        for(size_t ic = 0; ic < m; ic += GEMM_MC) {                           \
+          size_t mc = GEMM_MIN(GEMM_MC, m - ic);                              \
+          for(size_t jr = 0; jr < nc; jr += GEMM_NR) {                        \
+            size_t nr = GEMM_MIN(GEMM_NR, nc - jr);                           \
+            for(size_t ir = 0; ir < mc; ir += GEMM_MR) {                      \
+              size_t mr = GEMM_MIN(GEMM_MR, mc - ir);                         \
+              const C_TYPE *ap = a + (ic + ir) * lda + pc;                    \
+              const C_TYPE *bp = b + pc * ldb + jc + jr;                      \
+              C_TYPE *cp = c + (ic + ir) * ldc + jc + jr;                     \
+              if(mr == GEMM_MR && nr == GEMM_NR) {                            \
+                gemm_tile_ ## RUST_TYPE(mr, nr, kc, alpha,                    \
+                                        ap, lda, bp, ldb, cp, ldc);           \
+              } else {                                                        \
+                gemm_edge_tile_ ## RUST_TYPE(mr, nr, kc, alpha,               \
+                                             ap, lda, bp, ldb, cp, ldc);      \
+              }                                                               \
+            }                                                                 \
+          }                                                                   \
+        }                                                                     \
+      }                                                                       \
+    }                                                                         \
+  }                                                                           \
+
+IMPL_GEMM(float, f32)
+IMPL_GEMM(double, f64)
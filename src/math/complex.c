@@ -0,0 +1,40 @@
+/*
+ * The functions in this file implement the kernels over slices of complex
+ * values, from the `complex` module. They are compiled into the poison_safe
+ * library, with the same flags and under the same constraints: every
+ * operation here can accept poison values without triggering UB.
+ *
+ * Complex values are laid out as interleaved (re, im) pairs, so a slice of
+ * `len` complex values is an array of `2 * len` floats.
+ */
+
+#include <stddef.h>
+
+#define IMPL_COMPLEX_DOT(C_TYPE, RUST_TYPE, FN_NAME, RE_EXPR, IM_EXPR)  \
+  __attribute__((always_inline))                                        \
+  void FN_NAME ## _ ## RUST_TYPE(C_TYPE *restrict out,                  \
+                                 const C_TYPE *restrict a,              \
+                                 const C_TYPE *restrict b,              \
+                                 size_t len) {                          \
+    C_TYPE re = 0;                                                      \
+    C_TYPE im = 0;                                                      \
+    for(size_t i = 0; i < len; i++) {                                   \
+      C_TYPE a_re = a[2 * i];                                           \
+      C_TYPE a_im = a[2 * i + 1];                                       \
+      C_TYPE b_re = b[2 * i];                                           \
+      C_TYPE b_im = b[2 * i + 1];                                       \
+      re += RE_EXPR;                                                    \
+      im += IM_EXPR;                                                    \
+    }                                                                   \
+    out[0] = re;                                                        \
+    out[1] = im;                                                        \
+  }                                                                     \
+
+#define IMPL_COMPLEX(C_TYPE, RUST_TYPE)                                      \
+  IMPL_COMPLEX_DOT(C_TYPE, RUST_TYPE, dot,                                   \
+                   a_re * b_re - a_im * b_im, a_re * b_im + a_im * b_re)     \
+  IMPL_COMPLEX_DOT(C_TYPE, RUST_TYPE, dotc,                                  \
+                   a_re * b_re + a_im * b_im, a_re * b_im - a_im * b_re)     \
+
+IMPL_COMPLEX(float, complex_f32)
+IMPL_COMPLEX(double, complex_f64)
@@ -0,0 +1,54 @@
+/*
+ * The functions in this file back the contract-only types (`CF32`/`CF64`), as
+ * well as the `*_contract` methods of the fast types.
+ * They are compiled with `-ffp-contract=fast` and no other fast-math flags,
+ * so that separate multiplications and additions may be fused into FMA
+ * instructions once inlined into their callers, while every operation otherwise
+ * keeps IEEE 754 semantics.
+ *
+ * Contraction does not introduce poison values[1], so these functions need no
+ * freezing precautions on either side of the call. The fast types may already
+ * carry poison, but like the poison_safe library these operations accept it
+ * without UB.
+ *
+ * [1]: https://llvm.org/docs/LangRef.html#poison-values
+ */
+
+#define IMPL_OPERATIONS(C_TYPE, RUST_TYPE)                \
+  __attribute__((always_inline))                          \
+  C_TYPE contract_add_ ## RUST_TYPE(C_TYPE a, C_TYPE b) { \
+    return a + b;                                         \
+  }                                                       \
+                                                          \
+  __attribute__((always_inline))                          \
+  C_TYPE contract_sub_ ## RUST_TYPE(C_TYPE a, C_TYPE b) { \
+    return a - b;                                         \
+  }                                                       \
+                                                          \
+  __attribute__((always_inline))                          \
+  C_TYPE contract_mul_ ## RUST_TYPE(C_TYPE a, C_TYPE b) { \
+    return a * b;                                         \
+  }                                                       \
+                                                          \
+  __attribute__((always_inline))                          \
+  C_TYPE contract_div_ ## RUST_TYPE(C_TYPE a, C_TYPE b) { \
+    return a / b;                                         \
+  }                                                       \
+
+IMPL_OPERATIONS(float, f32)
+IMPL_OPERATIONS(double, f64)
+
+#define IMPL_BINARY_OPERATION(C_TYPE, RUST_TYPE, FN_NAME, OP) \
+  __attribute__((always_inline))                              \
+  C_TYPE FN_NAME ## _ ## RUST_TYPE(C_TYPE a, C_TYPE b) {      \
+    return a OP b;                                            \
+  }                                                           \
+
+IMPL_BINARY_OPERATION(float, f32, add_contract, +)
+IMPL_BINARY_OPERATION(double, f64, add_contract, +)
+
+IMPL_BINARY_OPERATION(float, f32, sub_contract, -)
+IMPL_BINARY_OPERATION(double, f64, sub_contract, -)
+
+IMPL_BINARY_OPERATION(float, f32, mul_contract, *)
+IMPL_BINARY_OPERATION(double, f64, mul_contract, *)
@@ -0,0 +1,53 @@
+/*
+ * The poison unsafe decibel conversions, from the `decibel` module. These are
+ * compiled into the poison_unsafe library, with the same flags and under the
+ * same constraints: arguments must be frozen before being passed to these
+ * functions.
+ *
+ * Each conversion has a scalar version and a slice version, which reads
+ * frozen values from `x` and writes the results to `out`. With the
+ * `approx-math` feature, exp and log10 are replaced by their approximations
+ * from approx.h, which unlike libm can be inlined and vectorized within the
+ * slice loops.
+ */
+
+#include <stddef.h>
+
+#include "libm.h"
+
+#ifdef FAST_FP_APPROX_MATH
+#include "approx.h"
+#define MATH(FN) approx_ ## FN
+#else
+#define MATH(FN) FN
+#endif
+
+/* ln(10) / 20, so that 10^(x / 20) = exp(x * DB_TO_EXP) */
+#define DB_TO_EXP 0.11512925464970229
+
+#define IMPL_DECIBEL_FN(C_TYPE, RUST_TYPE, FN_NAME, EXPR)                      \
+  __attribute__((always_inline))                                              \
+  C_TYPE FN_NAME ## _ ## RUST_TYPE(C_TYPE x) {                                \
+    return EXPR;                                                              \
+  }                                                                           \
+                                                                              \
+  __attribute__((always_inline))                                              \
+  void FN_NAME ## _slice_ ## RUST_TYPE(const C_TYPE *restrict in,             \
+                                       C_TYPE *restrict out,                  \
+                                       size_t len) {                          \
+    for(size_t i = 0; i < len; i++) {                                         \
+      C_TYPE x = in[i];                                                       \
+      out[i] = EXPR;                                                          \
+    }                                                                         \
+  }                                                                           \
+
+#define IMPL_DECIBEL(C_TYPE, RUST_TYPE, S)                                    \
+  IMPL_DECIBEL_FN(C_TYPE, RUST_TYPE, amp_to_db,                               \
+                  20 * MATH(log10 ## S)(x))                                   \
+  IMPL_DECIBEL_FN(C_TYPE, RUST_TYPE, power_to_db,                             \
+                  10 * MATH(log10 ## S)(x))                                   \
+  IMPL_DECIBEL_FN(C_TYPE, RUST_TYPE, db_to_amp,                               \
+                  MATH(exp ## S)(x * (C_TYPE)DB_TO_EXP))                      \
+
+IMPL_DECIBEL(float, f32, f)
+IMPL_DECIBEL(double, f64, )
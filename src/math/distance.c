@@ -0,0 +1,91 @@
+/*
+ * The functions in this file implement the pairwise distance kernels, from
+ * the `distance` module. They are compiled into the poison_safe library, with
+ * the same flags and under the same constraints: every operation here can
+ * accept poison values without triggering UB.
+ *
+ * Both sets of vectors are row-major, with `dim` values per row, and the
+ * output is an `m x n` row-major matrix with one row per query.
+ */
+
+#include <stddef.h>
+
+/*
+ * The matrix is computed in blocks of NC corpus rows, which stay in cache
+ * while every query is compared against them. Each block is covered by
+ * QR x CR micro-tiles of the output, whose accumulators are held in registers
+ * for the whole loop over the dimensions, so every value loaded is reused
+ * against several rows of the other set.
+ */
+#define DISTANCE_QR 4
+#define DISTANCE_CR 4
+#define DISTANCE_NC 64
+
+#define DISTANCE_MIN(a, b) ((a) < (b) ? (a) : (b))
+
+#define DOT_TERM(q, c) ((q) * (c))
+#define SQUARED_L2_TERM(q, c) (((q) - (c)) * ((q) - (c)))
+
+#define IMPL_DISTANCE_TILE(C_TYPE, RUST_TYPE, FN_NAME, TERM, QR, CR)          \
+  __attribute__((always_inline))                                              \
+  static inline void FN_NAME ## _ ## RUST_TYPE(size_t qr,                     \
+                                               size_t cr,                     \
+                                               size_t dim,                    \
+                                               const C_TYPE *restrict q,      \
+                                               const C_TYPE *restrict c,      \
+                                               C_TYPE *restrict out,          \
+                                               size_t ldo) {                  \
+    /* the full tile ignores the runtime sizes in favor of the constants */   \
+    (void)qr;                                                                 \
+    (void)cr;                                                                 \
+    C_TYPE acc[DISTANCE_QR][DISTANCE_CR] = {{0}};                             \
+    for(size_t p = 0; p < dim; p++) {                                         \
+      for(size_t i = 0; i < QR; i++) {                                        \
+        for(size_t j = 0; j < CR; j++) {                                      \
+          acc[i][j] += TERM(q[i * dim + p], c[j * dim + p]);                  \
+        }                                                                     \
+      }                                                                       \
+    }                                                                         \
+    for(size_t i = 0; i < QR; i++) {                                          \
+      for(size_t j = 0; j < CR; j++) {                                        \
+        out[i * ldo + j] = acc[i][j];                                         \
+      }                                                                       \
+    }                                                                         \
+  }                                                                           \
+
+#define IMPL_DISTANCE(C_TYPE, RUST_TYPE, NAME, TERM)                          \
+  /* full tiles use the constant sizes, partial tiles at the edges don't */   \
+  IMPL_DISTANCE_TILE(C_TYPE, RUST_TYPE, NAME ## _tile, TERM,                  \
+                     DISTANCE_QR, DISTANCE_CR)                                \
+  IMPL_DISTANCE_TILE(C_TYPE, RUST_TYPE, NAME ## _edge_tile, TERM, qr, cr)     \
+                                                                              \
+  __attribute__((always_inline))                                              \
+  void pairwise_ ## NAME ## _ ## RUST_TYPE(const C_TYPE *restrict queries,    \
+                                           size_t m,                          \
+                                           const C_TYPE *restrict corpus,     \
+                                           size_t n,                          \
+                                           size_t dim,                        \
+                                           C_TYPE *restrict out) {            \
+    for(size_t jc = 0; jc < n; jc += DISTANCE_NC) {                           \
+      size_t nc = DISTANCE_MIN(DISTANCE_NC, n - jc);                          \
+      for(size_t ir = 0; ir < m; ir += DISTANCE_QR) {                         \
+        size_t qr = DISTANCE_MIN(DISTANCE_QR, m - ir);                        \
+        for(size_t jr = 0; jr < nc; jr += DISTANCE_CR) {                      \
+          size_t cr = DISTANCE_MIN(DISTANCE_CR, nc - jr);                     \
+          const C_TYPE *qp = queries + ir * dim;                              \
+          const C_TYPE *cp = corpus + (jc + jr) * dim;                        \
+          C_TYPE *op = out + ir * n + jc + jr;                                \
+          if(qr == DISTANCE_QR && cr == DISTANCE_CR) {                        \
+            NAME ## _tile_ ## RUST_TYPE(qr, cr, dim, qp, cp, op, n);          \
+          } else {                                                            \
+            NAME ## _edge_tile_ ## RUST_TYPE(qr, cr, dim, qp, cp, op, n);     \
+          }                                                                   \
+        }                                                                     \
+      }                                                                       \
+    }                                                                         \
+  }                                                                           \
+
+IMPL_DISTANCE(float, f32, dot, DOT_TERM)
+IMPL_DISTANCE(float, f32, squared_l2, SQUARED_L2_TERM)
+IMPL_DISTANCE(double, f64, dot, DOT_TERM)
+IMPL_DISTANCE(double, f64, squared_l2, SQUARED_L2_TERM)
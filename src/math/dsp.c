@@ -0,0 +1,174 @@
+/*
+ * The functions in this file implement the signal processing kernels, from
+ * the `dsp` module. They are compiled into the poison_safe library, with the
+ * same flags and under the same constraints: every operation here can accept
+ * poison values without triggering UB.
+ */
+
+#include <stddef.h>
+
+/*
+ * The filter is applied as a correlation with the reversed taps, so both the
+ * input and the taps are read forwards. `x` holds `len + taps_len - 1` values,
+ * and each output is the dot product of the taps with a window of them:
+ *
+ *   out[i] = sum(reversed[j] * x[i + j])
+ *
+ * FIR_OUTPUTS outputs are computed together, so each tap is loaded once for
+ * all of them, and their accumulators are independent of each other.
+ */
+#define FIR_OUTPUTS 4
+
+/*
+ * The column pass of a separable convolution filters every column of a
+ * row-major matrix at once. `x` holds `rows + taps_len - 1` rows of `cols`
+ * values, and each output row is a weighted sum of `taps_len` consecutive
+ * input rows, which vectorizes along the rows. The columns are processed in
+ * strips of COLUMN_STRIP values, so the input rows of a strip stay in cache
+ * while they're reused for `taps_len` consecutive outputs.
+ */
+#define COLUMN_STRIP 256
+
+#define DSP_MIN(a, b) ((a) < (b) ? (a) : (b))
+
+#define IMPL_DSP(C_TYPE, RUST_TYPE)                                           \
+  __attribute__((always_inline))                                              \
+  void fir_ ## RUST_TYPE(const C_TYPE *restrict x,                            \
+                         size_t len,                                          \
+                         const C_TYPE *restrict reversed,                     \
+                         size_t taps_len,                                     \
+                         C_TYPE *restrict out) {                              \
+    size_t i = 0;                                                             \
+    for(; i + FIR_OUTPUTS <= len; i += FIR_OUTPUTS) {                         \
+      C_TYPE acc[FIR_OUTPUTS] = {0};                                          \
+      for(size_t j = 0; j < taps_len; j++) {                                  \
+        C_TYPE tap = reversed[j];                                             \
+        for(size_t k = 0; k < FIR_OUTPUTS; k++) {                             \
+          acc[k] += tap * x[i + j + k];                                       \
+        }                                                                     \
+      }                                                                       \
+      for(size_t k = 0; k < FIR_OUTPUTS; k++) {                               \
+        out[i + k] = acc[k];                                                  \
+      }                                                                       \
+    }                                                                         \
+    for(; i < len; i++) {                                                     \
+      C_TYPE acc = 0;                                                         \
+      for(size_t j = 0; j < taps_len; j++) {                                  \
+        acc += reversed[j] * x[i + j];                                        \
+      }                                                                       \
+      out[i] = acc;                                                           \
+    }                                                                         \
+  }                                                                           \
+                                                                              \
+  __attribute__((always_inline))                                              \
+  void fir_columns_ ## RUST_TYPE(const C_TYPE *restrict x,                    \
+                                 size_t rows,                                 \
+                                 size_t cols,                                 \
+                                 const C_TYPE *restrict reversed,             \
+                                 size_t taps_len,                             \
+                                 C_TYPE *restrict out) {                      \
+    for(size_t c0 = 0; c0 < cols; c0 += COLUMN_STRIP) {                       \
+      size_t width = DSP_MIN(COLUMN_STRIP, cols - c0);                        \
+      for(size_t r = 0; r < rows; r++) {                                      \
+        C_TYPE *restrict dst = out + r * cols + c0;                           \
+        for(size_t c = 0; c < width; c++) {                                   \
+          dst[c] = 0;                                                         \
+        }                                                                     \
+        for(size_t j = 0; j < taps_len; j++) {                                \
+          C_TYPE tap = reversed[j];                                           \
+          const C_TYPE *restrict src = x + (r + j) * cols + c0;               \
+          for(size_t c = 0; c < width; c++) {                                 \
+            dst[c] += tap * src[c];                                           \
+          }                                                                   \
+        }                                                                     \
+      }                                                                       \
+    }                                                                         \
+  }                                                                           \
+
+/*
+ * A biquad filter in transposed direct form II, which needs two values of
+ * state and has good numerical behavior in floating point. Each output depends
+ * on the previous one, so the loop over the samples can't be vectorized, but
+ * the whole loop runs here with the coefficients and state held in registers.
+ * The coefficients are b0, b1, b2, a1, a2, normalized so that a0 is one.
+ */
+#define IMPL_BIQUAD(C_TYPE, RUST_TYPE)                                        \
+  __attribute__((always_inline))                                              \
+  void biquad_ ## RUST_TYPE(C_TYPE *restrict x,                               \
+                            size_t len,                                       \
+                            const C_TYPE *restrict coeffs,                    \
+                            C_TYPE *restrict state) {                         \
+    C_TYPE b0 = coeffs[0], b1 = coeffs[1], b2 = coeffs[2];                    \
+    C_TYPE a1 = coeffs[3], a2 = coeffs[4];                                    \
+    C_TYPE z1 = state[0], z2 = state[1];                                      \
+    for(size_t i = 0; i < len; i++) {                                         \
+      C_TYPE in = x[i];                                                       \
+      C_TYPE out = b0 * in + z1;                                              \
+      z1 = b1 * in - a1 * out + z2;                                           \
+      z2 = b2 * in - a2 * out;                                                \
+      x[i] = out;                                                             \
+    }                                                                         \
+    state[0] = z1;                                                            \
+    state[1] = z2;                                                            \
+  }                                                                           \
+
+/*
+ * The Goertzel recurrence for a single frequency bin, s = x + coeff * s1 - s2
+ * with coeff = 2 cos(w), is a second-order filter without a numerator, so like
+ * the biquad its state is carried between blocks
+ */
+#define IMPL_GOERTZEL(C_TYPE, RUST_TYPE)                                      \
+  __attribute__((always_inline))                                              \
+  void goertzel_ ## RUST_TYPE(const C_TYPE *restrict x,                       \
+                              size_t len,                                     \
+                              C_TYPE coeff,                                   \
+                              C_TYPE *restrict state) {                       \
+    C_TYPE s1 = state[0], s2 = state[1];                                      \
+    for(size_t i = 0; i < len; i++) {                                         \
+      C_TYPE s0 = x[i] + coeff * s1 - s2;                                     \
+      s2 = s1;                                                                \
+      s1 = s0;                                                                \
+    }                                                                         \
+    state[0] = s1;                                                            \
+    state[1] = s2;                                                            \
+  }                                                                           \
+
+/*
+ * A polyphase resampler computes each output from a window of `taps` inputs,
+ * weighted by one of `up` phases of a filter bank. The output positions step
+ * by `down / up` inputs, tracked as `pos` in units of `1 / up`, so the window
+ * starts at `pos / up` and the phase is `pos % up`. The caller ensures every
+ * window of the `len` outputs is within `x`.
+ */
+#define IMPL_RESAMPLE(C_TYPE, RUST_TYPE)                                      \
+  __attribute__((always_inline))                                              \
+  void resample_ ## RUST_TYPE(const C_TYPE *restrict x,                       \
+                              const C_TYPE *restrict bank,                    \
+                              size_t taps,                                    \
+                              size_t up,                                      \
+                              size_t down,                                    \
+                              size_t pos,                                     \
+                              C_TYPE *restrict out,                           \
+                              size_t len) {                                   \
+    for(size_t n = 0; n < len; n++, pos += down) {                            \
+      const C_TYPE *window = x + pos / up;                                    \
+      const C_TYPE *coeffs = bank + (pos % up) * taps;                        \
+      C_TYPE sum = 0;                                                         \
+      for(size_t j = 0; j < taps; j++) {                                      \
+        sum += coeffs[j] * window[j];                                         \
+      }                                                                       \
+      out[n] = sum;                                                           \
+    }                                                                         \
+  }                                                                           \
+
+IMPL_DSP(float, f32)
+IMPL_DSP(double, f64)
+
+IMPL_BIQUAD(float, f32)
+IMPL_BIQUAD(double, f64)
+
+IMPL_GOERTZEL(float, f32)
+IMPL_GOERTZEL(double, f64)
+
+IMPL_RESAMPLE(float, f32)
+IMPL_RESAMPLE(double, f64)
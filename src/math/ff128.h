@@ -0,0 +1,19 @@
+/*
+ * The 128-bit float type backing `FF128`. Rust has no stable 128-bit float
+ * yet, so values are stored in rust as raw 16-byte storage and passed to these
+ * shims by pointer.
+ *
+ * x86 targets provide IEEE quad precision as `__float128`, while some other
+ * targets (like aarch64 linux) use it for `long double`. Targets with neither
+ * can't support `FF128`.
+ */
+
+#if defined(__SIZEOF_FLOAT128__)
+typedef __float128 f128;
+#elif __LDBL_MANT_DIG__ == 113
+typedef long double f128;
+#else
+#error "the ff128 feature requires a target with IEEE quad precision floats (__float128 or a 128-bit long double)"
+#endif
+
+_Static_assert(sizeof(f128) == 16, "f128 must be 16 bytes");
@@ -0,0 +1,35 @@
+/*
+ * The poison safe operations of `FF128`. These are compiled into the
+ * poison_safe library, with the same flags and under the same constraints:
+ * every operation here can accept poison values without triggering UB.
+ */
+
+#include "ff128.h"
+
+#define IMPL_F128_BINARY(FN_NAME, OP)                  \
+  __attribute__((always_inline))                       \
+  void FN_NAME ## _f128(f128 *restrict out,            \
+                        const f128 *restrict a,        \
+                        const f128 *restrict b) {      \
+    *out = *a OP *b;                                   \
+  }                                                    \
+
+IMPL_F128_BINARY(add, +)
+IMPL_F128_BINARY(sub, -)
+IMPL_F128_BINARY(mul, *)
+IMPL_F128_BINARY(div, /)
+
+__attribute__((always_inline))
+void neg_f128(f128 *restrict out, const f128 *restrict a) {
+  *out = -*a;
+}
+
+__attribute__((always_inline))
+void from_f64_f128(f128 *out, double a) {
+  *out = a;
+}
+
+__attribute__((always_inline))
+double to_f64_f128(const f128 *a) {
+  return *a;
+}
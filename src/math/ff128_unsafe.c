@@ -0,0 +1,24 @@
+/*
+ * The poison unsafe operations of `FF128`. These are compiled into the
+ * poison_unsafe library, with the same flags and under the same constraints:
+ * arguments must be frozen before being passed to these functions.
+ */
+
+#include "ff128.h"
+
+/*
+ * Returns -1, 0, or 1 for less, equal, or greater, and 2 if the values are
+ * unordered
+ */
+__attribute__((always_inline))
+int partial_cmp_f128(const f128 *a, const f128 *b) {
+  if (*a < *b) {
+    return -1;
+  } else if (*a > *b) {
+    return 1;
+  } else if (*a == *b) {
+    return 0;
+  } else {
+    return 2;
+  }
+}
@@ -0,0 +1,98 @@
+/*
+ * The functions in this file implement the butterfly passes of the
+ * power-of-two FFTs, from the `fft` module. They are compiled into the
+ * poison_safe library, with the same flags and under the same constraints:
+ * every operation here can accept poison values without triggering UB.
+ *
+ * Complex values are laid out as interleaved (re, im) pairs, like the
+ * `complex` kernels. The data must already be permuted into bit-reversed
+ * order, so the passes compute a decimation-in-time transform in place.
+ *
+ * The power spectrum of the transformed values is also computed here, since
+ * unlike the magnitude and phase it needs no math functions.
+ */
+
+#include <stddef.h>
+
+/*
+ * After an optional radix-2 pass (when the length is an odd power of two),
+ * each radix-4 pass combines four transforms of length m into one of length
+ * 4m. In bit-reversed order, the four blocks hold the transforms of the
+ * values with indices 0, 2, 1 and 3 (mod 4), in that order.
+ *
+ * `twiddles` holds the tables of every radix-4 pass, smallest first. The
+ * table of the pass combining blocks of length m holds m triples
+ * (w^k, w^2k, w^3k) for w = exp(-2 pi i / 4m).
+ */
+#define IMPL_FFT(C_TYPE, RUST_TYPE)                                     \
+  __attribute__((always_inline))                                        \
+  void fft_passes_ ## RUST_TYPE(C_TYPE *restrict data,                  \
+                                size_t len,                             \
+                                const C_TYPE *restrict twiddles) {      \
+    size_t m = 1;                                                       \
+    if (__builtin_ctzll(len) % 2) {                                     \
+      for(size_t i = 0; i < 2 * len; i += 4) {                          \
+        C_TYPE a_re = data[i], a_im = data[i + 1];                      \
+        C_TYPE b_re = data[i + 2], b_im = data[i + 3];                  \
+        data[i] = a_re + b_re;                                          \
+        data[i + 1] = a_im + b_im;                                      \
+        data[i + 2] = a_re - b_re;                                      \
+        data[i + 3] = a_im - b_im;                                      \
+      }                                                                 \
+      m = 2;                                                            \
+    }                                                                   \
+                                                                        \
+    for(; 4 * m <= len; m *= 4) {                                       \
+      for(size_t base = 0; base < len; base += 4 * m) {                 \
+        C_TYPE *d0 = data + 2 * base;                                   \
+        C_TYPE *d1 = d0 + 2 * m;                                        \
+        C_TYPE *d2 = d1 + 2 * m;                                        \
+        C_TYPE *d3 = d2 + 2 * m;                                        \
+        for(size_t k = 0; k < m; k++) {                                 \
+          const C_TYPE *w = twiddles + 6 * k;                           \
+          size_t re = 2 * k, im = 2 * k + 1;                            \
+          /* the residue 2 block is second, and residue 1 third */      \
+          C_TYPE a_re = d0[re], a_im = d0[im];                          \
+          C_TYPE c_re = d1[re] * w[2] - d1[im] * w[3];                  \
+          C_TYPE c_im = d1[re] * w[3] + d1[im] * w[2];                  \
+          C_TYPE b_re = d2[re] * w[0] - d2[im] * w[1];                  \
+          C_TYPE b_im = d2[re] * w[1] + d2[im] * w[0];                  \
+          C_TYPE e_re = d3[re] * w[4] - d3[im] * w[5];                  \
+          C_TYPE e_im = d3[re] * w[5] + d3[im] * w[4];                  \
+                                                                        \
+          C_TYPE s0_re = a_re + c_re, s0_im = a_im + c_im;              \
+          C_TYPE s1_re = a_re - c_re, s1_im = a_im - c_im;              \
+          C_TYPE s2_re = b_re + e_re, s2_im = b_im + e_im;              \
+          C_TYPE s3_re = b_re - e_re, s3_im = b_im - e_im;              \
+                                                                        \
+          d0[re] = s0_re + s2_re;                                       \
+          d0[im] = s0_im + s2_im;                                       \
+          /* s1 - i * s3 */                                             \
+          d1[re] = s1_re + s3_im;                                       \
+          d1[im] = s1_im - s3_re;                                       \
+          d2[re] = s0_re - s2_re;                                       \
+          d2[im] = s0_im - s2_im;                                       \
+          /* s1 + i * s3 */                                             \
+          d3[re] = s1_re - s3_im;                                       \
+          d3[im] = s1_im + s3_re;                                       \
+        }                                                               \
+      }                                                                 \
+      twiddles += 6 * m;                                                \
+    }                                                                   \
+  }                                                                     \
+
+#define IMPL_POWER_SPECTRUM(C_TYPE, RUST_TYPE)                          \
+  __attribute__((always_inline))                                        \
+  void power_spectrum_ ## RUST_TYPE(const C_TYPE *restrict z,           \
+                                    C_TYPE *restrict out,               \
+                                    size_t len) {                       \
+    for(size_t i = 0; i < len; i++) {                                   \
+      out[i] = z[2 * i] * z[2 * i] + z[2 * i + 1] * z[2 * i + 1];       \
+    }                                                                   \
+  }                                                                     \
+
+IMPL_FFT(float, f32)
+IMPL_FFT(double, f64)
+
+IMPL_POWER_SPECTRUM(float, f32)
+IMPL_POWER_SPECTRUM(double, f64)
@@ -0,0 +1,59 @@
+/*
+ * The poison unsafe spectrum kernels, from the `fft` module. These are
+ * compiled into the poison_unsafe library, with the same flags and under the
+ * same constraints: arguments must be frozen before being passed to these
+ * functions.
+ *
+ * Each kernel reads `len` frozen complex values, interleaved as (re, im)
+ * pairs, and writes one real value for each to `out`. With the `approx-math`
+ * feature, log10 is replaced by its approximation from approx.h, which unlike
+ * libm can be inlined and vectorized within the loops.
+ */
+
+#include <stddef.h>
+
+#include "libm.h"
+
+#ifdef FAST_FP_APPROX_MATH
+#include "approx.h"
+#define MATH(FN) approx_ ## FN
+#else
+#define MATH(FN) FN
+#endif
+
+#define IMPL_SPECTRUM_FN(C_TYPE, RUST_TYPE, FN_NAME, EXPR)                    \
+  __attribute__((always_inline))                                              \
+  void FN_NAME ## _ ## RUST_TYPE(const C_TYPE *restrict z,                    \
+                                 C_TYPE *restrict out,                        \
+                                 size_t len) {                                \
+    for(size_t i = 0; i < len; i++) {                                         \
+      C_TYPE re = z[2 * i];                                                   \
+      C_TYPE im = z[2 * i + 1];                                               \
+      out[i] = EXPR;                                                          \
+    }                                                                         \
+  }                                                                           \
+
+/*
+ * The level in decibels is computed from the power, so a zero value gives
+ * log10(0) = -inf (which is honored here, without finite-math-only), and is
+ * then raised to the floor by fmax
+ */
+#define IMPL_SPECTRUM(C_TYPE, RUST_TYPE, S)                                   \
+  IMPL_SPECTRUM_FN(C_TYPE, RUST_TYPE, magnitude_spectrum,                     \
+                   sqrt ## S(re * re + im * im))                              \
+  IMPL_SPECTRUM_FN(C_TYPE, RUST_TYPE, phase_spectrum,                         \
+                   atan2 ## S(im, re))                                        \
+                                                                              \
+  __attribute__((always_inline))                                              \
+  void decibel_spectrum_ ## RUST_TYPE(const C_TYPE *restrict z,               \
+                                      C_TYPE *restrict out,                   \
+                                      size_t len,                             \
+                                      C_TYPE floor_db) {                      \
+    for(size_t i = 0; i < len; i++) {                                         \
+      C_TYPE power = z[2 * i] * z[2 * i] + z[2 * i + 1] * z[2 * i + 1];       \
+      out[i] = fmax ## S(10 * MATH(log10 ## S)(power), floor_db);             \
+    }                                                                         \
+  }                                                                           \
+
+IMPL_SPECTRUM(float, f32, f)
+IMPL_SPECTRUM(double, f64, )
@@ -0,0 +1,21 @@
+/*
+ * With the runtime-dispatch feature, the slice and matrix kernels are compiled
+ * once more for each supported instruction set, and every build appends its
+ * variant to the exported symbol names (e.g. `sum_slice_f32_avx2`) so that all
+ * of the builds can be linked into the same binary. The variant is passed as
+ * the KERNEL_VARIANT define by build.rs; the default build keeps the plain
+ * names.
+ */
+
+#ifndef FAST_FP_KERNEL_H
+#define FAST_FP_KERNEL_H
+
+#ifdef KERNEL_VARIANT
+#define KERNEL_NAME_PASTE(name, variant) name ## _ ## variant
+#define KERNEL_NAME_EXPAND(name, variant) KERNEL_NAME_PASTE(name, variant)
+#define KERNEL_NAME(name) KERNEL_NAME_EXPAND(name, KERNEL_VARIANT)
+#else
+#define KERNEL_NAME(name) name
+#endif
+
+#endif
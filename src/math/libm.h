@@ -0,0 +1,67 @@
+/*
+ * The math functions used by the shims. Targets without a C standard library
+ * (such as wasm32-unknown-unknown) have no math.h, so the functions are
+ * declared here instead. Clang recognizes them as library builtins by name,
+ * lowering them to instructions where the target has them (like fabs, sqrt
+ * and floor on wasm) and to calls otherwise, which are resolved at link time
+ * by the math functions in rust's compiler-builtins.
+ */
+
+#ifndef FAST_FP_LIBM_H
+#define FAST_FP_LIBM_H
+
+#if __has_include(<math.h>)
+#include <math.h>
+#else
+
+#define DECLARE_UNARY(FN) \
+  double FN(double);      \
+  float FN ## f(float);   \
+
+#define DECLARE_BINARY(FN)     \
+  double FN(double, double);   \
+  float FN ## f(float, float); \
+
+DECLARE_UNARY(acos)
+DECLARE_UNARY(acosh)
+DECLARE_UNARY(asin)
+DECLARE_UNARY(asinh)
+DECLARE_UNARY(atan)
+DECLARE_BINARY(atan2)
+DECLARE_UNARY(atanh)
+DECLARE_UNARY(cbrt)
+DECLARE_UNARY(ceil)
+DECLARE_BINARY(copysign)
+DECLARE_UNARY(cos)
+DECLARE_UNARY(cosh)
+DECLARE_UNARY(erf)
+DECLARE_UNARY(erfc)
+DECLARE_UNARY(exp)
+DECLARE_UNARY(exp2)
+DECLARE_UNARY(expm1)
+DECLARE_UNARY(fabs)
+DECLARE_UNARY(floor)
+DECLARE_BINARY(fmax)
+DECLARE_BINARY(fmin)
+DECLARE_BINARY(fmod)
+DECLARE_UNARY(lgamma)
+DECLARE_UNARY(log)
+DECLARE_UNARY(log10)
+DECLARE_UNARY(log1p)
+DECLARE_UNARY(log2)
+DECLARE_BINARY(pow)
+DECLARE_UNARY(round)
+DECLARE_UNARY(sin)
+DECLARE_UNARY(sinh)
+DECLARE_UNARY(sqrt)
+DECLARE_UNARY(tan)
+DECLARE_UNARY(tanh)
+DECLARE_UNARY(tgamma)
+DECLARE_UNARY(trunc)
+
+#undef DECLARE_UNARY
+#undef DECLARE_BINARY
+
+#endif
+
+#endif
@@ -0,0 +1,60 @@
+/*
+ * The poison unsafe loss kernels, from the `loss` module. These are compiled
+ * into the poison_unsafe library, with the same flags and under the same
+ * constraints: arguments must be frozen before being passed to these
+ * functions.
+ *
+ * Each kernel reduces one block of frozen values, fusing the exp or log of
+ * every value into the sums. With the `approx-math` feature, exp and log are
+ * replaced by their approximations from approx.h, which unlike libm can be
+ * inlined and vectorized within the loops.
+ */
+
+#include <stddef.h>
+
+#include "libm.h"
+
+#ifdef FAST_FP_APPROX_MATH
+#include "approx.h"
+#define MATH(FN) approx_ ## FN
+#else
+#define MATH(FN) FN
+#endif
+
+/*
+ * The sums for the cross entropy of one block, added to `sums` in the order
+ * exp(z - max), t, t * z. Subtracting the maximum logit keeps every exp at
+ * most one.
+ */
+#define IMPL_LOSS(C_TYPE, RUST_TYPE, S)                                       \
+  __attribute__((always_inline))                                              \
+  void cross_entropy_sums_ ## RUST_TYPE(const C_TYPE *restrict z,             \
+                                        const C_TYPE *restrict t,             \
+                                        size_t len,                           \
+                                        C_TYPE max,                           \
+                                        C_TYPE *restrict sums) {              \
+    C_TYPE se = 0, st = 0, stz = 0;                                           \
+    for(size_t i = 0; i < len; i++) {                                         \
+      se += MATH(exp ## S)(z[i] - max);                                       \
+      st += t[i];                                                             \
+      stz += t[i] * z[i];                                                     \
+    }                                                                         \
+    sums[0] += se;                                                            \
+    sums[1] += st;                                                            \
+    sums[2] += stz;                                                           \
+  }                                                                           \
+                                                                              \
+  /* values of p which are not positive contribute nothing, like 0 * ln(0) */ \
+  __attribute__((always_inline))                                              \
+  C_TYPE kl_divergence_sum_ ## RUST_TYPE(const C_TYPE *restrict p,            \
+                                         const C_TYPE *restrict q,            \
+                                         size_t len) {                        \
+    C_TYPE sum = 0;                                                           \
+    for(size_t i = 0; i < len; i++) {                                         \
+      sum += p[i] > 0 ? p[i] * MATH(log ## S)(p[i] / q[i]) : 0;               \
+    }                                                                         \
+    return sum;                                                               \
+  }                                                                           \
+
+IMPL_LOSS(float, f32, f)
+IMPL_LOSS(double, f64, )
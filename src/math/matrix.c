@@ -0,0 +1,195 @@
+/*
+ * The functions in this file implement the small fixed-size matrix kernels,
+ * from the `matrix` module. They are compiled into the poison_safe library,
+ * with the same flags and under the same constraints: every operation here
+ * can accept poison values without triggering UB.
+ *
+ * Matrices are row-major arrays, so a 3x3 matrix is 9 consecutive values and
+ * element (i, j) is at index `i * 3 + j`. Batches are arrays of matrices.
+ */
+
+#include <stddef.h>
+
+#include "kernel.h"
+
+#define IMPL_MATRIX(C_TYPE, RUST_TYPE)                                        \
+  __attribute__((always_inline))                                              \
+  static inline void mat_mul_ ## RUST_TYPE(C_TYPE *restrict out,              \
+                                           const C_TYPE *restrict a,          \
+                                           const C_TYPE *restrict b,          \
+                                           size_t n) {                        \
+    for(size_t i = 0; i < n; i++) {                                           \
+      for(size_t j = 0; j < n; j++) {                                         \
+        C_TYPE acc = 0;                                                       \
+        for(size_t p = 0; p < n; p++) {                                       \
+          acc += a[i * n + p] * b[p * n + j];                                 \
+        }                                                                     \
+        out[i * n + j] = acc;                                                 \
+      }                                                                       \
+    }                                                                         \
+  }                                                                           \
+                                                                              \
+  /* the inverse is the adjugate divided by the determinant */                \
+  __attribute__((always_inline))                                              \
+  static inline void mat3_inv_ ## RUST_TYPE(C_TYPE *restrict out,             \
+                                            const C_TYPE *restrict m) {       \
+    C_TYPE c00 = m[4] * m[8] - m[5] * m[7];                                   \
+    C_TYPE c10 = m[5] * m[6] - m[3] * m[8];                                   \
+    C_TYPE c20 = m[3] * m[7] - m[4] * m[6];                                   \
+    C_TYPE inv_det = 1 / (m[0] * c00 + m[1] * c10 + m[2] * c20);              \
+    out[0] = c00 * inv_det;                                                   \
+    out[1] = (m[2] * m[7] - m[1] * m[8]) * inv_det;                           \
+    out[2] = (m[1] * m[5] - m[2] * m[4]) * inv_det;                           \
+    out[3] = c10 * inv_det;                                                   \
+    out[4] = (m[0] * m[8] - m[2] * m[6]) * inv_det;                           \
+    out[5] = (m[2] * m[3] - m[0] * m[5]) * inv_det;                           \
+    out[6] = c20 * inv_det;                                                   \
+    out[7] = (m[1] * m[6] - m[0] * m[7]) * inv_det;                           \
+    out[8] = (m[0] * m[4] - m[1] * m[3]) * inv_det;                           \
+  }                                                                           \
+                                                                              \
+  /*                                                                          \
+   * the 4x4 adjugate is built from the 2x2 minors of the top two rows (s)    \
+   * and the bottom two rows (c), which are each shared by several cofactors  \
+   */                                                                         \
+  __attribute__((always_inline))                                              \
+  static inline void mat4_inv_ ## RUST_TYPE(C_TYPE *restrict out,             \
+                                            const C_TYPE *restrict m) {       \
+    C_TYPE s0 = m[0] * m[5] - m[4] * m[1];                                    \
+    C_TYPE s1 = m[0] * m[6] - m[4] * m[2];                                    \
+    C_TYPE s2 = m[0] * m[7] - m[4] * m[3];                                    \
+    C_TYPE s3 = m[1] * m[6] - m[5] * m[2];                                    \
+    C_TYPE s4 = m[1] * m[7] - m[5] * m[3];                                    \
+    C_TYPE s5 = m[2] * m[7] - m[6] * m[3];                                    \
+    C_TYPE c5 = m[10] * m[15] - m[14] * m[11];                                \
+    C_TYPE c4 = m[9] * m[15] - m[13] * m[11];                                 \
+    C_TYPE c3 = m[9] * m[14] - m[13] * m[10];                                 \
+    C_TYPE c2 = m[8] * m[15] - m[12] * m[11];                                 \
+    C_TYPE c1 = m[8] * m[14] - m[12] * m[10];                                 \
+    C_TYPE c0 = m[8] * m[13] - m[12] * m[9];                                  \
+    C_TYPE inv_det = 1 / (s0 * c5 - s1 * c4 + s2 * c3                         \
+                          + s3 * c2 - s4 * c1 + s5 * c0);                     \
+    out[0] = (m[5] * c5 - m[6] * c4 + m[7] * c3) * inv_det;                   \
+    out[1] = (-m[1] * c5 + m[2] * c4 - m[3] * c3) * inv_det;                  \
+    out[2] = (m[13] * s5 - m[14] * s4 + m[15] * s3) * inv_det;                \
+    out[3] = (-m[9] * s5 + m[10] * s4 - m[11] * s3) * inv_det;                \
+    out[4] = (-m[4] * c5 + m[6] * c2 - m[7] * c1) * inv_det;                  \
+    out[5] = (m[0] * c5 - m[2] * c2 + m[3] * c1) * inv_det;                   \
+    out[6] = (-m[12] * s5 + m[14] * s2 - m[15] * s1) * inv_det;               \
+    out[7] = (m[8] * s5 - m[10] * s2 + m[11] * s1) * inv_det;                 \
+    out[8] = (m[4] * c4 - m[5] * c2 + m[7] * c0) * inv_det;                   \
+    out[9] = (-m[0] * c4 + m[1] * c2 - m[3] * c0) * inv_det;                  \
+    out[10] = (m[12] * s4 - m[13] * s2 + m[15] * s0) * inv_det;               \
+    out[11] = (-m[8] * s4 + m[9] * s2 - m[11] * s0) * inv_det;                \
+    out[12] = (-m[4] * c3 + m[5] * c1 - m[6] * c0) * inv_det;                 \
+    out[13] = (m[0] * c3 - m[1] * c1 + m[2] * c0) * inv_det;                  \
+    out[14] = (-m[12] * s3 + m[13] * s1 - m[14] * s0) * inv_det;              \
+    out[15] = (m[8] * s3 - m[9] * s1 + m[10] * s0) * inv_det;                 \
+  }                                                                           \
+                                                                              \
+  __attribute__((always_inline))                                              \
+  void KERNEL_NAME(mul3_batch_ ## RUST_TYPE)(C_TYPE *restrict out,            \
+                                             const C_TYPE *restrict a,        \
+                                             const C_TYPE *restrict b,        \
+                                             size_t count) {                  \
+    for(size_t k = 0; k < count; k++) {                                       \
+      mat_mul_ ## RUST_TYPE(out + 9 * k, a + 9 * k, b + 9 * k, 3);            \
+    }                                                                         \
+  }                                                                           \
+                                                                              \
+  __attribute__((always_inline))                                              \
+  void KERNEL_NAME(mul4_batch_ ## RUST_TYPE)(C_TYPE *restrict out,            \
+                                             const C_TYPE *restrict a,        \
+                                             const C_TYPE *restrict b,        \
+                                             size_t count) {                  \
+    for(size_t k = 0; k < count; k++) {                                       \
+      mat_mul_ ## RUST_TYPE(out + 16 * k, a + 16 * k, b + 16 * k, 4);         \
+    }                                                                         \
+  }                                                                           \
+                                                                              \
+  __attribute__((always_inline))                                              \
+  void KERNEL_NAME(inv3_batch_ ## RUST_TYPE)(C_TYPE *restrict out,            \
+                                             const C_TYPE *restrict a,        \
+                                             size_t count) {                  \
+    for(size_t k = 0; k < count; k++) {                                       \
+      mat3_inv_ ## RUST_TYPE(out + 9 * k, a + 9 * k);                         \
+    }                                                                         \
+  }                                                                           \
+                                                                              \
+  __attribute__((always_inline))                                              \
+  void KERNEL_NAME(inv4_batch_ ## RUST_TYPE)(C_TYPE *restrict out,            \
+                                             const C_TYPE *restrict a,        \
+                                             size_t count) {                  \
+    for(size_t k = 0; k < count; k++) {                                       \
+      mat4_inv_ ## RUST_TYPE(out + 16 * k, a + 16 * k);                       \
+    }                                                                         \
+  }                                                                           \
+                                                                              \
+  /* points are in homogeneous coordinates with w = 1, divided by the new w */ \
+  __attribute__((always_inline))                                              \
+  void KERNEL_NAME(transform_points_ ## RUST_TYPE)(const C_TYPE *restrict m,  \
+                                                   C_TYPE *restrict points,   \
+                                                   size_t count) {            \
+    for(size_t k = 0; k < count; k++) {                                       \
+      C_TYPE *p = points + 3 * k;                                             \
+      C_TYPE x = p[0];                                                        \
+      C_TYPE y = p[1];                                                        \
+      C_TYPE z = p[2];                                                        \
+      C_TYPE inv_w = 1 / (m[12] * x + m[13] * y + m[14] * z + m[15]);         \
+      p[0] = (m[0] * x + m[1] * y + m[2] * z + m[3]) * inv_w;                 \
+      p[1] = (m[4] * x + m[5] * y + m[6] * z + m[7]) * inv_w;                 \
+      p[2] = (m[8] * x + m[9] * y + m[10] * z + m[11]) * inv_w;               \
+    }                                                                         \
+  }                                                                           \
+
+/*
+ * Triangular solves overwrite the right-hand side `b` with the solution. The
+ * transposed solve reads a lower-triangular matrix as its upper-triangular
+ * transpose, which completes a solve with a Cholesky factor.
+ */
+#define IMPL_TRIANGULAR(C_TYPE, RUST_TYPE)                                    \
+  __attribute__((always_inline))                                              \
+  void KERNEL_NAME(solve_lower_ ## RUST_TYPE)(const C_TYPE *restrict l,       \
+                                              C_TYPE *restrict b,             \
+                                              size_t n) {                     \
+    for(size_t i = 0; i < n; i++) {                                           \
+      C_TYPE s = b[i];                                                        \
+      for(size_t k = 0; k < i; k++) {                                         \
+        s -= l[i * n + k] * b[k];                                             \
+      }                                                                       \
+      b[i] = s / l[i * n + i];                                                \
+    }                                                                         \
+  }                                                                           \
+                                                                              \
+  __attribute__((always_inline))                                              \
+  void KERNEL_NAME(solve_upper_ ## RUST_TYPE)(const C_TYPE *restrict u,       \
+                                              C_TYPE *restrict b,             \
+                                              size_t n) {                     \
+    for(size_t i = n; i-- > 0;) {                                             \
+      C_TYPE s = b[i];                                                        \
+      for(size_t k = i + 1; k < n; k++) {                                     \
+        s -= u[i * n + k] * b[k];                                             \
+      }                                                                       \
+      b[i] = s / u[i * n + i];                                                \
+    }                                                                         \
+  }                                                                           \
+                                                                              \
+  __attribute__((always_inline))                                              \
+  void KERNEL_NAME(solve_lower_transpose_ ## RUST_TYPE)(                      \
+      const C_TYPE *restrict l,                                               \
+      C_TYPE *restrict b,                                                     \
+      size_t n) {                                                             \
+    for(size_t i = n; i-- > 0;) {                                             \
+      C_TYPE s = b[i];                                                        \
+      for(size_t k = i + 1; k < n; k++) {                                     \
+        s -= l[k * n + i] * b[k];                                             \
+      }                                                                       \
+      b[i] = s / l[i * n + i];                                                \
+    }                                                                         \
+  }                                                                           \
+
+IMPL_MATRIX(float, f32)
+IMPL_MATRIX(double, f64)
+
+IMPL_TRIANGULAR(float, f32)
+IMPL_TRIANGULAR(double, f64)
@@ -0,0 +1,43 @@
+/*
+ * The poison unsafe small matrix kernels, from the `matrix` module. These are
+ * compiled into the poison_unsafe library, with the same flags and under the
+ * same constraints: arguments must be frozen before being passed to these
+ * functions.
+ */
+
+#include <stddef.h>
+
+#include "libm.h"
+
+/*
+ * Factor the n x n row-major matrix `a` in place into its lower-triangular
+ * Cholesky factor, zeroing the upper triangle. Returns 0 if the matrix is not
+ * positive definite, in which case `a` is left partially factored.
+ */
+#define IMPL_CHOLESKY(C_TYPE, RUST_TYPE, SQRT_FN)                             \
+  __attribute__((always_inline))                                              \
+  int cholesky_ ## RUST_TYPE(C_TYPE *a, size_t n) {                           \
+    for(size_t j = 0; j < n; j++) {                                           \
+      C_TYPE d = a[j * n + j];                                                \
+      for(size_t k = 0; k < j; k++) {                                         \
+        d -= a[j * n + k] * a[j * n + k];                                     \
+      }                                                                       \
+      if(!(d > 0)) {                                                          \
+        return 0;                                                             \
+      }                                                                       \
+      d = SQRT_FN(d);                                                         \
+      a[j * n + j] = d;                                                       \
+      for(size_t i = j + 1; i < n; i++) {                                     \
+        C_TYPE s = a[i * n + j];                                              \
+        for(size_t k = 0; k < j; k++) {                                       \
+          s -= a[i * n + k] * a[j * n + k];                                   \
+        }                                                                     \
+        a[i * n + j] = s / d;                                                 \
+        a[j * n + i] = 0;                                                     \
+      }                                                                       \
+    }                                                                         \
+    return 1;                                                                 \
+  }                                                                           \
+
+IMPL_CHOLESKY(float, f32, sqrtf)
+IMPL_CHOLESKY(double, f64, sqrt)
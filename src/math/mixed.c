@@ -0,0 +1,81 @@
+/*
+ * The mixed precision kernels, which read values of one precision and
+ * accumulate them in a wider one: the dot products from the `bf16` and `f16`
+ * modules, and the double precision sum from the `slice` module. They are
+ * compiled into the poison_safe library, with the same flags and under the
+ * same constraints: every operation here can accept poison values without
+ * triggering UB.
+ *
+ * The narrow inputs are widened as they're loaded, so no widened copy of the
+ * inputs is ever stored, and the results are accumulated with several
+ * independent accumulators. The 16-bit values are widened to float with
+ * integer operations.
+ */
+
+#include <stddef.h>
+#include <stdint.h>
+
+__attribute__((always_inline))
+static inline float widen_bf16(uint16_t h) {
+  uint32_t bits = (uint32_t)h << 16;
+  float f;
+  __builtin_memcpy(&f, &bits, sizeof(f));
+  return f;
+}
+
+/*
+ * Normal half values only need their exponent rebiased from 15 to 127, while
+ * subnormals (and zero) are a multiple of 2^-24. Both are computed and one
+ * selected, which keeps the loops free of branches. The rebiased exponent of
+ * infinity and NaN is 143, which is widened to 255 by setting every exponent
+ * bit, also with a select.
+ */
+__attribute__((always_inline))
+static inline float widen_f16(uint16_t h) {
+  uint32_t sign = (uint32_t)(h & 0x8000) << 16;
+  uint32_t magnitude = h & 0x7fff;
+  uint32_t non_finite = magnitude >= 0x7c00 ? 0x7f800000u : 0;
+  uint32_t bits = sign | ((magnitude << 13) + (112u << 23)) | non_finite;
+  float normal;
+  __builtin_memcpy(&normal, &bits, sizeof(normal));
+  float subnormal = (float)magnitude * 0x1p-24f;
+  return magnitude < 0x400 ? (sign ? -subnormal : subnormal) : normal;
+}
+
+#define IMPL_HALF_DOT(NAME, WIDEN)                                            \
+  __attribute__((always_inline))                                              \
+  float dot_ ## NAME(const uint16_t *restrict a,                              \
+                     const uint16_t *restrict b,                              \
+                     size_t len) {                                            \
+    float s0 = 0, s1 = 0, s2 = 0, s3 = 0;                                     \
+    size_t i = 0;                                                             \
+    for(; i + 4 <= len; i += 4) {                                             \
+      s0 += WIDEN(a[i]) * WIDEN(b[i]);                                        \
+      s1 += WIDEN(a[i + 1]) * WIDEN(b[i + 1]);                                \
+      s2 += WIDEN(a[i + 2]) * WIDEN(b[i + 2]);                                \
+      s3 += WIDEN(a[i + 3]) * WIDEN(b[i + 3]);                                \
+    }                                                                         \
+    for(; i < len; i++) {                                                     \
+      s0 += WIDEN(a[i]) * WIDEN(b[i]);                                        \
+    }                                                                         \
+    return (s0 + s1) + (s2 + s3);                                             \
+  }                                                                           \
+
+IMPL_HALF_DOT(bf16, widen_bf16)
+IMPL_HALF_DOT(f16, widen_f16)
+
+__attribute__((always_inline))
+double sum_f64_acc_f32(const float *restrict a, size_t len) {
+  double s0 = 0, s1 = 0, s2 = 0, s3 = 0;
+  size_t i = 0;
+  for(; i + 4 <= len; i += 4) {
+    s0 += (double)a[i];
+    s1 += (double)a[i + 1];
+    s2 += (double)a[i + 2];
+    s3 += (double)a[i + 3];
+  }
+  for(; i < len; i++) {
+    s0 += (double)a[i];
+  }
+  return (s0 + s1) + (s2 + s3);
+}
@@ -1,4 +1,4 @@
-use crate::{FF32, FF64};
+use crate::{CF32, CF64, FF32, FF64};
 use core::ops::{Add, Div, Mul, Neg, Rem, Sub};
 use paste::paste;
 
@@ -73,6 +73,7 @@ macro_rules! poison_safe_fns {
      $(fn $fn:ident(self $(, $arg:ident : Self)*) -> Self;)*) => {
         paste! {
             $(
+                #[cfg(not(scalar_fallback))]
                 #[link(name = "poison_safe")]
                 extern "C" {
                     // functions in the poison_safe lib can accept poison args.
@@ -80,6 +81,12 @@ macro_rules! poison_safe_fns {
                     // primitive type, we can pass them directly over FFI
                     fn [<$fn _ $base_ty>](a: $fast_ty $(, $arg: $fast_ty)*) -> $fast_ty;
                 }
+
+                #[cfg(scalar_fallback)]
+                #[inline(always)]
+                unsafe fn [<$fn _ $base_ty>](a: $fast_ty $(, $arg: $fast_ty)*) -> $fast_ty {
+                    <$fast_ty>::new(<$base_ty>::$fn(a.freeze_raw() $(, $arg.freeze_raw())*))
+                }
             )*
 
             impl $fast_ty {
@@ -99,6 +106,7 @@ macro_rules! poison_unsafe_fns {
      $(fn $fn:ident(self $(, $arg:ident : Self)*) -> Self;)*) => {
         paste! {
             $(
+                #[cfg(not(scalar_fallback))]
                 #[link(name = "poison_unsafe")]
                 extern "C" {
                     // functions in the poison_unsafe lib must have their arguments frozen, which
@@ -106,6 +114,11 @@ macro_rules! poison_unsafe_fns {
                     fn [<$fn _ $base_ty>](a: $base_ty $(, $arg: $base_ty)*) -> $fast_ty;
                 }
 
+                #[cfg(scalar_fallback)]
+                #[inline(always)]
+                unsafe fn [<$fn _ $base_ty>](a: $base_ty $(, $arg: $base_ty)*) -> $fast_ty {
+                    <$fast_ty>::new(<$base_ty>::$fn(a $(, $arg)*))
+                }
             )*
 
             impl $fast_ty {
@@ -120,14 +133,64 @@ macro_rules! poison_unsafe_fns {
     }
 }
 
+macro_rules! flagged_fns {
+    ($fast_ty:ident, $base_ty:ident, $lib:tt, $flag:ident:
+     $(
+         $(#[$attr:meta])*
+         fn $op:ident(self, $arg:ident : Self) -> Self;
+     )*) => {
+        paste! {
+            $(
+                #[cfg(not(scalar_fallback))]
+                #[link(name = $lib)]
+                extern "C" {
+                    // the single-flag libs don't enable any flags that produce poison, but like
+                    // the poison_safe lib they can accept poison args
+                    fn [<$op _ $flag _ $base_ty>](a: $fast_ty, $arg: $fast_ty) -> $fast_ty;
+                }
+
+                #[cfg(scalar_fallback)]
+                #[inline(always)]
+                unsafe fn [<$op _ $flag _ $base_ty>](a: $fast_ty, $arg: $fast_ty) -> $fast_ty {
+                    <$fast_ty>::new(<$base_ty>::$op(a.freeze_raw(), $arg.freeze_raw()))
+                }
+            )*
+
+            impl $fast_ty {
+                $(
+                    $(#[$attr])*
+                    #[inline]
+                    pub fn [<$op _ $flag>](self, $arg: Self) -> Self {
+                        unsafe { [<$op _ $flag _ $base_ty>](self, $arg) }
+                    }
+                )*
+            }
+        }
+    }
+}
+
+// The special functions have no rust implementation in std to fall back on (`f64::gamma` and the
+// like are unstable), and can't be linked from a libm on the targets the fallback is for
+#[cfg(scalar_fallback)]
+mod special;
+
 macro_rules! impl_extern_math {
     ($fast_ty:ident, $base_ty:ident) => {
         poison_safe_fns! {
             $fast_ty, $base_ty:
             fn abs(self) -> Self;
+            fn ceil(self) -> Self;
             fn copysign(self, other: Self) -> Self;
+            fn floor(self) -> Self;
+            fn hypot(self, other: Self) -> Self;
             fn max(self, other: Self) -> Self;
             fn min(self, other: Self) -> Self;
+            fn mul_add(self, mul: Self, add: Self) -> Self;
+            fn recip(self) -> Self;
+            fn round(self) -> Self;
+            fn to_degrees(self) -> Self;
+            fn to_radians(self) -> Self;
+            fn trunc(self) -> Self;
         }
 
         poison_unsafe_fns! {
@@ -140,28 +203,64 @@ macro_rules! impl_extern_math {
             fn atan2(self, other: Self) -> Self;
             fn atanh(self) -> Self;
             fn cbrt(self) -> Self;
-            fn ceil(self) -> Self;
             fn cos(self) -> Self;
             fn cosh(self) -> Self;
+            fn div_euclid(self, rhs: Self) -> Self;
             fn exp(self) -> Self;
             fn exp2(self) -> Self;
-            fn floor(self) -> Self;
             fn exp_m1(self) -> Self;
             fn ln(self) -> Self;
             fn ln_1p(self) -> Self;
+            fn log(self, base: Self) -> Self;
             fn log2(self) -> Self;
             fn log10(self) -> Self;
             fn powf(self, n: Self) -> Self;
-            fn round(self) -> Self;
+            fn rem_euclid(self, rhs: Self) -> Self;
             fn sin(self) -> Self;
             fn sinh(self) -> Self;
             fn sqrt(self) -> Self;
             fn tan(self) -> Self;
             fn tanh(self) -> Self;
-            fn trunc(self) -> Self;
+        }
+
+        // The operator impls enable every fast-math flag. These methods are compiled with only a
+        // single flag each, to narrow the relaxation to what a particular operation needs
+
+        flagged_fns! {
+            $fast_ty, $base_ty, "contract", contract:
+            /// Add two values with only contraction enabled, which allows fusing with a
+            /// neighboring multiplication.
+            fn add(self, other: Self) -> Self;
+
+            /// Subtract two values with only contraction enabled, which allows fusing with a
+            /// neighboring multiplication.
+            fn sub(self, other: Self) -> Self;
+
+            /// Multiply two values with only contraction enabled, which allows fusing with a
+            /// neighboring addition or subtraction.
+            fn mul(self, other: Self) -> Self;
+        }
+
+        flagged_fns! {
+            $fast_ty, $base_ty, "reassoc", reassoc:
+            /// Add two values with only reassociation enabled (along with the no-signed-zeros
+            /// flag that reassociation requires).
+            fn add(self, other: Self) -> Self;
+
+            /// Multiply two values with only reassociation enabled (along with the
+            /// no-signed-zeros flag that reassociation requires).
+            fn mul(self, other: Self) -> Self;
+        }
+
+        flagged_fns! {
+            $fast_ty, $base_ty, "reciprocal", reciprocal:
+            /// Divide two values with only reciprocal math enabled, which allows the division to
+            /// be replaced by multiplication with the reciprocal of `other`.
+            fn div(self, other: Self) -> Self;
         }
 
         paste! {
+            #[cfg(not(any(scalar_fallback, inline_arith)))]
             #[link(name = "poison_safe")]
             extern "C" {
                 fn [<add_ $base_ty>](a: $fast_ty, b: $fast_ty) -> $fast_ty;
@@ -169,28 +268,513 @@ macro_rules! impl_extern_math {
                 fn [<mul_ $base_ty>](a: $fast_ty, b: $fast_ty) -> $fast_ty;
                 fn [<div_ $base_ty>](a: $fast_ty, b: $fast_ty) -> $fast_ty;
                 fn [<neg_ $base_ty>](a: $fast_ty) -> $fast_ty;
+            }
 
+            #[cfg(not(scalar_fallback))]
+            #[link(name = "poison_safe")]
+            extern "C" {
                 fn [<clamp_ $base_ty>](a: $fast_ty, min: $fast_ty, max: $fast_ty) -> $fast_ty;
                 fn [<powi_ $base_ty>](a: $fast_ty, b: i32) -> $fast_ty;
+
+                // the polynomial approximations avoid branching on their arguments, unlike the
+                // ones replacing libm in the poison_unsafe lib
+                fn [<exp_approx_ $base_ty>](a: $fast_ty) -> $fast_ty;
+                fn [<ln_approx_ $base_ty>](a: $fast_ty) -> $fast_ty;
+                fn [<tanh_approx_ $base_ty>](a: $fast_ty) -> $fast_ty;
+
+                fn [<rsqrt_ $base_ty>](a: $fast_ty) -> $fast_ty;
+                fn [<rsqrt_refined_ $base_ty>](a: $fast_ty, steps: u32) -> $fast_ty;
+
+                fn [<hypot3_ $base_ty>](a: $fast_ty, b: $fast_ty, c: $fast_ty) -> $fast_ty;
+
+                // the standard library's abs_sub is deprecated, so the strict fallback can't be
+                // generated like the other poison safe functions
+                fn [<abs_sub_ $base_ty>](a: $fast_ty, b: $fast_ty) -> $fast_ty;
+
+                // likewise minimum and maximum, which aren't stable in the standard library yet
+                fn [<minimum_ $base_ty>](a: $fast_ty, b: $fast_ty) -> $fast_ty;
+                fn [<maximum_ $base_ty>](a: $fast_ty, b: $fast_ty) -> $fast_ty;
+            }
+
+            // the special functions, exp10 and the sinpi family have no rust counterpart for the
+            // strict fallback to call, so they're declared separately from the other libm wrappers
+            #[cfg(not(scalar_fallback))]
+            #[link(name = "poison_unsafe")]
+            extern "C" {
+                fn [<erf_ $base_ty>](a: $base_ty) -> $fast_ty;
+                fn [<erfc_ $base_ty>](a: $base_ty) -> $fast_ty;
+                fn [<gamma_ $base_ty>](a: $base_ty) -> $fast_ty;
+                fn [<ln_gamma_ $base_ty>](a: $base_ty) -> $fast_ty;
+                fn [<exp10_ $base_ty>](a: $base_ty) -> $fast_ty;
+                fn [<sinpi_ $base_ty>](a: $base_ty) -> $fast_ty;
+                fn [<cospi_ $base_ty>](a: $base_ty) -> $fast_ty;
+            }
+
+            #[cfg(not(scalar_fallback))]
+            #[link(name = "poison_unsafe")]
+            extern "C" {
+                // both results are written through pointers, as C can't return a pair
+                fn [<sincos_ $base_ty>](a: $base_ty, sin: *mut $fast_ty, cos: *mut $fast_ty);
             }
 
+            #[cfg(not(any(scalar_fallback, inline_arith)))]
             #[link(name = "poison_unsafe")]
             extern "C" {
                 fn [<rem_ $base_ty>](a: $base_ty, b: $base_ty) -> $fast_ty;
             }
 
+            // with the inline fallback, the arithmetic shims are replaced by the base type's
+            // algebraic operations, which can be inlined without cross-language LTO
+            #[cfg(inline_arith)]
+            mod [<inline_ $base_ty>] {
+                use super::*;
+
+                #[inline(always)]
+                pub(super) unsafe fn [<add_ $base_ty>](a: $fast_ty, b: $fast_ty) -> $fast_ty {
+                    <$fast_ty>::new(a.freeze_raw().algebraic_add(b.freeze_raw()))
+                }
+
+                #[inline(always)]
+                pub(super) unsafe fn [<sub_ $base_ty>](a: $fast_ty, b: $fast_ty) -> $fast_ty {
+                    <$fast_ty>::new(a.freeze_raw().algebraic_sub(b.freeze_raw()))
+                }
+
+                #[inline(always)]
+                pub(super) unsafe fn [<mul_ $base_ty>](a: $fast_ty, b: $fast_ty) -> $fast_ty {
+                    <$fast_ty>::new(a.freeze_raw().algebraic_mul(b.freeze_raw()))
+                }
+
+                #[inline(always)]
+                pub(super) unsafe fn [<div_ $base_ty>](a: $fast_ty, b: $fast_ty) -> $fast_ty {
+                    <$fast_ty>::new(a.freeze_raw().algebraic_div(b.freeze_raw()))
+                }
+
+                #[inline(always)]
+                pub(super) unsafe fn [<neg_ $base_ty>](a: $fast_ty) -> $fast_ty {
+                    <$fast_ty>::new(-a.freeze_raw())
+                }
+
+                #[inline(always)]
+                pub(super) unsafe fn [<rem_ $base_ty>](a: $base_ty, b: $base_ty) -> $fast_ty {
+                    <$fast_ty>::new(a.algebraic_rem(b))
+                }
+            }
+
+            #[cfg(inline_arith)]
+            use [<inline_ $base_ty>]::*;
+
+            // with the strict feature, the shims are replaced by the base type's own operations
+            #[cfg(scalar_fallback)]
+            mod [<strict_ $base_ty>] {
+                use super::*;
+
+                #[inline(always)]
+                pub(super) unsafe fn [<add_ $base_ty>](a: $fast_ty, b: $fast_ty) -> $fast_ty {
+                    <$fast_ty>::new(a.freeze_raw() + b.freeze_raw())
+                }
+
+                #[inline(always)]
+                pub(super) unsafe fn [<sub_ $base_ty>](a: $fast_ty, b: $fast_ty) -> $fast_ty {
+                    <$fast_ty>::new(a.freeze_raw() - b.freeze_raw())
+                }
+
+                #[inline(always)]
+                pub(super) unsafe fn [<mul_ $base_ty>](a: $fast_ty, b: $fast_ty) -> $fast_ty {
+                    <$fast_ty>::new(a.freeze_raw() * b.freeze_raw())
+                }
+
+                #[inline(always)]
+                pub(super) unsafe fn [<div_ $base_ty>](a: $fast_ty, b: $fast_ty) -> $fast_ty {
+                    <$fast_ty>::new(a.freeze_raw() / b.freeze_raw())
+                }
+
+                #[inline(always)]
+                pub(super) unsafe fn [<neg_ $base_ty>](a: $fast_ty) -> $fast_ty {
+                    <$fast_ty>::new(-a.freeze_raw())
+                }
+
+                #[inline(always)]
+                pub(super) unsafe fn [<clamp_ $base_ty>](a: $fast_ty, min: $fast_ty, max: $fast_ty) -> $fast_ty {
+                    <$fast_ty>::new(a.freeze_raw().clamp(min.freeze_raw(), max.freeze_raw()))
+                }
+
+                #[inline(always)]
+                pub(super) unsafe fn [<powi_ $base_ty>](a: $fast_ty, b: i32) -> $fast_ty {
+                    <$fast_ty>::new(a.freeze_raw().powi(b))
+                }
+
+                // strict builds compute the exact functions instead of the approximations
+                #[inline(always)]
+                pub(super) unsafe fn [<exp_approx_ $base_ty>](a: $fast_ty) -> $fast_ty {
+                    <$fast_ty>::new(a.freeze_raw().exp())
+                }
+
+                #[inline(always)]
+                pub(super) unsafe fn [<ln_approx_ $base_ty>](a: $fast_ty) -> $fast_ty {
+                    <$fast_ty>::new(a.freeze_raw().ln())
+                }
+
+                #[inline(always)]
+                pub(super) unsafe fn [<tanh_approx_ $base_ty>](a: $fast_ty) -> $fast_ty {
+                    <$fast_ty>::new(a.freeze_raw().tanh())
+                }
+
+                // likewise the exact reciprocal square root, regardless of the refinement steps
+                #[inline(always)]
+                pub(super) unsafe fn [<rsqrt_ $base_ty>](a: $fast_ty) -> $fast_ty {
+                    <$fast_ty>::new(1.0 / a.freeze_raw().sqrt())
+                }
+
+                #[inline(always)]
+                pub(super) unsafe fn [<rsqrt_refined_ $base_ty>](a: $fast_ty, _steps: u32) -> $fast_ty {
+                    <$fast_ty>::new(1.0 / a.freeze_raw().sqrt())
+                }
+
+                #[inline(always)]
+                pub(super) unsafe fn [<hypot3_ $base_ty>](a: $fast_ty, b: $fast_ty, c: $fast_ty) -> $fast_ty {
+                    let (a, b, c) = (a.freeze_raw(), b.freeze_raw(), c.freeze_raw());
+                    <$fast_ty>::new((a * a + b * b + c * c).sqrt())
+                }
+
+                #[inline(always)]
+                pub(super) unsafe fn [<abs_sub_ $base_ty>](a: $fast_ty, b: $fast_ty) -> $fast_ty {
+                    <$fast_ty>::new((a.freeze_raw() - b.freeze_raw()).max(0.0))
+                }
+
+                #[inline(always)]
+                pub(super) unsafe fn [<minimum_ $base_ty>](a: $fast_ty, b: $fast_ty) -> $fast_ty {
+                    let (a, b) = (a.freeze_raw(), b.freeze_raw());
+                    <$fast_ty>::new(if a.is_nan() || b.is_nan() {
+                        a + b
+                    } else if a == b {
+                        if a.is_sign_negative() { a } else { b }
+                    } else if a < b {
+                        a
+                    } else {
+                        b
+                    })
+                }
+
+                #[inline(always)]
+                pub(super) unsafe fn [<maximum_ $base_ty>](a: $fast_ty, b: $fast_ty) -> $fast_ty {
+                    let (a, b) = (a.freeze_raw(), b.freeze_raw());
+                    <$fast_ty>::new(if a.is_nan() || b.is_nan() {
+                        a + b
+                    } else if a == b {
+                        if a.is_sign_negative() { b } else { a }
+                    } else if a > b {
+                        a
+                    } else {
+                        b
+                    })
+                }
+
+                // the special functions are implemented in rust, see `special`
+                #[inline(always)]
+                pub(super) unsafe fn [<erf_ $base_ty>](a: $base_ty) -> $fast_ty {
+                    <$fast_ty>::new(special::[<erf_ $base_ty>](a))
+                }
+
+                #[inline(always)]
+                pub(super) unsafe fn [<erfc_ $base_ty>](a: $base_ty) -> $fast_ty {
+                    <$fast_ty>::new(special::[<erfc_ $base_ty>](a))
+                }
+
+                #[inline(always)]
+                pub(super) unsafe fn [<gamma_ $base_ty>](a: $base_ty) -> $fast_ty {
+                    <$fast_ty>::new(special::[<gamma_ $base_ty>](a))
+                }
+
+                #[inline(always)]
+                pub(super) unsafe fn [<ln_gamma_ $base_ty>](a: $base_ty) -> $fast_ty {
+                    <$fast_ty>::new(special::[<ln_gamma_ $base_ty>](a))
+                }
+
+                #[inline(always)]
+                pub(super) unsafe fn [<exp10_ $base_ty>](a: $base_ty) -> $fast_ty {
+                    <$fast_ty>::new((10.0 as $base_ty).powf(a))
+                }
+
+                // the same reduction as poison_unsafe.c, to a multiple of 1/2 and a remainder of
+                // at most 1/4, which is exact
+                #[inline(always)]
+                fn [<sincospi_ $base_ty>](a: $base_ty) -> ($base_ty, $base_ty) {
+                    if !a.is_finite() {
+                        return (a - a, a - a);
+                    }
+                    // values too large for the integer conversion are even integers
+                    if a.abs() >= 4_611_686_018_427_387_904.0 {
+                        return (0.0, 1.0);
+                    }
+                    let n = (2.0 * a).round();
+                    let (s, c) = ((a - 0.5 * n) * core::$base_ty::consts::PI).sin_cos();
+                    match n as i64 & 3 {
+                        0 => (s, c),
+                        1 => (c, -s),
+                        2 => (-s, -c),
+                        _ => (-c, s),
+                    }
+                }
+
+                #[inline(always)]
+                pub(super) unsafe fn [<sincos_ $base_ty>](a: $base_ty, sin: *mut $fast_ty, cos: *mut $fast_ty) {
+                    let (s, c) = a.sin_cos();
+                    *sin = <$fast_ty>::new(s);
+                    *cos = <$fast_ty>::new(c);
+                }
+
+                #[inline(always)]
+                pub(super) unsafe fn [<sinpi_ $base_ty>](a: $base_ty) -> $fast_ty {
+                    <$fast_ty>::new([<sincospi_ $base_ty>](a).0)
+                }
+
+                #[inline(always)]
+                pub(super) unsafe fn [<cospi_ $base_ty>](a: $base_ty) -> $fast_ty {
+                    <$fast_ty>::new([<sincospi_ $base_ty>](a).1)
+                }
+
+                #[inline(always)]
+                pub(super) unsafe fn [<rem_ $base_ty>](a: $base_ty, b: $base_ty) -> $fast_ty {
+                    <$fast_ty>::new(a % b)
+                }
+            }
+
+            #[cfg(scalar_fallback)]
+            use [<strict_ $base_ty>]::*;
+
             // a few functions are special cases and aren't defined in submacros
             impl $fast_ty {
+                /// The sine and cosine of this value in radians, computed in a single libm call
+                /// where the platform provides one
+                #[inline]
+                pub fn sin_cos(self) -> (Self, Self) {
+                    let (mut sin, mut cos) = (Self::ZERO, Self::ZERO);
+                    unsafe { [<sincos_ $base_ty>](self.freeze_raw(), &mut sin, &mut cos) };
+                    (sin, cos)
+                }
+
                 #[inline]
                 pub fn clamp(self, min: Self, max: Self) -> Self {
                     assert!(min <= max);
                     unsafe { [<clamp_ $base_ty>](self, min, max) }
                 }
 
+                /// Restrict this value to the interval `[0, 1]`.
+                ///
+                /// Unlike [`clamp`](Self::clamp), the bounds are known to be in order, so nothing
+                /// is frozen.
+                #[inline]
+                pub fn clamp01(self) -> Self {
+                    unsafe { [<clamp_ $base_ty>](self, Self::ZERO, Self::ONE) }
+                }
+
+                /// Restrict this value to the interval `[0, 1]`, like [`clamp01`](Self::clamp01)
+                /// under its name in shading languages.
+                #[inline]
+                pub fn saturate(self) -> Self {
+                    self.clamp01()
+                }
+
+                /// Map this value linearly from the interval `[in_min, in_max]` to
+                /// `[out_min, out_max]`.
+                ///
+                /// Values outside the input interval are extrapolated, so the result is only
+                /// within the output interval if it's clamped, for example with
+                /// `out_min.lerp(out_max, in_min.inverse_lerp(in_max, x).clamp01())`. `in_min` and
+                /// `in_max` must differ.
+                #[inline]
+                pub fn remap(self, in_min: Self, in_max: Self, out_min: Self, out_max: Self) -> Self {
+                    out_min.lerp(out_max, in_min.inverse_lerp(in_max, self))
+                }
+
                 #[inline]
                 pub fn powi(self, n: i32) -> Self {
                     unsafe { [<powi_ $base_ty>](self, n) }
                 }
+
+                /// Raise this value to the constant integer power `N`.
+                ///
+                /// The exponents 0, 1, 2, 3 and -1 are computed directly, with multiplications or
+                /// a reciprocal, and any other with [`powi`](Self::powi), without the comparisons
+                /// of [`powf`](Self::powf) against its special exponents.
+                #[inline]
+                pub fn powf_const<const N: i32>(self) -> Self {
+                    match N {
+                        0 => Self::ONE,
+                        1 => self,
+                        2 => self * self,
+                        3 => self * self * self,
+                        -1 => self.recip(),
+                        _ => self.powi(N),
+                    }
+                }
+
+                /// An approximation of [`exp`](Self::exp) by a polynomial, with a relative error
+                /// of about `1e-6` (and up to `4e-6` for `FF32` arguments far from zero, where the
+                /// rounding of the argument itself dominates).
+                ///
+                /// Unlike `exp`, this needs no libm call and no freezing, so it can be inlined and
+                /// vectorized along with the surrounding arithmetic. Results beyond the normal
+                /// range saturate at the largest power of two or the smallest normal value.
+                #[inline]
+                pub fn exp_approx(self) -> Self {
+                    unsafe { [<exp_approx_ $base_ty>](self) }
+                }
+
+                /// An approximation of [`ln`](Self::ln) by a polynomial, with a relative error of
+                /// about `1e-6` (or an absolute error of about `1e-7` close to 1).
+                ///
+                /// Like [`exp_approx`](Self::exp_approx), this needs no libm call and no freezing.
+                /// The value must be positive and normal; results for anything else are
+                /// unspecified.
+                #[inline]
+                pub fn ln_approx(self) -> Self {
+                    unsafe { [<ln_approx_ $base_ty>](self) }
+                }
+
+                /// An approximation of [`tanh`](Self::tanh) by polynomials, with a relative error
+                /// of about `1e-6`.
+                ///
+                /// Like [`exp_approx`](Self::exp_approx), this needs no libm call and no freezing.
+                #[inline]
+                pub fn tanh_approx(self) -> Self {
+                    unsafe { [<tanh_approx_ $base_ty>](self) }
+                }
+
+                /// The length of the three dimensional vector `(self, b, c)`,
+                /// `sqrt(self^2 + b^2 + c^2)`.
+                ///
+                /// The squares aren't scaled to guard against overflow (and neither are those of
+                /// [`hypot`](Self::hypot), unlike the standard library's), so the components must
+                /// be small enough for their squares to be finite.
+                #[inline]
+                pub fn hypot3(self, b: Self, c: Self) -> Self {
+                    unsafe { [<hypot3_ $base_ty>](self, b, c) }
+                }
+
+                /// The positive difference, `self - other` if that's positive and zero otherwise
+                /// (C's `fdim`).
+                ///
+                /// This matches the standard library's deprecated method of the same name, which
+                /// despite its name isn't the absolute value of the difference.
+                #[inline]
+                pub fn abs_sub(self, other: Self) -> Self {
+                    unsafe { [<abs_sub_ $base_ty>](self, other) }
+                }
+
+                /// The minimum of two values with IEEE 754-2019 semantics, like the standard
+                /// library's `minimum`.
+                ///
+                /// Unlike [`min`](Self::min), a NaN operand is propagated rather than ignored
+                /// (which only matters with the `non-finite` feature), and `-0` is less than `+0`
+                /// (in strict builds only, since fast-math ignores the sign of zeros).
+                #[inline]
+                pub fn minimum(self, other: Self) -> Self {
+                    unsafe { [<minimum_ $base_ty>](self, other) }
+                }
+
+                /// The maximum of two values with IEEE 754-2019 semantics, like the standard
+                /// library's `maximum`.
+                ///
+                /// Unlike [`max`](Self::max), a NaN operand is propagated rather than ignored
+                /// (which only matters with the `non-finite` feature), and `+0` is greater than
+                /// `-0` (in strict builds only, since fast-math ignores the sign of zeros).
+                #[inline]
+                pub fn maximum(self, other: Self) -> Self {
+                    unsafe { [<maximum_ $base_ty>](self, other) }
+                }
+
+                /// An estimate of the reciprocal square root, `1 / sqrt(self)`, from the
+                /// hardware's estimate instruction.
+                ///
+                /// The estimate is accurate to a relative error of about `2^-12` on x86, and
+                /// `2^-8` on aarch64. Other targets compute a much cruder estimate from the bits
+                /// of the value. [`rsqrt_refined`](Self::rsqrt_refined) improves on it. The value
+                /// must be positive and normal; results for anything else are unspecified.
+                #[inline]
+                pub fn rsqrt(self) -> Self {
+                    unsafe { [<rsqrt_ $base_ty>](self) }
+                }
+
+                /// The [`rsqrt`](Self::rsqrt) estimate, refined by `steps` Newton-Raphson
+                /// iterations.
+                ///
+                /// Each step roughly doubles the number of correct bits, up to the precision of
+                /// the type: one step is enough for most `FF32` uses on x86, and two on aarch64.
+                /// Strict builds compute the exact reciprocal square root whatever the number of
+                /// steps.
+                #[inline]
+                pub fn rsqrt_refined(self, steps: u32) -> Self {
+                    unsafe { [<rsqrt_refined_ $base_ty>](self, steps) }
+                }
+
+                /// The error function, `2 / sqrt(pi)` times the integral of `exp(-t^2)` from 0 to
+                /// this value.
+                ///
+                /// `(1 + (x / sqrt(2)).erf()) / 2` is the cumulative distribution function of the
+                /// standard normal distribution.
+                #[inline]
+                pub fn erf(self) -> Self {
+                    unsafe { [<erf_ $base_ty>](self.freeze_raw()) }
+                }
+
+                /// The complementary error function, `1 - erf(self)`.
+                ///
+                /// This is computed directly rather than by subtraction, so it keeps its relative
+                /// accuracy for large values, where `erf` is close to 1.
+                #[inline]
+                pub fn erfc(self) -> Self {
+                    unsafe { [<erfc_ $base_ty>](self.freeze_raw()) }
+                }
+
+                /// The gamma function, which extends the factorial to real values:
+                /// `(n + 1).gamma() == n!`.
+                ///
+                /// The function has poles at zero and the negative integers, where the result is
+                /// infinite and so unspecified.
+                #[inline]
+                pub fn gamma(self) -> Self {
+                    unsafe { [<gamma_ $base_ty>](self.freeze_raw()) }
+                }
+
+                /// The natural logarithm of the absolute value of the
+                /// [gamma function](Self::gamma).
+                ///
+                /// This stays finite far beyond the values where `gamma` itself overflows, which
+                /// makes it the usual choice for log-likelihoods.
+                #[inline]
+                pub fn ln_gamma(self) -> Self {
+                    unsafe { [<ln_gamma_ $base_ty>](self.freeze_raw()) }
+                }
+
+                /// `10^self`, like `powf(10, self)` but usually faster.
+                #[inline]
+                pub fn exp10(self) -> Self {
+                    unsafe { [<exp10_ $base_ty>](self.freeze_raw()) }
+                }
+
+                /// The sine of `pi * self`.
+                ///
+                /// The argument is reduced before it's multiplied by pi, which is more accurate
+                /// than `(self * PI).sin()`, and exact at the integers and half-integers. This
+                /// suits angles measured in half turns, like the phases of oscillators.
+                #[inline]
+                pub fn sinpi(self) -> Self {
+                    unsafe { [<sinpi_ $base_ty>](self.freeze_raw()) }
+                }
+
+                /// The cosine of `pi * self`, with the same accuracy as [`sinpi`](Self::sinpi).
+                #[inline]
+                pub fn cospi(self) -> Self {
+                    unsafe { [<cospi_ $base_ty>](self.freeze_raw()) }
+                }
+
+                /// The sine and cosine of `pi * self`, like [`sinpi`](Self::sinpi) and
+                /// [`cospi`](Self::cospi).
+                #[inline]
+                pub fn sincospi(self) -> (Self, Self) {
+                    (self.sinpi(), self.cospi())
+                }
             }
 
             impl_fast_ops! {
@@ -255,3 +839,110 @@ macro_rules! impl_extern_math {
 
 impl_extern_math! { FF32, f32 }
 impl_extern_math! { FF64, f64 }
+
+macro_rules! impl_contract_math {
+    ($contract_ty:ident, $base_ty:ident) => {
+        paste! {
+            #[cfg(not(scalar_fallback))]
+            #[link(name = "contract")]
+            extern "C" {
+                // the contract lib is compiled without any flags that produce poison, so the
+                // contract types can be passed over FFI without freezing
+                fn [<contract_add_ $base_ty>](a: $contract_ty, b: $contract_ty) -> $contract_ty;
+                fn [<contract_sub_ $base_ty>](a: $contract_ty, b: $contract_ty) -> $contract_ty;
+                fn [<contract_mul_ $base_ty>](a: $contract_ty, b: $contract_ty) -> $contract_ty;
+                fn [<contract_div_ $base_ty>](a: $contract_ty, b: $contract_ty) -> $contract_ty;
+            }
+
+            #[cfg(scalar_fallback)]
+            mod [<strict_contract_ $base_ty>] {
+                use super::*;
+
+                #[inline(always)]
+                pub(super) unsafe fn [<contract_add_ $base_ty>](a: $contract_ty, b: $contract_ty) -> $contract_ty {
+                    <$contract_ty>::new(<$base_ty>::from(a) + <$base_ty>::from(b))
+                }
+
+                #[inline(always)]
+                pub(super) unsafe fn [<contract_sub_ $base_ty>](a: $contract_ty, b: $contract_ty) -> $contract_ty {
+                    <$contract_ty>::new(<$base_ty>::from(a) - <$base_ty>::from(b))
+                }
+
+                #[inline(always)]
+                pub(super) unsafe fn [<contract_mul_ $base_ty>](a: $contract_ty, b: $contract_ty) -> $contract_ty {
+                    <$contract_ty>::new(<$base_ty>::from(a) * <$base_ty>::from(b))
+                }
+
+                #[inline(always)]
+                pub(super) unsafe fn [<contract_div_ $base_ty>](a: $contract_ty, b: $contract_ty) -> $contract_ty {
+                    <$contract_ty>::new(<$base_ty>::from(a) / <$base_ty>::from(b))
+                }
+            }
+
+            #[cfg(scalar_fallback)]
+            use [<strict_contract_ $base_ty>]::*;
+
+            impl_fast_ops! {
+                $contract_ty, $base_ty:
+                Add, add, [<contract_add_ $base_ty>],
+                Sub, sub, [<contract_sub_ $base_ty>],
+                Mul, mul, [<contract_mul_ $base_ty>],
+                Div, div, [<contract_div_ $base_ty>],
+            }
+
+            // negation and remainder are exact operations that contraction can't affect, so
+            // they're implemented with the base type directly
+            impl Neg for $contract_ty {
+                type Output = Self;
+
+                #[inline(always)]
+                fn neg(self) -> Self::Output {
+                    <$contract_ty>::new(-<$base_ty>::from(self))
+                }
+            }
+
+            impl Neg for &$contract_ty {
+                type Output = <$contract_ty as Neg>::Output;
+
+                #[inline]
+                fn neg(self) -> Self::Output {
+                    -(*self)
+                }
+            }
+
+            impl Rem <$contract_ty> for $contract_ty {
+                type Output = $contract_ty;
+
+                #[inline(always)]
+                fn rem(self, other: $contract_ty) -> Self::Output {
+                    <$contract_ty>::new(<$base_ty>::from(self) % <$base_ty>::from(other))
+                }
+            }
+
+            impl Rem <$base_ty> for $contract_ty {
+                type Output = $contract_ty;
+
+                #[inline(always)]
+                fn rem(self, other: $base_ty) -> Self::Output {
+                    <$contract_ty>::new(<$base_ty>::from(self) % other)
+                }
+            }
+
+            impl Rem <$contract_ty> for $base_ty {
+                type Output = $contract_ty;
+
+                #[inline(always)]
+                fn rem(self, other: $contract_ty) -> Self::Output {
+                    <$contract_ty>::new(self % <$base_ty>::from(other))
+                }
+            }
+
+            impl_binary_refs! { $contract_ty, $contract_ty, Rem, rem }
+            impl_binary_refs! { $contract_ty, $base_ty, Rem, rem }
+            impl_binary_refs! { $base_ty, $contract_ty, Rem, rem }
+        }
+    };
+}
+
+impl_contract_math! { CF32, f32 }
+impl_contract_math! { CF64, f64 }
@@ -7,7 +7,7 @@
  * [1]: https://llvm.org/docs/LangRef.html#poison-values
  */
 
-#include <math.h>
+#include "libm.h"
 
 #define IMPL_OPERATIONS(C_TYPE, RUST_TYPE)       \
   __attribute__((always_inline))                 \
@@ -53,6 +53,20 @@ IMPL_OPERATIONS(double, f64)
 IMPL_UNARY_FUNCTION(float, f32, abs, fabsf)
 IMPL_UNARY_FUNCTION(double, f64, abs, fabs)
 
+// the rounding functions are lowered to instructions (or calls, on targets
+// without them) which never branch on their argument
+IMPL_UNARY_FUNCTION(float, f32, ceil, ceilf)
+IMPL_UNARY_FUNCTION(double, f64, ceil, ceil)
+
+IMPL_UNARY_FUNCTION(float, f32, floor, floorf)
+IMPL_UNARY_FUNCTION(double, f64, floor, floor)
+
+IMPL_UNARY_FUNCTION(float, f32, round, roundf)
+IMPL_UNARY_FUNCTION(double, f64, round, round)
+
+IMPL_UNARY_FUNCTION(float, f32, trunc, truncf)
+IMPL_UNARY_FUNCTION(double, f64, trunc, trunc)
+
 IMPL_BINARY_FUNCTION(float, f32, copysign, copysignf)
 IMPL_BINARY_FUNCTION(double, f64, copysign, copysign)
 
@@ -72,6 +86,108 @@ double powi_f64(double a, int b) {
   return __builtin_powi(a, b);
 }
 
+// the squares aren't scaled like libm's hypot does, which would need branches
+// on the values. Finite-math assumes they don't overflow anyway
+__attribute__((always_inline))
+float hypot_f32(float a, float b) {
+  return sqrtf(a * a + b * b);
+}
+
+__attribute__((always_inline))
+double hypot_f64(double a, double b) {
+  return sqrt(a * a + b * b);
+}
+
+__attribute__((always_inline))
+float hypot3_f32(float a, float b, float c) {
+  return sqrtf(a * a + b * b + c * c);
+}
+
+__attribute__((always_inline))
+double hypot3_f64(double a, double b, double c) {
+  return sqrt(a * a + b * b + c * c);
+}
+
+// with contraction, this compiles to a single fused multiply-add on targets
+// which have one, and to a separate multiplication and addition elsewhere
+// (where a libm fma call would be much slower)
+__attribute__((always_inline))
+float mul_add_f32(float a, float mul, float add) {
+  return a * mul + add;
+}
+
+__attribute__((always_inline))
+double mul_add_f64(double a, double mul, double add) {
+  return a * mul + add;
+}
+
+// the conversions between degrees and radians are a single multiplication, by
+// the same constants as rust's to_degrees and to_radians
+__attribute__((always_inline))
+float to_degrees_f32(float a) {
+  return a * 57.2957795130823208767981548141051703f;
+}
+
+__attribute__((always_inline))
+double to_degrees_f64(double a) {
+  return a * (180.0 / 3.14159265358979323846264338327950288);
+}
+
+__attribute__((always_inline))
+float to_radians_f32(float a) {
+  return a * (3.14159265358979323846264338327950288f / 180.0f);
+}
+
+__attribute__((always_inline))
+double to_radians_f64(double a) {
+  return a * (3.14159265358979323846264338327950288 / 180.0);
+}
+
+// fmax is lowered to a max instruction under fast-math, rather than the
+// comparison and branch of a libm fdim
+__attribute__((always_inline))
+float abs_sub_f32(float a, float b) {
+  return fmaxf(a - b, 0);
+}
+
+__attribute__((always_inline))
+double abs_sub_f64(double a, double b) {
+  return fmax(a - b, 0);
+}
+
+// IEEE 754-2019's minimum and maximum, which propagate NaN (only honored with
+// the non-finite feature) and order -0 below +0 (which fast-math doesn't
+// honor at all), unlike min and max. Every choice is a select
+#define IMPL_MINIMUM_MAXIMUM(C_TYPE, RUST_TYPE)                               \
+  __attribute__((always_inline))                                              \
+  C_TYPE minimum_ ## RUST_TYPE(C_TYPE a, C_TYPE b) {                          \
+    C_TYPE r = a < b ? a : b;                                                 \
+    r = a == b ? (__builtin_signbit(a) ? a : b) : r;                          \
+    return a != a || b != b ? a + b : r;                                      \
+  }                                                                           \
+                                                                              \
+  __attribute__((always_inline))                                              \
+  C_TYPE maximum_ ## RUST_TYPE(C_TYPE a, C_TYPE b) {                          \
+    C_TYPE r = a > b ? a : b;                                                 \
+    r = a == b ? (__builtin_signbit(a) ? b : a) : r;                          \
+    return a != a || b != b ? a + b : r;                                      \
+  }                                                                           \
+
+IMPL_MINIMUM_MAXIMUM(float, f32)
+IMPL_MINIMUM_MAXIMUM(double, f64)
+
+// with reciprocal math, this can be lowered to a reciprocal estimate and a
+// refinement step rather than a full division, on targets which have one
+__attribute__((always_inline))
+float recip_f32(float a) {
+  return 1 / a;
+}
+
+__attribute__((always_inline))
+double recip_f64(double a) {
+  return 1 / a;
+}
+
 __attribute__((always_inline))
 float clamp_f32(float a, float min, float max) {
   // under -O3 these comparisons are compiled to selects which, unlike
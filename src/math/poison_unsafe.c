@@ -20,7 +20,45 @@
  * [1]: https://llvm.org/docs/LangRef.html#poison-values
  */
 
-#include <math.h>
+#include <stdint.h>
+
+#include "approx.h"
+#include "libm.h"
+
+/*
+ * With the `approx-math` feature, the functions which have approximations in
+ * approx.h call those instead of libm
+ */
+#ifdef FAST_FP_APPROX_MATH
+#define MATH(FN) approx_ ## FN
+#else
+#define MATH(FN) FN
+#endif
+
+/*
+ * exp10 isn't standard C, but glibc has it as an extension, and Apple's libm
+ * under a reserved name. Elsewhere (and with the `approx-math` feature) it's
+ * computed with pow instead
+ */
+#if defined(__GLIBC__) && !defined(FAST_FP_APPROX_MATH)
+double exp10(double);
+float exp10f(float);
+#define EXP10(FN) FN
+#elif defined(__APPLE__) && !defined(FAST_FP_APPROX_MATH)
+#define EXP10(FN) __ ## FN
+#else
+#define EXP10(FN) FN ## _pow
+#endif
+
+__attribute__((always_inline))
+static inline double exp10_pow(double a) {
+  return MATH(pow)(10, a);
+}
+
+__attribute__((always_inline))
+static inline float exp10f_pow(float a) {
+  return MATH(powf)(10, a);
+}
 
 #define IMPL_UNARY_FUNCTION(C_TYPE, RUST_TYPE, FN_NAME, FN_IMPL) \
   __attribute__((always_inline))                                 \
@@ -32,6 +70,18 @@
   IMPL_UNARY_FUNCTION(double, f64, DOUBLE_FN, DOUBLE_FN)     \
   IMPL_UNARY_FUNCTION(float, f32, DOUBLE_FN, DOUBLE_FN ## f) \
 
+#define IMPL_UNARY_APPROX(DOUBLE_FN)                                      \
+  IMPL_UNARY_FUNCTION(double, f64, DOUBLE_FN, MATH(DOUBLE_FN))            \
+  IMPL_UNARY_FUNCTION(float, f32, DOUBLE_FN, MATH(DOUBLE_FN ## f))        \
+
+/*
+ * The approximations are also always available under their own names, for
+ * the `Accuracy::Fastest` tier of the `*_with` methods
+ */
+#define IMPL_UNARY_POLY(DOUBLE_FN)                                                  \
+  IMPL_UNARY_FUNCTION(double, f64, DOUBLE_FN ## _approx, approx_ ## DOUBLE_FN)      \
+  IMPL_UNARY_FUNCTION(float, f32, DOUBLE_FN ## _approx, approx_ ## DOUBLE_FN ## f)  \
+
 #define IMPL_BINARY_FUNCTION(C_TYPE, RUST_TYPE, FN_NAME, FN_IMPL) \
   __attribute__((always_inline))                                  \
   C_TYPE FN_NAME ## _ ## RUST_TYPE(C_TYPE a, C_TYPE b) {          \
@@ -50,12 +100,15 @@ IMPL_UNARY(atan)
 IMPL_BINARY(atan2)
 IMPL_UNARY(atanh)
 IMPL_UNARY(cbrt)
-IMPL_UNARY(ceil)
-IMPL_UNARY(cos)
+IMPL_UNARY_APPROX(cos)
 IMPL_UNARY(cosh)
-IMPL_UNARY(exp)
-IMPL_UNARY(exp2)
-IMPL_UNARY(floor)
+IMPL_UNARY(erf)
+IMPL_UNARY(erfc)
+IMPL_UNARY_APPROX(exp)
+IMPL_UNARY_APPROX(exp2)
+
+IMPL_UNARY_FUNCTION(double, f64, gamma, tgamma)
+IMPL_UNARY_FUNCTION(float, f32, gamma, tgammaf)
 
 IMPL_UNARY_FUNCTION(double, f64, exp_m1, expm1)
 IMPL_UNARY_FUNCTION(float, f32, exp_m1, expm1f)
@@ -63,23 +116,157 @@ IMPL_UNARY_FUNCTION(float, f32, exp_m1, expm1f)
 IMPL_BINARY_FUNCTION(double, f64, rem, fmod)
 IMPL_BINARY_FUNCTION(float, f32, rem, fmodf)
 
-IMPL_UNARY_FUNCTION(double, f64, ln, log)
-IMPL_UNARY_FUNCTION(float, f32, ln, logf)
+/*
+ * Euclidean division and remainder, built on fmod like rem. The remainder is
+ * never negative, and the quotient is rounded to match it, as in rust's
+ * div_euclid and rem_euclid
+ */
+#define IMPL_EUCLID(C_TYPE, RUST_TYPE, S)                                     \
+  __attribute__((always_inline))                                              \
+  C_TYPE div_euclid_ ## RUST_TYPE(C_TYPE a, C_TYPE b) {                       \
+    C_TYPE q = trunc ## S(a / b);                                             \
+    if(fmod ## S(a, b) < 0) {                                                 \
+      return b > 0 ? q - 1 : q + 1;                                           \
+    }                                                                         \
+    return q;                                                                 \
+  }                                                                           \
+                                                                              \
+  __attribute__((always_inline))                                              \
+  C_TYPE rem_euclid_ ## RUST_TYPE(C_TYPE a, C_TYPE b) {                       \
+    C_TYPE r = fmod ## S(a, b);                                               \
+    return r < 0 ? r + fabs ## S(b) : r;                                      \
+  }                                                                           \
+
+IMPL_EUCLID(double, f64, )
+IMPL_EUCLID(float, f32, f)
+
+IMPL_UNARY_FUNCTION(double, f64, ln, MATH(log))
+IMPL_UNARY_FUNCTION(float, f32, ln, MATH(logf))
+
+/*
+ * The logarithm in an arbitrary base, as the natural logarithm multiplied by
+ * the reciprocal of the base's, which reciprocal math can lower to an estimate
+ */
+#define IMPL_LOG(C_TYPE, RUST_TYPE, S)                                        \
+  __attribute__((always_inline))                                              \
+  C_TYPE log_ ## RUST_TYPE(C_TYPE a, C_TYPE base) {                           \
+    return MATH(log ## S)(a) * (1 / MATH(log ## S)(base));                    \
+  }                                                                           \
+
+IMPL_LOG(double, f64, )
+IMPL_LOG(float, f32, f)
 
 IMPL_UNARY_FUNCTION(double, f64, ln_1p, log1p)
 IMPL_UNARY_FUNCTION(float, f32, ln_1p, log1pf)
 
-IMPL_UNARY(log2)
-IMPL_UNARY(log10)
+IMPL_UNARY_FUNCTION(double, f64, ln_gamma, lgamma)
+IMPL_UNARY_FUNCTION(float, f32, ln_gamma, lgammaf)
 
-IMPL_BINARY_FUNCTION(double, f64, powf, pow)
-IMPL_BINARY_FUNCTION(float, f32, powf, powf)
+IMPL_UNARY_APPROX(log2)
+IMPL_UNARY_APPROX(log10)
 
-IMPL_UNARY(round)
-IMPL_UNARY(sin)
+/*
+ * The common exponents are computed with arithmetic and sqrt instead of a pow
+ * call. The exponent is frozen, so it's safe to branch on, and the branches
+ * fold away when it's a constant after inlining. sqrt differs from pow for the
+ * exponent 0.5 at -0 and -inf, so only positive bases take that shortcut
+ */
+#define IMPL_POWF(C_TYPE, RUST_TYPE, S)                                       \
+  __attribute__((always_inline))                                              \
+  C_TYPE powf_ ## RUST_TYPE(C_TYPE a, C_TYPE b) {                             \
+    if(b == (C_TYPE)0.5 && a > 0) {                                           \
+      return sqrt ## S(a);                                                    \
+    } else if(b == 1) {                                                       \
+      return a;                                                               \
+    } else if(b == 2) {                                                       \
+      return a * a;                                                           \
+    } else if(b == 3) {                                                       \
+      return a * a * a;                                                       \
+    } else if(b == -1) {                                                      \
+      return 1 / a;                                                           \
+    }                                                                         \
+    return MATH(pow ## S)(a, b);                                              \
+  }                                                                           \
+
+IMPL_POWF(double, f64, )
+IMPL_POWF(float, f32, f)
+
+IMPL_UNARY_APPROX(sin)
 IMPL_UNARY(sinh)
 IMPL_UNARY(sqrt)
-IMPL_UNARY(tan)
+IMPL_UNARY_APPROX(tan)
 IMPL_UNARY(tanh)
-IMPL_UNARY(trunc)
 
+IMPL_UNARY_POLY(cos)
+IMPL_UNARY_POLY(exp2)
+IMPL_UNARY_POLY(log2)
+IMPL_UNARY_POLY(log10)
+IMPL_UNARY_POLY(sin)
+IMPL_UNARY_POLY(tan)
+
+IMPL_BINARY_FUNCTION(double, f64, powf_approx, approx_pow)
+IMPL_BINARY_FUNCTION(float, f32, powf_approx, approx_powf)
+
+IMPL_UNARY_FUNCTION(double, f64, exp10, EXP10(exp10))
+IMPL_UNARY_FUNCTION(float, f32, exp10, EXP10(exp10f))
+
+/*
+ * The sine and cosine of the same argument. Computing both in one function
+ * lets clang combine the libm calls into a single sincos where the platform
+ * has one, and the approximations share their range reduction
+ */
+#define IMPL_SINCOS(C_TYPE, RUST_TYPE, S)                                     \
+  __attribute__((always_inline))                                              \
+  void sincos_ ## RUST_TYPE(C_TYPE a, C_TYPE *sin_out, C_TYPE *cos_out) {     \
+    *sin_out = MATH(sin ## S)(a);                                             \
+    *cos_out = MATH(cos ## S)(a);                                             \
+  }                                                                           \
+
+IMPL_SINCOS(double, f64, )
+IMPL_SINCOS(float, f32, f)
+
+/*
+ * sin(pi * x) and cos(pi * x), with the argument reduced before it's
+ * multiplied by pi: x = r + n / 2 with |r| <= 1/4 is exact, so the results
+ * don't suffer from the rounding of pi * x, and are exact at the integers and
+ * half-integers. Reassociation must be disabled for the reduction to stay
+ * exact. Values too large for the integer conversion are even integers
+ */
+#define IMPL_SINCOSPI(C_TYPE, RUST_TYPE, S)                                   \
+  __attribute__((always_inline))                                              \
+  static inline void sincospi ## S(C_TYPE a, C_TYPE *sin_out,                 \
+                                   C_TYPE *cos_out) {                         \
+    APPROX_NO_REASSOC                                                         \
+    if(!(fabs ## S(a) < (C_TYPE)0x1p62)) {                                    \
+      *sin_out = __builtin_isfinite(a) ? 0 : a - a;                           \
+      *cos_out = __builtin_isfinite(a) ? 1 : a - a;                           \
+      return;                                                                 \
+    }                                                                         \
+    C_TYPE n = round ## S(2 * a);                                             \
+    C_TYPE r = (a - n * (C_TYPE)0.5) * (C_TYPE)3.14159265358979323846;        \
+    C_TYPE s = MATH(sin ## S)(r);                                             \
+    C_TYPE c = MATH(cos ## S)(r);                                             \
+    switch((int64_t)n & 3) {                                                  \
+    case 0: *sin_out = s; *cos_out = c; break;                                \
+    case 1: *sin_out = c; *cos_out = -s; break;                               \
+    case 2: *sin_out = -s; *cos_out = -c; break;                              \
+    default: *sin_out = -c; *cos_out = s; break;                              \
+    }                                                                         \
+  }                                                                           \
+                                                                              \
+  __attribute__((always_inline))                                              \
+  C_TYPE sinpi_ ## RUST_TYPE(C_TYPE a) {                                      \
+    C_TYPE s, c;                                                              \
+    sincospi ## S(a, &s, &c);                                                 \
+    return s;                                                                 \
+  }                                                                           \
+                                                                              \
+  __attribute__((always_inline))                                              \
+  C_TYPE cospi_ ## RUST_TYPE(C_TYPE a) {                                      \
+    C_TYPE s, c;                                                              \
+    sincospi ## S(a, &s, &c);                                                 \
+    return c;                                                                 \
+  }                                                                           \
+
+IMPL_SINCOSPI(double, f64, )
+IMPL_SINCOSPI(float, f32, f)
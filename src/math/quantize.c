@@ -0,0 +1,30 @@
+/*
+ * The poison safe half of the quantization kernels, from the `quantize`
+ * module. They are compiled into the poison_safe library, with the same flags
+ * and under the same constraints: every operation here can accept poison
+ * values without triggering UB.
+ *
+ * Dequantizing only converts integers to floats, so it's here. Quantizing
+ * converts floats to integers, which is UB for poison and out of range values,
+ * and is in quantize_unsafe.c.
+ */
+
+#include <stddef.h>
+#include <stdint.h>
+
+#define IMPL_DEQUANTIZE(C_TYPE, RUST_TYPE, INT_TYPE, INT_NAME)                \
+  __attribute__((always_inline))                                              \
+  void dequantize_ ## INT_NAME ## _ ## RUST_TYPE(const INT_TYPE *restrict q,  \
+                                                 C_TYPE *restrict out,        \
+                                                 size_t len,                  \
+                                                 C_TYPE scale,                \
+                                                 C_TYPE zero_point) {         \
+    for(size_t i = 0; i < len; i++) {                                         \
+      out[i] = ((C_TYPE)q[i] - zero_point) * scale;                           \
+    }                                                                         \
+  }                                                                           \
+
+IMPL_DEQUANTIZE(float, f32, int8_t, i8)
+IMPL_DEQUANTIZE(float, f32, uint8_t, u8)
+IMPL_DEQUANTIZE(double, f64, int8_t, i8)
+IMPL_DEQUANTIZE(double, f64, uint8_t, u8)
@@ -0,0 +1,34 @@
+/*
+ * The poison unsafe half of the quantization kernels, from the `quantize`
+ * module. These are compiled into the poison_unsafe library, with the same
+ * flags and under the same constraints: arguments must be frozen before being
+ * passed to these functions.
+ *
+ * The scaled values are clamped to the integer range before they're
+ * converted, so the conversion is defined for every input. Without
+ * finite-math-only, fmax and fmin return the bound for a NaN, which a frozen
+ * poison value may turn out to be.
+ */
+
+#include <stddef.h>
+#include <stdint.h>
+
+#include "libm.h"
+
+#define IMPL_QUANTIZE(C_TYPE, RUST_TYPE, S, INT_TYPE, INT_NAME, MIN, MAX)      \
+  __attribute__((always_inline))                                              \
+  void quantize_ ## INT_NAME ## _ ## RUST_TYPE(const C_TYPE *restrict x,      \
+                                               INT_TYPE *restrict out,        \
+                                               size_t len,                    \
+                                               C_TYPE inv_scale,              \
+                                               C_TYPE zero_point) {           \
+    for(size_t i = 0; i < len; i++) {                                         \
+      C_TYPE q = round ## S(x[i] * inv_scale) + zero_point;                   \
+      out[i] = (INT_TYPE)fmin ## S(fmax ## S(q, MIN), MAX);                   \
+    }                                                                         \
+  }                                                                           \
+
+IMPL_QUANTIZE(float, f32, f, int8_t, i8, -128, 127)
+IMPL_QUANTIZE(float, f32, f, uint8_t, u8, 0, 255)
+IMPL_QUANTIZE(double, f64, , int8_t, i8, -128, 127)
+IMPL_QUANTIZE(double, f64, , uint8_t, u8, 0, 255)
@@ -0,0 +1,20 @@
+/*
+ * The functions in this file back the `*_reassoc` methods of the fast types.
+ * They are compiled with reassociation enabled, and only the other flags clang
+ * requires for reassociation to take effect (`-fno-signed-zeros` and
+ * `-fno-trapping-math`). None of these flags produce poison values, but the
+ * fast types may already carry poison, so arguments are passed through
+ * unfrozen like the poison_safe library.
+ */
+
+#define IMPL_BINARY_OPERATION(C_TYPE, RUST_TYPE, FN_NAME, OP) \
+  __attribute__((always_inline))                              \
+  C_TYPE FN_NAME ## _ ## RUST_TYPE(C_TYPE a, C_TYPE b) {      \
+    return a OP b;                                            \
+  }                                                           \
+
+IMPL_BINARY_OPERATION(float, f32, add_reassoc, +)
+IMPL_BINARY_OPERATION(double, f64, add_reassoc, +)
+
+IMPL_BINARY_OPERATION(float, f32, mul_reassoc, *)
+IMPL_BINARY_OPERATION(double, f64, mul_reassoc, *)
@@ -0,0 +1,16 @@
+/*
+ * The functions in this file back the `*_reciprocal` methods of the fast
+ * types. They are compiled with only `-freciprocal-math`, which allows
+ * division to be replaced by multiplication with a reciprocal. This flag
+ * doesn't produce poison values, but the fast types may already carry poison,
+ * so arguments are passed through unfrozen like the poison_safe library.
+ */
+
+#define IMPL_BINARY_OPERATION(C_TYPE, RUST_TYPE, FN_NAME, OP) \
+  __attribute__((always_inline))                              \
+  C_TYPE FN_NAME ## _ ## RUST_TYPE(C_TYPE a, C_TYPE b) {      \
+    return a OP b;                                            \
+  }                                                           \
+
+IMPL_BINARY_OPERATION(float, f32, div_reciprocal, /)
+IMPL_BINARY_OPERATION(double, f64, div_reciprocal, /)
@@ -0,0 +1,90 @@
+/*
+ * The functions in this file compute reciprocal square roots from the
+ * hardware estimate instructions, for the `rsqrt` and `rsqrt_refined`
+ * methods. They're compiled into the poison_safe library: the estimates and
+ * the Newton-Raphson steps only perform arithmetic and bit manipulation on
+ * the values, and never branch on them.
+ *
+ * The estimates are rsqrtss on x86 (a relative error of at most 1.5 * 2^-12)
+ * and frsqrte on aarch64 (about 2^-8). Other targets start from the integer
+ * approximation of the exponent bits instead, which is only accurate to
+ * about 3.5%.
+ */
+
+#include <stdint.h>
+
+#if defined(__SSE__)
+#include <xmmintrin.h>
+#elif defined(__aarch64__) && defined(__ARM_NEON)
+#include <arm_neon.h>
+#endif
+
+__attribute__((always_inline))
+static inline float rsqrt_estimate_f32(float a) {
+#if defined(__SSE__)
+  return _mm_cvtss_f32(_mm_rsqrt_ss(_mm_set_ss(a)));
+#elif defined(__aarch64__) && defined(__ARM_NEON)
+  return vrsqrtes_f32(a);
+#else
+  uint32_t bits;
+  __builtin_memcpy(&bits, &a, sizeof(bits));
+  bits = 0x5f3759df - (bits >> 1);
+  float estimate;
+  __builtin_memcpy(&estimate, &bits, sizeof(estimate));
+  return estimate;
+#endif
+}
+
+__attribute__((always_inline))
+static inline double rsqrt_estimate_f64(double a) {
+#if defined(__SSE__)
+  /*
+   * x86 has no double precision estimate before AVX-512, so the value is
+   * scaled by an even power of two into [1, 4), where the float estimate
+   * applies, and the estimate is scaled back by the square root of that power
+   */
+  uint64_t bits;
+  __builtin_memcpy(&bits, &a, sizeof(bits));
+  int64_t exponent = (int64_t)((bits >> 52) & 0x7ff) - 1023;
+  int64_t even = exponent & ~(int64_t)1;
+  bits -= (uint64_t)even << 52;
+  double scaled;
+  __builtin_memcpy(&scaled, &bits, sizeof(scaled));
+  uint64_t scale_bits = (uint64_t)(1023 - even / 2) << 52;
+  double scale;
+  __builtin_memcpy(&scale, &scale_bits, sizeof(scale));
+  return (double)rsqrt_estimate_f32((float)scaled) * scale;
+#elif defined(__aarch64__) && defined(__ARM_NEON)
+  return vrsqrted_f64(a);
+#else
+  uint64_t bits;
+  __builtin_memcpy(&bits, &a, sizeof(bits));
+  bits = 0x5fe6eb50c7b537a9 - (bits >> 1);
+  double estimate;
+  __builtin_memcpy(&estimate, &bits, sizeof(estimate));
+  return estimate;
+#endif
+}
+
+/*
+ * Each Newton-Raphson step roughly doubles the number of correct bits. The
+ * number of steps is an ordinary integer, never poison, so it can be looped on
+ */
+#define IMPL_RSQRT(C_TYPE, RUST_TYPE)                                         \
+  __attribute__((always_inline))                                              \
+  C_TYPE rsqrt_ ## RUST_TYPE(C_TYPE a) {                                      \
+    return rsqrt_estimate_ ## RUST_TYPE(a);                                   \
+  }                                                                           \
+                                                                              \
+  __attribute__((always_inline))                                              \
+  C_TYPE rsqrt_refined_ ## RUST_TYPE(C_TYPE a, uint32_t steps) {              \
+    C_TYPE y = rsqrt_estimate_ ## RUST_TYPE(a);                               \
+    C_TYPE half = a * (C_TYPE)0.5;                                            \
+    for(uint32_t i = 0; i < steps; i++) {                                     \
+      y = y * ((C_TYPE)1.5 - half * y * y);                                   \
+    }                                                                         \
+    return y;                                                                 \
+  }                                                                           \
+
+IMPL_RSQRT(float, f32)
+IMPL_RSQRT(double, f64)
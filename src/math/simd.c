@@ -0,0 +1,58 @@
+/*
+ * The functions in this file implement the lane-wise and horizontal operations
+ * of the fast SIMD types. They are compiled into the poison_safe library, with
+ * the same flags and under the same constraints: every operation here can
+ * accept poison lanes without triggering UB.
+ *
+ * Arrays can't be passed by value over FFI, so vectors are passed by pointer
+ * instead. Once inlined with LTO, the loops below are unrolled into vector
+ * instructions and the memory round-trip is optimized away.
+ */
+
+#include "libm.h"
+
+#define IMPL_SIMD_BINARY(C_TYPE, SIMD_TYPE, LANES, FN_NAME, EXPR)      \
+  __attribute__((always_inline))                                       \
+  void FN_NAME ## _ ## SIMD_TYPE(C_TYPE *restrict out,                 \
+                                 const C_TYPE *restrict a,             \
+                                 const C_TYPE *restrict b) {           \
+    for(int i = 0; i < LANES; i++) {                                   \
+      out[i] = EXPR;                                                   \
+    }                                                                  \
+  }                                                                    \
+
+#define IMPL_SIMD_UNARY(C_TYPE, SIMD_TYPE, LANES, FN_NAME, EXPR)       \
+  __attribute__((always_inline))                                       \
+  void FN_NAME ## _ ## SIMD_TYPE(C_TYPE *restrict out,                 \
+                                 const C_TYPE *restrict a) {           \
+    for(int i = 0; i < LANES; i++) {                                   \
+      out[i] = EXPR;                                                   \
+    }                                                                  \
+  }                                                                    \
+
+#define IMPL_SIMD_REDUCE(C_TYPE, SIMD_TYPE, LANES, FN_NAME, ACC_EXPR)  \
+  __attribute__((always_inline))                                       \
+  C_TYPE FN_NAME ## _ ## SIMD_TYPE(const C_TYPE *restrict a) {         \
+    C_TYPE acc = a[0];                                                 \
+    for(int i = 1; i < LANES; i++) {                                   \
+      acc = ACC_EXPR;                                                  \
+    }                                                                  \
+    return acc;                                                        \
+  }                                                                    \
+
+#define IMPL_SIMD(C_TYPE, SIMD_TYPE, LANES, ABS_FN, MIN_FN, MAX_FN)          \
+  IMPL_SIMD_BINARY(C_TYPE, SIMD_TYPE, LANES, add, a[i] + b[i])               \
+  IMPL_SIMD_BINARY(C_TYPE, SIMD_TYPE, LANES, sub, a[i] - b[i])               \
+  IMPL_SIMD_BINARY(C_TYPE, SIMD_TYPE, LANES, mul, a[i] * b[i])               \
+  IMPL_SIMD_BINARY(C_TYPE, SIMD_TYPE, LANES, div, a[i] / b[i])               \
+  IMPL_SIMD_BINARY(C_TYPE, SIMD_TYPE, LANES, min, MIN_FN(a[i], b[i]))        \
+  IMPL_SIMD_BINARY(C_TYPE, SIMD_TYPE, LANES, max, MAX_FN(a[i], b[i]))        \
+  IMPL_SIMD_UNARY(C_TYPE, SIMD_TYPE, LANES, neg, -a[i])                      \
+  IMPL_SIMD_UNARY(C_TYPE, SIMD_TYPE, LANES, abs, ABS_FN(a[i]))               \
+  IMPL_SIMD_REDUCE(C_TYPE, SIMD_TYPE, LANES, reduce_sum, acc + a[i])         \
+  IMPL_SIMD_REDUCE(C_TYPE, SIMD_TYPE, LANES, reduce_min, MIN_FN(acc, a[i]))  \
+  IMPL_SIMD_REDUCE(C_TYPE, SIMD_TYPE, LANES, reduce_max, MAX_FN(acc, a[i]))  \
+
+IMPL_SIMD(float, f32x4, 4, fabsf, fminf, fmaxf)
+IMPL_SIMD(float, f32x8, 8, fabsf, fminf, fmaxf)
+IMPL_SIMD(double, f64x4, 4, fabs, fmin, fmax)
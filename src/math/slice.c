@@ -0,0 +1,236 @@
+/*
+ * The functions in this file implement the kernels over whole slices, from the
+ * `slice` module. They are compiled into the poison_safe library, with the
+ * same flags and under the same constraints: every operation here can accept
+ * poison values without triggering UB.
+ *
+ * Processing whole buffers in C lets the fast-math flags apply to the entire
+ * loop, so accumulations can be reassociated and vectorized. Folding over a
+ * slice in rust only gets the flags one operation at a time.
+ */
+
+#include <stddef.h>
+#include <stdint.h>
+
+#include "kernel.h"
+#include "libm.h"
+
+#define IMPL_SLICE_SUM(C_TYPE, RUST_TYPE)                                  \
+  __attribute__((always_inline))                                           \
+  C_TYPE KERNEL_NAME(sum_slice_ ## RUST_TYPE)(const C_TYPE *restrict a,    \
+                                              size_t len) {                \
+    C_TYPE acc = 0;                                                        \
+    for(size_t i = 0; i < len; i++) {                                      \
+      acc += a[i];                                                         \
+    }                                                                      \
+    return acc;                                                            \
+  }                                                                        \
+
+#define IMPL_SLICE_DOT(C_TYPE, RUST_TYPE)                                  \
+  __attribute__((always_inline))                                           \
+  C_TYPE KERNEL_NAME(dot_slice_ ## RUST_TYPE)(const C_TYPE *restrict a,    \
+                                              const C_TYPE *restrict b,    \
+                                              size_t len) {                \
+    C_TYPE acc = 0;                                                        \
+    for(size_t i = 0; i < len; i++) {                                      \
+      acc += a[i] * b[i];                                                  \
+    }                                                                      \
+    return acc;                                                            \
+  }                                                                        \
+
+/*
+ * Pairwise summation splits the slice in halves until the blocks are small
+ * enough for the plain loop, so the rounding error grows with the log of the
+ * length instead of the length itself. Reassociation and vectorization still
+ * apply within each block, but not across the recursion.
+ */
+#define PAIRWISE_BLOCK 128
+
+#define IMPL_SLICE_SUM_PAIRWISE(C_TYPE, RUST_TYPE)                         \
+  static C_TYPE sum_pairwise_ ## RUST_TYPE(const C_TYPE *restrict a,       \
+                                           size_t len) {                   \
+    if(len <= PAIRWISE_BLOCK) {                                            \
+      C_TYPE acc = 0;                                                      \
+      for(size_t i = 0; i < len; i++) {                                    \
+        acc += a[i];                                                       \
+      }                                                                    \
+      return acc;                                                          \
+    }                                                                      \
+    size_t half = len / 2;                                                 \
+    return sum_pairwise_ ## RUST_TYPE(a, half)                             \
+           + sum_pairwise_ ## RUST_TYPE(a + half, len - half);             \
+  }                                                                        \
+                                                                           \
+  __attribute__((always_inline))                                           \
+  C_TYPE KERNEL_NAME(sum_pairwise_slice_ ## RUST_TYPE)(                    \
+      const C_TYPE *restrict a,                                            \
+      size_t len) {                                                        \
+    return sum_pairwise_ ## RUST_TYPE(a, len);                             \
+  }                                                                        \
+
+/* folds require a non-empty slice, which is checked on the rust side */
+#define IMPL_SLICE_FOLD(C_TYPE, RUST_TYPE, FN_NAME, FOLD_FN)               \
+  __attribute__((always_inline))                                           \
+  C_TYPE KERNEL_NAME(FN_NAME ## _slice_ ## RUST_TYPE)(                     \
+      const C_TYPE *restrict a,                                            \
+      size_t len) {                                                        \
+    C_TYPE acc = a[0];                                                     \
+    for(size_t i = 1; i < len; i++) {                                      \
+      acc = FOLD_FN(acc, a[i]);                                            \
+    }                                                                      \
+    return acc;                                                            \
+  }                                                                        \
+
+/*
+ * The index kernels track the best value and its index in several independent
+ * lanes, which compile to vector selects, and merge the lanes at the end.
+ * Each lane keeps the first of equal values, and the merge prefers the lowest
+ * index, so the first occurrence wins. The comparisons are combined with
+ * bitwise operators rather than short-circuiting ones, which would branch.
+ *
+ * The index is derived from comparisons of possibly poison values, so the rust
+ * side freezes it and bounds it to the slice before using it. These require a
+ * non-empty slice, which is checked on the rust side.
+ */
+#define ARG_LANES 8
+
+#define IMPL_SLICE_ARG(C_TYPE, RUST_TYPE, FN_NAME, CMP)                    \
+  __attribute__((always_inline))                                           \
+  uint64_t KERNEL_NAME(FN_NAME ## _slice_ ## RUST_TYPE)(                   \
+      const C_TYPE *restrict a,                                            \
+      size_t len) {                                                        \
+    C_TYPE best[ARG_LANES];                                                \
+    uint64_t index[ARG_LANES];                                             \
+    for(size_t l = 0; l < ARG_LANES; l++) {                                \
+      best[l] = a[0];                                                      \
+      index[l] = 0;                                                        \
+    }                                                                      \
+    size_t i = 0;                                                          \
+    for(; i + ARG_LANES <= len; i += ARG_LANES) {                          \
+      for(size_t l = 0; l < ARG_LANES; l++) {                              \
+        int better = a[i + l] CMP best[l];                                 \
+        best[l] = better ? a[i + l] : best[l];                             \
+        index[l] = better ? i + l : index[l];                              \
+      }                                                                    \
+    }                                                                      \
+    for(; i < len; i++) {                                                  \
+      int better = a[i] CMP best[0];                                       \
+      best[0] = better ? a[i] : best[0];                                   \
+      index[0] = better ? i : index[0];                                    \
+    }                                                                      \
+    for(size_t l = 1; l < ARG_LANES; l++) {                                \
+      int better = (best[l] CMP best[0])                                   \
+                   | ((best[l] == best[0]) & (index[l] < index[0]));       \
+      best[0] = better ? best[l] : best[0];                                \
+      index[0] = better ? index[l] : index[0];                             \
+    }                                                                      \
+    return index[0];                                                       \
+  }                                                                        \
+
+/*
+ * The strided kernels read every `stride`th element, for values like the
+ * columns of a row-major matrix or a channel of interleaved audio. `len` is
+ * the number of elements read, not the length of the underlying buffer.
+ */
+#define IMPL_STRIDED_SUM(C_TYPE, RUST_TYPE)                                \
+  __attribute__((always_inline))                                           \
+  C_TYPE KERNEL_NAME(sum_strided_ ## RUST_TYPE)(const C_TYPE *restrict a,  \
+                                                size_t stride,             \
+                                                size_t len) {              \
+    C_TYPE acc = 0;                                                        \
+    for(size_t i = 0; i < len; i++) {                                      \
+      acc += a[i * stride];                                                \
+    }                                                                      \
+    return acc;                                                            \
+  }                                                                        \
+
+#define IMPL_STRIDED_DOT(C_TYPE, RUST_TYPE)                                \
+  __attribute__((always_inline))                                           \
+  C_TYPE KERNEL_NAME(dot_strided_ ## RUST_TYPE)(const C_TYPE *restrict a,  \
+                                                size_t a_stride,           \
+                                                const C_TYPE *restrict b,  \
+                                                size_t b_stride,           \
+                                                size_t len) {              \
+    C_TYPE acc = 0;                                                        \
+    for(size_t i = 0; i < len; i++) {                                      \
+      acc += a[i * a_stride] * b[i * b_stride];                            \
+    }                                                                      \
+    return acc;                                                            \
+  }                                                                        \
+
+#define IMPL_STRIDED_FOLD(C_TYPE, RUST_TYPE, FN_NAME, FOLD_FN)             \
+  __attribute__((always_inline))                                           \
+  C_TYPE KERNEL_NAME(FN_NAME ## _strided_ ## RUST_TYPE)(                   \
+      const C_TYPE *restrict a,                                            \
+      size_t stride,                                                       \
+      size_t len) {                                                        \
+    C_TYPE acc = a[0];                                                     \
+    for(size_t i = 1; i < len; i++) {                                      \
+      acc = FOLD_FN(acc, a[i * stride]);                                   \
+    }                                                                      \
+    return acc;                                                            \
+  }                                                                        \
+
+/* contraction is enabled for this library, so this compiles to fused operations */
+#define IMPL_SLICE_FMA(C_TYPE, RUST_TYPE)                                  \
+  __attribute__((always_inline))                                           \
+  void KERNEL_NAME(fma_slice_ ## RUST_TYPE)(C_TYPE *restrict out,          \
+                                            const C_TYPE *restrict a,      \
+                                            const C_TYPE *restrict b,      \
+                                            const C_TYPE *restrict c,      \
+                                            size_t len) {                  \
+    for(size_t i = 0; i < len; i++) {                                      \
+      out[i] = a[i] * b[i] + c[i];                                         \
+    }                                                                      \
+  }                                                                        \
+
+/* the trailing arguments are any extra parameters used by the expression */
+#define IMPL_SLICE_IN_PLACE(C_TYPE, RUST_TYPE, FN_NAME, EXPR, ...)         \
+  __attribute__((always_inline))                                           \
+  void KERNEL_NAME(FN_NAME ## _slice_ ## RUST_TYPE)(                       \
+      C_TYPE *restrict a,                                                  \
+      size_t len, ##__VA_ARGS__) {                                         \
+    for(size_t i = 0; i < len; i++) {                                      \
+      a[i] = EXPR;                                                         \
+    }                                                                      \
+  }                                                                        \
+
+/*
+ * The reductions, folds and clamp are the hottest kernels, and on aarch64
+ * they're replaced by the NEON implementations in slice_neon.h. Every other
+ * target uses the generic loops.
+ *
+ * under -O3 the clamp comparisons are compiled to selects which, unlike
+ * branches, propagate poison without UB
+ */
+#if defined(__aarch64__) && defined(__ARM_NEON)
+#include "slice_neon.h"
+#define IMPL_SLICE_HOT(C_TYPE, RUST_TYPE, MIN_FN, MAX_FN)
+#else
+#define IMPL_SLICE_HOT(C_TYPE, RUST_TYPE, MIN_FN, MAX_FN)                     \
+  IMPL_SLICE_SUM(C_TYPE, RUST_TYPE)                                           \
+  IMPL_SLICE_DOT(C_TYPE, RUST_TYPE)                                           \
+  IMPL_SLICE_FOLD(C_TYPE, RUST_TYPE, min, MIN_FN)                             \
+  IMPL_SLICE_FOLD(C_TYPE, RUST_TYPE, max, MAX_FN)                             \
+  IMPL_SLICE_IN_PLACE(C_TYPE, RUST_TYPE, clamp,                               \
+                      a[i] < min ? min : (a[i] > max ? max : a[i]),           \
+                      C_TYPE min, C_TYPE max)                                 \
+
+#endif
+
+#define IMPL_SLICE(C_TYPE, RUST_TYPE, ABS_FN, MIN_FN, MAX_FN)                 \
+  IMPL_SLICE_HOT(C_TYPE, RUST_TYPE, MIN_FN, MAX_FN)                           \
+  IMPL_SLICE_SUM_PAIRWISE(C_TYPE, RUST_TYPE)                                  \
+  IMPL_SLICE_ARG(C_TYPE, RUST_TYPE, argmin, <)                                \
+  IMPL_SLICE_ARG(C_TYPE, RUST_TYPE, argmax, >)                                \
+  IMPL_SLICE_FMA(C_TYPE, RUST_TYPE)                                           \
+  IMPL_STRIDED_SUM(C_TYPE, RUST_TYPE)                                         \
+  IMPL_STRIDED_DOT(C_TYPE, RUST_TYPE)                                         \
+  IMPL_STRIDED_FOLD(C_TYPE, RUST_TYPE, min, MIN_FN)                           \
+  IMPL_STRIDED_FOLD(C_TYPE, RUST_TYPE, max, MAX_FN)                           \
+  IMPL_SLICE_IN_PLACE(C_TYPE, RUST_TYPE, scale, a[i] * k, C_TYPE k)           \
+  IMPL_SLICE_IN_PLACE(C_TYPE, RUST_TYPE, offset, a[i] + k, C_TYPE k)          \
+  IMPL_SLICE_IN_PLACE(C_TYPE, RUST_TYPE, abs, ABS_FN(a[i]))                   \
+
+IMPL_SLICE(float, f32, fabsf, fminf, fmaxf)
+IMPL_SLICE(double, f64, fabs, fmin, fmax)
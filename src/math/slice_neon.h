@@ -0,0 +1,130 @@
+/*
+ * NEON implementations of the hottest slice kernels, used in place of the
+ * generic loops in slice.c when compiling for aarch64. Clang vectorizes the
+ * generic reductions poorly without a target-specific cost model, and can't
+ * always see through the libm calls in the folds, so these are written with
+ * intrinsics directly.
+ *
+ * Like the rest of the poison_safe library, these only perform arithmetic on
+ * the values and never branch on them, so poison lanes can't trigger UB. The
+ * min/max intrinsics are the `nm` variants, which match fmin/fmax.
+ */
+
+#include <arm_neon.h>
+#include <stddef.h>
+
+#include "kernel.h"
+
+/*
+ * VEC_TYPE is the NEON register type holding LANES values of C_TYPE, and
+ * SUFFIX is the element suffix of its intrinsics (e.g. `vaddq_f32`)
+ */
+#define IMPL_SLICE_NEON(C_TYPE, RUST_TYPE, VEC_TYPE, LANES, SUFFIX,            \
+                        MIN_FN, MAX_FN)                                        \
+  /* four accumulators hide the latency of the vector adds */                  \
+  __attribute__((always_inline))                                               \
+  C_TYPE KERNEL_NAME(sum_slice_ ## RUST_TYPE)(const C_TYPE *restrict a,        \
+                                              size_t len) {                    \
+    VEC_TYPE acc0 = vdupq_n_ ## SUFFIX(0);                                     \
+    VEC_TYPE acc1 = vdupq_n_ ## SUFFIX(0);                                     \
+    VEC_TYPE acc2 = vdupq_n_ ## SUFFIX(0);                                     \
+    VEC_TYPE acc3 = vdupq_n_ ## SUFFIX(0);                                     \
+    size_t i = 0;                                                              \
+    for(; i + 4 * LANES <= len; i += 4 * LANES) {                              \
+      acc0 = vaddq_ ## SUFFIX(acc0, vld1q_ ## SUFFIX(a + i));                  \
+      acc1 = vaddq_ ## SUFFIX(acc1, vld1q_ ## SUFFIX(a + i + LANES));          \
+      acc2 = vaddq_ ## SUFFIX(acc2, vld1q_ ## SUFFIX(a + i + 2 * LANES));      \
+      acc3 = vaddq_ ## SUFFIX(acc3, vld1q_ ## SUFFIX(a + i + 3 * LANES));      \
+    }                                                                          \
+    for(; i + LANES <= len; i += LANES) {                                      \
+      acc0 = vaddq_ ## SUFFIX(acc0, vld1q_ ## SUFFIX(a + i));                  \
+    }                                                                          \
+    acc0 = vaddq_ ## SUFFIX(vaddq_ ## SUFFIX(acc0, acc1),                      \
+                            vaddq_ ## SUFFIX(acc2, acc3));                     \
+    C_TYPE acc = vaddvq_ ## SUFFIX(acc0);                                      \
+    for(; i < len; i++) {                                                      \
+      acc += a[i];                                                             \
+    }                                                                          \
+    return acc;                                                                \
+  }                                                                            \
+                                                                               \
+  __attribute__((always_inline))                                               \
+  C_TYPE KERNEL_NAME(dot_slice_ ## RUST_TYPE)(const C_TYPE *restrict a,        \
+                                              const C_TYPE *restrict b,        \
+                                              size_t len) {                    \
+    VEC_TYPE acc0 = vdupq_n_ ## SUFFIX(0);                                     \
+    VEC_TYPE acc1 = vdupq_n_ ## SUFFIX(0);                                     \
+    VEC_TYPE acc2 = vdupq_n_ ## SUFFIX(0);                                     \
+    VEC_TYPE acc3 = vdupq_n_ ## SUFFIX(0);                                     \
+    size_t i = 0;                                                              \
+    for(; i + 4 * LANES <= len; i += 4 * LANES) {                              \
+      acc0 = vfmaq_ ## SUFFIX(acc0, vld1q_ ## SUFFIX(a + i),                   \
+                              vld1q_ ## SUFFIX(b + i));                        \
+      acc1 = vfmaq_ ## SUFFIX(acc1, vld1q_ ## SUFFIX(a + i + LANES),           \
+                              vld1q_ ## SUFFIX(b + i + LANES));                \
+      acc2 = vfmaq_ ## SUFFIX(acc2, vld1q_ ## SUFFIX(a + i + 2 * LANES),       \
+                              vld1q_ ## SUFFIX(b + i + 2 * LANES));            \
+      acc3 = vfmaq_ ## SUFFIX(acc3, vld1q_ ## SUFFIX(a + i + 3 * LANES),       \
+                              vld1q_ ## SUFFIX(b + i + 3 * LANES));            \
+    }                                                                          \
+    for(; i + LANES <= len; i += LANES) {                                      \
+      acc0 = vfmaq_ ## SUFFIX(acc0, vld1q_ ## SUFFIX(a + i),                   \
+                              vld1q_ ## SUFFIX(b + i));                        \
+    }                                                                          \
+    acc0 = vaddq_ ## SUFFIX(vaddq_ ## SUFFIX(acc0, acc1),                      \
+                            vaddq_ ## SUFFIX(acc2, acc3));                     \
+    C_TYPE acc = vaddvq_ ## SUFFIX(acc0);                                      \
+    for(; i < len; i++) {                                                      \
+      acc += a[i] * b[i];                                                      \
+    }                                                                          \
+    return acc;                                                                \
+  }                                                                            \
+                                                                               \
+  /* folds require a non-empty slice, which is checked on the rust side */     \
+  IMPL_SLICE_NEON_FOLD(C_TYPE, RUST_TYPE, VEC_TYPE, LANES, SUFFIX,             \
+                       min, vminnmq_, vminnmvq_, MIN_FN)                       \
+  IMPL_SLICE_NEON_FOLD(C_TYPE, RUST_TYPE, VEC_TYPE, LANES, SUFFIX,             \
+                       max, vmaxnmq_, vmaxnmvq_, MAX_FN)                       \
+                                                                               \
+  __attribute__((always_inline))                                               \
+  void KERNEL_NAME(clamp_slice_ ## RUST_TYPE)(C_TYPE *restrict a,              \
+                                              size_t len,                      \
+                                              C_TYPE min,                      \
+                                              C_TYPE max) {                    \
+    VEC_TYPE min_v = vdupq_n_ ## SUFFIX(min);                                  \
+    VEC_TYPE max_v = vdupq_n_ ## SUFFIX(max);                                  \
+    size_t i = 0;                                                              \
+    for(; i + LANES <= len; i += LANES) {                                      \
+      VEC_TYPE v = vld1q_ ## SUFFIX(a + i);                                    \
+      v = vminnmq_ ## SUFFIX(vmaxnmq_ ## SUFFIX(v, min_v), max_v);             \
+      vst1q_ ## SUFFIX(a + i, v);                                              \
+    }                                                                          \
+    for(; i < len; i++) {                                                      \
+      a[i] = MIN_FN(MAX_FN(a[i], min), max);                                   \
+    }                                                                          \
+  }                                                                            \
+
+/* VEC_FN and REDUCE_FN are the lane-wise and horizontal intrinsic prefixes */
+#define IMPL_SLICE_NEON_FOLD(C_TYPE, RUST_TYPE, VEC_TYPE, LANES, SUFFIX,       \
+                             FN_NAME, VEC_FN, REDUCE_FN, FOLD_FN)              \
+  __attribute__((always_inline))                                               \
+  C_TYPE KERNEL_NAME(FN_NAME ## _slice_ ## RUST_TYPE)(                         \
+      const C_TYPE *restrict a,                                                \
+      size_t len) {                                                            \
+    C_TYPE acc = a[0];                                                         \
+    size_t i = 1;                                                              \
+    if(len >= LANES) {                                                         \
+      VEC_TYPE acc_v = vld1q_ ## SUFFIX(a);                                    \
+      for(i = LANES; i + LANES <= len; i += LANES) {                           \
+        acc_v = VEC_FN ## SUFFIX(acc_v, vld1q_ ## SUFFIX(a + i));              \
+      }                                                                        \
+      acc = REDUCE_FN ## SUFFIX(acc_v);                                        \
+    }                                                                          \
+    for(; i < len; i++) {                                                      \
+      acc = FOLD_FN(acc, a[i]);                                                \
+    }                                                                          \
+    return acc;                                                                \
+  }                                                                            \
+
+IMPL_SLICE_NEON(float, f32, float32x4_t, 4, f32, fminf, fmaxf)
+IMPL_SLICE_NEON(double, f64, float64x2_t, 2, f64, fmin, fmax)
@@ -0,0 +1,167 @@
+//! The special functions for the scalar fallback, where they can't come from the platform libm.
+//!
+//! These are evaluated in `f64` by short series, continued fractions and the Lanczos
+//! approximation, and rounded for `f32`. In `f64` they're accurate to about `1e-14` relative to
+//! the result (absolutely for `ln_gamma` close to its zeros at 1 and 2, and for `erfc` where it's
+//! subnormal), short of a correctly rounded libm but well within the tolerances of fast builds.
+
+use core::f64::consts::{FRAC_2_SQRT_PI, PI};
+
+// the iteration limit of the continued fraction, which converges within about 200 terms from
+// where it's used
+const MAX_TERMS: usize = 500;
+
+// The Lanczos approximation with g = 7 and 9 terms
+const LANCZOS_G: f64 = 7.0;
+const LANCZOS_COEFFS: [f64; 9] = [
+    0.9999999999998099,
+    676.5203681218851,
+    -1259.1392167224028,
+    771.3234287776531,
+    -176.6150291621406,
+    12.507343278686905,
+    -0.13857109526572012,
+    9.984369578019572e-6,
+    1.5056327351493116e-7,
+];
+
+// exp(-x^2), with x split so that the square of its high half is exact, as the rounding of x^2
+// would otherwise be magnified by the exponential
+#[inline]
+fn exp_neg_square(x: f64) -> f64 {
+    let high = f64::from_bits(x.to_bits() & 0xffff_ffff_f800_0000);
+    (-high * high).exp() * ((high - x) * (high + x)).exp()
+}
+
+// erf(x) for x >= 0 by the series 2 / sqrt(pi) exp(-x^2) sum(2^n x^(2n + 1) / (2n + 1)!!), whose
+// terms are all positive. It's used below 2, where it takes at most about 30 terms
+#[inline]
+fn erf_series(x: f64) -> f64 {
+    let x2 = x * x;
+    let (mut term, mut sum) = (x, x);
+    let mut n = 1.0;
+    while term > sum * f64::EPSILON * 0.5 {
+        term *= 2.0 * x2 / (2.0 * n + 1.0);
+        sum += term;
+        n += 1.0;
+    }
+    FRAC_2_SQRT_PI * exp_neg_square(x) * sum
+}
+
+// erfc(x) for x >= 1 by the continued fraction
+// exp(-x^2) / sqrt(pi) / (x + (1/2) / (x + 1 / (x + (3/2) / (x + ...)))), with Lentz's method
+#[inline]
+fn erfc_fraction(x: f64) -> f64 {
+    let (mut fraction, mut c, mut d) = (x, x, 0.0);
+    for k in 1..=MAX_TERMS {
+        let a = k as f64 * 0.5;
+        d = 1.0 / (x + a * d);
+        c = x + a / c;
+        let delta = c * d;
+        fraction *= delta;
+        if (delta - 1.0).abs() <= f64::EPSILON {
+            break;
+        }
+    }
+    exp_neg_square(x) * (0.5 * FRAC_2_SQRT_PI) / fraction
+}
+
+#[inline]
+pub(super) fn erf_f64(x: f64) -> f64 {
+    let a = x.abs();
+    // beyond 6, erfc is below half an ulp of 1
+    let erf = if a >= 6.0 {
+        1.0
+    } else if a >= 2.0 {
+        1.0 - erfc_fraction(a)
+    } else {
+        erf_series(a)
+    };
+    erf.copysign(x)
+}
+
+#[inline]
+pub(super) fn erfc_f64(x: f64) -> f64 {
+    // beyond 27 erfc underflows, and 1 - erf only cancels for positive values
+    if x >= 27.0 {
+        0.0
+    } else if x >= 1.0 {
+        erfc_fraction(x)
+    } else {
+        1.0 - erf_f64(x)
+    }
+}
+
+// sin(pi * x), reduced exactly around the nearest integer so that it's accurate near the poles of
+// gamma
+#[inline]
+fn sin_pi(x: f64) -> f64 {
+    let n = x.round();
+    let sin = (PI * (x - n)).sin();
+    if n % 2.0 == 0.0 {
+        sin
+    } else {
+        -sin
+    }
+}
+
+// The Lanczos sum and the shifted argument t = x + g - 1/2, for x >= 1/2, where
+// gamma(x) = sqrt(2 pi) t^(x - 1/2) exp(-t) sum
+#[inline]
+fn lanczos(x: f64) -> (f64, f64) {
+    let z = x - 1.0;
+    let sum = LANCZOS_COEFFS[1..]
+        .iter()
+        .enumerate()
+        .fold(LANCZOS_COEFFS[0], |sum, (i, &c)| {
+            sum + c / (z + (i + 1) as f64)
+        });
+    (sum, z + LANCZOS_G + 0.5)
+}
+
+#[inline]
+pub(super) fn gamma_f64(x: f64) -> f64 {
+    if x == f64::INFINITY {
+        return x;
+    }
+    if x < 0.5 {
+        // the reflection formula, gamma(x) gamma(1 - x) = pi / sin(pi x)
+        return PI / (sin_pi(x) * gamma_f64(1.0 - x));
+    }
+
+    let (sum, t) = lanczos(x);
+    // the power is split in two, so that it doesn't overflow before exp(-t) brings it back
+    let power = t.powf(0.5 * (x - 0.5));
+    (2.0 * PI).sqrt() * sum * power * (power * (-t).exp())
+}
+
+#[inline]
+pub(super) fn ln_gamma_f64(x: f64) -> f64 {
+    if x.is_infinite() {
+        return f64::INFINITY;
+    }
+    if x < 0.5 {
+        return (PI / sin_pi(x).abs()).ln() - ln_gamma_f64(1.0 - x);
+    }
+
+    let (sum, t) = lanczos(x);
+    0.5 * (2.0 * PI).ln() + sum.ln() + (x - 0.5) * t.ln() - t
+}
+
+macro_rules! f32_wrappers {
+    ($($f32_fn:ident => $f64_fn:ident;)*) => {
+        $(
+            #[inline]
+            pub(super) fn $f32_fn(x: f32) -> f32 {
+                $f64_fn(f64::from(x)) as f32
+            }
+        )*
+    };
+}
+
+f32_wrappers! {
+    erf_f32 => erf_f64;
+    erfc_f32 => erfc_f64;
+    gamma_f32 => gamma_f64;
+    ln_gamma_f32 => ln_gamma_f64;
+}
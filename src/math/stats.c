@@ -0,0 +1,126 @@
+/*
+ * The functions in this file implement the statistics kernels, from the
+ * `stats` module. They are compiled into the poison_safe library, with the
+ * same flags and under the same constraints: every operation here can accept
+ * poison values without triggering UB.
+ *
+ * Like the BLAS level-1 reductions, these use several independent
+ * accumulators, and each makes a single pass over its input.
+ */
+
+#include <stddef.h>
+
+#include "libm.h"
+
+/*
+ * The variance is computed from the sums of the values and of their squares,
+ * after subtracting a shift from each value. A shift close to the mean (such
+ * as the first value) avoids the catastrophic cancellation of the naive
+ * sum-of-squares formula when the mean is large compared to the spread.
+ */
+#define IMPL_STATS(C_TYPE, RUST_TYPE, MIN_FN, MAX_FN)                       \
+  __attribute__((always_inline))                                            \
+  void shifted_sums_ ## RUST_TYPE(const C_TYPE *restrict x,                 \
+                                  size_t len,                               \
+                                  C_TYPE shift,                             \
+                                  C_TYPE *restrict sum,                     \
+                                  C_TYPE *restrict sum_squares) {           \
+    C_TYPE s0 = 0, s1 = 0, s2 = 0, s3 = 0;                                  \
+    C_TYPE q0 = 0, q1 = 0, q2 = 0, q3 = 0;                                  \
+    size_t i = 0;                                                           \
+    for(; i + 4 <= len; i += 4) {                                           \
+      C_TYPE d0 = x[i] - shift;                                             \
+      C_TYPE d1 = x[i + 1] - shift;                                         \
+      C_TYPE d2 = x[i + 2] - shift;                                         \
+      C_TYPE d3 = x[i + 3] - shift;                                         \
+      s0 += d0;                                                             \
+      s1 += d1;                                                             \
+      s2 += d2;                                                             \
+      s3 += d3;                                                             \
+      q0 += d0 * d0;                                                        \
+      q1 += d1 * d1;                                                        \
+      q2 += d2 * d2;                                                        \
+      q3 += d3 * d3;                                                        \
+    }                                                                       \
+    for(; i < len; i++) {                                                   \
+      C_TYPE d = x[i] - shift;                                              \
+      s0 += d;                                                              \
+      q0 += d * d;                                                          \
+    }                                                                       \
+    *sum = (s0 + s1) + (s2 + s3);                                           \
+    *sum_squares = (q0 + q1) + (q2 + q3);                                   \
+  }                                                                         \
+                                                                            \
+  /* min_max requires a non-empty slice, which is checked on the rust side */ \
+  __attribute__((always_inline))                                            \
+  void min_max_ ## RUST_TYPE(const C_TYPE *restrict x,                      \
+                             size_t len,                                    \
+                             C_TYPE *restrict min,                          \
+                             C_TYPE *restrict max) {                        \
+    C_TYPE lo = x[0];                                                       \
+    C_TYPE hi = x[0];                                                       \
+    for(size_t i = 1; i < len; i++) {                                       \
+      lo = MIN_FN(lo, x[i]);                                                \
+      hi = MAX_FN(hi, x[i]);                                                \
+    }                                                                       \
+    *min = lo;                                                              \
+    *max = hi;                                                              \
+  }                                                                         \
+
+/*
+ * The paired sums for the covariance and correlation are shifted the same way,
+ * each slice by its own first value. The five sums are written to `sums` in
+ * the order a, b, a * a, b * b, a * b, and reassociation lets the compiler
+ * vectorize all of them together.
+ */
+#define IMPL_PAIRED_STATS(C_TYPE, RUST_TYPE)                                \
+  __attribute__((always_inline))                                            \
+  void paired_sums_ ## RUST_TYPE(const C_TYPE *restrict a,                  \
+                                 const C_TYPE *restrict b,                  \
+                                 size_t len,                                \
+                                 C_TYPE shift_a,                            \
+                                 C_TYPE shift_b,                            \
+                                 C_TYPE *restrict sums) {                   \
+    C_TYPE sa = 0, sb = 0, saa = 0, sbb = 0, sab = 0;                       \
+    for(size_t i = 0; i < len; i++) {                                       \
+      C_TYPE da = a[i] - shift_a;                                           \
+      C_TYPE db = b[i] - shift_b;                                           \
+      sa += da;                                                             \
+      sb += db;                                                             \
+      saa += da * da;                                                       \
+      sbb += db * db;                                                       \
+      sab += da * db;                                                       \
+    }                                                                       \
+    sums[0] = sa;                                                           \
+    sums[1] = sb;                                                           \
+    sums[2] = saa;                                                          \
+    sums[3] = sbb;                                                          \
+    sums[4] = sab;                                                          \
+  }                                                                         \
+
+/*
+ * The second pass of the layer normalization, which applies the normalization
+ * and the per-element scale and shift together. The mean and reciprocal
+ * standard deviation come from the shifted sums, finished on the rust side.
+ */
+#define IMPL_LAYER_NORM(C_TYPE, RUST_TYPE)                                  \
+  __attribute__((always_inline))                                            \
+  void layer_norm_apply_ ## RUST_TYPE(C_TYPE *restrict x,                   \
+                                      const C_TYPE *restrict gamma,         \
+                                      const C_TYPE *restrict beta,          \
+                                      size_t len,                           \
+                                      C_TYPE mean,                          \
+                                      C_TYPE inv_std) {                     \
+    for(size_t i = 0; i < len; i++) {                                       \
+      x[i] = (x[i] - mean) * inv_std * gamma[i] + beta[i];                  \
+    }                                                                       \
+  }                                                                         \
+
+IMPL_STATS(float, f32, fminf, fmaxf)
+IMPL_STATS(double, f64, fmin, fmax)
+
+IMPL_PAIRED_STATS(float, f32)
+IMPL_PAIRED_STATS(double, f64)
+
+IMPL_LAYER_NORM(float, f32)
+IMPL_LAYER_NORM(double, f64)
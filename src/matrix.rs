@@ -0,0 +1,642 @@
+//! Kernels for small fixed-size matrices.
+//!
+//! Matrices are row-major nested arrays, so `m[i][j]` is the element in row `i` and column `j`.
+//! The batched kernels process whole slices of matrices at once, for workloads like transforming
+//! thousands of poses per frame.
+//!
+//! ```
+//! use fast_fp::{ff32, matrix, FF32};
+//!
+//! let rotate = [[0.0, -1.0, 0.0], [1.0, 0.0, 0.0], [0.0, 0.0, 1.0]].map(|row| row.map(ff32));
+//! let scale = [[2.0, 0.0, 0.0], [0.0, 2.0, 0.0], [0.0, 0.0, 2.0]].map(|row| row.map(ff32));
+//!
+//! let mut products = [[[ff32(0.0); 3]; 3]; 2];
+//! matrix::mul3_batch(&[rotate, scale], &[scale, scale], &mut products);
+//!
+//! let mut inverses = [[[ff32(0.0); 3]; 3]; 2];
+//! matrix::inv3_batch(&products, &mut inverses);
+//! assert_eq!(inverses[1][0], [0.25, 0.0, 0.0]);
+//! ```
+
+use crate::{FF32, FF64};
+use paste::paste;
+
+/// The fast scalar types which have small matrix kernels, used to select the implementation of
+/// the functions in this module.
+pub trait MatrixKernels: Sized {
+    /// See [`mul3_batch`]
+    fn mul3_batch(a: &[[[Self; 3]; 3]], b: &[[[Self; 3]; 3]], out: &mut [[[Self; 3]; 3]]);
+
+    /// See [`mul4_batch`]
+    fn mul4_batch(a: &[[[Self; 4]; 4]], b: &[[[Self; 4]; 4]], out: &mut [[[Self; 4]; 4]]);
+
+    /// See [`inv3_batch`]
+    fn inv3_batch(src: &[[[Self; 3]; 3]], dst: &mut [[[Self; 3]; 3]]);
+
+    /// See [`inv4_batch`]
+    fn inv4_batch(src: &[[[Self; 4]; 4]], dst: &mut [[[Self; 4]; 4]]);
+
+    /// See [`transform_points`]
+    fn transform_points(m: &[[Self; 4]; 4], points: &mut [[Self; 3]]);
+
+    /// See [`cholesky`]
+    fn cholesky<const N: usize>(a: &[[Self; N]; N]) -> Option<[[Self; N]; N]>;
+
+    /// See [`solve_lower`]
+    fn solve_lower<const N: usize>(l: &[[Self; N]; N], b: &mut [Self; N]);
+
+    /// See [`solve_upper`]
+    fn solve_upper<const N: usize>(u: &[[Self; N]; N], b: &mut [Self; N]);
+
+    /// See [`cholesky_solve`]
+    fn cholesky_solve<const N: usize>(l: &[[Self; N]; N], b: &mut [Self; N]);
+
+    /// See [`det2`]
+    fn det2(m: &[[Self; 2]; 2]) -> Self;
+
+    /// See [`det3`]
+    fn det3(m: &[[Self; 3]; 3]) -> Self;
+
+    /// See [`inv2`]
+    fn inv2(m: &[[Self; 2]; 2]) -> [[Self; 2]; 2];
+
+    /// See [`inv3`]
+    fn inv3(m: &[[Self; 3]; 3]) -> [[Self; 3]; 3];
+
+    /// See [`solve2`]
+    fn solve2(a: &[[Self; 2]; 2], b: &[Self; 2]) -> [Self; 2];
+
+    /// See [`solve3`]
+    fn solve3(a: &[[Self; 3]; 3], b: &[Self; 3]) -> [Self; 3];
+}
+
+/// Compute the products `out[i] = a[i] * b[i]` of a batch of 3x3 matrices.
+///
+/// # Panics
+/// Panics if the slices have different lengths
+#[inline]
+pub fn mul3_batch<T: MatrixKernels>(a: &[[[T; 3]; 3]], b: &[[[T; 3]; 3]], out: &mut [[[T; 3]; 3]]) {
+    T::mul3_batch(a, b, out)
+}
+
+/// Compute the products `out[i] = a[i] * b[i]` of a batch of 4x4 matrices.
+///
+/// # Panics
+/// Panics if the slices have different lengths
+#[inline]
+pub fn mul4_batch<T: MatrixKernels>(a: &[[[T; 4]; 4]], b: &[[[T; 4]; 4]], out: &mut [[[T; 4]; 4]]) {
+    T::mul4_batch(a, b, out)
+}
+
+/// Compute the inverses of a batch of 3x3 matrices, storing them in `dst`.
+///
+/// The inverse of a singular matrix is unspecified.
+///
+/// # Panics
+/// Panics if the two slices have different lengths
+#[inline]
+pub fn inv3_batch<T: MatrixKernels>(src: &[[[T; 3]; 3]], dst: &mut [[[T; 3]; 3]]) {
+    T::inv3_batch(src, dst)
+}
+
+/// Compute the inverses of a batch of 4x4 matrices, storing them in `dst`.
+///
+/// The inverse of a singular matrix is unspecified.
+///
+/// # Panics
+/// Panics if the two slices have different lengths
+#[inline]
+pub fn inv4_batch<T: MatrixKernels>(src: &[[[T; 4]; 4]], dst: &mut [[[T; 4]; 4]]) {
+    T::inv4_batch(src, dst)
+}
+
+/// Apply a 4x4 affine or projective transform to each point in place.
+///
+/// Each point is treated as the homogeneous column vector `[x, y, z, 1]` and multiplied by `m`;
+/// the result is divided by its `w` component. For affine transforms (where the bottom row of `m`
+/// is `[0, 0, 0, 1]`), that division has no effect.
+///
+/// ```
+/// use fast_fp::{ff32, matrix};
+///
+/// let translate = [
+///     [1.0, 0.0, 0.0, 1.0],
+///     [0.0, 1.0, 0.0, 2.0],
+///     [0.0, 0.0, 1.0, 3.0],
+///     [0.0, 0.0, 0.0, 1.0],
+/// ]
+/// .map(|row| row.map(ff32));
+///
+/// let mut points = [[0.0, 0.0, 0.0], [1.0, 1.0, 1.0]].map(|p| p.map(ff32));
+/// matrix::transform_points(&translate, &mut points);
+/// assert_eq!(points, [[1.0, 2.0, 3.0], [2.0, 3.0, 4.0]].map(|p| p.map(ff32)));
+/// ```
+#[inline]
+pub fn transform_points<T: MatrixKernels>(m: &[[T; 4]; 4], points: &mut [[T; 3]]) {
+    T::transform_points(m, points)
+}
+
+/// Compute the Cholesky factorization `A = L * Lᵀ` of a symmetric positive-definite matrix,
+/// returning the lower-triangular factor `L` (with its upper triangle zeroed).
+///
+/// Only the lower triangle of `a` is read. Returns `None` if the matrix is not positive definite.
+/// This is intended for small sizes (up to about 8x8), where the factorization can run in a tight
+/// loop without any generic overhead. Checking definiteness requires comparisons, so like the
+/// fast types' comparisons, the matrix is frozen first.
+///
+/// ```
+/// use fast_fp::{ff64, matrix};
+///
+/// let a = [[4.0, 2.0, 2.0], [2.0, 5.0, 3.0], [2.0, 3.0, 6.0]].map(|row| row.map(ff64));
+/// let l = matrix::cholesky(&a).unwrap();
+/// assert_eq!(l, [[2.0, 0.0, 0.0], [1.0, 2.0, 0.0], [1.0, 1.0, 2.0]].map(|row| row.map(ff64)));
+///
+/// // solve `A * x = b`
+/// let mut x = [1.0, 2.0, 3.0].map(ff64);
+/// matrix::cholesky_solve(&l, &mut x);
+/// assert_eq!(x, [-0.046875, 0.15625, 0.4375].map(ff64));
+/// ```
+#[inline]
+pub fn cholesky<T: MatrixKernels, const N: usize>(a: &[[T; N]; N]) -> Option<[[T; N]; N]> {
+    T::cholesky(a)
+}
+
+/// Solve `L * x = b` for a lower-triangular `L` by forward substitution, overwriting `b` with `x`.
+///
+/// Only the lower triangle of `l` is read. The solution is unspecified if `L` is singular.
+#[inline]
+pub fn solve_lower<T: MatrixKernels, const N: usize>(l: &[[T; N]; N], b: &mut [T; N]) {
+    T::solve_lower(l, b)
+}
+
+/// Solve `U * x = b` for an upper-triangular `U` by back substitution, overwriting `b` with `x`.
+///
+/// Only the upper triangle of `u` is read. The solution is unspecified if `U` is singular.
+#[inline]
+pub fn solve_upper<T: MatrixKernels, const N: usize>(u: &[[T; N]; N], b: &mut [T; N]) {
+    T::solve_upper(u, b)
+}
+
+/// Solve `A * x = b` given the Cholesky factor `L` of `A` (see [`cholesky`]), overwriting `b`
+/// with `x`.
+#[inline]
+pub fn cholesky_solve<T: MatrixKernels, const N: usize>(l: &[[T; N]; N], b: &mut [T; N]) {
+    T::cholesky_solve(l, b)
+}
+
+/// Compute the determinant of a 2x2 matrix.
+#[inline]
+pub fn det2<T: MatrixKernels>(m: &[[T; 2]; 2]) -> T {
+    T::det2(m)
+}
+
+/// Compute the determinant of a 3x3 matrix.
+///
+/// ```
+/// use fast_fp::{ff32, matrix};
+///
+/// let a = [[2.0, 0.0, 1.0], [1.0, 1.0, 0.0], [0.0, 2.0, 1.0]].map(|row| row.map(ff32));
+/// assert_eq!(matrix::det3(&a), 4.0);
+///
+/// let x = matrix::solve3(&a, &[3.0, 2.0, 3.0].map(ff32));
+/// assert_eq!(x, [1.0, 1.0, 1.0]);
+/// ```
+#[inline]
+pub fn det3<T: MatrixKernels>(m: &[[T; 3]; 3]) -> T {
+    T::det3(m)
+}
+
+/// Compute the inverse of a 2x2 matrix in closed form.
+///
+/// The inverse of a singular matrix is unspecified.
+#[inline]
+pub fn inv2<T: MatrixKernels>(m: &[[T; 2]; 2]) -> [[T; 2]; 2] {
+    T::inv2(m)
+}
+
+/// Compute the inverse of a 3x3 matrix in closed form.
+///
+/// The inverse of a singular matrix is unspecified. See [`inv3_batch`] to invert many matrices
+/// at once.
+#[inline]
+pub fn inv3<T: MatrixKernels>(m: &[[T; 3]; 3]) -> [[T; 3]; 3] {
+    T::inv3(m)
+}
+
+/// Solve `A * x = b` for a 2x2 matrix `A` in closed form, returning `x`.
+///
+/// The solution is unspecified if `A` is singular.
+#[inline]
+pub fn solve2<T: MatrixKernels>(a: &[[T; 2]; 2], b: &[T; 2]) -> [T; 2] {
+    T::solve2(a, b)
+}
+
+/// Solve `A * x = b` for a 3x3 matrix `A` in closed form, returning `x`.
+///
+/// The solution is unspecified if `A` is singular.
+#[inline]
+pub fn solve3<T: MatrixKernels>(a: &[[T; 3]; 3], b: &[T; 3]) -> [T; 3] {
+    T::solve3(a, b)
+}
+
+macro_rules! impl_matrix {
+    ($fast_ty:ident, $base_ty:ident) => {
+        paste! {
+            dispatch_kernels! {
+                // batches are passed as a pointer and a count of matrices. Like the scalar
+                // poison_safe functions, these can accept poison values
+                fn [<mul3_batch_ $base_ty>](
+                    out: *mut [[$fast_ty; 3]; 3],
+                    a: *const [[$fast_ty; 3]; 3],
+                    b: *const [[$fast_ty; 3]; 3],
+                    count: usize,
+                );
+                fn [<mul4_batch_ $base_ty>](
+                    out: *mut [[$fast_ty; 4]; 4],
+                    a: *const [[$fast_ty; 4]; 4],
+                    b: *const [[$fast_ty; 4]; 4],
+                    count: usize,
+                );
+                fn [<inv3_batch_ $base_ty>](out: *mut [[$fast_ty; 3]; 3], a: *const [[$fast_ty; 3]; 3], count: usize);
+                fn [<inv4_batch_ $base_ty>](out: *mut [[$fast_ty; 4]; 4], a: *const [[$fast_ty; 4]; 4], count: usize);
+                fn [<transform_points_ $base_ty>](m: *const [[$fast_ty; 4]; 4], points: *mut [$fast_ty; 3], count: usize);
+
+                fn [<solve_lower_ $base_ty>](l: *const $fast_ty, b: *mut $fast_ty, n: usize);
+                fn [<solve_upper_ $base_ty>](u: *const $fast_ty, b: *mut $fast_ty, n: usize);
+                fn [<solve_lower_transpose_ $base_ty>](l: *const $fast_ty, b: *mut $fast_ty, n: usize);
+            }
+
+            #[cfg(not(scalar_fallback))]
+            #[link(name = "poison_unsafe")]
+            extern "C" {
+                // the matrix must be frozen before the call, so it's passed as the base type
+                fn [<cholesky_ $base_ty>](a: *mut $base_ty, n: usize) -> i32;
+            }
+
+            // with the strict feature, the shims are replaced by the same formulas over the base
+            // type
+            #[cfg(scalar_fallback)]
+            mod [<strict_matrix_ $base_ty>] {
+                use super::*;
+
+                #[inline(always)]
+                pub(super) fn freeze<const N: usize>(m: &[[$fast_ty; N]; N]) -> [[$base_ty; N]; N] {
+                    m.map(|row| row.map(|v| v.freeze_raw()))
+                }
+
+                #[inline(always)]
+                pub(super) fn wrap<const N: usize>(m: [[$base_ty; N]; N]) -> [[$fast_ty; N]; N] {
+                    m.map(|row| row.map(<$fast_ty>::new))
+                }
+
+                #[inline(always)]
+                pub(super) fn mat_mul<const N: usize>(a: [[$base_ty; N]; N], b: [[$base_ty; N]; N]) -> [[$base_ty; N]; N] {
+                    let mut out = [[0.0; N]; N];
+                    for (i, row) in out.iter_mut().enumerate() {
+                        for (j, out) in row.iter_mut().enumerate() {
+                            *out = (0..N).fold(0.0, |acc, p| acc + a[i][p] * b[p][j]);
+                        }
+                    }
+                    out
+                }
+
+                #[inline(always)]
+                pub(super) fn mat3_inv(m: [[$base_ty; 3]; 3]) -> [[$base_ty; 3]; 3] {
+                    let c00 = m[1][1] * m[2][2] - m[1][2] * m[2][1];
+                    let c10 = m[1][2] * m[2][0] - m[1][0] * m[2][2];
+                    let c20 = m[1][0] * m[2][1] - m[1][1] * m[2][0];
+                    let inv_det = 1.0 / (m[0][0] * c00 + m[0][1] * c10 + m[0][2] * c20);
+                    [
+                        [
+                            c00 * inv_det,
+                            (m[0][2] * m[2][1] - m[0][1] * m[2][2]) * inv_det,
+                            (m[0][1] * m[1][2] - m[0][2] * m[1][1]) * inv_det,
+                        ],
+                        [
+                            c10 * inv_det,
+                            (m[0][0] * m[2][2] - m[0][2] * m[2][0]) * inv_det,
+                            (m[0][2] * m[1][0] - m[0][0] * m[1][2]) * inv_det,
+                        ],
+                        [
+                            c20 * inv_det,
+                            (m[0][1] * m[2][0] - m[0][0] * m[2][1]) * inv_det,
+                            (m[0][0] * m[1][1] - m[0][1] * m[1][0]) * inv_det,
+                        ],
+                    ]
+                }
+
+                #[inline(always)]
+                pub(super) fn mat4_inv(m: [[$base_ty; 4]; 4]) -> [[$base_ty; 4]; 4] {
+                    let s0 = m[0][0] * m[1][1] - m[1][0] * m[0][1];
+                    let s1 = m[0][0] * m[1][2] - m[1][0] * m[0][2];
+                    let s2 = m[0][0] * m[1][3] - m[1][0] * m[0][3];
+                    let s3 = m[0][1] * m[1][2] - m[1][1] * m[0][2];
+                    let s4 = m[0][1] * m[1][3] - m[1][1] * m[0][3];
+                    let s5 = m[0][2] * m[1][3] - m[1][2] * m[0][3];
+                    let c5 = m[2][2] * m[3][3] - m[3][2] * m[2][3];
+                    let c4 = m[2][1] * m[3][3] - m[3][1] * m[2][3];
+                    let c3 = m[2][1] * m[3][2] - m[3][1] * m[2][2];
+                    let c2 = m[2][0] * m[3][3] - m[3][0] * m[2][3];
+                    let c1 = m[2][0] * m[3][2] - m[3][0] * m[2][2];
+                    let c0 = m[2][0] * m[3][1] - m[3][0] * m[2][1];
+                    let inv_det = 1.0 / (s0 * c5 - s1 * c4 + s2 * c3 + s3 * c2 - s4 * c1 + s5 * c0);
+                    [
+                        [
+                            (m[1][1] * c5 - m[1][2] * c4 + m[1][3] * c3) * inv_det,
+                            (-m[0][1] * c5 + m[0][2] * c4 - m[0][3] * c3) * inv_det,
+                            (m[3][1] * s5 - m[3][2] * s4 + m[3][3] * s3) * inv_det,
+                            (-m[2][1] * s5 + m[2][2] * s4 - m[2][3] * s3) * inv_det,
+                        ],
+                        [
+                            (-m[1][0] * c5 + m[1][2] * c2 - m[1][3] * c1) * inv_det,
+                            (m[0][0] * c5 - m[0][2] * c2 + m[0][3] * c1) * inv_det,
+                            (-m[3][0] * s5 + m[3][2] * s2 - m[3][3] * s1) * inv_det,
+                            (m[2][0] * s5 - m[2][2] * s2 + m[2][3] * s1) * inv_det,
+                        ],
+                        [
+                            (m[1][0] * c4 - m[1][1] * c2 + m[1][3] * c0) * inv_det,
+                            (-m[0][0] * c4 + m[0][1] * c2 - m[0][3] * c0) * inv_det,
+                            (m[3][0] * s4 - m[3][1] * s2 + m[3][3] * s0) * inv_det,
+                            (-m[2][0] * s4 + m[2][1] * s2 - m[2][3] * s0) * inv_det,
+                        ],
+                        [
+                            (-m[1][0] * c3 + m[1][1] * c1 - m[1][2] * c0) * inv_det,
+                            (m[0][0] * c3 - m[0][1] * c1 + m[0][2] * c0) * inv_det,
+                            (-m[3][0] * s3 + m[3][1] * s1 - m[3][2] * s0) * inv_det,
+                            (m[2][0] * s3 - m[2][1] * s1 + m[2][2] * s0) * inv_det,
+                        ],
+                    ]
+                }
+
+                #[inline(always)]
+                unsafe fn zip<const N: usize>(
+                    out: *mut [[$fast_ty; N]; N],
+                    a: *const [[$fast_ty; N]; N],
+                    b: *const [[$fast_ty; N]; N],
+                    count: usize,
+                    op: fn([[$base_ty; N]; N], [[$base_ty; N]; N]) -> [[$base_ty; N]; N],
+                ) {
+                    let out = core::slice::from_raw_parts_mut(out, count);
+                    let a = core::slice::from_raw_parts(a, count);
+                    let b = core::slice::from_raw_parts(b, count);
+                    for (out, (a, b)) in out.iter_mut().zip(a.iter().zip(b)) {
+                        *out = wrap(op(freeze(a), freeze(b)));
+                    }
+                }
+
+                #[inline(always)]
+                unsafe fn map<const N: usize>(
+                    out: *mut [[$fast_ty; N]; N],
+                    a: *const [[$fast_ty; N]; N],
+                    count: usize,
+                    op: fn([[$base_ty; N]; N]) -> [[$base_ty; N]; N],
+                ) {
+                    let out = core::slice::from_raw_parts_mut(out, count);
+                    let a = core::slice::from_raw_parts(a, count);
+                    for (out, a) in out.iter_mut().zip(a) {
+                        *out = wrap(op(freeze(a)));
+                    }
+                }
+
+                #[inline(always)]
+                pub(super) unsafe fn [<mul3_batch_ $base_ty>](
+                    out: *mut [[$fast_ty; 3]; 3],
+                    a: *const [[$fast_ty; 3]; 3],
+                    b: *const [[$fast_ty; 3]; 3],
+                    count: usize,
+                ) {
+                    zip(out, a, b, count, mat_mul)
+                }
+
+                #[inline(always)]
+                pub(super) unsafe fn [<mul4_batch_ $base_ty>](
+                    out: *mut [[$fast_ty; 4]; 4],
+                    a: *const [[$fast_ty; 4]; 4],
+                    b: *const [[$fast_ty; 4]; 4],
+                    count: usize,
+                ) {
+                    zip(out, a, b, count, mat_mul)
+                }
+
+                #[inline(always)]
+                pub(super) unsafe fn [<inv3_batch_ $base_ty>](
+                    out: *mut [[$fast_ty; 3]; 3],
+                    a: *const [[$fast_ty; 3]; 3],
+                    count: usize,
+                ) {
+                    map(out, a, count, mat3_inv)
+                }
+
+                #[inline(always)]
+                pub(super) unsafe fn [<inv4_batch_ $base_ty>](
+                    out: *mut [[$fast_ty; 4]; 4],
+                    a: *const [[$fast_ty; 4]; 4],
+                    count: usize,
+                ) {
+                    map(out, a, count, mat4_inv)
+                }
+
+                #[inline(always)]
+                pub(super) unsafe fn [<transform_points_ $base_ty>](
+                    m: *const [[$fast_ty; 4]; 4],
+                    points: *mut [$fast_ty; 3],
+                    count: usize,
+                ) {
+                    let m = freeze(&*m);
+                    for p in core::slice::from_raw_parts_mut(points, count) {
+                        let [x, y, z] = p.map(|v| v.freeze_raw());
+                        let row = |i: usize| m[i][0] * x + m[i][1] * y + m[i][2] * z + m[i][3];
+                        let inv_w = 1.0 / row(3);
+                        *p = [row(0) * inv_w, row(1) * inv_w, row(2) * inv_w].map(<$fast_ty>::new);
+                    }
+                }
+
+                #[inline(always)]
+                pub(super) unsafe fn [<cholesky_ $base_ty>](a: *mut $base_ty, n: usize) -> i32 {
+                    let a = core::slice::from_raw_parts_mut(a, n * n);
+                    for j in 0..n {
+                        let d = a[j * n + j] - (0..j).fold(0.0, |acc, k| acc + a[j * n + k] * a[j * n + k]);
+                        if d.is_nan() || d <= 0.0 {
+                            return 0;
+                        }
+                        let d = d.sqrt();
+                        a[j * n + j] = d;
+                        for i in j + 1..n {
+                            let s = a[i * n + j] - (0..j).fold(0.0, |acc, k| acc + a[i * n + k] * a[j * n + k]);
+                            a[i * n + j] = s / d;
+                            a[j * n + i] = 0.0;
+                        }
+                    }
+                    1
+                }
+
+                // substitution over the frozen matrix, where `coef(i, k)` is the coefficient of
+                // unknown `k` in equation `i`, and equations are solved in the order of `rows`
+                #[inline(always)]
+                unsafe fn substitute(
+                    m: *const $fast_ty,
+                    b: *mut $fast_ty,
+                    n: usize,
+                    rows: impl Iterator<Item = usize>,
+                    solved: fn(usize, usize) -> core::ops::Range<usize>,
+                    coef: fn(usize, usize, usize) -> usize,
+                ) {
+                    let m = core::slice::from_raw_parts(m, n * n);
+                    let b = core::slice::from_raw_parts_mut(b, n);
+                    for i in rows {
+                        let s = solved(i, n).fold(b[i].freeze_raw(), |acc, k| {
+                            acc - m[coef(i, k, n)].freeze_raw() * b[k].freeze_raw()
+                        });
+                        b[i] = <$fast_ty>::new(s / m[i * n + i].freeze_raw());
+                    }
+                }
+
+                #[inline(always)]
+                pub(super) unsafe fn [<solve_lower_ $base_ty>](l: *const $fast_ty, b: *mut $fast_ty, n: usize) {
+                    substitute(l, b, n, 0..n, |i, _| 0..i, |i, k, n| i * n + k)
+                }
+
+                #[inline(always)]
+                pub(super) unsafe fn [<solve_upper_ $base_ty>](u: *const $fast_ty, b: *mut $fast_ty, n: usize) {
+                    substitute(u, b, n, (0..n).rev(), |i, n| i + 1..n, |i, k, n| i * n + k)
+                }
+
+                #[inline(always)]
+                pub(super) unsafe fn [<solve_lower_transpose_ $base_ty>](l: *const $fast_ty, b: *mut $fast_ty, n: usize) {
+                    substitute(l, b, n, (0..n).rev(), |i, n| i + 1..n, |i, k, n| k * n + i)
+                }
+            }
+
+            #[cfg(scalar_fallback)]
+            use [<strict_matrix_ $base_ty>]::*;
+
+            impl MatrixKernels for $fast_ty {
+                #[inline]
+                fn mul3_batch(a: &[[[Self; 3]; 3]], b: &[[[Self; 3]; 3]], out: &mut [[[Self; 3]; 3]]) {
+                    assert_eq!(a.len(), out.len(), "slice lengths differ");
+                    assert_eq!(b.len(), out.len(), "slice lengths differ");
+                    unsafe { [<mul3_batch_ $base_ty>](out.as_mut_ptr(), a.as_ptr(), b.as_ptr(), out.len()) }
+                }
+
+                #[inline]
+                fn mul4_batch(a: &[[[Self; 4]; 4]], b: &[[[Self; 4]; 4]], out: &mut [[[Self; 4]; 4]]) {
+                    assert_eq!(a.len(), out.len(), "slice lengths differ");
+                    assert_eq!(b.len(), out.len(), "slice lengths differ");
+                    unsafe { [<mul4_batch_ $base_ty>](out.as_mut_ptr(), a.as_ptr(), b.as_ptr(), out.len()) }
+                }
+
+                #[inline]
+                fn inv3_batch(src: &[[[Self; 3]; 3]], dst: &mut [[[Self; 3]; 3]]) {
+                    assert_eq!(src.len(), dst.len(), "source and destination lengths differ");
+                    unsafe { [<inv3_batch_ $base_ty>](dst.as_mut_ptr(), src.as_ptr(), dst.len()) }
+                }
+
+                #[inline]
+                fn inv4_batch(src: &[[[Self; 4]; 4]], dst: &mut [[[Self; 4]; 4]]) {
+                    assert_eq!(src.len(), dst.len(), "source and destination lengths differ");
+                    unsafe { [<inv4_batch_ $base_ty>](dst.as_mut_ptr(), src.as_ptr(), dst.len()) }
+                }
+
+                #[inline]
+                fn transform_points(m: &[[Self; 4]; 4], points: &mut [[Self; 3]]) {
+                    unsafe { [<transform_points_ $base_ty>](m, points.as_mut_ptr(), points.len()) }
+                }
+
+                #[inline]
+                fn cholesky<const N: usize>(a: &[[Self; N]; N]) -> Option<[[Self; N]; N]> {
+                    let mut l = a.map(|row| row.map(|v| v.freeze_raw()));
+                    if unsafe { [<cholesky_ $base_ty>](l.as_mut_ptr().cast(), N) } == 0 {
+                        return None;
+                    }
+                    Some(l.map(|row| row.map(<$fast_ty>::new)))
+                }
+
+                #[inline]
+                fn solve_lower<const N: usize>(l: &[[Self; N]; N], b: &mut [Self; N]) {
+                    unsafe { [<solve_lower_ $base_ty>](l.as_ptr().cast(), b.as_mut_ptr(), N) }
+                }
+
+                #[inline]
+                fn solve_upper<const N: usize>(u: &[[Self; N]; N], b: &mut [Self; N]) {
+                    unsafe { [<solve_upper_ $base_ty>](u.as_ptr().cast(), b.as_mut_ptr(), N) }
+                }
+
+                #[inline]
+                fn cholesky_solve<const N: usize>(l: &[[Self; N]; N], b: &mut [Self; N]) {
+                    unsafe {
+                        [<solve_lower_ $base_ty>](l.as_ptr().cast(), b.as_mut_ptr(), N);
+                        [<solve_lower_transpose_ $base_ty>](l.as_ptr().cast(), b.as_mut_ptr(), N);
+                    }
+                }
+
+                // the closed forms are short enough to write directly with the fast operators,
+                // which inline to the same fast-math instructions as the C kernels
+
+                #[inline]
+                fn det2(m: &[[Self; 2]; 2]) -> Self {
+                    m[0][0] * m[1][1] - m[0][1] * m[1][0]
+                }
+
+                #[inline]
+                fn det3(m: &[[Self; 3]; 3]) -> Self {
+                    m[0][0] * (m[1][1] * m[2][2] - m[1][2] * m[2][1])
+                        + m[0][1] * (m[1][2] * m[2][0] - m[1][0] * m[2][2])
+                        + m[0][2] * (m[1][0] * m[2][1] - m[1][1] * m[2][0])
+                }
+
+                #[inline]
+                fn inv2(m: &[[Self; 2]; 2]) -> [[Self; 2]; 2] {
+                    let inv_det = Self::ONE / Self::det2(m);
+                    [
+                        [m[1][1] * inv_det, (Self::ZERO - m[0][1]) * inv_det],
+                        [(Self::ZERO - m[1][0]) * inv_det, m[0][0] * inv_det],
+                    ]
+                }
+
+                // the inverse is the adjugate divided by the determinant
+                #[inline]
+                fn inv3(m: &[[Self; 3]; 3]) -> [[Self; 3]; 3] {
+                    let c00 = m[1][1] * m[2][2] - m[1][2] * m[2][1];
+                    let c10 = m[1][2] * m[2][0] - m[1][0] * m[2][2];
+                    let c20 = m[1][0] * m[2][1] - m[1][1] * m[2][0];
+                    let inv_det = Self::ONE / (m[0][0] * c00 + m[0][1] * c10 + m[0][2] * c20);
+                    [
+                        [
+                            c00 * inv_det,
+                            (m[0][2] * m[2][1] - m[0][1] * m[2][2]) * inv_det,
+                            (m[0][1] * m[1][2] - m[0][2] * m[1][1]) * inv_det,
+                        ],
+                        [
+                            c10 * inv_det,
+                            (m[0][0] * m[2][2] - m[0][2] * m[2][0]) * inv_det,
+                            (m[0][2] * m[1][0] - m[0][0] * m[1][2]) * inv_det,
+                        ],
+                        [
+                            c20 * inv_det,
+                            (m[0][1] * m[2][0] - m[0][0] * m[2][1]) * inv_det,
+                            (m[0][0] * m[1][1] - m[0][1] * m[1][0]) * inv_det,
+                        ],
+                    ]
+                }
+
+                // Cramer's rule, sharing the reciprocal of the determinant
+                #[inline]
+                fn solve2(a: &[[Self; 2]; 2], b: &[Self; 2]) -> [Self; 2] {
+                    let inv_det = Self::ONE / Self::det2(a);
+                    [
+                        (b[0] * a[1][1] - a[0][1] * b[1]) * inv_det,
+                        (a[0][0] * b[1] - b[0] * a[1][0]) * inv_det,
+                    ]
+                }
+
+                #[inline]
+                fn solve3(a: &[[Self; 3]; 3], b: &[Self; 3]) -> [Self; 3] {
+                    Self::inv3(a).map(|row| row[0] * b[0] + row[1] * b[1] + row[2] * b[2])
+                }
+            }
+        }
+    };
+}
+
+impl_matrix! { FF32, f32 }
+impl_matrix! { FF64, f64 }
@@ -367,6 +367,441 @@ macro_rules! impl_nalgebra {
     };
 }
 
+// Multi-lane SIMD support. simba's SIMD traits changed significantly after 0.1, so these impls are
+// only provided for the newer nalgebra versions. This must be invoked after `impl_nalgebra`, which
+// brings the versioned crates into scope and implements the scalar traits each lane relies on
+#[cfg(feature = "nalgebra-v029")]
+macro_rules! impl_nalgebra_simd {
+    ($($simd_ty:ident, $fast_ty:ident, $base_ty:ident, $lanes:literal),*) => {
+        use $crate::{FF32x4, FF32x8, FF64x4};
+        use simba::simd::AutoSimd;
+
+        $(
+            // lane-wise helpers for the many operations without a dedicated vector shim
+            impl $simd_ty {
+                #[inline(always)]
+                fn map_lanes_fast(self, f: impl Fn($fast_ty) -> $fast_ty) -> Self {
+                    <$simd_ty>::from_array(self.to_array().map(f))
+                }
+
+                #[inline(always)]
+                fn zip_map_lanes_fast(self, other: Self, f: impl Fn($fast_ty, $fast_ty) -> $fast_ty) -> Self {
+                    let mut lanes = self.to_array();
+                    for (lane, other) in lanes.iter_mut().zip(other.to_array().iter()) {
+                        *lane = f(*lane, *other);
+                    }
+                    <$simd_ty>::from_array(lanes)
+                }
+
+                #[inline(always)]
+                fn map_lanes_bool(self, f: impl Fn($fast_ty) -> bool) -> AutoSimd<[bool; $lanes]> {
+                    AutoSimd(self.to_array().map(f))
+                }
+
+                #[inline(always)]
+                fn zip_map_lanes_bool(self, other: Self, f: impl Fn($fast_ty, $fast_ty) -> bool) -> AutoSimd<[bool; $lanes]> {
+                    let mut mask = [false; $lanes];
+                    let (lanes, others) = (self.to_array(), other.to_array());
+                    for (mask, (lane, other)) in mask.iter_mut().zip(lanes.iter().zip(others.iter())) {
+                        *mask = f(*lane, *other);
+                    }
+                    AutoSimd(mask)
+                }
+            }
+
+            impl simba::simd::SimdValue for $simd_ty {
+                type Element = $fast_ty;
+                type SimdBool = AutoSimd<[bool; $lanes]>;
+
+                #[inline]
+                fn lanes() -> usize {
+                    $lanes
+                }
+
+                #[inline]
+                fn splat(val: Self::Element) -> Self {
+                    <$simd_ty>::splat(val)
+                }
+
+                #[inline]
+                fn extract(&self, i: usize) -> Self::Element {
+                    <$simd_ty>::extract(*self, i)
+                }
+
+                #[inline]
+                unsafe fn extract_unchecked(&self, i: usize) -> Self::Element {
+                    <$simd_ty>::extract(*self, i)
+                }
+
+                #[inline]
+                fn replace(&mut self, i: usize, val: Self::Element) {
+                    <$simd_ty>::replace(self, i, val)
+                }
+
+                #[inline]
+                unsafe fn replace_unchecked(&mut self, i: usize, val: Self::Element) {
+                    <$simd_ty>::replace(self, i, val)
+                }
+
+                #[inline]
+                fn select(self, cond: Self::SimdBool, other: Self) -> Self {
+                    let mut lanes = other.to_array();
+                    let selected = self.to_array();
+                    for ((lane, selected), cond) in lanes.iter_mut().zip(selected.iter()).zip(cond.0.iter()) {
+                        if *cond {
+                            *lane = *selected;
+                        }
+                    }
+                    <$simd_ty>::from_array(lanes)
+                }
+            }
+
+            impl simba::scalar::SubsetOf<$simd_ty> for $simd_ty {
+                #[inline]
+                fn to_superset(&self) -> $simd_ty {
+                    *self
+                }
+
+                #[inline]
+                fn from_superset_unchecked(element: &$simd_ty) -> Self {
+                    *element
+                }
+
+                #[inline]
+                fn is_in_subset(_: &$simd_ty) -> bool {
+                    true
+                }
+            }
+
+            impl simba::scalar::SubsetOf<$simd_ty> for f64 {
+                #[inline]
+                fn to_superset(&self) -> $simd_ty {
+                    <$simd_ty>::splat(<$fast_ty>::new(*self as $base_ty))
+                }
+
+                #[inline]
+                fn from_superset_unchecked(element: &$simd_ty) -> Self {
+                    <$base_ty>::from(element.extract(0)) as f64
+                }
+
+                #[inline]
+                fn is_in_subset(element: &$simd_ty) -> bool {
+                    let lanes = <[$base_ty; $lanes]>::from(*element);
+                    lanes.iter().all(|lane| *lane == lanes[0])
+                }
+            }
+
+            impl num_traits::Zero for $simd_ty {
+                #[inline]
+                fn zero() -> Self {
+                    <$simd_ty>::splat(<$fast_ty as num_traits::Zero>::zero())
+                }
+
+                #[inline]
+                fn is_zero(&self) -> bool {
+                    <[$base_ty; $lanes]>::from(*self).iter().all(|lane| *lane == 0.0)
+                }
+            }
+
+            impl num_traits::One for $simd_ty {
+                #[inline]
+                fn one() -> Self {
+                    <$simd_ty>::splat(<$fast_ty as num_traits::One>::one())
+                }
+            }
+
+            impl num_traits::Num for $simd_ty {
+                type FromStrRadixErr = <$fast_ty as num_traits::Num>::FromStrRadixErr;
+
+                fn from_str_radix(str: &str, radix: u32) -> Result<Self, Self::FromStrRadixErr> {
+                    Ok(<$simd_ty>::splat(<$fast_ty as num_traits::Num>::from_str_radix(str, radix)?))
+                }
+            }
+
+            impl na::Field for $simd_ty {}
+
+            // Branching on poison is UB, so every comparison freezes its lanes through the scalar
+            // fast type's comparisons
+            impl simba::simd::SimdPartialOrd for $simd_ty {
+                #[inline]
+                fn simd_gt(self, other: Self) -> Self::SimdBool {
+                    self.zip_map_lanes_bool(other, |a, b| a > b)
+                }
+
+                #[inline]
+                fn simd_lt(self, other: Self) -> Self::SimdBool {
+                    self.zip_map_lanes_bool(other, |a, b| a < b)
+                }
+
+                #[inline]
+                fn simd_ge(self, other: Self) -> Self::SimdBool {
+                    self.zip_map_lanes_bool(other, |a, b| a >= b)
+                }
+
+                #[inline]
+                fn simd_le(self, other: Self) -> Self::SimdBool {
+                    self.zip_map_lanes_bool(other, |a, b| a <= b)
+                }
+
+                #[inline]
+                fn simd_eq(self, other: Self) -> Self::SimdBool {
+                    self.zip_map_lanes_bool(other, |a, b| a == b)
+                }
+
+                #[inline]
+                fn simd_ne(self, other: Self) -> Self::SimdBool {
+                    self.zip_map_lanes_bool(other, |a, b| a != b)
+                }
+
+                #[inline]
+                fn simd_max(self, other: Self) -> Self {
+                    <$simd_ty>::max(self, other)
+                }
+
+                #[inline]
+                fn simd_min(self, other: Self) -> Self {
+                    <$simd_ty>::min(self, other)
+                }
+
+                #[inline]
+                fn simd_clamp(self, min: Self, max: Self) -> Self {
+                    <$simd_ty>::min(<$simd_ty>::max(self, min), max)
+                }
+
+                #[inline]
+                fn simd_horizontal_min(self) -> Self::Element {
+                    self.reduce_min()
+                }
+
+                #[inline]
+                fn simd_horizontal_max(self) -> Self::Element {
+                    self.reduce_max()
+                }
+            }
+
+            impl simba::simd::SimdSigned for $simd_ty {
+                #[inline]
+                fn simd_abs(&self) -> Self {
+                    <$simd_ty>::abs(*self)
+                }
+
+                #[inline]
+                fn simd_abs_sub(&self, other: &Self) -> Self {
+                    (*self - *other).max(<$simd_ty as num_traits::Zero>::zero())
+                }
+
+                #[inline]
+                fn simd_signum(&self) -> Self {
+                    self.map_lanes_fast(<$fast_ty>::signum)
+                }
+
+                #[inline]
+                fn is_simd_positive(&self) -> Self::SimdBool {
+                    self.map_lanes_bool(|lane| lane > <$fast_ty as num_traits::Zero>::zero())
+                }
+
+                #[inline]
+                fn is_simd_negative(&self) -> Self::SimdBool {
+                    self.map_lanes_bool(|lane| lane < <$fast_ty as num_traits::Zero>::zero())
+                }
+            }
+
+            impl simba::simd::SimdRealField for $simd_ty {
+                #[inline]
+                fn simd_copysign(self, sign: Self) -> Self {
+                    self.zip_map_lanes_fast(sign, <$fast_ty>::copysign)
+                }
+
+                #[inline]
+                fn simd_atan2(self, other: Self) -> Self {
+                    self.zip_map_lanes_fast(other, <$fast_ty>::atan2)
+                }
+
+                #[inline]
+                fn simd_default_epsilon() -> Self {
+                    <$simd_ty>::splat(<$fast_ty as approx::AbsDiffEq>::default_epsilon())
+                }
+
+                impl_nalgebra_simd! {
+                    @simd_consts $simd_ty, $fast_ty
+                    fn simd_pi() -> Self = pi;
+                    fn simd_two_pi() -> Self = two_pi;
+                    fn simd_frac_pi_2() -> Self = frac_pi_2;
+                    fn simd_frac_pi_3() -> Self = frac_pi_3;
+                    fn simd_frac_pi_4() -> Self = frac_pi_4;
+                    fn simd_frac_pi_6() -> Self = frac_pi_6;
+                    fn simd_frac_pi_8() -> Self = frac_pi_8;
+                    fn simd_frac_1_pi() -> Self = frac_1_pi;
+                    fn simd_frac_2_pi() -> Self = frac_2_pi;
+                    fn simd_frac_2_sqrt_pi() -> Self = frac_2_sqrt_pi;
+                    fn simd_e() -> Self = e;
+                    fn simd_log2_e() -> Self = log2_e;
+                    fn simd_log10_e() -> Self = log10_e;
+                    fn simd_ln_2() -> Self = ln_2;
+                    fn simd_ln_10() -> Self = ln_10;
+                }
+            }
+
+            impl simba::simd::SimdComplexField for $simd_ty {
+                type SimdRealField = Self;
+
+                impl_nalgebra_simd! {
+                    @simd_lanewise $fast_ty
+                    fn simd_floor(self) -> Self = floor;
+                    fn simd_ceil(self) -> Self = ceil;
+                    fn simd_round(self) -> Self = round;
+                    fn simd_trunc(self) -> Self = trunc;
+                    fn simd_fract(self) -> Self = fract;
+                    fn simd_signum(self) -> Self = signum;
+                    fn simd_recip(self) -> Self = recip;
+                    fn simd_sin(self) -> Self = sin;
+                    fn simd_cos(self) -> Self = cos;
+                    fn simd_tan(self) -> Self = tan;
+                    fn simd_asin(self) -> Self = asin;
+                    fn simd_acos(self) -> Self = acos;
+                    fn simd_atan(self) -> Self = atan;
+                    fn simd_sinh(self) -> Self = sinh;
+                    fn simd_cosh(self) -> Self = cosh;
+                    fn simd_tanh(self) -> Self = tanh;
+                    fn simd_asinh(self) -> Self = asinh;
+                    fn simd_acosh(self) -> Self = acosh;
+                    fn simd_atanh(self) -> Self = atanh;
+                    fn simd_sinc(self) -> Self = sinc;
+                    fn simd_sinhc(self) -> Self = sinhc;
+                    fn simd_cosc(self) -> Self = cosc;
+                    fn simd_coshc(self) -> Self = coshc;
+                    fn simd_log2(self) -> Self = log2;
+                    fn simd_log10(self) -> Self = log10;
+                    fn simd_ln(self) -> Self = ln;
+                    fn simd_ln_1p(self) -> Self = ln_1p;
+                    fn simd_sqrt(self) -> Self = sqrt;
+                    fn simd_exp(self) -> Self = exp;
+                    fn simd_exp2(self) -> Self = exp2;
+                    fn simd_exp_m1(self) -> Self = exp_m1;
+                    fn simd_cbrt(self) -> Self = cbrt;
+                    fn simd_hypot(self, other: Self) -> Self = hypot;
+                    fn simd_log(self, base: Self) -> Self = log;
+                    fn simd_powf(self, n: Self) -> Self = powf;
+                    fn simd_powc(self, n: Self) -> Self = powc;
+                }
+
+                #[inline]
+                fn simd_horizontal_sum(self) -> Self::Element {
+                    self.reduce_sum()
+                }
+
+                #[inline]
+                fn simd_horizontal_product(self) -> Self::Element {
+                    self.to_array().iter().product()
+                }
+
+                #[inline]
+                fn from_simd_real(re: Self::SimdRealField) -> Self {
+                    re
+                }
+
+                #[inline]
+                fn simd_real(self) -> Self::SimdRealField {
+                    self
+                }
+
+                #[inline]
+                fn simd_imaginary(self) -> Self::SimdRealField {
+                    <$simd_ty as num_traits::Zero>::zero()
+                }
+
+                #[inline]
+                fn simd_norm1(self) -> Self::SimdRealField {
+                    self.abs()
+                }
+
+                #[inline]
+                fn simd_modulus(self) -> Self::SimdRealField {
+                    self.abs()
+                }
+
+                #[inline]
+                fn simd_modulus_squared(self) -> Self::SimdRealField {
+                    self * self
+                }
+
+                #[inline]
+                fn simd_argument(self) -> Self::SimdRealField {
+                    self.map_lanes_fast(na::ComplexField::argument)
+                }
+
+                #[inline]
+                fn simd_scale(self, factor: Self::SimdRealField) -> Self {
+                    self * factor
+                }
+
+                #[inline]
+                fn simd_unscale(self, factor: Self::SimdRealField) -> Self {
+                    self / factor
+                }
+
+                #[inline]
+                fn simd_abs(self) -> Self::SimdRealField {
+                    self.abs()
+                }
+
+                #[inline]
+                fn simd_conjugate(self) -> Self {
+                    self
+                }
+
+                #[inline]
+                fn simd_mul_add(self, a: Self, b: Self) -> Self {
+                    self * a + b
+                }
+
+                #[inline]
+                fn simd_powi(self, n: i32) -> Self {
+                    self.map_lanes_fast(|lane| lane.powi(n))
+                }
+
+                #[inline]
+                fn simd_sin_cos(self) -> (Self, Self) {
+                    (self.simd_sin(), self.simd_cos())
+                }
+            }
+        )*
+    };
+
+    (@simd_consts $simd_ty:ident, $fast_ty:ident
+        $(fn $fn_name:ident () -> Self = $scalar_fn:ident ;)*
+    ) => {
+        $(
+            #[inline]
+            fn $fn_name () -> $simd_ty {
+                <$simd_ty>::splat(<$fast_ty as na::RealField>::$scalar_fn())
+            }
+        )*
+    };
+
+    (@simd_lanewise $fast_ty:ident
+        $(fn $fn_name:ident (self $(, $arg:ident : Self)?) -> Self = $scalar_fn:ident ;)*
+    ) => {
+        $(
+            impl_nalgebra_simd! { @simd_lanewise_fn $fast_ty, $fn_name, $scalar_fn $(, $arg)? }
+        )*
+    };
+
+    (@simd_lanewise_fn $fast_ty:ident, $fn_name:ident, $scalar_fn:ident) => {
+        #[inline]
+        fn $fn_name(self) -> Self {
+            self.map_lanes_fast(<$fast_ty as na::ComplexField>::$scalar_fn)
+        }
+    };
+
+    (@simd_lanewise_fn $fast_ty:ident, $fn_name:ident, $scalar_fn:ident, $arg:ident) => {
+        #[inline]
+        fn $fn_name(self, $arg: Self) -> Self {
+            self.zip_map_lanes_fast($arg, <$fast_ty as na::ComplexField>::$scalar_fn)
+        }
+    };
+}
+
 #[cfg(feature = "nalgebra-v021")]
 #[cfg_attr(docsrs, doc(cfg(feature = "nalgebra-v021")))]
 mod nalgebra_v021 {
@@ -418,4 +853,8 @@ mod nalgebra_v029 {
         ::nalgebra_v029, ::simba_v06, ::approx_v05 ;
         @RealField: real_field
     }
+
+    impl_nalgebra_simd! {
+        FF32x4, FF32, f32, 4, FF32x8, FF32, f32, 8, FF64x4, FF64, f64, 4
+    }
 }
@@ -111,10 +111,9 @@ macro_rules! impl_num_traits {
                 fn signum(&self) -> Self ;
             }
 
-            forward_freeze_self! {
-                $fast_ty, $base_ty
-                #[allow(deprecated)]
-                fn abs_sub(&self, other: &Self) -> Self;
+            #[inline]
+            fn abs_sub(&self, other: &Self) -> Self {
+                <$fast_ty>::abs_sub(*self, *other)
             }
 
             #[inline]
@@ -254,11 +253,6 @@ macro_rules! impl_num_traits {
                 fn atanh(self) -> Self;
                 fn to_degrees(self) -> Self;
                 fn to_radians(self) -> Self;
-            }
-
-            forward_freeze_self! {
-                $fast_ty, $base_ty
-                #[allow(deprecated)]
                 fn abs_sub(self, other: Self) -> Self;
             }
         }
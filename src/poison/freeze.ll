@@ -8,4 +8,9 @@ define double @freeze_f64(double %a) unnamed_addr #0 {
   ret double %b
 }
 
+define i64 @freeze_u64(i64 %a) unnamed_addr #0 {
+  %b = freeze i64 %a
+  ret i64 %b
+}
+
 attributes #0 = { alwaysinline nofree norecurse willreturn nosync nounwind readnone }
@@ -20,25 +20,54 @@ impl<T> MaybePoison<T> {
     }
 }
 
+impl<T: Copy, const N: usize> MaybePoison<[T; N]> {
+    /// Split an array into its individual elements, without freezing any of them
+    #[inline(always)]
+    pub(crate) fn split(self) -> [MaybePoison<T>; N] {
+        // MaybeUninit<[T; N]> has the same layout as [MaybeUninit<T>; N]
+        unsafe { core::mem::transmute_copy(&self) }
+    }
+
+    /// Join individual elements into an array, without freezing any of them
+    #[inline(always)]
+    pub(crate) fn join(elements: [MaybePoison<T>; N]) -> Self {
+        unsafe { core::mem::transmute_copy(&elements) }
+    }
+}
+
 macro_rules! impl_freeze {
     ($($raw_ty:ty, $fn_name:ident;)*) => {
         $(
+            #[cfg(not(any(scalar_fallback, poison_free)))]
             #[link(name = "freeze")]
             extern "C" {
                 fn $fn_name(val: MaybePoison<$raw_ty>) -> $raw_ty;
             }
 
             impl MaybePoison<$raw_ty> {
+                #[cfg(not(any(scalar_fallback, poison_free)))]
                 #[inline(always)]
                 pub(crate) fn freeze(self) -> $raw_ty {
                     unsafe { $fn_name(self) }
                 }
+
+                // with the strict feature no operation can produce poison, so the value is always
+                // the initialized one it was constructed with. The same holds for builds without
+                // finite-math-only (the non-finite feature and the inline fallback)
+                #[cfg(any(scalar_fallback, poison_free))]
+                #[inline(always)]
+                pub(crate) fn freeze(self) -> $raw_ty {
+                    unsafe { self.0.assume_init() }
+                }
             }
         )*
     }
 }
 
+// u64 freezes the storage of `FF128`, which has no rust float type to freeze, and the indices
+// returned by kernels like `slice::argmax`
 impl_freeze! {
     f32, freeze_f32;
     f64, freeze_f64;
+    u64, freeze_u64;
 }
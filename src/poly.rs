@@ -0,0 +1,175 @@
+//! Polynomial evaluation and fitting, the core of custom function approximations.
+//!
+//! Coefficients are ordered from the constant term up, so `[c0, c1, c2]` is
+//! `c0 + c1 * x + c2 * x^2`. [`eval_horner`] evaluates them with Horner's scheme, a chain of
+//! multiply-adds which each wait on the previous one. [`eval_estrin`] uses Estrin's scheme
+//! instead, which evaluates independent halves of the polynomial and combines them with powers of
+//! `x`: it needs a few more multiplications, but the independent multiply-adds can execute in
+//! parallel, so it's usually faster for polynomials of degree 5 or more. Both fuse their
+//! multiplications and additions under contraction.
+//!
+//! The [`polynomial!`](crate::polynomial) macro unrolls Horner's scheme for a fixed list of
+//! coefficients, which can then be inlined into the surrounding code like a handwritten
+//! expression. [`fit`] computes the coefficients which best fit a set of points.
+//!
+//! ```
+//! use fast_fp::{ff64, poly, polynomial};
+//!
+//! // 1 + 2x + 3x^2 at x = 2
+//! let coeffs = [1.0, 2.0, 3.0].map(ff64);
+//! assert_eq!(poly::eval_horner(&coeffs, ff64(2.0)), 17.0);
+//! assert_eq!(poly::eval_estrin(&coeffs, ff64(2.0)), 17.0);
+//! assert_eq!(polynomial!(ff64(2.0); 1.0, 2.0, 3.0), 17.0);
+//! ```
+
+use crate::FastFloat;
+
+/// Evaluate the polynomial with the given coefficients (constant term first) at `x`, with
+/// Horner's scheme.
+///
+/// The polynomial with no coefficients is zero.
+#[inline]
+pub fn eval_horner<T: FastFloat>(coeffs: &[T], x: T) -> T {
+    coeffs.iter().rev().fold(T::ZERO, |acc, &c| acc * x + c)
+}
+
+/// Evaluate the polynomial with the given coefficients (constant term first) at `x`, with
+/// Estrin's scheme.
+///
+/// The coefficients are split where the lower part has a power of two terms, and the two parts are
+/// evaluated independently and recombined as `lower + x^(2^k) * upper`, with the powers of `x`
+/// computed by repeated squaring. The result is the same polynomial as [`eval_horner`]'s, but
+/// rounded differently.
+///
+/// The polynomial with no coefficients is zero.
+#[inline]
+pub fn eval_estrin<T: FastFloat>(coeffs: &[T], x: T) -> T {
+    // powers[k] = x^(2^k), up to the largest split of the coefficients
+    fn estrin<T: FastFloat>(coeffs: &[T], powers: &[T]) -> T {
+        match coeffs.len() {
+            0 => T::ZERO,
+            1 => coeffs[0],
+            2 => coeffs[0] + coeffs[1] * powers[0],
+            len => {
+                let k = (usize::BITS - 1 - (len - 1).leading_zeros()) as usize;
+                let (lower, upper) = coeffs.split_at(1 << k);
+                estrin(lower, powers) + estrin(upper, powers) * powers[k]
+            }
+        }
+    }
+
+    let mut powers = [x; usize::BITS as usize];
+    let mut k = 1;
+    while k < powers.len() && coeffs.len() > (1 << k) {
+        powers[k] = powers[k - 1] * powers[k - 1];
+        k += 1;
+    }
+    estrin(coeffs, &powers[..k])
+}
+
+/// Fit a polynomial of the given degree to the points `(xs[i], ys[i])` by least squares,
+/// returning its `degree + 1` coefficients (constant term first).
+///
+/// The least-squares system is solved by a Householder QR decomposition of its Vandermonde matrix,
+/// which avoids squaring the matrix's condition number like the normal equations would. The
+/// condition number still grows quickly with the degree and with the distance of the `xs` from
+/// zero, so for higher degrees the `xs` are best centered and scaled to about `[-1, 1]` first.
+/// There must be at least `degree + 1` distinct `xs`, otherwise the fit isn't unique and the
+/// result is unspecified.
+///
+/// ```
+/// use fast_fp::{ff64, poly};
+///
+/// // points on 1 - 2x + 0.5x^2
+/// let xs = [-2.0, -1.0, 0.0, 1.0, 2.0, 3.0].map(ff64);
+/// let ys = xs.map(|x| 1.0 - 2.0 * x + 0.5 * x * x);
+///
+/// let coeffs = poly::fit(&xs, &ys, 2);
+/// assert!((coeffs[0] - 1.0).abs() < ff64(1e-12));
+/// assert!((coeffs[1] + 2.0).abs() < ff64(1e-12));
+/// assert!((coeffs[2] - 0.5).abs() < ff64(1e-12));
+/// ```
+///
+/// # Panics
+/// Panics if the slices have different lengths, or have fewer than `degree + 1` points
+#[inline]
+pub fn fit<T: FastFloat>(xs: &[T], ys: &[T], degree: usize) -> Vec<T> {
+    assert_eq!(xs.len(), ys.len(), "slice lengths differ");
+    assert!(xs.len() > degree, "too few points for the degree");
+    let (n, m) = (xs.len(), degree + 1);
+
+    // the Vandermonde matrix in column-major order, column k holding xs^k
+    let mut a = vec![T::ONE; n * m];
+    for k in 1..m {
+        for i in 0..n {
+            a[k * n + i] = a[(k - 1) * n + i] * xs[i];
+        }
+    }
+    let mut b = ys.to_vec();
+
+    // Each reflection zeroes column j below the diagonal, and is applied to the remaining columns
+    // and to b. Its sign is taken from the diagonal with copysign, so choosing it needs no
+    // comparison
+    let mut v = vec![T::ZERO; n];
+    for j in 0..m {
+        let column = &a[j * n + j..(j + 1) * n];
+        let norm = column.iter().map(|&a| a * a).sum::<T>().sqrt();
+        let alpha = -norm.copysign(column[0]);
+        let v = &mut v[j..];
+        v.copy_from_slice(column);
+        v[0] -= alpha;
+        let scale = T::from_f64(2.0) / v.iter().map(|&v| v * v).sum::<T>();
+
+        let reflect = |x: &mut [T]| {
+            let s = scale * v.iter().zip(x.iter()).map(|(&v, &x)| v * x).sum::<T>();
+            for (x, &v) in x.iter_mut().zip(v.iter()) {
+                *x -= s * v;
+            }
+        };
+        for k in j..m {
+            reflect(&mut a[k * n + j..(k + 1) * n]);
+        }
+        reflect(&mut b[j..]);
+    }
+
+    // back substitution with the upper triangle R, against the first m rows of Q^T b
+    let mut coeffs = b[..m].to_vec();
+    for j in (0..m).rev() {
+        for k in j + 1..m {
+            let (r, c) = (a[k * n + j], coeffs[k]);
+            coeffs[j] -= r * c;
+        }
+        coeffs[j] /= a[j * n + j];
+    }
+    coeffs
+}
+
+/// Evaluate a polynomial with a fixed list of coefficients (constant term first), unrolled into a
+/// single expression with Horner's scheme.
+///
+/// `polynomial!(x; c0, c1, c2)` expands to `c0 + x * (c1 + x * c2)`, with `x` evaluated only once.
+/// There must be at least two coefficients, which can be fast values or plain floating point
+/// literals; the result is always a fast value.
+///
+/// ```
+/// use fast_fp::{ff32, polynomial};
+///
+/// // the first terms of the Taylor series of exp
+/// let x = ff32(0.1);
+/// let exp = polynomial!(x; 1.0, 1.0, 0.5, 1.0 / 6.0, 1.0 / 24.0);
+/// assert!((exp - x.exp()).abs() < ff32(1e-6));
+/// ```
+#[macro_export]
+macro_rules! polynomial {
+    // the nested terms, with x already evaluated into a variable
+    (@horner $x:ident; $c:expr) => {
+        $c
+    };
+    (@horner $x:ident; $c:expr, $($rest:expr),+) => {
+        $c + $x * $crate::polynomial!(@horner $x; $($rest),+)
+    };
+    ($x:expr; $c:expr, $($rest:expr),+ $(,)?) => {{
+        let x = $x;
+        $crate::polynomial!(@horner x; $c, $($rest),+)
+    }};
+}
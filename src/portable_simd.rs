@@ -0,0 +1,70 @@
+#![cfg(feature = "portable-simd")]
+#![cfg_attr(docsrs, doc(cfg(feature = "portable-simd")))]
+
+use crate::{FF32x4, FF32x8, FF64x4, FastSimdExt, FF32, FF64};
+use core::simd::Simd;
+
+macro_rules! impl_portable_simd {
+    ($fast_ty:ident, $base_ty:ident; $($simd_ty:ident, $lanes:literal;)*) => {
+        // the set of supported lane counts has changed across nightly versions, so reductions are
+        // implemented for each power-of-two count instead of generically
+        impl_portable_simd! { @reduce $fast_ty, $base_ty: 1, 2, 4, 8, 16, 32, 64 }
+
+        $(
+            impl From<Simd<$base_ty, $lanes>> for $simd_ty {
+                #[inline(always)]
+                fn from(from: Simd<$base_ty, $lanes>) -> Self {
+                    <$simd_ty>::new(from.to_array())
+                }
+            }
+
+            impl From<$simd_ty> for Simd<$base_ty, $lanes> {
+                #[inline(always)]
+                fn from(from: $simd_ty) -> Self {
+                    // std's vectors are no longer in our API control, so lanes must be frozen
+                    Simd::from_array(from.into())
+                }
+            }
+        )*
+    };
+
+    (@reduce $fast_ty:ident, $base_ty:ident: $($lanes:literal),*) => {
+        $(
+            impl FastSimdExt for Simd<$base_ty, $lanes> {
+                type Scalar = $fast_ty;
+
+                #[inline]
+                fn fast_reduce_sum(self) -> $fast_ty {
+                    self.to_array().iter().sum()
+                }
+
+                #[inline]
+                fn fast_reduce_min(self) -> $fast_ty {
+                    let lanes = self.to_array();
+                    lanes[1..]
+                        .iter()
+                        .fold(<$fast_ty>::new(lanes[0]), |acc, &lane| acc.min(<$fast_ty>::new(lane)))
+                }
+
+                #[inline]
+                fn fast_reduce_max(self) -> $fast_ty {
+                    let lanes = self.to_array();
+                    lanes[1..]
+                        .iter()
+                        .fold(<$fast_ty>::new(lanes[0]), |acc, &lane| acc.max(<$fast_ty>::new(lane)))
+                }
+            }
+        )*
+    };
+}
+
+impl_portable_simd! {
+    FF32, f32;
+    FF32x4, 4;
+    FF32x8, 8;
+}
+
+impl_portable_simd! {
+    FF64, f64;
+    FF64x4, 4;
+}
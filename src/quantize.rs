@@ -0,0 +1,318 @@
+//! Conversions between fast values and 8-bit quantized integers.
+//!
+//! Values are quantized with an affine mapping, `q = round(x / scale) + zero_point`, clamped to
+//! the range of the integer type, and dequantized by the reverse, `x = (q - zero_point) * scale`.
+//! The zero point is the integer which represents zero exactly. Ties round away from zero.
+//!
+//! [`calibrate_i8`] and [`calibrate_u8`] choose the scale and zero point which cover the range of
+//! some values, so that only rounding is lost when quantizing them.
+//!
+//! Converting a float to an integer can't accept poison values, so the values to quantize are
+//! frozen a block at a time before the conversion loop runs in C.
+//!
+//! ```
+//! use fast_fp::{ff32, quantize};
+//!
+//! let weights = [-1.0, 0.0, 0.5, 3.0].map(ff32);
+//! let (scale, zero_point) = quantize::calibrate_u8(&weights).unwrap();
+//! assert_eq!((scale, zero_point), (ff32(4.0 / 255.0), 64));
+//!
+//! let mut q = [0; 4];
+//! quantize::quantize_u8(&weights, scale, zero_point, &mut q);
+//! assert_eq!(q, [0, 64, 96, 255]);
+//!
+//! let mut x = [ff32(0.0); 4];
+//! quantize::dequantize_u8(&q, scale, zero_point, &mut x);
+//! assert_eq!(x[1], 0.0);
+//! assert!((x[2] - 0.5).abs() < scale);
+//! ```
+
+use crate::{stats, FF32, FF64};
+use paste::paste;
+
+// the number of values frozen at a time before calling the poison unsafe kernels
+const QUANTIZE_BLOCK: usize = 256;
+
+/// The fast scalar types which have quantization kernels, used to select the implementation of
+/// the functions in this module.
+pub trait QuantizeKernels: Sized {
+    /// See [`quantize_i8`]
+    fn quantize_i8(a: &[Self], scale: Self, zero_point: i8, out: &mut [i8]);
+
+    /// See [`quantize_u8`]
+    fn quantize_u8(a: &[Self], scale: Self, zero_point: u8, out: &mut [u8]);
+
+    /// See [`dequantize_i8`]
+    fn dequantize_i8(q: &[i8], scale: Self, zero_point: i8, out: &mut [Self]);
+
+    /// See [`dequantize_u8`]
+    fn dequantize_u8(q: &[u8], scale: Self, zero_point: u8, out: &mut [Self]);
+
+    /// See [`calibrate_i8`]
+    fn calibrate_i8(a: &[Self]) -> Option<(Self, i8)>;
+
+    /// See [`calibrate_u8`]
+    fn calibrate_u8(a: &[Self]) -> Option<(Self, u8)>;
+}
+
+/// Quantize each value of `a` to an `i8` in `out`, with the given scale and zero point.
+///
+/// Values beyond the range of `i8` after scaling are clamped to it.
+///
+/// # Panics
+/// Panics if `a` and `out` have different lengths, or if `scale` is not positive
+#[inline]
+pub fn quantize_i8<T: QuantizeKernels>(a: &[T], scale: T, zero_point: i8, out: &mut [i8]) {
+    T::quantize_i8(a, scale, zero_point, out)
+}
+
+/// Quantize each value of `a` to a `u8` in `out`, with the given scale and zero point.
+///
+/// Values beyond the range of `u8` after scaling are clamped to it.
+///
+/// # Panics
+/// Panics if `a` and `out` have different lengths, or if `scale` is not positive
+#[inline]
+pub fn quantize_u8<T: QuantizeKernels>(a: &[T], scale: T, zero_point: u8, out: &mut [u8]) {
+    T::quantize_u8(a, scale, zero_point, out)
+}
+
+/// Dequantize each `i8` of `q` to a fast value in `out`, with the given scale and zero point.
+///
+/// # Panics
+/// Panics if `q` and `out` have different lengths
+#[inline]
+pub fn dequantize_i8<T: QuantizeKernels>(q: &[i8], scale: T, zero_point: i8, out: &mut [T]) {
+    T::dequantize_i8(q, scale, zero_point, out)
+}
+
+/// Dequantize each `u8` of `q` to a fast value in `out`, with the given scale and zero point.
+///
+/// # Panics
+/// Panics if `q` and `out` have different lengths
+#[inline]
+pub fn dequantize_u8<T: QuantizeKernels>(q: &[u8], scale: T, zero_point: u8, out: &mut [T]) {
+    T::dequantize_u8(q, scale, zero_point, out)
+}
+
+/// The scale and zero point which map the range of `a` onto the range of `i8`, or `None` if `a`
+/// is empty.
+///
+/// The range is widened to include zero if necessary, so that zero is always represented
+/// exactly. A slice of zeros gets a scale of one.
+#[inline]
+pub fn calibrate_i8<T: QuantizeKernels>(a: &[T]) -> Option<(T, i8)> {
+    T::calibrate_i8(a)
+}
+
+/// The scale and zero point which map the range of `a` onto the range of `u8`, or `None` if `a`
+/// is empty.
+///
+/// Like [`calibrate_i8`], the range is widened to include zero if necessary.
+#[inline]
+pub fn calibrate_u8<T: QuantizeKernels>(a: &[T]) -> Option<(T, u8)> {
+    T::calibrate_u8(a)
+}
+
+macro_rules! impl_quantize {
+    ($fast_ty:ident, $base_ty:ident) => {
+        paste! {
+            #[cfg(not(scalar_fallback))]
+            #[link(name = "poison_unsafe")]
+            extern "C" {
+                // functions in the poison_unsafe lib must have their arguments frozen, so the
+                // inputs are passed as the base type
+                fn [<quantize_i8_ $base_ty>](
+                    x: *const $base_ty,
+                    out: *mut i8,
+                    len: usize,
+                    inv_scale: $base_ty,
+                    zero_point: $base_ty,
+                );
+                fn [<quantize_u8_ $base_ty>](
+                    x: *const $base_ty,
+                    out: *mut u8,
+                    len: usize,
+                    inv_scale: $base_ty,
+                    zero_point: $base_ty,
+                );
+            }
+
+            #[cfg(not(scalar_fallback))]
+            #[link(name = "poison_safe")]
+            extern "C" {
+                // like the scalar poison_safe functions, these can accept poison values
+                fn [<dequantize_i8_ $base_ty>](
+                    q: *const i8,
+                    out: *mut $fast_ty,
+                    len: usize,
+                    scale: $fast_ty,
+                    zero_point: $fast_ty,
+                );
+                fn [<dequantize_u8_ $base_ty>](
+                    q: *const u8,
+                    out: *mut $fast_ty,
+                    len: usize,
+                    scale: $fast_ty,
+                    zero_point: $fast_ty,
+                );
+            }
+
+            // with the strict feature, the shims are replaced by the same formulas over the base
+            // type, with the same clamping as in C
+            #[cfg(scalar_fallback)]
+            mod [<strict_quantize_ $base_ty>] {
+                use super::*;
+
+                #[inline(always)]
+                pub(super) unsafe fn [<quantize_i8_ $base_ty>](
+                    x: *const $base_ty,
+                    out: *mut i8,
+                    len: usize,
+                    inv_scale: $base_ty,
+                    zero_point: $base_ty,
+                ) {
+                    let x = core::slice::from_raw_parts(x, len);
+                    for (out, x) in core::slice::from_raw_parts_mut(out, len).iter_mut().zip(x) {
+                        let q = (x * inv_scale).round() + zero_point;
+                        *out = q.max(i8::MIN.into()).min(i8::MAX.into()) as i8;
+                    }
+                }
+
+                #[inline(always)]
+                pub(super) unsafe fn [<quantize_u8_ $base_ty>](
+                    x: *const $base_ty,
+                    out: *mut u8,
+                    len: usize,
+                    inv_scale: $base_ty,
+                    zero_point: $base_ty,
+                ) {
+                    let x = core::slice::from_raw_parts(x, len);
+                    for (out, x) in core::slice::from_raw_parts_mut(out, len).iter_mut().zip(x) {
+                        let q = (x * inv_scale).round() + zero_point;
+                        *out = q.max(u8::MIN.into()).min(u8::MAX.into()) as u8;
+                    }
+                }
+
+                #[inline(always)]
+                pub(super) unsafe fn [<dequantize_i8_ $base_ty>](
+                    q: *const i8,
+                    out: *mut $fast_ty,
+                    len: usize,
+                    scale: $fast_ty,
+                    zero_point: $fast_ty,
+                ) {
+                    let (scale, zero_point) = (scale.freeze_raw(), zero_point.freeze_raw());
+                    let q = core::slice::from_raw_parts(q, len);
+                    for (out, q) in core::slice::from_raw_parts_mut(out, len).iter_mut().zip(q) {
+                        *out = <$fast_ty>::new((*q as $base_ty - zero_point) * scale);
+                    }
+                }
+
+                #[inline(always)]
+                pub(super) unsafe fn [<dequantize_u8_ $base_ty>](
+                    q: *const u8,
+                    out: *mut $fast_ty,
+                    len: usize,
+                    scale: $fast_ty,
+                    zero_point: $fast_ty,
+                ) {
+                    let (scale, zero_point) = (scale.freeze_raw(), zero_point.freeze_raw());
+                    let q = core::slice::from_raw_parts(q, len);
+                    for (out, q) in core::slice::from_raw_parts_mut(out, len).iter_mut().zip(q) {
+                        *out = <$fast_ty>::new((*q as $base_ty - zero_point) * scale);
+                    }
+                }
+            }
+
+            #[cfg(scalar_fallback)]
+            use [<strict_quantize_ $base_ty>]::*;
+
+            // freeze a block of values at a time into a buffer, which the poison unsafe kernel
+            // converts into the matching block of the output
+            #[inline(always)]
+            fn [<quantize_frozen_ $base_ty>]<Q>(
+                a: &[$fast_ty],
+                scale: $fast_ty,
+                out: &mut [Q],
+                kernel: impl Fn(&[$base_ty], &mut [Q], $base_ty),
+            ) {
+                assert_eq!(a.len(), out.len(), "slice lengths differ");
+                assert!(scale > <$fast_ty>::ZERO, "scale must be positive");
+                let inv_scale = (<$fast_ty>::ONE / scale).freeze_raw();
+
+                let mut frozen = [0.0; QUANTIZE_BLOCK];
+                for (a, out) in a.chunks(QUANTIZE_BLOCK).zip(out.chunks_mut(QUANTIZE_BLOCK)) {
+                    let frozen = &mut frozen[..a.len()];
+                    for (frozen, a) in frozen.iter_mut().zip(a) {
+                        *frozen = a.freeze_raw();
+                    }
+                    kernel(frozen, out, inv_scale);
+                }
+            }
+
+            // the scale and zero point mapping the range of `a`, widened to include zero, onto
+            // the integers from `q_min` to `q_max`
+            #[inline(always)]
+            fn [<calibrate_ $base_ty>](a: &[$fast_ty], q_min: i32, q_max: i32) -> Option<($fast_ty, i32)> {
+                let (min, max) = stats::min_max(a)?;
+                let (min, max) = (min.min(<$fast_ty>::ZERO), max.max(<$fast_ty>::ZERO));
+                // the comparison freezes its operands, so a zero range is detected even for
+                // poison values
+                if max - min <= <$fast_ty>::ZERO {
+                    return Some((<$fast_ty>::ONE, 0));
+                }
+
+                let scale = (max - min) / (q_max - q_min) as $base_ty;
+                let offset = (min / scale).round().freeze_raw();
+                // `as` saturates, and the clamp absorbs any rounding past the end of the range
+                Some((scale, (q_min - offset as i32).clamp(q_min, q_max)))
+            }
+
+            impl QuantizeKernels for $fast_ty {
+                #[inline]
+                fn quantize_i8(a: &[Self], scale: Self, zero_point: i8, out: &mut [i8]) {
+                    [<quantize_frozen_ $base_ty>](a, scale, out, |x, out, inv_scale| unsafe {
+                        [<quantize_i8_ $base_ty>](x.as_ptr(), out.as_mut_ptr(), out.len(), inv_scale, zero_point.into())
+                    })
+                }
+
+                #[inline]
+                fn quantize_u8(a: &[Self], scale: Self, zero_point: u8, out: &mut [u8]) {
+                    [<quantize_frozen_ $base_ty>](a, scale, out, |x, out, inv_scale| unsafe {
+                        [<quantize_u8_ $base_ty>](x.as_ptr(), out.as_mut_ptr(), out.len(), inv_scale, zero_point.into())
+                    })
+                }
+
+                #[inline]
+                fn dequantize_i8(q: &[i8], scale: Self, zero_point: i8, out: &mut [Self]) {
+                    assert_eq!(q.len(), out.len(), "slice lengths differ");
+                    let zero_point = <$fast_ty>::new(zero_point.into());
+                    unsafe { [<dequantize_i8_ $base_ty>](q.as_ptr(), out.as_mut_ptr(), q.len(), scale, zero_point) }
+                }
+
+                #[inline]
+                fn dequantize_u8(q: &[u8], scale: Self, zero_point: u8, out: &mut [Self]) {
+                    assert_eq!(q.len(), out.len(), "slice lengths differ");
+                    let zero_point = <$fast_ty>::new(zero_point.into());
+                    unsafe { [<dequantize_u8_ $base_ty>](q.as_ptr(), out.as_mut_ptr(), q.len(), scale, zero_point) }
+                }
+
+                #[inline]
+                fn calibrate_i8(a: &[Self]) -> Option<(Self, i8)> {
+                    [<calibrate_ $base_ty>](a, i8::MIN.into(), i8::MAX.into())
+                        .map(|(scale, zero_point)| (scale, zero_point as i8))
+                }
+
+                #[inline]
+                fn calibrate_u8(a: &[Self]) -> Option<(Self, u8)> {
+                    [<calibrate_ $base_ty>](a, u8::MIN.into(), u8::MAX.into())
+                        .map(|(scale, zero_point)| (scale, zero_point as u8))
+                }
+            }
+        }
+    };
+}
+
+impl_quantize! { FF32, f32 }
+impl_quantize! { FF64, f64 }
@@ -0,0 +1,305 @@
+//! Rolling-window statistics, over whole slices or one value at a time.
+//!
+//! The window functions write one result for each full window in a slice: [`mean`] computes a
+//! moving average and [`variance`] a rolling sample variance. [`RollingMean`] and [`RollingVar`]
+//! compute the same statistics incrementally, over the most recent values pushed into them.
+//!
+//! Each update costs O(1) regardless of the window size: the statistics of a window are updated
+//! from the previous window's, by adding the new value and removing the oldest one. Rounding
+//! errors would accumulate across a long series of updates, so the statistics are recomputed from
+//! scratch once per `window` updates, which keeps the amortized cost constant.
+//!
+//! ```
+//! use fast_fp::{ff32, rolling::{self, RollingMean}, FF32};
+//!
+//! let a = [1.0, 2.0, 3.0, 4.0, 5.0].map(ff32);
+//! let mut out = [ff32(0.0); 3];
+//! rolling::mean(&a, 3, &mut out);
+//! assert_eq!(out, [2.0, 3.0, 4.0]);
+//!
+//! let mut mean = RollingMean::<FF32>::new(2);
+//! assert_eq!(mean.mean(), None);
+//! mean.push(ff32(1.0));
+//! mean.push(ff32(3.0));
+//! assert_eq!(mean.mean(), Some(ff32(2.0)));
+//! mean.push(ff32(5.0));
+//! assert_eq!(mean.mean(), Some(ff32(4.0)));
+//! ```
+
+use crate::{slice, stats, FF32, FF64};
+
+/// The fast scalar types which have rolling-window kernels, used to select the implementation of
+/// the functions in this module.
+pub trait RollingKernels: Sized {
+    /// See [`mean`]
+    fn rolling_mean(a: &[Self], window: usize, out: &mut [Self]);
+
+    /// See [`variance`]
+    fn rolling_variance(a: &[Self], window: usize, out: &mut [Self]);
+}
+
+/// Compute the mean of every window of `window` consecutive values in `a`, storing the results in
+/// `out`.
+///
+/// `out[i]` is the mean of `a[i..i + window]`.
+///
+/// # Panics
+/// Panics if `window` is zero or larger than `a`, or if `out` doesn't have exactly
+/// `a.len() - window + 1` elements
+#[inline]
+pub fn mean<T: RollingKernels>(a: &[T], window: usize, out: &mut [T]) {
+    T::rolling_mean(a, window, out)
+}
+
+/// Compute the sample variance of every window of `window` consecutive values in `a`, storing the
+/// results in `out`.
+///
+/// `out[i]` is the [`stats::variance`] of `a[i..i + window]`.
+///
+/// # Panics
+/// Panics if `window` is less than two or larger than `a`, or if `out` doesn't have exactly
+/// `a.len() - window + 1` elements
+#[inline]
+pub fn variance<T: RollingKernels>(a: &[T], window: usize, out: &mut [T]) {
+    T::rolling_variance(a, window, out)
+}
+
+/// The mean of the most recent values in a stream, updated in constant time.
+///
+/// Until `window` values have been pushed, this is the mean of all values so far.
+#[derive(Clone, Debug)]
+pub struct RollingMean<T> {
+    values: Vec<T>,
+    window: usize,
+    // the index of the oldest value once the window is full
+    next: usize,
+    sum: T,
+    updates: usize,
+}
+
+/// The mean and sample variance of the most recent values in a stream, updated in constant time.
+///
+/// Until `window` values have been pushed, these are the statistics of all values so far.
+///
+/// ```
+/// use fast_fp::{ff64, rolling::RollingVar, FF64};
+///
+/// let mut var = RollingVar::<FF64>::new(3);
+/// for value in [1.0, 2.0, 3.0, 10.0, 11.0, 12.0] {
+///     var.push(ff64(value));
+/// }
+/// assert_eq!(var.mean(), Some(ff64(11.0)));
+/// assert!((var.variance().unwrap() - 1.0).abs() < ff64(1e-12));
+/// ```
+#[derive(Clone, Debug)]
+pub struct RollingVar<T> {
+    values: Vec<T>,
+    window: usize,
+    next: usize,
+    mean: T,
+    // the sum of squared deviations from the mean
+    m2: T,
+    updates: usize,
+}
+
+macro_rules! impl_rolling {
+    ($fast_ty:ident, $base_ty:ident) => {
+        impl RollingKernels for $fast_ty {
+            #[inline]
+            fn rolling_mean(a: &[Self], window: usize, out: &mut [Self]) {
+                check_window(a.len(), window, 1, out.len());
+
+                let n = window as $base_ty;
+                let mut sum = Self::ZERO;
+                for (i, out) in out.iter_mut().enumerate() {
+                    if i % window == 0 {
+                        sum = slice::sum(&a[i..i + window]);
+                    } else {
+                        sum += a[i + window - 1] - a[i - 1];
+                    }
+                    *out = sum / n;
+                }
+            }
+
+            #[inline]
+            fn rolling_variance(a: &[Self], window: usize, out: &mut [Self]) {
+                check_window(a.len(), window, 2, out.len());
+
+                let n = window as $base_ty;
+                let (mut mean, mut m2) = (Self::ZERO, Self::ZERO);
+                for (i, out) in out.iter_mut().enumerate() {
+                    if i % window == 0 {
+                        let (new_mean, variance) = stats::mean_variance(&a[i..i + window]).unwrap();
+                        mean = new_mean;
+                        m2 = variance * (n - 1.0);
+                    } else {
+                        let (new, old) = (a[i + window - 1], a[i - 1]);
+                        let new_mean = mean + (new - old) / n;
+                        m2 = (m2 + (new - old) * (new - new_mean + old - mean)).max(Self::ZERO);
+                        mean = new_mean;
+                    }
+                    *out = m2 / (n - 1.0);
+                }
+            }
+        }
+
+        impl RollingMean<$fast_ty> {
+            /// Create an empty rolling mean over the last `window` values.
+            ///
+            /// # Panics
+            /// Panics if `window` is zero
+            #[inline]
+            pub fn new(window: usize) -> Self {
+                assert!(window > 0, "window must be non-zero");
+                RollingMean {
+                    values: Vec::with_capacity(window),
+                    window,
+                    next: 0,
+                    sum: <$fast_ty>::ZERO,
+                    updates: 0,
+                }
+            }
+
+            /// Add a value, removing the oldest one if the window is full
+            #[inline]
+            pub fn push(&mut self, value: $fast_ty) {
+                if self.values.len() < self.window {
+                    self.values.push(value);
+                    self.sum += value;
+                    return;
+                }
+
+                let old = core::mem::replace(&mut self.values[self.next], value);
+                self.next = (self.next + 1) % self.window;
+                self.updates += 1;
+                if self.updates == self.window {
+                    self.updates = 0;
+                    self.sum = slice::sum(&self.values);
+                } else {
+                    self.sum += value - old;
+                }
+            }
+
+            /// The mean of the values in the window, or `None` if no values have been pushed
+            #[inline]
+            pub fn mean(&self) -> Option<$fast_ty> {
+                if self.values.is_empty() {
+                    return None;
+                }
+                Some(self.sum / self.values.len() as $base_ty)
+            }
+
+            /// The number of values in the window
+            #[inline]
+            pub fn len(&self) -> usize {
+                self.values.len()
+            }
+
+            /// Whether no values have been pushed
+            #[inline]
+            pub fn is_empty(&self) -> bool {
+                self.values.is_empty()
+            }
+        }
+
+        impl RollingVar<$fast_ty> {
+            /// Create an empty rolling variance over the last `window` values.
+            ///
+            /// # Panics
+            /// Panics if `window` is less than two
+            #[inline]
+            pub fn new(window: usize) -> Self {
+                assert!(window >= 2, "window must have at least two values");
+                RollingVar {
+                    values: Vec::with_capacity(window),
+                    window,
+                    next: 0,
+                    mean: <$fast_ty>::ZERO,
+                    m2: <$fast_ty>::ZERO,
+                    updates: 0,
+                }
+            }
+
+            /// Add a value, removing the oldest one if the window is full
+            #[inline]
+            pub fn push(&mut self, value: $fast_ty) {
+                // Welford's algorithm, extended to remove the oldest value once the window is full
+                if self.values.len() < self.window {
+                    self.values.push(value);
+                    let delta = value - self.mean;
+                    self.mean += delta / self.values.len() as $base_ty;
+                    self.m2 += delta * (value - self.mean);
+                    return;
+                }
+
+                let old = core::mem::replace(&mut self.values[self.next], value);
+                self.next = (self.next + 1) % self.window;
+                self.updates += 1;
+                let n = self.window as $base_ty;
+                if self.updates == self.window {
+                    self.updates = 0;
+                    let (mean, variance) = stats::mean_variance(&self.values).unwrap();
+                    self.mean = mean;
+                    self.m2 = variance * (n - 1.0);
+                } else {
+                    let mean = self.mean + (value - old) / n;
+                    self.m2 = (self.m2 + (value - old) * (value - mean + old - self.mean))
+                        .max(<$fast_ty>::ZERO);
+                    self.mean = mean;
+                }
+            }
+
+            /// The mean of the values in the window, or `None` if no values have been pushed
+            #[inline]
+            pub fn mean(&self) -> Option<$fast_ty> {
+                if self.values.is_empty() {
+                    return None;
+                }
+                Some(self.mean)
+            }
+
+            /// The sample variance of the values in the window, or `None` if fewer than two
+            /// values have been pushed
+            #[inline]
+            pub fn variance(&self) -> Option<$fast_ty> {
+                if self.values.len() < 2 {
+                    return None;
+                }
+                Some(self.m2 / (self.values.len() - 1) as $base_ty)
+            }
+
+            /// The sample standard deviation of the values in the window, or `None` if fewer
+            /// than two values have been pushed
+            #[inline]
+            pub fn std_dev(&self) -> Option<$fast_ty> {
+                self.variance().map(<$fast_ty>::sqrt)
+            }
+
+            /// The number of values in the window
+            #[inline]
+            pub fn len(&self) -> usize {
+                self.values.len()
+            }
+
+            /// Whether no values have been pushed
+            #[inline]
+            pub fn is_empty(&self) -> bool {
+                self.values.is_empty()
+            }
+        }
+    };
+}
+
+#[inline(always)]
+fn check_window(len: usize, window: usize, min_window: usize, out_len: usize) {
+    assert!(window >= min_window, "window is too small");
+    assert!(window <= len, "window is larger than the slice");
+    assert_eq!(
+        out_len,
+        len - window + 1,
+        "output length differs from the number of windows"
+    );
+}
+
+impl_rolling! { FF32, f32 }
+impl_rolling! { FF64, f64 }
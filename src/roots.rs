@@ -0,0 +1,270 @@
+//! Root finding: the real roots of quadratic and cubic equations in closed form, and iterative
+//! solvers for any function.
+//!
+//! The closed forms are the solvers behind ray intersections, collision times and the parameters
+//! of points on splines. The textbook formulas lose most of their precision in common cases, so
+//! each uses a numerically careful formulation instead: [`quadratic`] never subtracts nearly equal
+//! values to compute a root, and [`cubic`] uses the trigonometric form when there are three real
+//! roots. The roots are returned in ascending order, in a [`Roots`] which holds up to three of them
+//! without allocating.
+//!
+//! [`newton`] and [`brent`] find a root of a function by iteration, for equations with no closed
+//! form such as implied volatilities and inverse kinematics. The function is an ordinary closure
+//! over the fast types, so each iteration runs under the fast-math flags.
+//!
+//! Every function here is generic over [`FastFloat`], so it accepts both [`FF32`](crate::FF32) and
+//! [`FF64`](crate::FF64).
+//!
+//! ```
+//! use fast_fp::{ff64, roots};
+//!
+//! // (x - 1)(x - 2) = x^2 - 3x + 2
+//! let r = roots::quadratic(ff64(1.0), ff64(-3.0), ff64(2.0));
+//! assert_eq!(r.as_slice(), [1.0, 2.0]);
+//!
+//! // x^2 + 1 has no real roots
+//! assert!(roots::quadratic(ff64(1.0), ff64(0.0), ff64(1.0)).as_slice().is_empty());
+//!
+//! // (x + 1)(x - 1)(x - 3) = x^3 - 3x^2 - x + 3
+//! let r = roots::cubic(ff64(1.0), ff64(-3.0), ff64(-1.0), ff64(3.0));
+//! assert_eq!(r.as_slice().len(), 3);
+//! assert!((r.as_slice()[0] + 1.0).abs() < ff64(1e-6));
+//! assert!((r.as_slice()[1] - 1.0).abs() < ff64(1e-6));
+//! assert!((r.as_slice()[2] - 3.0).abs() < ff64(1e-6));
+//!
+//! // the cube root of 2
+//! let x = roots::brent(|x| x * x * x - 2.0, ff64(0.0), ff64(2.0), ff64(1e-12)).unwrap();
+//! assert!((x - ff64(2.0).cbrt()).abs() < ff64(1e-12));
+//! ```
+
+use crate::FastFloat;
+
+// the iteration limit of the iterative solvers, far more than they need to converge on any
+// reasonable function
+const MAX_ITERATIONS: usize = 100;
+
+/// The real roots of a polynomial equation, in ascending order, of which there are at most three.
+///
+/// A double root of a [`quadratic`] appears twice. Rounding decides whether a repeated root of a
+/// [`cubic`] is computed as one root or as three, so it may appear only once.
+#[derive(Clone, Copy, Debug)]
+pub struct Roots<T> {
+    roots: [T; 3],
+    len: usize,
+}
+
+impl<T> Roots<T> {
+    /// The roots, in ascending order
+    #[inline]
+    pub fn as_slice(&self) -> &[T] {
+        &self.roots[..self.len]
+    }
+}
+
+/// The real roots of `a * x^2 + b * x + c = 0`.
+///
+/// The roots are computed as `q / a` and `c / q`, with `q = -(b + sign(b) * sqrt(b^2 - 4ac)) / 2`,
+/// which never subtracts the square root from a value of about the same size. A double root
+/// appears twice, and if `a` is zero the single root of the linear equation is returned. If both
+/// `a` and `b` are zero there are no roots.
+#[inline]
+pub fn quadratic<T: FastFloat>(a: T, b: T, c: T) -> Roots<T> {
+    let mut roots = Roots {
+        roots: [T::ZERO; 3],
+        len: 0,
+    };
+    // comparisons freeze their operands, so the branches are safe for poison values
+    if a == T::ZERO {
+        if b != T::ZERO {
+            roots.roots[0] = -c / b;
+            roots.len = 1;
+        }
+        return roots;
+    }
+
+    let discriminant = b * b - T::from_f64(4.0) * a * c;
+    if discriminant < T::ZERO {
+        return roots;
+    }
+
+    let q = T::from_f64(-0.5) * (b + discriminant.sqrt().copysign(b));
+    // q is only zero when b and c both are, which is a double root at zero
+    let (x0, x1) = if q == T::ZERO { (q, q) } else { (q / a, c / q) };
+    roots.roots[0] = x0.min(x1);
+    roots.roots[1] = x0.max(x1);
+    roots.len = 2;
+    roots
+}
+
+/// The real roots of `a * x^3 + b * x^2 + c * x + d = 0`.
+///
+/// The cubic is first divided by `a`, and shifted to remove its quadratic term. When it has three
+/// real roots they're computed with the trigonometric form, which avoids the complex arithmetic
+/// of Cardano's formula, and otherwise its single real root is computed with Cardano's formula.
+/// Roots which are repeated, or very nearly so, can lose up to two thirds of their precision. If
+/// `a` is zero the roots of the [`quadratic`] are returned.
+#[inline]
+pub fn cubic<T: FastFloat>(a: T, b: T, c: T, d: T) -> Roots<T> {
+    if a == T::ZERO {
+        return quadratic(b, c, d);
+    }
+
+    let lit = T::from_f64;
+    // x^3 + b x^2 + c x + d, with x = t - b / 3 giving t^3 - 3q t + 2r
+    let (b, c, d) = (b / a, c / a, d / a);
+    let shift = b * lit(1.0 / 3.0);
+    let q = (b * b - lit(3.0) * c) * lit(1.0 / 9.0);
+    let r = (b * (lit(2.0) * b * b - lit(9.0) * c) + lit(27.0) * d) * lit(1.0 / 54.0);
+    let q3 = q * q * q;
+
+    let mut roots = Roots {
+        roots: [T::ZERO; 3],
+        len: 0,
+    };
+    if r * r < q3 {
+        // with the scaling by -2 sqrt(q), the angles theta / 3 + 2 pi k / 3 for k = 0, -1, 1 give
+        // the roots in ascending order
+        let angle = (r / q3.sqrt()).acos() * lit(1.0 / 3.0);
+        let scale = lit(-2.0) * q.sqrt();
+        let step = lit(2.0 * core::f64::consts::FRAC_PI_3);
+        roots.roots[0] = scale * angle.cos() - shift;
+        roots.roots[1] = scale * (angle - step).cos() - shift;
+        roots.roots[2] = scale * (angle + step).cos() - shift;
+        roots.len = 3;
+    } else {
+        let s = -(r.abs() + (r * r - q3).sqrt()).cbrt().copysign(r);
+        let t = if s == T::ZERO { s } else { q / s };
+        roots.roots[0] = s + t - shift;
+        roots.len = 1;
+    }
+    roots
+}
+
+/// Find a root of `f` with Newton's method, starting from `x0`, given its derivative `df`.
+///
+/// Each step moves `x` by `-f(x) / df(x)`, and the iteration stops once a step is no larger than
+/// `tol`. Near a simple root the number of correct digits roughly doubles with each step, but
+/// from a poor starting point the iteration can wander or cycle, so it gives up after 100 steps.
+/// For a function which changes sign over a known interval, [`brent`] is guaranteed to converge.
+///
+/// Returns `None` if the derivative is zero at some step, or the iteration doesn't converge.
+///
+/// ```
+/// use fast_fp::{ff32, roots};
+///
+/// // the square root of 5
+/// let x = roots::newton(|x| x * x - 5.0, |x| 2.0 * x, ff32(2.0), ff32(1e-6)).unwrap();
+/// assert!((x - ff32(5.0).sqrt()).abs() < ff32(1e-6));
+/// ```
+#[inline]
+pub fn newton<T: FastFloat, F: FnMut(T) -> T, D: FnMut(T) -> T>(
+    mut f: F,
+    mut df: D,
+    x0: T,
+    tol: T,
+) -> Option<T> {
+    let mut x = x0;
+    for _ in 0..MAX_ITERATIONS {
+        let slope = df(x);
+        if slope == T::ZERO {
+            return None;
+        }
+        let step = f(x) / slope;
+        x -= step;
+        if step.abs() <= tol {
+            return Some(x);
+        }
+    }
+    None
+}
+
+/// Find a root of `f` in the interval between `a` and `b` with Brent's method, to within `tol`.
+///
+/// `f(a)` and `f(b)` must have opposite signs (or one be zero), so that the interval brackets a
+/// root. Each step takes an inverse quadratic interpolation or secant step when it stays well
+/// inside the bracket, and bisects it otherwise, so the method converges superlinearly on smooth
+/// functions yet never does much worse than bisection. `tol` is an absolute tolerance, to which
+/// a relative tolerance of a few ulps of the root is added.
+///
+/// Returns `None` if the interval doesn't bracket a root, or the iteration doesn't converge within
+/// 100 steps.
+#[inline]
+pub fn brent<T: FastFloat, F: FnMut(T) -> T>(mut f: F, a: T, b: T, tol: T) -> Option<T> {
+    // This follows zbrent from Numerical Recipes. b is the best estimate of the root, a is the
+    // previous one, and the root is bracketed between b and c
+    let (zero, one, two, half) = (T::ZERO, T::ONE, T::from_f64(2.0), T::from_f64(0.5));
+    let (mut a, mut b) = (a, b);
+    let (mut fa, mut fb) = (f(a), f(b));
+    if (fa > zero && fb > zero) || (fa < zero && fb < zero) {
+        return None;
+    }
+
+    let (mut c, mut fc) = (b, fb);
+    // the latest step, and the one before it
+    let (mut d, mut e) = (zero, zero);
+    for _ in 0..MAX_ITERATIONS {
+        if (fb > zero && fc > zero) || (fb < zero && fc < zero) {
+            c = a;
+            fc = fa;
+            d = b - a;
+            e = d;
+        }
+        if fc.abs() < fb.abs() {
+            a = b;
+            b = c;
+            c = a;
+            fa = fb;
+            fb = fc;
+            fc = fa;
+        }
+
+        let tol1 = two * T::EPSILON * b.abs() + half * tol;
+        let mid = half * (c - b);
+        if mid.abs() <= tol1 || fb == zero {
+            return Some(b);
+        }
+
+        if e.abs() >= tol1 && fa.abs() > fb.abs() {
+            let s = fb / fa;
+            let (mut p, mut q) = if a == c {
+                // secant
+                (two * mid * s, one - s)
+            } else {
+                // inverse quadratic interpolation
+                let (q, r) = (fa / fc, fb / fc);
+                (
+                    s * (two * mid * q * (q - r) - (b - a) * (r - one)),
+                    (q - one) * (r - one) * (s - one),
+                )
+            };
+            if p > zero {
+                q = -q;
+            }
+            p = p.abs();
+
+            // accept the interpolation only if it falls well inside the bracket, and shrinks
+            // faster than the step before last
+            let limit = (T::from_f64(3.0) * mid * q - (tol1 * q).abs()).min((e * q).abs());
+            if two * p < limit {
+                e = d;
+                d = p / q;
+            } else {
+                d = mid;
+                e = d;
+            }
+        } else {
+            d = mid;
+            e = d;
+        }
+
+        a = b;
+        fa = fb;
+        b += if d.abs() > tol1 {
+            d
+        } else {
+            tol1.copysign(mid)
+        };
+        fb = f(b);
+    }
+    None
+}
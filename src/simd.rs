@@ -0,0 +1,383 @@
+use crate::{poison::MaybePoison, FF32, FF64};
+use core::{
+    fmt,
+    ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Neg, Rem, RemAssign, Sub, SubAssign},
+};
+use paste::paste;
+
+// The SIMD types follow the same poison discipline as the scalar types: lanes are stored as a
+// single `MaybePoison` array, operations that can accept poison are called directly, and lanes are
+// frozen before any value leaves the API (comparisons, conversions to the base type, formatting).
+// Converting to and from arrays of the scalar fast types doesn't freeze, because those carry
+// poison just the same.
+
+/// A vector of four `f32` lanes which enables some fast-math optimizations.
+///
+/// The same restrictions apply to each lane as apply to [`FF32`]: lanes **MUST NOT** be infinite
+/// or NaN, and the output of any operation involving such values is unspecified.
+#[derive(Clone, Copy)]
+#[repr(transparent)]
+pub struct FF32x4(MaybePoison<[f32; 4]>);
+
+/// A vector of eight `f32` lanes which enables some fast-math optimizations.
+///
+/// The same restrictions apply to each lane as apply to [`FF32`]: lanes **MUST NOT** be infinite
+/// or NaN, and the output of any operation involving such values is unspecified.
+#[derive(Clone, Copy)]
+#[repr(transparent)]
+pub struct FF32x8(MaybePoison<[f32; 8]>);
+
+/// A vector of four `f64` lanes which enables some fast-math optimizations.
+///
+/// The same restrictions apply to each lane as apply to [`FF64`]: lanes **MUST NOT** be infinite
+/// or NaN, and the output of any operation involving such values is unspecified.
+#[derive(Clone, Copy)]
+#[repr(transparent)]
+pub struct FF64x4(MaybePoison<[f64; 4]>);
+
+/// Fast-math reductions for SIMD vectors of plain `f32` and `f64` lanes, such as those from
+/// `std::simd` (with the `portable-simd` feature) or the `wide` crate (with the `wide-v07`
+/// feature).
+///
+/// The lanes are converted to the fast scalar type, so the same restrictions apply: lanes **MUST
+/// NOT** be infinite or NaN, and the output of any reduction involving such values is unspecified.
+pub trait FastSimdExt {
+    /// The fast scalar type produced by reductions
+    type Scalar;
+
+    /// Sum all lanes, added in an unspecified order
+    fn fast_reduce_sum(self) -> Self::Scalar;
+
+    /// The minimum of all lanes
+    fn fast_reduce_min(self) -> Self::Scalar;
+
+    /// The maximum of all lanes
+    fn fast_reduce_max(self) -> Self::Scalar;
+}
+
+macro_rules! impl_binary_ops {
+    ($simd_ty:ident: $($op_trait:ident, $op_fn:ident, $assign_trait:ident, $assign_fn:ident, $shim:ident,)*) => {
+        $(
+            impl $op_trait<$simd_ty> for $simd_ty {
+                type Output = $simd_ty;
+
+                #[inline(always)]
+                fn $op_fn(self, other: $simd_ty) -> Self::Output {
+                    let mut out = self;
+                    unsafe { $shim(&mut out, &self, &other) };
+                    out
+                }
+            }
+
+            impl $assign_trait<$simd_ty> for $simd_ty {
+                #[inline(always)]
+                fn $assign_fn(&mut self, rhs: $simd_ty) {
+                    *self = <$simd_ty>::$op_fn(*self, rhs)
+                }
+            }
+        )*
+    };
+}
+
+macro_rules! impl_simd {
+    ($simd_ty:ident, $fast_ty:ident, $base_ty:ident, $lanes:literal, $shim:ident) => {
+        paste! {
+            #[cfg(not(scalar_fallback))]
+            #[link(name = "poison_safe")]
+            extern "C" {
+                // vectors are passed by pointer because arrays can't be passed by value over FFI.
+                // Like the scalar poison_safe functions, these can accept poison lanes
+                fn [<add_ $shim>](out: *mut $simd_ty, a: *const $simd_ty, b: *const $simd_ty);
+                fn [<sub_ $shim>](out: *mut $simd_ty, a: *const $simd_ty, b: *const $simd_ty);
+                fn [<mul_ $shim>](out: *mut $simd_ty, a: *const $simd_ty, b: *const $simd_ty);
+                fn [<div_ $shim>](out: *mut $simd_ty, a: *const $simd_ty, b: *const $simd_ty);
+                fn [<min_ $shim>](out: *mut $simd_ty, a: *const $simd_ty, b: *const $simd_ty);
+                fn [<max_ $shim>](out: *mut $simd_ty, a: *const $simd_ty, b: *const $simd_ty);
+                fn [<neg_ $shim>](out: *mut $simd_ty, a: *const $simd_ty);
+                fn [<abs_ $shim>](out: *mut $simd_ty, a: *const $simd_ty);
+
+                fn [<reduce_sum_ $shim>](a: *const $simd_ty) -> $fast_ty;
+                fn [<reduce_min_ $shim>](a: *const $simd_ty) -> $fast_ty;
+                fn [<reduce_max_ $shim>](a: *const $simd_ty) -> $fast_ty;
+            }
+
+            // with the strict feature, the shims are replaced by lane-wise loops over the base type
+            #[cfg(scalar_fallback)]
+            mod [<strict_ $shim>] {
+                use super::*;
+
+                #[inline(always)]
+                unsafe fn zip(
+                    out: *mut $simd_ty,
+                    a: *const $simd_ty,
+                    b: *const $simd_ty,
+                    op: fn($base_ty, $base_ty) -> $base_ty,
+                ) {
+                    let mut lanes = (*a).freeze_raw();
+                    for (lane, other) in lanes.iter_mut().zip((*b).freeze_raw().iter()) {
+                        *lane = op(*lane, *other);
+                    }
+                    *out = <$simd_ty>::new(lanes);
+                }
+
+                #[inline(always)]
+                unsafe fn map(out: *mut $simd_ty, a: *const $simd_ty, op: fn($base_ty) -> $base_ty) {
+                    *out = <$simd_ty>::new((*a).freeze_raw().map(op));
+                }
+
+                #[inline(always)]
+                unsafe fn fold(a: *const $simd_ty, op: fn($base_ty, $base_ty) -> $base_ty) -> $fast_ty {
+                    let lanes = (*a).freeze_raw();
+                    <$fast_ty>::new(lanes[1..].iter().fold(lanes[0], |acc, lane| op(acc, *lane)))
+                }
+
+                #[inline(always)]
+                pub(super) unsafe fn [<add_ $shim>](out: *mut $simd_ty, a: *const $simd_ty, b: *const $simd_ty) {
+                    zip(out, a, b, |a, b| a + b)
+                }
+
+                #[inline(always)]
+                pub(super) unsafe fn [<sub_ $shim>](out: *mut $simd_ty, a: *const $simd_ty, b: *const $simd_ty) {
+                    zip(out, a, b, |a, b| a - b)
+                }
+
+                #[inline(always)]
+                pub(super) unsafe fn [<mul_ $shim>](out: *mut $simd_ty, a: *const $simd_ty, b: *const $simd_ty) {
+                    zip(out, a, b, |a, b| a * b)
+                }
+
+                #[inline(always)]
+                pub(super) unsafe fn [<div_ $shim>](out: *mut $simd_ty, a: *const $simd_ty, b: *const $simd_ty) {
+                    zip(out, a, b, |a, b| a / b)
+                }
+
+                #[inline(always)]
+                pub(super) unsafe fn [<min_ $shim>](out: *mut $simd_ty, a: *const $simd_ty, b: *const $simd_ty) {
+                    zip(out, a, b, <$base_ty>::min)
+                }
+
+                #[inline(always)]
+                pub(super) unsafe fn [<max_ $shim>](out: *mut $simd_ty, a: *const $simd_ty, b: *const $simd_ty) {
+                    zip(out, a, b, <$base_ty>::max)
+                }
+
+                #[inline(always)]
+                pub(super) unsafe fn [<neg_ $shim>](out: *mut $simd_ty, a: *const $simd_ty) {
+                    map(out, a, |a| -a)
+                }
+
+                #[inline(always)]
+                pub(super) unsafe fn [<abs_ $shim>](out: *mut $simd_ty, a: *const $simd_ty) {
+                    map(out, a, <$base_ty>::abs)
+                }
+
+                #[inline(always)]
+                pub(super) unsafe fn [<reduce_sum_ $shim>](a: *const $simd_ty) -> $fast_ty {
+                    fold(a, |a, b| a + b)
+                }
+
+                #[inline(always)]
+                pub(super) unsafe fn [<reduce_min_ $shim>](a: *const $simd_ty) -> $fast_ty {
+                    fold(a, <$base_ty>::min)
+                }
+
+                #[inline(always)]
+                pub(super) unsafe fn [<reduce_max_ $shim>](a: *const $simd_ty) -> $fast_ty {
+                    fold(a, <$base_ty>::max)
+                }
+            }
+
+            #[cfg(scalar_fallback)]
+            use [<strict_ $shim>]::*;
+
+            impl $simd_ty {
+                /// The number of lanes in the vector
+                pub const LANES: usize = $lanes;
+
+                #[doc = "Create a new `"]
+                #[doc = stringify!($simd_ty)]
+                #[doc = "` instance from the given lane values."]
+                ///
+                /// The given values **MUST NOT** be infinite or NaN, and any operations involving
+                /// these values must not produce infinite or NaN results. The output of any such
+                /// operation is unspecified.
+                #[inline(always)]
+                pub const fn new(lanes: [$base_ty; $lanes]) -> Self {
+                    $simd_ty(MaybePoison::new(lanes))
+                }
+
+                /// Create a vector with every lane set to the given value
+                #[inline(always)]
+                pub fn splat(value: $fast_ty) -> Self {
+                    $simd_ty(MaybePoison::join([value.0; $lanes]))
+                }
+
+                /// Create a vector from an array of fast scalars
+                #[inline(always)]
+                pub fn from_array(lanes: [$fast_ty; $lanes]) -> Self {
+                    $simd_ty(MaybePoison::join(lanes.map(|lane| lane.0)))
+                }
+
+                /// Split the vector into an array of fast scalars
+                #[inline(always)]
+                pub fn to_array(self) -> [$fast_ty; $lanes] {
+                    self.0.split().map($fast_ty)
+                }
+
+                /// Get the value of the lane at the given index
+                ///
+                /// # Panics
+                /// Panics if the index is out of bounds
+                #[inline]
+                pub fn extract(self, index: usize) -> $fast_ty {
+                    self.to_array()[index]
+                }
+
+                /// Set the value of the lane at the given index
+                ///
+                /// # Panics
+                /// Panics if the index is out of bounds
+                #[inline]
+                pub fn replace(&mut self, index: usize, value: $fast_ty) {
+                    let mut lanes = self.to_array();
+                    lanes[index] = value;
+                    *self = Self::from_array(lanes);
+                }
+
+                #[inline(always)]
+                fn freeze_raw(self) -> [$base_ty; $lanes] {
+                    self.0.split().map(|lane| lane.freeze())
+                }
+
+                /// The lane-wise absolute value
+                #[inline]
+                pub fn abs(self) -> Self {
+                    let mut out = self;
+                    unsafe { [<abs_ $shim>](&mut out, &self) };
+                    out
+                }
+
+                /// The lane-wise minimum of two vectors
+                #[inline]
+                pub fn min(self, other: Self) -> Self {
+                    let mut out = self;
+                    unsafe { [<min_ $shim>](&mut out, &self, &other) };
+                    out
+                }
+
+                /// The lane-wise maximum of two vectors
+                #[inline]
+                pub fn max(self, other: Self) -> Self {
+                    let mut out = self;
+                    unsafe { [<max_ $shim>](&mut out, &self, &other) };
+                    out
+                }
+
+                /// The sum of all lanes, added in an unspecified order
+                #[inline]
+                pub fn reduce_sum(self) -> $fast_ty {
+                    unsafe { [<reduce_sum_ $shim>](&self) }
+                }
+
+                /// The minimum of all lanes
+                #[inline]
+                pub fn reduce_min(self) -> $fast_ty {
+                    unsafe { [<reduce_min_ $shim>](&self) }
+                }
+
+                /// The maximum of all lanes
+                #[inline]
+                pub fn reduce_max(self) -> $fast_ty {
+                    unsafe { [<reduce_max_ $shim>](&self) }
+                }
+            }
+
+            impl_binary_ops! {
+                $simd_ty:
+                Add, add, AddAssign, add_assign, [<add_ $shim>],
+                Sub, sub, SubAssign, sub_assign, [<sub_ $shim>],
+                Mul, mul, MulAssign, mul_assign, [<mul_ $shim>],
+                Div, div, DivAssign, div_assign, [<div_ $shim>],
+            }
+
+            // remainder is poison unsafe, so there's no vector shim; each lane is computed (and
+            // frozen) by the scalar implementation instead
+            impl Rem<$simd_ty> for $simd_ty {
+                type Output = $simd_ty;
+
+                #[inline]
+                fn rem(self, other: $simd_ty) -> Self::Output {
+                    let mut lanes = self.to_array();
+                    for (lane, other) in lanes.iter_mut().zip(other.to_array().iter()) {
+                        *lane %= *other;
+                    }
+                    <$simd_ty>::from_array(lanes)
+                }
+            }
+
+            impl RemAssign<$simd_ty> for $simd_ty {
+                #[inline(always)]
+                fn rem_assign(&mut self, rhs: $simd_ty) {
+                    *self = <$simd_ty>::rem(*self, rhs)
+                }
+            }
+
+            impl Neg for $simd_ty {
+                type Output = Self;
+
+                #[inline(always)]
+                fn neg(self) -> Self::Output {
+                    let mut out = self;
+                    unsafe { [<neg_ $shim>](&mut out, &self) };
+                    out
+                }
+            }
+
+            impl fmt::Debug for $simd_ty {
+                fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                    fmt::Debug::fmt(&self.freeze_raw(), f)
+                }
+            }
+
+            // like the scalar types, comparisons must freeze to keep poison out of bool branching
+            impl PartialEq for $simd_ty {
+                #[inline]
+                fn eq(&self, other: &$simd_ty) -> bool {
+                    self.freeze_raw() == other.freeze_raw()
+                }
+            }
+
+            impl From<[$base_ty; $lanes]> for $simd_ty {
+                #[inline(always)]
+                fn from(from: [$base_ty; $lanes]) -> Self {
+                    <$simd_ty>::new(from)
+                }
+            }
+
+            impl From<$simd_ty> for [$base_ty; $lanes] {
+                #[inline(always)]
+                fn from(from: $simd_ty) -> Self {
+                    from.freeze_raw()
+                }
+            }
+
+            impl From<[$fast_ty; $lanes]> for $simd_ty {
+                #[inline(always)]
+                fn from(from: [$fast_ty; $lanes]) -> Self {
+                    <$simd_ty>::from_array(from)
+                }
+            }
+
+            impl From<$simd_ty> for [$fast_ty; $lanes] {
+                #[inline(always)]
+                fn from(from: $simd_ty) -> Self {
+                    from.to_array()
+                }
+            }
+        }
+    };
+}
+
+impl_simd! { FF32x4, FF32, f32, 4, f32x4 }
+impl_simd! { FF32x8, FF32, f32, 8, f32x8 }
+impl_simd! { FF64x4, FF64, f64, 4, f64x4 }
@@ -0,0 +1,1116 @@
+//! Kernels over whole slices of fast values.
+//!
+//! Folding over a slice in rust (for example with [`Iterator::sum`]) calls into the fast-math
+//! operations one at a time, and relies on LTO to inline and vectorize the loop across the FFI
+//! boundary. These kernels instead run the entire loop in C, where the fast-math flags apply to
+//! the loop as a whole.
+//!
+//! ```
+//! use fast_fp::{ff32, slice, FF32};
+//!
+//! let a: Vec<FF32> = (1..=4).map(|i| ff32(i as f32)).collect();
+//! let b = vec![ff32(0.5); 4];
+//!
+//! assert_eq!(slice::sum(&a), 10.0);
+//! assert_eq!(slice::dot(&a, &b), 5.0);
+//! assert_eq!(slice::min(&a), Some(ff32(1.0)));
+//! assert_eq!(slice::max(&b), Some(ff32(0.5)));
+//! assert_eq!(slice::argmax(&a), Some(3));
+//!
+//! let mut c = a.clone();
+//! slice::offset(&mut c, ff32(-2.5));
+//! slice::clamp_in_place(&mut c, ff32(-1.0), ff32(1.0));
+//! assert_eq!(c, [-1.0, -0.5, 0.5, 1.0]);
+//!
+//! let mut d = vec![ff32(0.0); 4];
+//! slice::fma_slices(&mut d, &a, &b, &c);
+//! assert_eq!(d, [-0.5, 0.5, 2.0, 3.0]);
+//! ```
+//!
+//! The kernels handle any length, including the remainders of their vectorized loops. With small
+//! integer values, whose sums are exact in any order, they match a plain fold over the slice:
+//!
+//! ```
+//! use fast_fp::{ff32, slice, FF32};
+//!
+//! for len in [0, 1, 7, 33, 1000] {
+//!     let a: Vec<FF32> = (0..len).map(|i| ff32((i * 7 % 13) as f32 - 6.0)).collect();
+//!     let b: Vec<FF32> = (0..len).map(|i| ff32((i * 5 % 11) as f32 - 5.0)).collect();
+//!
+//!     assert_eq!(slice::sum(&a), a.iter().fold(ff32(0.0), |acc, &x| acc + x));
+//!     let dot = a.iter().zip(&b).fold(ff32(0.0), |acc, (&x, &y)| acc + x * y);
+//!     assert_eq!(slice::dot(&a, &b), dot);
+//!     assert_eq!(slice::min(&a), a.iter().copied().reduce(|m, x| if x < m { x } else { m }));
+//!     assert_eq!(slice::max(&a), a.iter().copied().reduce(|m, x| if x > m { x } else { m }));
+//!
+//!     // the first occurrence of the maximum
+//!     let argmax = (0..a.len()).reduce(|m, i| if a[i] > a[m] { i } else { m });
+//!     assert_eq!(slice::argmax(&a), argmax);
+//! }
+//! ```
+
+use crate::{MaybePoison, FF32, FF64};
+use paste::paste;
+
+// the shape of the deterministic sums, see `sum_deterministic`
+const DETERMINISTIC_LANES: usize = 8;
+const DETERMINISTIC_BLOCK: usize = 256;
+
+// the largest `k` for which `top_k` keeps a sorted buffer, instead of selecting over the whole
+// slice
+const TOP_K_BUFFERED: usize = 64;
+
+// the number of values taken from an iterator at a time by `FF32::sum_f64_acc`
+const SUM_F64_ACC_BLOCK: usize = 256;
+
+/// The fast scalar types which have slice kernels, used to select the implementation of the
+/// functions in this module.
+pub trait SliceKernels: Sized {
+    /// See [`sum`]
+    fn sum(a: &[Self]) -> Self;
+
+    /// See [`sum_deterministic`]
+    fn sum_deterministic(a: &[Self]) -> Self;
+
+    /// See [`sum_pairwise`]
+    fn sum_pairwise(a: &[Self]) -> Self;
+
+    /// See [`dot`]
+    fn dot(a: &[Self], b: &[Self]) -> Self;
+
+    /// See [`min`]
+    fn min(a: &[Self]) -> Option<Self>;
+
+    /// See [`max`]
+    fn max(a: &[Self]) -> Option<Self>;
+
+    /// See [`argmin`]
+    fn argmin(a: &[Self]) -> Option<usize>;
+
+    /// See [`argmax`]
+    fn argmax(a: &[Self]) -> Option<usize>;
+
+    /// See [`top_k`]
+    fn top_k(a: &[Self], k: usize) -> Vec<(usize, Self)>;
+
+    /// See [`sum_strided`]
+    fn sum_strided(a: Strided<Self>) -> Self;
+
+    /// See [`dot_strided`]
+    fn dot_strided(a: Strided<Self>, b: Strided<Self>) -> Self;
+
+    /// See [`min_strided`]
+    fn min_strided(a: Strided<Self>) -> Option<Self>;
+
+    /// See [`max_strided`]
+    fn max_strided(a: Strided<Self>) -> Option<Self>;
+
+    /// See [`fma_slices`]
+    fn fma_slices(dst: &mut [Self], a: &[Self], b: &[Self], c: &[Self]);
+
+    /// See [`scale`]
+    fn scale(a: &mut [Self], k: Self);
+
+    /// See [`offset`]
+    fn offset(a: &mut [Self], k: Self);
+
+    /// See [`abs_in_place`]
+    fn abs_in_place(a: &mut [Self]);
+
+    /// See [`clamp_in_place`]
+    fn clamp_in_place(a: &mut [Self], min: Self, max: Self);
+
+    /// See [`sort_fast`]
+    fn sort_fast(a: &mut [Self]);
+
+    /// See [`sort_unstable_fast`]
+    fn sort_unstable_fast(a: &mut [Self]);
+
+    /// See [`sort_by_key_cached`]
+    fn sort_by_key_cached<T, F: FnMut(&T) -> Self>(a: &mut [T], key: F);
+
+    /// See [`binary_search_ff`]
+    fn binary_search_ff(a: &[Self], x: Self) -> Result<usize, usize>;
+
+    /// See [`lower_bound`]
+    fn lower_bound(a: &[Self], x: Self) -> usize;
+
+    /// See [`partition_point`]
+    fn partition_point(a: &[Self], x: Self) -> usize;
+}
+
+/// A view of every `stride`th element of a slice, such as a column of a row-major matrix or a
+/// single channel of interleaved audio.
+///
+/// ```
+/// use fast_fp::{ff32, slice::{self, Strided}};
+///
+/// // a 3x2 row-major matrix
+/// let matrix = [1.0, 2.0, 3.0, 4.0, 5.0, 6.0].map(ff32);
+/// let second_column = Strided::new(&matrix[1..], 2);
+///
+/// assert_eq!(second_column.len(), 3);
+/// assert_eq!(slice::sum_strided(second_column), 12.0);
+/// ```
+#[derive(Debug)]
+pub struct Strided<'a, T> {
+    pub(crate) data: &'a [T],
+    pub(crate) stride: usize,
+}
+
+// manual impls avoid derive's `T: Clone` bound
+impl<'a, T> Clone for Strided<'a, T> {
+    #[inline(always)]
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<'a, T> Copy for Strided<'a, T> {}
+
+impl<'a, T> Strided<'a, T> {
+    /// Create a view of `data[0]`, `data[stride]`, `data[2 * stride]`, and so on, up to the end of
+    /// `data`.
+    ///
+    /// # Panics
+    /// Panics if `stride` is zero
+    #[inline]
+    pub fn new(data: &'a [T], stride: usize) -> Self {
+        assert!(stride > 0, "stride must be non-zero");
+        Strided { data, stride }
+    }
+
+    /// The number of elements in the view
+    #[inline]
+    pub fn len(&self) -> usize {
+        if self.data.is_empty() {
+            0
+        } else {
+            (self.data.len() - 1) / self.stride + 1
+        }
+    }
+
+    /// Whether the view has no elements
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.data.is_empty()
+    }
+
+    /// The distance between consecutive elements of the view
+    #[inline]
+    pub fn stride(&self) -> usize {
+        self.stride
+    }
+
+    /// Iterate over the elements of the view
+    #[inline]
+    pub fn iter(&self) -> impl Iterator<Item = &'a T> {
+        self.data.iter().step_by(self.stride)
+    }
+}
+
+impl<'a, T> From<&'a [T]> for Strided<'a, T> {
+    #[inline]
+    fn from(data: &'a [T]) -> Self {
+        Strided::new(data, 1)
+    }
+}
+
+/// Sum all values in a slice, added in an unspecified order.
+///
+/// The sum of an empty slice is zero.
+#[inline]
+pub fn sum<T: SliceKernels>(a: &[T]) -> T {
+    T::sum(a)
+}
+
+/// Sum all values in a slice in a fixed order, which only depends on the length of the slice.
+///
+/// The values are split into blocks of 256, each summed in 8 interleaved lanes, and the sums of
+/// the blocks are then added pairwise. Every addition follows IEEE 754 semantics, so unlike
+/// [`sum`] the result doesn't depend on how the compiler reassociates or vectorizes the code, and
+/// is identical from run to run and across targets. The independent lanes still allow the
+/// additions to be vectorized, and the pairwise tree keeps the rounding error smaller than that of
+/// a sequential sum such as [`FF32::strict_sum`](crate::FF32::strict_sum).
+///
+/// The result is the same as [`FF32::sum_deterministic`](crate::FF32::sum_deterministic) over the
+/// slice's values. The sum of an empty slice is zero.
+///
+/// ```
+/// use fast_fp::{ff32, slice, FF32};
+///
+/// let a: Vec<FF32> = (0..1000).map(|i| ff32(i as f32 * 0.1)).collect();
+///
+/// let sum = slice::sum_deterministic(&a);
+/// assert_eq!(sum, FF32::sum_deterministic(a.iter().copied()));
+/// assert!((sum - 49950.0).abs() < ff32(0.01));
+/// ```
+#[inline]
+pub fn sum_deterministic<T: SliceKernels>(a: &[T]) -> T {
+    T::sum_deterministic(a)
+}
+
+/// Sum all values in a slice by recursively splitting it in halves, adding the sums of the
+/// halves.
+///
+/// Once the halves are small enough, they're summed with the same vectorized loop as [`sum`]. The
+/// rounding error then grows with the logarithm of the slice's length, rather than linearly like
+/// a sequential sum, at almost the same speed as [`sum`].
+///
+/// The sum of an empty slice is zero.
+///
+/// ```
+/// use fast_fp::{ff32, slice, FF32};
+///
+/// // a sequential f32 sum of these drifts away from the exact result of 100000
+/// let a = vec![ff32(0.1); 1_000_000];
+///
+/// assert!((slice::sum_pairwise(&a) - 100_000.0).abs() < ff32(1.0));
+/// ```
+#[inline]
+pub fn sum_pairwise<T: SliceKernels>(a: &[T]) -> T {
+    T::sum_pairwise(a)
+}
+
+/// Sum all values in a slice of [`FF32`], accumulating in [`FF64`] precision.
+///
+/// Each value is widened as it's read, and the additions are in an unspecified order like
+/// [`sum`]. The rounding error of a long `f32` reduction grows with its length, while each value
+/// is exact in `f64`, so this is accurate to well beyond `f32` precision for any practical length
+/// at a similar speed. The iterator equivalent is [`FF32::sum_f64_acc`].
+///
+/// The sum of an empty slice is zero.
+///
+/// ```
+/// use fast_fp::{ff32, ff64, slice, FF32};
+///
+/// // the values are each 0.1 rounded to f32, which is slightly more than 0.1
+/// let a = vec![ff32(0.1); 1_000_000];
+///
+/// assert!((slice::sum_f64_acc(&a) - 100_000.0015).abs() < ff64(1e-4));
+/// assert!((FF32::sum_f64_acc(a.iter().copied()) - 100_000.0015).abs() < ff64(1e-4));
+/// ```
+#[inline]
+pub fn sum_f64_acc(a: &[FF32]) -> FF64 {
+    unsafe { sum_f64_acc_f32(a.as_ptr(), a.len()) }
+}
+
+/// Compute the dot product of two slices, `sum(a[i] * b[i])`, added in an unspecified order.
+///
+/// # Panics
+/// Panics if the two slices have different lengths
+#[inline]
+pub fn dot<T: SliceKernels>(a: &[T], b: &[T]) -> T {
+    T::dot(a, b)
+}
+
+/// The minimum value in a slice, or `None` if the slice is empty.
+#[inline]
+pub fn min<T: SliceKernels>(a: &[T]) -> Option<T> {
+    T::min(a)
+}
+
+/// The maximum value in a slice, or `None` if the slice is empty.
+#[inline]
+pub fn max<T: SliceKernels>(a: &[T]) -> Option<T> {
+    T::max(a)
+}
+
+/// The index of the minimum value in a slice, or `None` if the slice is empty.
+///
+/// If the minimum occurs more than once, this is the index of the first occurrence.
+///
+/// ```
+/// use fast_fp::{ff32, slice};
+///
+/// let a = [3.0, 1.0, 4.0, 1.0, 5.0].map(ff32);
+///
+/// assert_eq!(slice::argmin(&a), Some(1));
+/// assert_eq!(slice::argmax(&a), Some(4));
+/// ```
+#[inline]
+pub fn argmin<T: SliceKernels>(a: &[T]) -> Option<usize> {
+    T::argmin(a)
+}
+
+/// The index of the maximum value in a slice, or `None` if the slice is empty.
+///
+/// If the maximum occurs more than once, this is the index of the first occurrence.
+#[inline]
+pub fn argmax<T: SliceKernels>(a: &[T]) -> Option<usize> {
+    T::argmax(a)
+}
+
+/// The `k` largest values in a slice with their indices, ordered from largest to smallest.
+///
+/// Equal values are ordered by index, so the earliest occurrences are kept. If `k` is larger than
+/// the slice, every value is returned. NaN (with the `non-finite` feature) is ordered above every
+/// other value, as in [`sort_fast`].
+///
+/// For small `k` the values are scanned once, against the smallest of the largest values seen so
+/// far; most values fail that single comparison, and only those which pass are inserted into a
+/// sorted buffer of `k` values. Larger `k` instead partially sorts the whole slice, selecting the
+/// `k` largest values in linear time before sorting only those.
+///
+/// ```
+/// use fast_fp::{ff32, slice};
+///
+/// let scores = [0.1, 0.9, 0.4, 0.9, 0.7].map(ff32);
+///
+/// assert_eq!(slice::top_k(&scores, 3), [(1, ff32(0.9)), (3, ff32(0.9)), (4, ff32(0.7))]);
+/// ```
+#[inline]
+pub fn top_k<T: SliceKernels>(a: &[T], k: usize) -> Vec<(usize, T)> {
+    T::top_k(a, k)
+}
+
+// The index kernels compare possibly poison values, so the index they return may be poison too.
+// It's frozen and bounded to the slice, making it safe to use whatever value it was relaxed to
+#[inline(always)]
+pub(crate) fn kernel_index(index: MaybePoison<u64>, len: usize) -> usize {
+    (index.freeze() as usize).min(len - 1)
+}
+
+/// Sum all values in a strided view, added in an unspecified order.
+///
+/// The sum of an empty view is zero.
+#[inline]
+pub fn sum_strided<T: SliceKernels>(a: Strided<T>) -> T {
+    T::sum_strided(a)
+}
+
+/// Compute the dot product of two strided views, added in an unspecified order.
+///
+/// # Panics
+/// Panics if the two views have different lengths
+#[inline]
+pub fn dot_strided<T: SliceKernels>(a: Strided<T>, b: Strided<T>) -> T {
+    T::dot_strided(a, b)
+}
+
+/// The minimum value in a strided view, or `None` if the view is empty.
+#[inline]
+pub fn min_strided<T: SliceKernels>(a: Strided<T>) -> Option<T> {
+    T::min_strided(a)
+}
+
+/// The maximum value in a strided view, or `None` if the view is empty.
+#[inline]
+pub fn max_strided<T: SliceKernels>(a: Strided<T>) -> Option<T> {
+    T::max_strided(a)
+}
+
+/// Compute `a[i] * b[i] + c[i]` for every index, storing the results in `dst`.
+///
+/// The multiplication and addition may be fused into a single operation, like
+/// [`FF32::mul_add`](crate::FF32::mul_add).
+///
+/// # Panics
+/// Panics if the slices have different lengths
+#[inline]
+pub fn fma_slices<T: SliceKernels>(dst: &mut [T], a: &[T], b: &[T], c: &[T]) {
+    T::fma_slices(dst, a, b, c)
+}
+
+/// Multiply every value in a slice by `k`, in place.
+#[inline]
+pub fn scale<T: SliceKernels>(a: &mut [T], k: T) {
+    T::scale(a, k)
+}
+
+/// Add `k` to every value in a slice, in place.
+#[inline]
+pub fn offset<T: SliceKernels>(a: &mut [T], k: T) {
+    T::offset(a, k)
+}
+
+/// Replace every value in a slice with its absolute value.
+#[inline]
+pub fn abs_in_place<T: SliceKernels>(a: &mut [T]) {
+    T::abs_in_place(a)
+}
+
+/// Restrict every value in a slice to the interval `[min, max]`, in place.
+///
+/// # Panics
+/// Panics if `min > max`
+#[inline]
+pub fn clamp_in_place<T: SliceKernels>(a: &mut [T], min: T, max: T) {
+    T::clamp_in_place(a, min, max)
+}
+
+/// Sort a slice in ascending order, freezing each value only once.
+///
+/// Sorting with the comparison operators freezes both operands of every comparison. Instead, the
+/// values are frozen once into a buffer, which is sorted and written back. The sort is stable, and
+/// NaN (with the `non-finite` feature) sorts after every other value.
+///
+/// ```
+/// use fast_fp::{ff32, slice};
+///
+/// let mut a = [3.0, -1.0, 2.5, 0.0].map(ff32);
+/// slice::sort_fast(&mut a);
+/// assert_eq!(a, [-1.0, 0.0, 2.5, 3.0]);
+///
+/// let mut points = [(1, ff32(0.5)), (2, ff32(-0.5)), (3, ff32(0.25))];
+/// slice::sort_by_key_cached(&mut points, |&(_, x)| x * x);
+/// assert_eq!(points.map(|(i, _)| i), [3, 1, 2]);
+/// ```
+#[inline]
+pub fn sort_fast<T: SliceKernels>(a: &mut [T]) {
+    T::sort_fast(a)
+}
+
+/// Sort a slice in ascending order like [`sort_fast`], but without preserving the order of equal
+/// values (such as `-0` and `+0`), which is usually faster.
+#[inline]
+pub fn sort_unstable_fast<T: SliceKernels>(a: &mut [T]) {
+    T::sort_unstable_fast(a)
+}
+
+/// Sort a slice by a fast key, which is computed and frozen only once per element.
+///
+/// Like the standard library's `sort_by_cached_key`, this suits keys which are expensive to
+/// compute, such as distances. The sort is stable, and NaN keys sort last.
+#[inline]
+pub fn sort_by_key_cached<T, K: SliceKernels, F: FnMut(&T) -> K>(a: &mut [T], key: F) {
+    K::sort_by_key_cached(a, key)
+}
+
+/// Search a sorted slice for a value, like the standard library's `binary_search`.
+///
+/// Returns `Ok` with the index of a matching value, or `Err` with the index where the value could
+/// be inserted to keep the slice sorted. The value is frozen once for the whole search, and only
+/// the elements it's compared against are frozen as they're read. The slice must be sorted in the
+/// order of [`sort_fast`].
+///
+/// ```
+/// use fast_fp::{ff32, slice};
+///
+/// let a = [0.5, 1.0, 1.0, 2.0, 4.0].map(ff32);
+///
+/// assert_eq!(slice::binary_search_ff(&a, ff32(2.0)), Ok(3));
+/// assert_eq!(slice::binary_search_ff(&a, ff32(3.0)), Err(4));
+/// assert_eq!(slice::lower_bound(&a, ff32(1.0)), 1);
+/// assert_eq!(slice::partition_point(&a, ff32(1.0)), 3);
+/// ```
+#[inline]
+pub fn binary_search_ff<T: SliceKernels>(a: &[T], x: T) -> Result<usize, usize> {
+    T::binary_search_ff(a, x)
+}
+
+/// The index of the first value in a sorted slice which is not less than `x`, or the slice's
+/// length if there's none.
+///
+/// This is where `x` would be inserted before any equal values. Like [`binary_search_ff`], `x`
+/// is frozen only once.
+#[inline]
+pub fn lower_bound<T: SliceKernels>(a: &[T], x: T) -> usize {
+    T::lower_bound(a, x)
+}
+
+/// The index of the first value in a sorted slice which is greater than `x`, or the slice's
+/// length if there's none.
+///
+/// This is the partition point of the values at most `x` (the upper bound of `x`), where `x` would
+/// be inserted after any equal values. Like [`binary_search_ff`], `x` is frozen only once.
+#[inline]
+pub fn partition_point<T: SliceKernels>(a: &[T], x: T) -> usize {
+    T::partition_point(a, x)
+}
+
+macro_rules! impl_slice {
+    ($fast_ty:ident, $base_ty:ident) => {
+        paste! {
+            dispatch_kernels! {
+                // slices are passed as a pointer and a length. Like the scalar poison_safe
+                // functions, these can accept poison values
+                fn [<sum_slice_ $base_ty>](a: *const $fast_ty, len: usize) -> $fast_ty;
+                fn [<sum_pairwise_slice_ $base_ty>](a: *const $fast_ty, len: usize) -> $fast_ty;
+                fn [<dot_slice_ $base_ty>](a: *const $fast_ty, b: *const $fast_ty, len: usize) -> $fast_ty;
+                fn [<min_slice_ $base_ty>](a: *const $fast_ty, len: usize) -> $fast_ty;
+                fn [<max_slice_ $base_ty>](a: *const $fast_ty, len: usize) -> $fast_ty;
+                fn [<argmin_slice_ $base_ty>](a: *const $fast_ty, len: usize) -> MaybePoison<u64>;
+                fn [<argmax_slice_ $base_ty>](a: *const $fast_ty, len: usize) -> MaybePoison<u64>;
+
+                fn [<sum_strided_ $base_ty>](a: *const $fast_ty, stride: usize, len: usize) -> $fast_ty;
+                fn [<dot_strided_ $base_ty>](
+                    a: *const $fast_ty,
+                    a_stride: usize,
+                    b: *const $fast_ty,
+                    b_stride: usize,
+                    len: usize,
+                ) -> $fast_ty;
+                fn [<min_strided_ $base_ty>](a: *const $fast_ty, stride: usize, len: usize) -> $fast_ty;
+                fn [<max_strided_ $base_ty>](a: *const $fast_ty, stride: usize, len: usize) -> $fast_ty;
+
+                fn [<fma_slice_ $base_ty>](
+                    out: *mut $fast_ty,
+                    a: *const $fast_ty,
+                    b: *const $fast_ty,
+                    c: *const $fast_ty,
+                    len: usize,
+                );
+
+                fn [<scale_slice_ $base_ty>](a: *mut $fast_ty, len: usize, k: $fast_ty);
+                fn [<offset_slice_ $base_ty>](a: *mut $fast_ty, len: usize, k: $fast_ty);
+                fn [<abs_slice_ $base_ty>](a: *mut $fast_ty, len: usize);
+                fn [<clamp_slice_ $base_ty>](a: *mut $fast_ty, len: usize, min: $fast_ty, max: $fast_ty);
+            }
+
+            // with the strict feature, the shims are replaced by loops over the base type
+            #[cfg(scalar_fallback)]
+            mod [<strict_slice_ $base_ty>] {
+                use super::*;
+
+                #[inline(always)]
+                unsafe fn values<'a>(a: *const $fast_ty, len: usize) -> impl Iterator<Item = $base_ty> + 'a {
+                    core::slice::from_raw_parts(a, len).iter().map(|a| a.freeze_raw())
+                }
+
+                #[inline(always)]
+                unsafe fn fold(a: *const $fast_ty, len: usize, op: fn($base_ty, $base_ty) -> $base_ty) -> $fast_ty {
+                    let mut values = values(a, len);
+                    let first = values.next().unwrap();
+                    <$fast_ty>::new(values.fold(first, op))
+                }
+
+                #[inline(always)]
+                unsafe fn strided_values<'a>(
+                    a: *const $fast_ty,
+                    stride: usize,
+                    len: usize,
+                ) -> impl Iterator<Item = $base_ty> + 'a {
+                    (0..len).map(move |i| (*a.add(i * stride)).freeze_raw())
+                }
+
+                #[inline(always)]
+                unsafe fn map(a: *mut $fast_ty, len: usize, op: impl Fn($base_ty) -> $base_ty) {
+                    for a in core::slice::from_raw_parts_mut(a, len) {
+                        *a = <$fast_ty>::new(op(a.freeze_raw()));
+                    }
+                }
+
+                #[inline(always)]
+                pub(super) unsafe fn [<sum_slice_ $base_ty>](a: *const $fast_ty, len: usize) -> $fast_ty {
+                    <$fast_ty>::new(values(a, len).fold(0.0, |acc, a| acc + a))
+                }
+
+                // the same block size as the C kernel
+                const PAIRWISE_BLOCK: usize = 128;
+
+                fn sum_pairwise(a: &[$fast_ty]) -> $base_ty {
+                    if a.len() <= PAIRWISE_BLOCK {
+                        return a.iter().fold(0.0, |acc, a| acc + a.freeze_raw());
+                    }
+                    let (left, right) = a.split_at(a.len() / 2);
+                    sum_pairwise(left) + sum_pairwise(right)
+                }
+
+                #[inline(always)]
+                pub(super) unsafe fn [<sum_pairwise_slice_ $base_ty>](a: *const $fast_ty, len: usize) -> $fast_ty {
+                    <$fast_ty>::new(sum_pairwise(core::slice::from_raw_parts(a, len)))
+                }
+
+                #[inline(always)]
+                pub(super) unsafe fn [<dot_slice_ $base_ty>](a: *const $fast_ty, b: *const $fast_ty, len: usize) -> $fast_ty {
+                    <$fast_ty>::new(values(a, len).zip(values(b, len)).fold(0.0, |acc, (a, b)| acc + a * b))
+                }
+
+                #[inline(always)]
+                pub(super) unsafe fn [<min_slice_ $base_ty>](a: *const $fast_ty, len: usize) -> $fast_ty {
+                    fold(a, len, <$base_ty>::min)
+                }
+
+                #[inline(always)]
+                pub(super) unsafe fn [<max_slice_ $base_ty>](a: *const $fast_ty, len: usize) -> $fast_ty {
+                    fold(a, len, <$base_ty>::max)
+                }
+
+                // keeps the first of equal values, like the C kernels
+                #[inline(always)]
+                unsafe fn arg_fold(a: *const $fast_ty, len: usize, better: fn(&$base_ty, &$base_ty) -> bool) -> MaybePoison<u64> {
+                    let mut values = values(a, len).enumerate();
+                    let first = values.next().unwrap();
+                    let (index, _) = values.fold(first, |best, next| if better(&next.1, &best.1) { next } else { best });
+                    MaybePoison::new(index as u64)
+                }
+
+                #[inline(always)]
+                pub(super) unsafe fn [<argmin_slice_ $base_ty>](a: *const $fast_ty, len: usize) -> MaybePoison<u64> {
+                    arg_fold(a, len, <$base_ty as PartialOrd>::lt)
+                }
+
+                #[inline(always)]
+                pub(super) unsafe fn [<argmax_slice_ $base_ty>](a: *const $fast_ty, len: usize) -> MaybePoison<u64> {
+                    arg_fold(a, len, <$base_ty as PartialOrd>::gt)
+                }
+
+                #[inline(always)]
+                pub(super) unsafe fn [<sum_strided_ $base_ty>](a: *const $fast_ty, stride: usize, len: usize) -> $fast_ty {
+                    <$fast_ty>::new(strided_values(a, stride, len).fold(0.0, |acc, a| acc + a))
+                }
+
+                #[inline(always)]
+                pub(super) unsafe fn [<dot_strided_ $base_ty>](
+                    a: *const $fast_ty,
+                    a_stride: usize,
+                    b: *const $fast_ty,
+                    b_stride: usize,
+                    len: usize,
+                ) -> $fast_ty {
+                    let values = strided_values(a, a_stride, len).zip(strided_values(b, b_stride, len));
+                    <$fast_ty>::new(values.fold(0.0, |acc, (a, b)| acc + a * b))
+                }
+
+                #[inline(always)]
+                pub(super) unsafe fn [<min_strided_ $base_ty>](a: *const $fast_ty, stride: usize, len: usize) -> $fast_ty {
+                    let mut values = strided_values(a, stride, len);
+                    let first = values.next().unwrap();
+                    <$fast_ty>::new(values.fold(first, <$base_ty>::min))
+                }
+
+                #[inline(always)]
+                pub(super) unsafe fn [<max_strided_ $base_ty>](a: *const $fast_ty, stride: usize, len: usize) -> $fast_ty {
+                    let mut values = strided_values(a, stride, len);
+                    let first = values.next().unwrap();
+                    <$fast_ty>::new(values.fold(first, <$base_ty>::max))
+                }
+
+                #[inline(always)]
+                pub(super) unsafe fn [<fma_slice_ $base_ty>](
+                    out: *mut $fast_ty,
+                    a: *const $fast_ty,
+                    b: *const $fast_ty,
+                    c: *const $fast_ty,
+                    len: usize,
+                ) {
+                    let out = core::slice::from_raw_parts_mut(out, len);
+                    let inputs = values(a, len).zip(values(b, len)).zip(values(c, len));
+                    for (out, ((a, b), c)) in out.iter_mut().zip(inputs) {
+                        *out = <$fast_ty>::new(a * b + c);
+                    }
+                }
+
+                #[inline(always)]
+                pub(super) unsafe fn [<scale_slice_ $base_ty>](a: *mut $fast_ty, len: usize, k: $fast_ty) {
+                    let k = k.freeze_raw();
+                    map(a, len, |a| a * k)
+                }
+
+                #[inline(always)]
+                pub(super) unsafe fn [<offset_slice_ $base_ty>](a: *mut $fast_ty, len: usize, k: $fast_ty) {
+                    let k = k.freeze_raw();
+                    map(a, len, |a| a + k)
+                }
+
+                #[inline(always)]
+                pub(super) unsafe fn [<abs_slice_ $base_ty>](a: *mut $fast_ty, len: usize) {
+                    map(a, len, <$base_ty>::abs)
+                }
+
+                #[inline(always)]
+                pub(super) unsafe fn [<clamp_slice_ $base_ty>](a: *mut $fast_ty, len: usize, min: $fast_ty, max: $fast_ty) {
+                    let (min, max) = (min.freeze_raw(), max.freeze_raw());
+                    map(a, len, |a| a.clamp(min, max))
+                }
+            }
+
+            #[cfg(scalar_fallback)]
+            use [<strict_slice_ $base_ty>]::*;
+
+            // A total order of frozen values for the sorts and searches, with NaN after everything
+            // else. `total_cmp` would also order -0 before +0, but isn't available on the minimum
+            // supported rust version
+            #[inline(always)]
+            fn [<total_order_ $base_ty>](a: &$base_ty, b: &$base_ty) -> core::cmp::Ordering {
+                a.partial_cmp(b).unwrap_or_else(|| a.is_nan().cmp(&b.is_nan()))
+            }
+
+            // the cached keys of `sort_by_key_cached`, which must be Ord
+            struct [<SortKey $fast_ty>]($base_ty);
+
+            impl PartialEq for [<SortKey $fast_ty>] {
+                #[inline(always)]
+                fn eq(&self, other: &Self) -> bool {
+                    self.cmp(other) == core::cmp::Ordering::Equal
+                }
+            }
+
+            impl Eq for [<SortKey $fast_ty>] {}
+
+            impl PartialOrd for [<SortKey $fast_ty>] {
+                #[inline(always)]
+                fn partial_cmp(&self, other: &Self) -> Option<core::cmp::Ordering> {
+                    Some(self.cmp(other))
+                }
+            }
+
+            impl Ord for [<SortKey $fast_ty>] {
+                #[inline(always)]
+                fn cmp(&self, other: &Self) -> core::cmp::Ordering {
+                    [<total_order_ $base_ty>](&self.0, &other.0)
+                }
+            }
+
+            // The deterministic sums are written in rust with the base type's strict arithmetic,
+            // which the compiler may not reorder. The partial sums of the blocks are merged like a
+            // binary counter: two sums covering the same number of blocks are added as soon as
+            // the second one is complete, so the tree only depends on the number of values
+            struct [<DeterministicSum $fast_ty>] {
+                sums: [$base_ty; usize::BITS as usize],
+                blocks: [usize; usize::BITS as usize],
+                len: usize,
+            }
+
+            impl [<DeterministicSum $fast_ty>] {
+                #[inline(always)]
+                fn new() -> Self {
+                    Self {
+                        sums: [0.0; usize::BITS as usize],
+                        blocks: [0; usize::BITS as usize],
+                        len: 0,
+                    }
+                }
+
+                #[inline(always)]
+                fn push_block(&mut self, block: &[$fast_ty]) {
+                    let mut lanes = [0.0; DETERMINISTIC_LANES];
+                    let mut chunks = block.chunks_exact(DETERMINISTIC_LANES);
+                    for chunk in &mut chunks {
+                        for (lane, a) in lanes.iter_mut().zip(chunk) {
+                            *lane += a.freeze_raw();
+                        }
+                    }
+                    for (lane, a) in lanes.iter_mut().zip(chunks.remainder()) {
+                        *lane += a.freeze_raw();
+                    }
+
+                    let mut width = DETERMINISTIC_LANES;
+                    while width > 1 {
+                        width /= 2;
+                        for i in 0..width {
+                            lanes[i] += lanes[i + width];
+                        }
+                    }
+
+                    let (mut sum, mut blocks) = (lanes[0], 1);
+                    while self.len > 0 && self.blocks[self.len - 1] == blocks {
+                        self.len -= 1;
+                        sum += self.sums[self.len];
+                        blocks *= 2;
+                    }
+                    self.sums[self.len] = sum;
+                    self.blocks[self.len] = blocks;
+                    self.len += 1;
+                }
+
+                #[inline(always)]
+                fn finish(self) -> $fast_ty {
+                    let sum = self.sums[..self.len]
+                        .iter()
+                        .rev()
+                        .fold(0.0, |acc, sum| sum + acc);
+                    <$fast_ty>::new(sum)
+                }
+            }
+
+            impl $fast_ty {
+                /// Sum the values of an iterator in a fixed order, which only depends on the
+                /// number of values.
+                ///
+                /// The result is the same as [`slice::sum_deterministic`] over the same values,
+                /// see its documentation for details.
+                #[inline]
+                pub fn sum_deterministic<I>(iter: I) -> Self
+                    where I: IntoIterator<Item = Self>
+                {
+                    let mut iter = iter.into_iter();
+                    let mut sum = [<DeterministicSum $fast_ty>]::new();
+                    let mut block = [Self::ZERO; DETERMINISTIC_BLOCK];
+                    loop {
+                        let mut len = 0;
+                        for (slot, a) in block.iter_mut().zip(&mut iter) {
+                            *slot = a;
+                            len += 1;
+                        }
+                        if len == 0 {
+                            break;
+                        }
+                        sum.push_block(&block[..len]);
+                    }
+                    sum.finish()
+                }
+            }
+
+            impl SliceKernels for $fast_ty {
+                #[inline]
+                fn sum(a: &[Self]) -> Self {
+                    unsafe { [<sum_slice_ $base_ty>](a.as_ptr(), a.len()) }
+                }
+
+                #[inline]
+                fn sum_deterministic(a: &[Self]) -> Self {
+                    let mut sum = [<DeterministicSum $fast_ty>]::new();
+                    for block in a.chunks(DETERMINISTIC_BLOCK) {
+                        sum.push_block(block);
+                    }
+                    sum.finish()
+                }
+
+                #[inline]
+                fn sum_pairwise(a: &[Self]) -> Self {
+                    unsafe { [<sum_pairwise_slice_ $base_ty>](a.as_ptr(), a.len()) }
+                }
+
+                #[inline]
+                fn dot(a: &[Self], b: &[Self]) -> Self {
+                    assert_eq!(a.len(), b.len(), "slice lengths differ");
+                    unsafe { [<dot_slice_ $base_ty>](a.as_ptr(), b.as_ptr(), a.len()) }
+                }
+
+                #[inline]
+                fn min(a: &[Self]) -> Option<Self> {
+                    if a.is_empty() {
+                        return None;
+                    }
+                    Some(unsafe { [<min_slice_ $base_ty>](a.as_ptr(), a.len()) })
+                }
+
+                #[inline]
+                fn max(a: &[Self]) -> Option<Self> {
+                    if a.is_empty() {
+                        return None;
+                    }
+                    Some(unsafe { [<max_slice_ $base_ty>](a.as_ptr(), a.len()) })
+                }
+
+                #[inline]
+                fn argmin(a: &[Self]) -> Option<usize> {
+                    if a.is_empty() {
+                        return None;
+                    }
+                    let index = unsafe { [<argmin_slice_ $base_ty>](a.as_ptr(), a.len()) };
+                    Some(kernel_index(index, a.len()))
+                }
+
+                #[inline]
+                fn argmax(a: &[Self]) -> Option<usize> {
+                    if a.is_empty() {
+                        return None;
+                    }
+                    let index = unsafe { [<argmax_slice_ $base_ty>](a.as_ptr(), a.len()) };
+                    Some(kernel_index(index, a.len()))
+                }
+
+                #[inline]
+                fn top_k(a: &[Self], k: usize) -> Vec<(usize, Self)> {
+                    use core::cmp::Ordering;
+
+                    // values are frozen once and then compared as copies, so each one is seen
+                    // consistently. Larger values come first in the total order of the sorts, so
+                    // NaN before everything else, then lower indices
+                    fn order(x: &($base_ty, usize), y: &($base_ty, usize)) -> Ordering {
+                        [<total_order_ $base_ty>](&y.0, &x.0).then(x.1.cmp(&y.1))
+                    }
+
+                    let k = k.min(a.len());
+                    if k == 0 {
+                        return Vec::new();
+                    }
+
+                    let mut values = a.iter().map(|a| a.freeze_raw()).enumerate().map(|(i, a)| (a, i));
+                    let mut top: Vec<_>;
+                    if k <= TOP_K_BUFFERED {
+                        top = values.by_ref().take(k).collect();
+                        top.sort_by(order);
+
+                        let mut threshold = top[k - 1].0;
+                        for (value, i) in values {
+                            if [<total_order_ $base_ty>](&value, &threshold) == Ordering::Greater {
+                                // after any equal values, which have lower indices
+                                let position = top.partition_point(|(top, _)| {
+                                    [<total_order_ $base_ty>](top, &value) != Ordering::Less
+                                });
+                                top.pop();
+                                top.insert(position, (value, i));
+                                threshold = top[k - 1].0;
+                            }
+                        }
+                    } else {
+                        top = values.collect();
+                        top.select_nth_unstable_by(k - 1, order);
+                        top.truncate(k);
+                        top.sort_unstable_by(order);
+                    }
+
+                    top.into_iter().map(|(_, i)| (i, a[i])).collect()
+                }
+
+                #[inline]
+                fn sum_strided(a: Strided<Self>) -> Self {
+                    unsafe { [<sum_strided_ $base_ty>](a.data.as_ptr(), a.stride, a.len()) }
+                }
+
+                #[inline]
+                fn dot_strided(a: Strided<Self>, b: Strided<Self>) -> Self {
+                    assert_eq!(a.len(), b.len(), "view lengths differ");
+                    unsafe {
+                        [<dot_strided_ $base_ty>](
+                            a.data.as_ptr(),
+                            a.stride,
+                            b.data.as_ptr(),
+                            b.stride,
+                            a.len(),
+                        )
+                    }
+                }
+
+                #[inline]
+                fn min_strided(a: Strided<Self>) -> Option<Self> {
+                    if a.is_empty() {
+                        return None;
+                    }
+                    Some(unsafe { [<min_strided_ $base_ty>](a.data.as_ptr(), a.stride, a.len()) })
+                }
+
+                #[inline]
+                fn max_strided(a: Strided<Self>) -> Option<Self> {
+                    if a.is_empty() {
+                        return None;
+                    }
+                    Some(unsafe { [<max_strided_ $base_ty>](a.data.as_ptr(), a.stride, a.len()) })
+                }
+
+                #[inline]
+                fn fma_slices(dst: &mut [Self], a: &[Self], b: &[Self], c: &[Self]) {
+                    assert_eq!(dst.len(), a.len(), "slice lengths differ");
+                    assert_eq!(dst.len(), b.len(), "slice lengths differ");
+                    assert_eq!(dst.len(), c.len(), "slice lengths differ");
+                    unsafe {
+                        [<fma_slice_ $base_ty>](
+                            dst.as_mut_ptr(),
+                            a.as_ptr(),
+                            b.as_ptr(),
+                            c.as_ptr(),
+                            dst.len(),
+                        )
+                    }
+                }
+
+                #[inline]
+                fn scale(a: &mut [Self], k: Self) {
+                    unsafe { [<scale_slice_ $base_ty>](a.as_mut_ptr(), a.len(), k) }
+                }
+
+                #[inline]
+                fn offset(a: &mut [Self], k: Self) {
+                    unsafe { [<offset_slice_ $base_ty>](a.as_mut_ptr(), a.len(), k) }
+                }
+
+                #[inline]
+                fn abs_in_place(a: &mut [Self]) {
+                    unsafe { [<abs_slice_ $base_ty>](a.as_mut_ptr(), a.len()) }
+                }
+
+                #[inline]
+                fn clamp_in_place(a: &mut [Self], min: Self, max: Self) {
+                    assert!(min <= max);
+                    unsafe { [<clamp_slice_ $base_ty>](a.as_mut_ptr(), a.len(), min, max) }
+                }
+
+                #[inline]
+                fn sort_fast(a: &mut [Self]) {
+                    let mut values: Vec<$base_ty> = a.iter().map(|a| a.freeze_raw()).collect();
+                    values.sort_by([<total_order_ $base_ty>]);
+                    for (a, value) in a.iter_mut().zip(values) {
+                        *a = <$fast_ty>::new(value);
+                    }
+                }
+
+                #[inline]
+                fn sort_unstable_fast(a: &mut [Self]) {
+                    let mut values: Vec<$base_ty> = a.iter().map(|a| a.freeze_raw()).collect();
+                    values.sort_unstable_by([<total_order_ $base_ty>]);
+                    for (a, value) in a.iter_mut().zip(values) {
+                        *a = <$fast_ty>::new(value);
+                    }
+                }
+
+                #[inline]
+                fn sort_by_key_cached<T, F: FnMut(&T) -> Self>(a: &mut [T], mut key: F) {
+                    a.sort_by_cached_key(|a| [<SortKey $fast_ty>](key(a).freeze_raw()))
+                }
+
+                #[inline]
+                fn binary_search_ff(a: &[Self], x: Self) -> Result<usize, usize> {
+                    let x = x.freeze_raw();
+                    a.binary_search_by(|a| [<total_order_ $base_ty>](&a.freeze_raw(), &x))
+                }
+
+                #[inline]
+                fn lower_bound(a: &[Self], x: Self) -> usize {
+                    use core::cmp::Ordering;
+
+                    let x = x.freeze_raw();
+                    a.partition_point(|a| [<total_order_ $base_ty>](&a.freeze_raw(), &x) == Ordering::Less)
+                }
+
+                #[inline]
+                fn partition_point(a: &[Self], x: Self) -> usize {
+                    use core::cmp::Ordering;
+
+                    let x = x.freeze_raw();
+                    a.partition_point(|a| [<total_order_ $base_ty>](&a.freeze_raw(), &x) != Ordering::Greater)
+                }
+            }
+        }
+    };
+}
+
+impl_slice! { FF32, f32 }
+impl_slice! { FF64, f64 }
+
+#[cfg(not(scalar_fallback))]
+#[link(name = "poison_safe")]
+extern "C" {
+    // the mixed precision sum only exists for f32, and isn't compiled for each instruction set
+    fn sum_f64_acc_f32(a: *const FF32, len: usize) -> FF64;
+}
+
+// with the strict feature, the shim is replaced by a loop over the base types
+#[cfg(scalar_fallback)]
+unsafe fn sum_f64_acc_f32(a: *const FF32, len: usize) -> FF64 {
+    let sum = core::slice::from_raw_parts(a, len)
+        .iter()
+        .fold(0.0, |acc, a| acc + f64::from(a.freeze_raw()));
+    FF64::new(sum)
+}
+
+impl FF32 {
+    /// Sum the values of an iterator, accumulating in [`FF64`] precision.
+    ///
+    /// The values are collected into blocks, which are each summed like
+    /// [`slice::sum_f64_acc`](sum_f64_acc).
+    #[inline]
+    pub fn sum_f64_acc<I>(iter: I) -> FF64
+    where
+        I: IntoIterator<Item = Self>,
+    {
+        let mut iter = iter.into_iter();
+        let mut sum = FF64::ZERO;
+        let mut block = [Self::ZERO; SUM_F64_ACC_BLOCK];
+        loop {
+            let mut len = 0;
+            for (slot, a) in block.iter_mut().zip(&mut iter) {
+                *slot = a;
+                len += 1;
+            }
+            if len == 0 {
+                break;
+            }
+            sum += sum_f64_acc(&block[..len]);
+        }
+        sum
+    }
+}
@@ -0,0 +1,180 @@
+//! Evenly and logarithmically spaced sequences, for sample grids and plot axes.
+//!
+//! [`linspace`] and [`arange`] space their values evenly, by a count or by a step, and
+//! [`logspace`] and [`geomspace`] space them evenly in the logarithm, so that each value is a
+//! constant multiple of the one before. Each returns a [`Space`], an iterator which computes every
+//! value from its index rather than by repeatedly adding the step, so rounding errors don't
+//! accumulate along the sequence. The endpoints of [`linspace`] and [`geomspace`] are exactly the
+//! given ones. To build a `Vec` of the values, collect the iterator.
+//!
+//! ```
+//! use fast_fp::{ff64, space, FF64};
+//!
+//! let grid: Vec<FF64> = space::linspace(ff64(0.0), ff64(1.0), 5).collect();
+//! assert_eq!(grid, [0.0, 0.25, 0.5, 0.75, 1.0]);
+//!
+//! let steps: Vec<FF64> = space::arange(ff64(0.0), ff64(1.0), ff64(0.25)).collect();
+//! assert_eq!(steps, [0.0, 0.25, 0.5, 0.75]);
+//!
+//! let decades: Vec<FF64> = space::geomspace(ff64(1.0), ff64(1000.0), 4).collect();
+//! assert_eq!(decades[0], 1.0);
+//! assert!((decades[1] - 10.0).abs() < ff64(1e-6));
+//! assert!((decades[2] - 100.0).abs() < ff64(1e-6));
+//! assert_eq!(decades[3], 1000.0);
+//! ```
+
+use crate::FastFloat;
+
+/// `n` evenly spaced values from `start` to `end`, including both.
+///
+/// A single value is `start`, and no values are an empty sequence.
+#[inline]
+pub fn linspace<T: FastFloat>(start: T, end: T, n: usize) -> Space<T> {
+    let step = if n > 1 {
+        (end - start) / index(n - 1)
+    } else {
+        T::ZERO
+    };
+    Space::new(start, step, None, start, end, n)
+}
+
+/// The values `start + i * step` which are before `end`, which is excluded.
+///
+/// The number of values is computed up front, as `ceil((end - start) / step)`, so a value very
+/// close to `end` may be included or excluded depending on rounding. The step can be negative, to
+/// count down to `end`.
+///
+/// # Panics
+/// Panics if `step` is zero
+#[inline]
+pub fn arange<T: FastFloat>(start: T, end: T, step: T) -> Space<T> {
+    assert!(step != T::ZERO, "step must be non-zero");
+    // the conversion saturates, so a step away from the end gives no values
+    let n = ((end - start) / step).ceil().to_f64() as usize;
+    let last = start + step * index(n.saturating_sub(1));
+    Space::new(start, step, None, start, last, n)
+}
+
+/// `n` values from `base^start` to `base^end`, with evenly spaced exponents.
+///
+/// Every value, including the endpoints, is computed as `exp(ln(base) * exponent)`, so even with
+/// an integer base and exponents they're only approximately the powers of the base. For exact
+/// endpoints, pass the powers to [`geomspace`] instead, whose interior values are approximate in
+/// the same way.
+#[inline]
+pub fn logspace<T: FastFloat>(start: T, end: T, n: usize, base: T) -> Space<T> {
+    let ln_base = base.ln();
+    let step = if n > 1 {
+        (end - start) / index(n - 1)
+    } else {
+        T::ZERO
+    };
+    let (first, last) = ((ln_base * start).exp(), (ln_base * end).exp());
+    Space::new(start, step, Some(ln_base), first, last, n)
+}
+
+/// `n` values from `start` to `end`, including both, in a geometric progression.
+///
+/// Each value is the one before multiplied by `(end / start)^(1 / (n - 1))`. Only the endpoints
+/// are exact: the values between them are computed as `exp(ln(start) + i * step)`, so even a
+/// progression of integers such as powers of ten is only approximate between its ends.
+///
+/// # Panics
+/// Panics if `start` or `end` is not positive
+#[inline]
+pub fn geomspace<T: FastFloat>(start: T, end: T, n: usize) -> Space<T> {
+    assert!(start > T::ZERO && end > T::ZERO, "bounds must be positive");
+    let (ln_start, ln_end) = (start.ln(), end.ln());
+    let step = if n > 1 {
+        (ln_end - ln_start) / index(n - 1)
+    } else {
+        T::ZERO
+    };
+    Space::new(ln_start, step, Some(T::ONE), start, end, n)
+}
+
+/// An iterator over a spaced sequence of values, returned by the functions in this module.
+#[derive(Clone, Debug)]
+pub struct Space<T> {
+    // the value at index i is start + i * step, or exp(log_scale * (start + i * step)), except for
+    // the first and last values which are given directly
+    start: T,
+    step: T,
+    log_scale: Option<T>,
+    first: T,
+    last: T,
+    len: usize,
+    // the indices of the values which remain to be taken from the front and back
+    front: usize,
+    back: usize,
+}
+
+// an index or count as a fast value
+#[inline(always)]
+fn index<T: FastFloat>(i: usize) -> T {
+    T::from_f64(i as f64)
+}
+
+impl<T: FastFloat> Space<T> {
+    #[inline]
+    fn new(start: T, step: T, log_scale: Option<T>, first: T, last: T, len: usize) -> Self {
+        Space {
+            start,
+            step,
+            log_scale,
+            first,
+            last,
+            len,
+            front: 0,
+            back: len,
+        }
+    }
+
+    #[inline]
+    fn value(&self, i: usize) -> T {
+        if i == 0 {
+            return self.first;
+        }
+        if i == self.len - 1 {
+            return self.last;
+        }
+
+        let linear = self.start + self.step * index(i);
+        match self.log_scale {
+            Some(scale) => (scale * linear).exp(),
+            None => linear,
+        }
+    }
+}
+
+impl<T: FastFloat> Iterator for Space<T> {
+    type Item = T;
+
+    #[inline]
+    fn next(&mut self) -> Option<T> {
+        if self.front == self.back {
+            return None;
+        }
+        self.front += 1;
+        Some(self.value(self.front - 1))
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.back - self.front;
+        (len, Some(len))
+    }
+}
+
+impl<T: FastFloat> DoubleEndedIterator for Space<T> {
+    #[inline]
+    fn next_back(&mut self) -> Option<T> {
+        if self.front == self.back {
+            return None;
+        }
+        self.back -= 1;
+        Some(self.value(self.back))
+    }
+}
+
+impl<T: FastFloat> ExactSizeIterator for Space<T> {}
@@ -0,0 +1,486 @@
+//! Descriptive statistics over slices of fast values.
+//!
+//! Each function makes a single pass over its input, with the loop compiled in C under the
+//! fast-math flags and split across several independent accumulators. The functions accept slices
+//! of the plain `f32` and `f64` types too, which are read as the fast types without copying. The
+//! same restrictions apply to those values: they **MUST NOT** be infinite or NaN.
+//!
+//! [`layer_norm`] builds on the same single-pass mean and variance to normalize a slice in place,
+//! and [`linear_regression`] on the paired sums of [`covariance`] to fit a line to a set of points.
+//!
+//! ```
+//! use fast_fp::{ff64, stats};
+//!
+//! let a = [1.0, 2.0, 3.0, 4.0, 5.0].map(ff64);
+//!
+//! assert_eq!(stats::mean(&a), Some(ff64(3.0)));
+//! assert_eq!(stats::variance(&a), Some(ff64(2.5)));
+//! assert_eq!(stats::min_max(&a), Some((ff64(1.0), ff64(5.0))));
+//!
+//! let b = [2.0, 4.0, 6.0, 8.0, 10.0].map(ff64);
+//! assert_eq!(stats::covariance(&a, &b), Some(ff64(5.0)));
+//! assert!((stats::correlation(&a, &b).unwrap() - 1.0).abs() < ff64(1e-12));
+//!
+//! let mut x = [1.0, 3.0].map(ff64);
+//! stats::layer_norm(&mut x, &[2.0, 2.0].map(ff64), &[0.0, 1.0].map(ff64), ff64(1e-300));
+//! assert_eq!(x, [-2.0, 3.0]);
+//!
+//! // plain floats work too
+//! let b = [2.0f32, 4.0, 6.0];
+//! assert_eq!(stats::mean(&b), Some(4.0));
+//! assert_eq!(stats::std_dev(&b), Some(2.0));
+//! ```
+
+use crate::{slice, FF32, FF64};
+use paste::paste;
+
+/// The types which have statistics kernels, used to select the implementation of the functions in
+/// this module.
+pub trait StatsKernels: Sized {
+    /// See [`mean`]
+    fn mean(a: &[Self]) -> Option<Self>;
+
+    /// See [`variance`]
+    fn variance(a: &[Self]) -> Option<Self>;
+
+    /// See [`std_dev`]
+    fn std_dev(a: &[Self]) -> Option<Self>;
+
+    /// See [`mean_variance`]
+    fn mean_variance(a: &[Self]) -> Option<(Self, Self)>;
+
+    /// See [`min_max`]
+    fn min_max(a: &[Self]) -> Option<(Self, Self)>;
+
+    /// See [`covariance`]
+    fn covariance(a: &[Self], b: &[Self]) -> Option<Self>;
+
+    /// See [`correlation`]
+    fn correlation(a: &[Self], b: &[Self]) -> Option<Self>;
+
+    /// See [`linear_regression`]
+    fn linear_regression(xs: &[Self], ys: &[Self]) -> Option<(Self, Self, Self)>;
+
+    /// See [`layer_norm`]
+    fn layer_norm(x: &mut [Self], gamma: &[Self], beta: &[Self], eps: Self);
+}
+
+/// The arithmetic mean of a slice, or `None` if the slice is empty.
+#[inline]
+pub fn mean<T: StatsKernels>(a: &[T]) -> Option<T> {
+    T::mean(a)
+}
+
+/// The sample variance of a slice, or `None` if the slice has fewer than two values.
+///
+/// This is the unbiased estimate, which divides the sum of squared deviations by `n - 1`.
+#[inline]
+pub fn variance<T: StatsKernels>(a: &[T]) -> Option<T> {
+    T::variance(a)
+}
+
+/// The sample standard deviation of a slice (the square root of its [`variance`]), or `None` if
+/// the slice has fewer than two values.
+#[inline]
+pub fn std_dev<T: StatsKernels>(a: &[T]) -> Option<T> {
+    T::std_dev(a)
+}
+
+/// The mean and sample variance of a slice, computed together in a single pass, or `None` if the
+/// slice has fewer than two values.
+#[inline]
+pub fn mean_variance<T: StatsKernels>(a: &[T]) -> Option<(T, T)> {
+    T::mean_variance(a)
+}
+
+/// The minimum and maximum values of a slice, computed together in a single pass, or `None` if
+/// the slice is empty.
+#[inline]
+pub fn min_max<T: StatsKernels>(a: &[T]) -> Option<(T, T)> {
+    T::min_max(a)
+}
+
+/// The sample covariance of two paired slices, or `None` if they have fewer than two values.
+///
+/// Like [`variance`], this divides by `n - 1`.
+///
+/// # Panics
+/// Panics if the two slices have different lengths
+#[inline]
+pub fn covariance<T: StatsKernels>(a: &[T], b: &[T]) -> Option<T> {
+    T::covariance(a, b)
+}
+
+/// The Pearson correlation coefficient of two paired slices, or `None` if they have fewer than two
+/// values or either slice has no variance.
+///
+/// The sums needed for the covariance and both variances are computed together in a single pass.
+///
+/// # Panics
+/// Panics if the two slices have different lengths
+#[inline]
+pub fn correlation<T: StatsKernels>(a: &[T], b: &[T]) -> Option<T> {
+    T::correlation(a, b)
+}
+
+/// The least-squares line through the points `(xs[i], ys[i])`, as its slope, its intercept and
+/// the coefficient of determination `r2`, or `None` if there are fewer than two points or `xs` has
+/// no variance.
+///
+/// Like [`correlation`], the sums are computed together in a single pass. `r2` is the square of
+/// the correlation, the fraction of the variance of `ys` explained by the line, and is 1 when `ys`
+/// has no variance, since the line then fits exactly.
+///
+/// ```
+/// use fast_fp::stats;
+///
+/// let xs = [0.0, 1.0, 2.0, 3.0];
+/// let ys = [1.0, 3.0, 5.0, 7.0];
+/// let (slope, intercept, r2) = stats::linear_regression(&xs, &ys).unwrap();
+/// assert!((slope - 2.0f64).abs() < 1e-12);
+/// assert!((intercept - 1.0f64).abs() < 1e-12);
+/// assert!((r2 - 1.0f64).abs() < 1e-12);
+/// ```
+///
+/// # Panics
+/// Panics if the two slices have different lengths
+#[inline]
+pub fn linear_regression<T: StatsKernels>(xs: &[T], ys: &[T]) -> Option<(T, T, T)> {
+    T::linear_regression(xs, ys)
+}
+
+/// Normalize a slice in place to zero mean and unit variance, then scale and shift each value:
+/// `x[i] = (x[i] - mean) / sqrt(variance + eps) * gamma[i] + beta[i]`.
+///
+/// This is the layer normalization used in transformer models. Unlike [`variance`], the variance
+/// here is the population variance, which divides by `n`. The input is read twice: once for the
+/// mean and variance together, and once to normalize, scale and shift each value in a fused loop.
+///
+/// # Panics
+/// Panics if `gamma` or `beta` has a different length than `x`, or if `eps` is not positive
+#[inline]
+pub fn layer_norm<T: StatsKernels>(x: &mut [T], gamma: &[T], beta: &[T], eps: T) {
+    T::layer_norm(x, gamma, beta, eps)
+}
+
+macro_rules! impl_stats {
+    ($fast_ty:ident, $base_ty:ident) => {
+        paste! {
+            #[cfg(not(scalar_fallback))]
+            #[link(name = "poison_safe")]
+            extern "C" {
+                // slices are passed as a pointer and a length, and results are written through
+                // pointers. Like the scalar poison_safe functions, these can accept poison values
+                fn [<shifted_sums_ $base_ty>](
+                    x: *const $fast_ty,
+                    len: usize,
+                    shift: $fast_ty,
+                    sum: *mut $fast_ty,
+                    sum_squares: *mut $fast_ty,
+                );
+                fn [<min_max_ $base_ty>](x: *const $fast_ty, len: usize, min: *mut $fast_ty, max: *mut $fast_ty);
+                fn [<paired_sums_ $base_ty>](
+                    a: *const $fast_ty,
+                    b: *const $fast_ty,
+                    len: usize,
+                    shift_a: $fast_ty,
+                    shift_b: $fast_ty,
+                    sums: *mut $fast_ty,
+                );
+                fn [<layer_norm_apply_ $base_ty>](
+                    x: *mut $fast_ty,
+                    gamma: *const $fast_ty,
+                    beta: *const $fast_ty,
+                    len: usize,
+                    mean: $fast_ty,
+                    inv_std: $fast_ty,
+                );
+            }
+
+            // with the strict feature, the shims are replaced by loops over the base type
+            #[cfg(scalar_fallback)]
+            mod [<strict_stats_ $base_ty>] {
+                use super::*;
+
+                #[inline(always)]
+                pub(super) unsafe fn [<shifted_sums_ $base_ty>](
+                    x: *const $fast_ty,
+                    len: usize,
+                    shift: $fast_ty,
+                    sum: *mut $fast_ty,
+                    sum_squares: *mut $fast_ty,
+                ) {
+                    let shift = shift.freeze_raw();
+                    let (s, q) = core::slice::from_raw_parts(x, len)
+                        .iter()
+                        .map(|x| x.freeze_raw() - shift)
+                        .fold((0.0, 0.0), |(s, q), d| (s + d, q + d * d));
+                    *sum = <$fast_ty>::new(s);
+                    *sum_squares = <$fast_ty>::new(q);
+                }
+
+                #[inline(always)]
+                pub(super) unsafe fn [<min_max_ $base_ty>](x: *const $fast_ty, len: usize, min: *mut $fast_ty, max: *mut $fast_ty) {
+                    let mut values = core::slice::from_raw_parts(x, len).iter().map(|x| x.freeze_raw());
+                    let first = values.next().unwrap();
+                    let (lo, hi) = values.fold((first, first), |(lo, hi), x| (lo.min(x), hi.max(x)));
+                    *min = <$fast_ty>::new(lo);
+                    *max = <$fast_ty>::new(hi);
+                }
+
+                #[inline(always)]
+                pub(super) unsafe fn [<paired_sums_ $base_ty>](
+                    a: *const $fast_ty,
+                    b: *const $fast_ty,
+                    len: usize,
+                    shift_a: $fast_ty,
+                    shift_b: $fast_ty,
+                    sums: *mut $fast_ty,
+                ) {
+                    let (shift_a, shift_b) = (shift_a.freeze_raw(), shift_b.freeze_raw());
+                    let a = core::slice::from_raw_parts(a, len);
+                    let b = core::slice::from_raw_parts(b, len);
+                    let mut acc = [0.0; 5];
+                    for (a, b) in a.iter().zip(b) {
+                        let (da, db) = (a.freeze_raw() - shift_a, b.freeze_raw() - shift_b);
+                        acc[0] += da;
+                        acc[1] += db;
+                        acc[2] += da * da;
+                        acc[3] += db * db;
+                        acc[4] += da * db;
+                    }
+                    for (i, acc) in acc.iter().enumerate() {
+                        *sums.add(i) = <$fast_ty>::new(*acc);
+                    }
+                }
+
+                #[inline(always)]
+                pub(super) unsafe fn [<layer_norm_apply_ $base_ty>](
+                    x: *mut $fast_ty,
+                    gamma: *const $fast_ty,
+                    beta: *const $fast_ty,
+                    len: usize,
+                    mean: $fast_ty,
+                    inv_std: $fast_ty,
+                ) {
+                    let (mean, inv_std) = (mean.freeze_raw(), inv_std.freeze_raw());
+                    let x = core::slice::from_raw_parts_mut(x, len);
+                    let gamma = core::slice::from_raw_parts(gamma, len);
+                    let beta = core::slice::from_raw_parts(beta, len);
+                    for ((x, gamma), beta) in x.iter_mut().zip(gamma).zip(beta) {
+                        let y = (x.freeze_raw() - mean) * inv_std * gamma.freeze_raw() + beta.freeze_raw();
+                        *x = <$fast_ty>::new(y);
+                    }
+                }
+            }
+
+            #[cfg(scalar_fallback)]
+            use [<strict_stats_ $base_ty>]::*;
+
+            impl StatsKernels for $fast_ty {
+                #[inline]
+                fn mean(a: &[Self]) -> Option<Self> {
+                    if a.is_empty() {
+                        return None;
+                    }
+                    Some(slice::sum(a) / a.len() as $base_ty)
+                }
+
+                #[inline]
+                fn variance(a: &[Self]) -> Option<Self> {
+                    Self::mean_variance(a).map(|(_, variance)| variance)
+                }
+
+                #[inline]
+                fn std_dev(a: &[Self]) -> Option<Self> {
+                    Self::variance(a).map(Self::sqrt)
+                }
+
+                #[inline]
+                fn mean_variance(a: &[Self]) -> Option<(Self, Self)> {
+                    if a.len() < 2 {
+                        return None;
+                    }
+
+                    let shift = a[0];
+                    let (mut sum, mut sum_squares) = (Self::ZERO, Self::ZERO);
+                    unsafe {
+                        [<shifted_sums_ $base_ty>](a.as_ptr(), a.len(), shift, &mut sum, &mut sum_squares)
+                    };
+
+                    let n = a.len() as $base_ty;
+                    let mean = shift + sum / n;
+                    // rounding can leave a tiny negative result for values with almost no spread
+                    let variance = ((sum_squares - sum * sum / n) / (n - 1.0)).max(Self::ZERO);
+                    Some((mean, variance))
+                }
+
+                #[inline]
+                fn min_max(a: &[Self]) -> Option<(Self, Self)> {
+                    if a.is_empty() {
+                        return None;
+                    }
+
+                    let (mut min, mut max) = (Self::ZERO, Self::ZERO);
+                    unsafe { [<min_max_ $base_ty>](a.as_ptr(), a.len(), &mut min, &mut max) };
+                    Some((min, max))
+                }
+
+                #[inline]
+                fn covariance(a: &[Self], b: &[Self]) -> Option<Self> {
+                    let [sa, sb, _, _, sab] = [<paired_stats_ $base_ty>](a, b)?;
+                    let n = a.len() as $base_ty;
+                    Some((sab - sa * sb / n) / (n - 1.0))
+                }
+
+                #[inline]
+                fn correlation(a: &[Self], b: &[Self]) -> Option<Self> {
+                    let [sa, sb, saa, sbb, sab] = [<paired_stats_ $base_ty>](a, b)?;
+                    let n = a.len() as $base_ty;
+                    let variances = (saa - sa * sa / n) * (sbb - sb * sb / n);
+                    // comparisons freeze their operands, so this is safe for poison values
+                    if variances <= Self::ZERO {
+                        return None;
+                    }
+                    Some((sab - sa * sb / n) / variances.sqrt())
+                }
+
+                #[inline]
+                fn linear_regression(xs: &[Self], ys: &[Self]) -> Option<(Self, Self, Self)> {
+                    let [sx, sy, sxx, syy, sxy] = [<paired_stats_ $base_ty>](xs, ys)?;
+                    let n = xs.len() as $base_ty;
+                    let (vx, vy, cxy) = (sxx - sx * sx / n, syy - sy * sy / n, sxy - sx * sy / n);
+                    if vx <= Self::ZERO {
+                        return None;
+                    }
+
+                    let slope = cxy / vx;
+                    // the sums are shifted by the first point, so the means are shifted back
+                    let intercept = (ys[0] + sy / n) - slope * (xs[0] + sx / n);
+                    let r2 = if vy > Self::ZERO { (cxy * cxy / (vx * vy)).min(Self::ONE) } else { Self::ONE };
+                    Some((slope, intercept, r2))
+                }
+
+                #[inline]
+                fn layer_norm(x: &mut [Self], gamma: &[Self], beta: &[Self], eps: Self) {
+                    assert_eq!(x.len(), gamma.len(), "gamma length differs from x");
+                    assert_eq!(x.len(), beta.len(), "beta length differs from x");
+                    assert!(eps > Self::ZERO, "eps must be positive");
+                    if x.is_empty() {
+                        return;
+                    }
+
+                    let shift = x[0];
+                    let (mut sum, mut sum_squares) = (Self::ZERO, Self::ZERO);
+                    unsafe {
+                        [<shifted_sums_ $base_ty>](x.as_ptr(), x.len(), shift, &mut sum, &mut sum_squares)
+                    };
+
+                    let n = x.len() as $base_ty;
+                    let mean = shift + sum / n;
+                    let variance = ((sum_squares - sum * sum / n) / n).max(Self::ZERO);
+                    // sqrt is poison unsafe, so the reciprocal is computed here rather than in C
+                    let inv_std = Self::ONE / (variance + eps).sqrt();
+                    unsafe {
+                        [<layer_norm_apply_ $base_ty>](
+                            x.as_mut_ptr(),
+                            gamma.as_ptr(),
+                            beta.as_ptr(),
+                            x.len(),
+                            mean,
+                            inv_std,
+                        )
+                    };
+                }
+            }
+
+            // the shifted sums shared by the covariance and correlation, or `None` if there are
+            // fewer than two values
+            #[inline(always)]
+            fn [<paired_stats_ $base_ty>](a: &[$fast_ty], b: &[$fast_ty]) -> Option<[$fast_ty; 5]> {
+                assert_eq!(a.len(), b.len(), "slice lengths differ");
+                if a.len() < 2 {
+                    return None;
+                }
+
+                let mut sums = [<$fast_ty>::ZERO; 5];
+                unsafe {
+                    [<paired_sums_ $base_ty>](a.as_ptr(), b.as_ptr(), a.len(), a[0], b[0], sums.as_mut_ptr())
+                };
+                Some(sums)
+            }
+
+            // plain values are valid fast values, so their slices are reinterpreted without copying
+            #[inline(always)]
+            fn [<as_fast_ $base_ty>](a: &[$base_ty]) -> &[$fast_ty] {
+                // safety: the fast type is a transparent wrapper of the base type
+                unsafe { core::slice::from_raw_parts(a.as_ptr().cast::<$fast_ty>(), a.len()) }
+            }
+
+            #[inline(always)]
+            fn [<as_fast_mut_ $base_ty>](a: &mut [$base_ty]) -> &mut [$fast_ty] {
+                // safety: as above. Fast values written through the view may be poison, so the
+                // caller must freeze every value before the plain slice is used again
+                unsafe { core::slice::from_raw_parts_mut(a.as_mut_ptr().cast::<$fast_ty>(), a.len()) }
+            }
+
+            impl StatsKernels for $base_ty {
+                #[inline]
+                fn mean(a: &[Self]) -> Option<Self> {
+                    <$fast_ty>::mean([<as_fast_ $base_ty>](a)).map(Self::from)
+                }
+
+                #[inline]
+                fn variance(a: &[Self]) -> Option<Self> {
+                    <$fast_ty>::variance([<as_fast_ $base_ty>](a)).map(Self::from)
+                }
+
+                #[inline]
+                fn std_dev(a: &[Self]) -> Option<Self> {
+                    <$fast_ty>::std_dev([<as_fast_ $base_ty>](a)).map(Self::from)
+                }
+
+                #[inline]
+                fn mean_variance(a: &[Self]) -> Option<(Self, Self)> {
+                    <$fast_ty>::mean_variance([<as_fast_ $base_ty>](a))
+                        .map(|(mean, variance)| (mean.into(), variance.into()))
+                }
+
+                #[inline]
+                fn min_max(a: &[Self]) -> Option<(Self, Self)> {
+                    <$fast_ty>::min_max([<as_fast_ $base_ty>](a)).map(|(min, max)| (min.into(), max.into()))
+                }
+
+                #[inline]
+                fn covariance(a: &[Self], b: &[Self]) -> Option<Self> {
+                    <$fast_ty>::covariance([<as_fast_ $base_ty>](a), [<as_fast_ $base_ty>](b)).map(Self::from)
+                }
+
+                #[inline]
+                fn correlation(a: &[Self], b: &[Self]) -> Option<Self> {
+                    <$fast_ty>::correlation([<as_fast_ $base_ty>](a), [<as_fast_ $base_ty>](b)).map(Self::from)
+                }
+
+                #[inline]
+                fn linear_regression(xs: &[Self], ys: &[Self]) -> Option<(Self, Self, Self)> {
+                    <$fast_ty>::linear_regression([<as_fast_ $base_ty>](xs), [<as_fast_ $base_ty>](ys))
+                        .map(|(slope, intercept, r2)| (slope.into(), intercept.into(), r2.into()))
+                }
+
+                #[inline]
+                fn layer_norm(x: &mut [Self], gamma: &[Self], beta: &[Self], eps: Self) {
+                    let x = [<as_fast_mut_ $base_ty>](x);
+                    <$fast_ty>::layer_norm(x, [<as_fast_ $base_ty>](gamma), [<as_fast_ $base_ty>](beta), eps.into());
+                    // finite values which overflow in the kernel become poison, which must not
+                    // reach the plain slice
+                    for x in x.iter_mut() {
+                        *x = <$fast_ty>::new(x.freeze_raw());
+                    }
+                }
+            }
+        }
+    };
+}
+
+impl_stats! { FF32, f32 }
+impl_stats! { FF64, f64 }
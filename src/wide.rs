@@ -0,0 +1,84 @@
+#![cfg(feature = "wide-v07")]
+#![cfg_attr(docsrs, doc(cfg(feature = "wide-v07")))]
+
+use crate::{FF32x4, FF32x8, FF64x4, FastSimdExt, FF32, FF64};
+use core::ops::{Add, Div, Mul, Sub};
+use wide_v07 as wide;
+
+macro_rules! impl_wide_ops {
+    ($simd_ty:ident, $wide_ty:ident: $($op_trait:ident, $op_fn:ident,)*) => {
+        $(
+            impl $op_trait<wide::$wide_ty> for $simd_ty {
+                type Output = $simd_ty;
+
+                #[inline(always)]
+                fn $op_fn(self, other: wide::$wide_ty) -> Self::Output {
+                    self.$op_fn(<$simd_ty>::from(other))
+                }
+            }
+
+            impl $op_trait<$simd_ty> for wide::$wide_ty {
+                type Output = $simd_ty;
+
+                #[inline(always)]
+                fn $op_fn(self, other: $simd_ty) -> Self::Output {
+                    <$simd_ty>::from(self).$op_fn(other)
+                }
+            }
+        )*
+    };
+}
+
+macro_rules! impl_wide {
+    ($($simd_ty:ident, $fast_ty:ident, $wide_ty:ident;)*) => {
+        $(
+            impl From<wide::$wide_ty> for $simd_ty {
+                #[inline(always)]
+                fn from(from: wide::$wide_ty) -> Self {
+                    <$simd_ty>::new(from.to_array())
+                }
+            }
+
+            impl From<$simd_ty> for wide::$wide_ty {
+                #[inline(always)]
+                fn from(from: $simd_ty) -> Self {
+                    // wide's vectors are no longer in our API control, so lanes must be frozen
+                    wide::$wide_ty::new(from.into())
+                }
+            }
+
+            impl_wide_ops! {
+                $simd_ty, $wide_ty:
+                Add, add,
+                Sub, sub,
+                Mul, mul,
+                Div, div,
+            }
+
+            impl FastSimdExt for wide::$wide_ty {
+                type Scalar = $fast_ty;
+
+                #[inline]
+                fn fast_reduce_sum(self) -> $fast_ty {
+                    <$simd_ty>::from(self).reduce_sum()
+                }
+
+                #[inline]
+                fn fast_reduce_min(self) -> $fast_ty {
+                    <$simd_ty>::from(self).reduce_min()
+                }
+
+                #[inline]
+                fn fast_reduce_max(self) -> $fast_ty {
+                    <$simd_ty>::from(self).reduce_max()
+                }
+            }
+        )*
+    };
+}
+
+impl_wide! {
+    FF32x4, FF32, f32x4;
+    FF32x8, FF32, f32x8;
+    FF64x4, FF64, f64x4;
+}